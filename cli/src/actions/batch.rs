@@ -0,0 +1,150 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::fs;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use grid_sdk::client::batch::{header_signatures, BatchClient, BatchStatus};
+use protobuf::Message;
+use sawtooth_sdk::messages::batch::BatchList;
+
+use crate::error::CliError;
+
+/// Shows the status of a batch.
+///
+/// If `wait` is given, the status is polled until the batch is committed or invalid, updating the
+/// status line as it changes, and an error is returned if the batch is still pending once `wait`
+/// seconds have elapsed. An error is also returned if the batch is invalid, after printing the
+/// messages of its invalid transactions.
+pub fn do_show_batch_status(
+    client: Box<dyn BatchClient>,
+    id: &str,
+    service_id: Option<&str>,
+    wait: Option<u64>,
+) -> Result<(), CliError> {
+    let status = match wait {
+        Some(wait) => await_status(&*client, id, service_id, wait)?,
+        None => {
+            let status = client.get_batch_status(id, service_id)?;
+            print_status_line(&status);
+            println!();
+            status
+        }
+    };
+
+    check_valid(&status)
+}
+
+/// Submits the serialized `BatchList` in the file at `path`.
+///
+/// If `wait` is given, the status of each submitted batch is then polled until it is committed
+/// or invalid, as with `do_show_batch_status`, and an error is returned if any batch is invalid
+/// or is still pending once `wait` seconds have elapsed.
+pub fn do_submit_batch_file(
+    client: Box<dyn BatchClient>,
+    path: &str,
+    service_id: Option<&str>,
+    wait: Option<u64>,
+) -> Result<(), CliError> {
+    let bytes = fs::read(path)
+        .map_err(|err| CliError::UserError(format!("Unable to read {}: {}", path, err)))?;
+    let batch_list = BatchList::parse_from_bytes(&bytes).map_err(|err| {
+        CliError::UserError(format!("{} is not a serialized batch list: {}", path, err))
+    })?;
+    let ids = header_signatures(&batch_list)
+        .map_err(|err| CliError::UserError(format!("Invalid batch list in {}: {}", path, err)))?;
+    if ids.is_empty() {
+        return Err(CliError::UserError(format!(
+            "The batch list in {} has no batches",
+            path
+        )));
+    }
+
+    client.submit_batches(&batch_list, service_id)?;
+
+    let wait = match wait {
+        Some(wait) => wait,
+        None => return Ok(()),
+    };
+
+    let mut invalid = 0;
+    for id in &ids {
+        let status = await_status(&*client, id, service_id, wait)?;
+        if check_valid(&status).is_err() {
+            invalid += 1;
+        }
+    }
+
+    if invalid > 0 {
+        return Err(CliError::ActionError(format!(
+            "{} of {} batches are invalid",
+            invalid,
+            ids.len()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Polls the status of a batch until it is committed or invalid, returning an error if it is
+/// still pending once `wait` seconds have elapsed.
+fn await_status(
+    client: &dyn BatchClient,
+    id: &str,
+    service_id: Option<&str>,
+    wait: u64,
+) -> Result<BatchStatus, CliError> {
+    let status =
+        client.await_batch_status(id, service_id, Duration::from_secs(wait), &mut |status| {
+            print_status_line(status)
+        })?;
+    println!();
+
+    if !status.is_terminal() {
+        return Err(CliError::ActionError(format!(
+            "Timed out after {}s waiting for batch {}; last status was {}",
+            wait, status.id, status.status
+        )));
+    }
+
+    Ok(status)
+}
+
+/// Returns an error if the batch is invalid, after printing the messages of its invalid
+/// transactions.
+fn check_valid(status: &BatchStatus) -> Result<(), CliError> {
+    if status.is_invalid() {
+        for txn in &status.invalid_transactions {
+            println!("Invalid transaction {}: {}", txn.id, txn.message);
+        }
+
+        return Err(CliError::ActionError(format!(
+            "Batch {} is invalid",
+            status.id
+        )));
+    }
+
+    Ok(())
+}
+
+/// Overwrites the current terminal line with the batch's status.
+fn print_status_line(status: &BatchStatus) {
+    print!("\r\x1b[2KBatch {}: {}", status.id, status.status);
+    // The status line is informational only, so a failure to flush it is not an error
+    let _ = io::stdout().flush();
+}
@@ -21,7 +21,7 @@ use std::{
 
 use grid_sdk::{
     client::location::{Location, LocationClient},
-    client::schema::{DataType, PropertyDefinition, SchemaClient},
+    client::schema::{DataType, PropertyDefinition, Schema, SchemaClient},
     location::addressing::GRID_LOCATION_NAMESPACE,
     pike::addressing::GRID_PIKE_NAMESPACE,
     protocol::{
@@ -39,6 +39,9 @@ use grid_sdk::{
 use cylinder::Signer;
 use serde::Deserialize;
 
+use crate::actions::property::{
+    fetch_display_schema, find_definition, format_enum, format_number, parse_number,
+};
 use crate::error::CliError;
 use crate::transaction::location_batch_builder;
 
@@ -101,11 +104,17 @@ pub fn do_list_locations(
 
 pub fn do_show_location(
     client: Box<dyn LocationClient>,
+    schema_client: Box<dyn SchemaClient>,
     location_id: &str,
     service_id: Option<&str>,
 ) -> Result<(), CliError> {
     let location = client.get_location(location_id.into(), service_id)?;
-    display_location(&location);
+    let schema = if location.location_namespace.eq_ignore_ascii_case("gs1") {
+        fetch_display_schema(&*schema_client, "gs1_location", service_id)
+    } else {
+        None
+    };
+    display_location(&location, schema.as_ref());
     Ok(())
 }
 
@@ -232,10 +241,11 @@ fn yaml_to_property_values(
                 property_values.push(property_value);
             }
             DataType::Number => {
+                let number_value = parse_number(value, &def)?;
                 let property_value = PropertyValueBuilder::new()
                     .with_name(def.name.clone())
                     .with_data_type(def.data_type.into())
-                    .with_number_value(serde_yaml::from_value(value.clone())?)
+                    .with_number_value(number_value)
                     .build()
                     .map_err(|err| CliError::PayloadError(format!("{}", err)))?;
                 property_values.push(property_value);
@@ -337,7 +347,9 @@ fn display_locations_info(locations: &[Location]) {
     });
 }
 
-fn display_location(location: &Location) {
+/// Prints a location, formatting number and enum property values according to the schema's
+/// property definitions, or printing them raw if there is no schema.
+fn display_location(location: &Location, schema: Option<&Schema>) {
     println!(
         "Location ID: {}\nNamespace: {}\nOwner: {}\nProperties",
         location.location_id, location.location_namespace, location.owner,
@@ -351,13 +363,24 @@ fn display_location(location: &Location) {
             println!("{}: {:?}", p.name, p.boolean_value.as_ref().unwrap());
         }
         DataType::Number => {
-            println!("{}: {:?}", p.name, p.number_value.as_ref().unwrap());
+            println!(
+                "{}: {}",
+                p.name,
+                format_number(p.number_value.unwrap(), find_definition(schema, &p.name))
+            );
         }
         DataType::String => {
             println!("{}: {:?}", p.name, p.string_value.as_ref().unwrap());
         }
         DataType::Enum => {
-            println!("{}: {:?}", p.name, p.enum_value.as_ref().unwrap());
+            println!(
+                "{}: {}",
+                p.name,
+                format_enum(
+                    p.enum_value.unwrap().into(),
+                    find_definition(schema, &p.name)
+                )
+            );
         }
         DataType::Struct => {
             println!("{}: {:?}", p.name, p.struct_values.as_ref().unwrap());
@@ -34,6 +34,14 @@ use super::error::CliError;
 
 #[cfg(feature = "pike")]
 pub mod agent;
+#[cfg(any(
+    feature = "location",
+    feature = "pike",
+    feature = "product",
+    feature = "purchase-order",
+    feature = "schema",
+))]
+pub mod batch;
 #[cfg(feature = "database")]
 pub mod database;
 pub mod keygen;
@@ -41,8 +49,12 @@ pub mod keygen;
 pub mod location;
 #[cfg(feature = "pike")]
 pub mod organization;
+#[cfg(feature = "pike")]
+pub mod pike;
 #[cfg(feature = "product")]
 pub mod product;
+#[cfg(any(feature = "location", feature = "product"))]
+pub mod property;
 #[cfg(any(feature = "purchase-order"))]
 pub mod purchase_order;
 #[cfg(feature = "pike")]
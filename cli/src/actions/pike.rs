@@ -0,0 +1,317 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cylinder::Signer;
+use grid_sdk::client::pike::PikeClient;
+use grid_sdk::{
+    pike::addressing::GRID_PIKE_NAMESPACE,
+    protocol::pike::{
+        payload::{
+            Action, CreateAgentActionBuilder, CreateOrganizationActionBuilder, PikePayloadBuilder,
+        },
+        state::{AlternateIdBuilder, KeyValueEntry, KeyValueEntryBuilder},
+    },
+    protos::IntoProto,
+};
+use serde::Deserialize;
+
+use crate::error::CliError;
+use crate::transaction::pike_batch_builder;
+
+/// A document describing organizations and the agents that belong to them.
+#[derive(Debug, Deserialize)]
+struct PikeImport {
+    #[serde(default)]
+    organizations: Vec<ImportOrganization>,
+    #[serde(default)]
+    agents: Vec<ImportAgent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportOrganization {
+    org_id: String,
+    name: String,
+    #[serde(default)]
+    alternate_ids: Vec<ImportAlternateId>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportAlternateId {
+    id_type: String,
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportAgent {
+    org_id: String,
+    public_key: String,
+    #[serde(default = "default_active")]
+    active: bool,
+    #[serde(default)]
+    roles: Vec<String>,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+}
+
+fn default_active() -> bool {
+    true
+}
+
+/// Submits the organizations and agents in the given file as a single batch list.
+///
+/// Organizations are created before agents. The file is validated in full before anything is
+/// submitted.
+pub fn do_import(
+    client: Box<dyn PikeClient>,
+    signer: Box<dyn Signer>,
+    wait: u64,
+    path: &str,
+    service_id: Option<&str>,
+) -> Result<(), CliError> {
+    let import = load_import_file(path)?;
+    let actions = import_actions(import)?;
+
+    let mut builder = pike_batch_builder(signer);
+
+    for action in actions {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .map_err(|err| CliError::PayloadError(format!("{}", err)))?;
+
+        let payload = PikePayloadBuilder::new()
+            .with_action(action)
+            .with_timestamp(timestamp)
+            .build()
+            .map_err(|err| CliError::PayloadError(format!("{}", err)))?;
+
+        builder.add_transaction(
+            &payload.into_proto()?,
+            &[GRID_PIKE_NAMESPACE.to_string()],
+            &[GRID_PIKE_NAMESPACE.to_string()],
+        )?;
+    }
+
+    let batch_list = builder.create_batch_list();
+
+    client.post_batches(wait, &batch_list, service_id)?;
+    Ok(())
+}
+
+fn load_import_file(path: &str) -> Result<PikeImport, CliError> {
+    let file = std::fs::File::open(path)?;
+    serde_json::from_reader(&file)
+        .map_err(|err| CliError::UserError(format!("Unable to parse {}: {}", path, err)))
+}
+
+/// Validates the import document and converts it into the pike actions to submit.
+fn import_actions(import: PikeImport) -> Result<Vec<Action>, CliError> {
+    validate_import(&import)?;
+
+    let mut actions = Vec::new();
+
+    for (i, org) in import.organizations.into_iter().enumerate() {
+        let record = format!("organizations[{}] ({})", i, org.org_id);
+
+        let alternate_ids = org
+            .alternate_ids
+            .into_iter()
+            .map(|alt_id| {
+                AlternateIdBuilder::new()
+                    .with_id_type(alt_id.id_type)
+                    .with_id(alt_id.id)
+                    .build()
+                    .map_err(|err| CliError::UserError(format!("{}: {}", record, err)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let create_org = CreateOrganizationActionBuilder::new()
+            .with_org_id(org.org_id)
+            .with_name(org.name)
+            .with_alternate_ids(alternate_ids)
+            .with_metadata(to_key_value_entries(org.metadata, &record)?)
+            .build()
+            .map_err(|err| CliError::UserError(format!("{}: {}", record, err)))?;
+
+        actions.push(Action::CreateOrganization(create_org));
+    }
+
+    for (i, agent) in import.agents.into_iter().enumerate() {
+        let record = format!("agents[{}] ({})", i, agent.public_key);
+
+        let create_agent = CreateAgentActionBuilder::new()
+            .with_org_id(agent.org_id)
+            .with_public_key(agent.public_key)
+            .with_active(agent.active)
+            .with_roles(agent.roles)
+            .with_metadata(to_key_value_entries(agent.metadata, &record)?)
+            .build()
+            .map_err(|err| CliError::UserError(format!("{}: {}", record, err)))?;
+
+        actions.push(Action::CreateAgent(create_agent));
+    }
+
+    Ok(actions)
+}
+
+fn validate_import(import: &PikeImport) -> Result<(), CliError> {
+    if import.organizations.is_empty() && import.agents.is_empty() {
+        return Err(CliError::UserError(
+            "Import file does not define any organizations or agents".into(),
+        ));
+    }
+
+    let mut org_ids = HashSet::new();
+    for (i, org) in import.organizations.iter().enumerate() {
+        if !org_ids.insert(org.org_id.as_str()) {
+            return Err(CliError::UserError(format!(
+                "organizations[{}] ({}): organization is defined more than once",
+                i, org.org_id
+            )));
+        }
+    }
+
+    let mut public_keys = HashSet::new();
+    for (i, agent) in import.agents.iter().enumerate() {
+        if !org_ids.contains(agent.org_id.as_str()) {
+            return Err(CliError::UserError(format!(
+                "agents[{}] ({}): organization {} is not defined in the import file",
+                i, agent.public_key, agent.org_id
+            )));
+        }
+
+        if !public_keys.insert(agent.public_key.as_str()) {
+            return Err(CliError::UserError(format!(
+                "agents[{}] ({}): agent is defined more than once",
+                i, agent.public_key
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn to_key_value_entries(
+    metadata: HashMap<String, String>,
+    record: &str,
+) -> Result<Vec<KeyValueEntry>, CliError> {
+    let mut metadata = metadata.into_iter().collect::<Vec<_>>();
+    metadata.sort();
+
+    metadata
+        .into_iter()
+        .map(|(key, value)| {
+            KeyValueEntryBuilder::new()
+                .with_key(key)
+                .with_value(value)
+                .build()
+                .map_err(|err| {
+                    CliError::UserError(format!("{}: Metadata malformed: {}", record, err))
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> PikeImport {
+        serde_json::from_str(json).expect("Unable to parse import document")
+    }
+
+    /// Verifies that organizations are converted before agents and that agent defaults are
+    /// applied.
+    #[test]
+    fn test_import_actions_orders_organizations_first() {
+        let import = parse(
+            r#"{
+                "agents": [
+                    {"org_id": "org-1", "public_key": "key-1", "roles": ["admin"]}
+                ],
+                "organizations": [
+                    {"org_id": "org-1", "name": "Org One", "metadata": {"a": "b"}}
+                ]
+            }"#,
+        );
+
+        let actions = import_actions(import).expect("Unable to convert import document");
+
+        assert_eq!(actions.len(), 2);
+        match &actions[0] {
+            Action::CreateOrganization(org) => assert_eq!(org.org_id(), "org-1"),
+            action => panic!("Expected CreateOrganization, got {:?}", action),
+        }
+        match &actions[1] {
+            Action::CreateAgent(agent) => {
+                assert_eq!(agent.public_key(), "key-1");
+                assert!(*agent.active());
+                assert_eq!(agent.roles(), ["admin".to_string()]);
+            }
+            action => panic!("Expected CreateAgent, got {:?}", action),
+        }
+    }
+
+    /// Verifies that an agent referencing an undefined organization is rejected, naming the
+    /// offending record.
+    #[test]
+    fn test_import_actions_rejects_undefined_organization() {
+        let import = parse(
+            r#"{
+                "organizations": [{"org_id": "org-1", "name": "Org One"}],
+                "agents": [
+                    {"org_id": "org-1", "public_key": "key-1"},
+                    {"org_id": "org-2", "public_key": "key-2"}
+                ]
+            }"#,
+        );
+
+        match import_actions(import) {
+            Err(CliError::UserError(msg)) => assert_eq!(
+                msg,
+                "agents[1] (key-2): organization org-2 is not defined in the import file"
+            ),
+            res => panic!("Expected UserError, got {:?}", res),
+        }
+    }
+
+    /// Verifies that an organization defined twice is rejected.
+    #[test]
+    fn test_import_actions_rejects_duplicate_organization() {
+        let import = parse(
+            r#"{
+                "organizations": [
+                    {"org_id": "org-1", "name": "Org One"},
+                    {"org_id": "org-1", "name": "Org One Again"}
+                ]
+            }"#,
+        );
+
+        match import_actions(import) {
+            Err(CliError::UserError(msg)) => assert_eq!(
+                msg,
+                "organizations[1] (org-1): organization is defined more than once"
+            ),
+            res => panic!("Expected UserError, got {:?}", res),
+        }
+    }
+}
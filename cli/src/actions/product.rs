@@ -18,34 +18,44 @@ use cylinder::Signer;
 use grid_sdk::client::product::{
     Product as GridProduct, ProductClient, PropertyValue as GridPropertyValue,
 };
-use grid_sdk::client::schema::{DataType, PropertyDefinition, SchemaClient};
+use grid_sdk::client::schema::{
+    CachingSchemaClient, DataType, PropertyDefinition, Schema, SchemaClient,
+};
 use grid_sdk::data_validation::validate_gdsn_3_1;
 use grid_sdk::pike::addressing::GRID_PIKE_NAMESPACE;
 use grid_sdk::product::addressing::GRID_PRODUCT_NAMESPACE;
 use grid_sdk::product::gdsn::{get_trade_items_from_xml, GDSN_3_1_PROPERTY_NAME};
+use grid_sdk::product::gtin;
 use grid_sdk::protocol::product::payload::{
     Action, ProductCreateAction, ProductCreateActionBuilder, ProductDeleteAction,
-    ProductPayloadBuilder, ProductUpdateAction, ProductUpdateActionBuilder,
+    ProductPayloadBuilder, ProductUpdateAction, ProductUpdateActionBuilder, UpdateMode,
 };
 use grid_sdk::protocol::product::state::ProductNamespace;
-use grid_sdk::protocol::schema::state::{LatLongBuilder, PropertyValue, PropertyValueBuilder};
+use grid_sdk::protocol::schema::state::{
+    DataType as StateDataType, LatLongBuilder, PropertyValue, PropertyValueBuilder,
+};
 use grid_sdk::protos::IntoProto;
 use grid_sdk::schema::addressing::GRID_SCHEMA_NAMESPACE;
 
+use crate::actions::property::{
+    fetch_display_schema, find_definition, format_enum, format_number, parse_number,
+};
 use crate::error::CliError;
 use serde::Deserialize;
 
-use std::borrow::Borrow;
 use std::{
     collections::HashMap,
     fs::File,
     io::prelude::*,
     path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use crate::actions;
 
+/// How long a schema fetched while building product payloads is reused
+const SCHEMA_CACHE_TTL: Duration = Duration::from_secs(60);
+
 /**
  * Prints basic info for products
  *
@@ -84,32 +94,45 @@ pub fn display_products_info(products: &[GridProduct]) {
  * Print the fields for a given product
  *
  * product - Product to be printed
+ * schema - Schema used to format the product's property values, if available
  */
-pub fn display_product(product: &GridProduct) {
+pub fn display_product(product: &GridProduct, schema: Option<&Schema>) {
     println!(
         "Product Id: {:?}\n Product Namespace: {:?}\n Owner: {:?}\n Properties:",
         product.product_id, product.product_namespace, product.owner,
     );
-    display_product_property_definitions(&product.properties);
+    display_product_property_definitions(&product.properties, schema);
 }
 
 /**
  * Iterate through all fields of a Property Value and print the given value
  *
+ * Number values are scaled and enum values are labeled according to the schema's property
+ * definitions; without a schema, they are printed raw.
+ *
  * properties - Property values to be printed
+ * schema - Schema used to format the property values, if available
  */
-pub fn display_product_property_definitions(properties: &[GridPropertyValue]) {
+pub fn display_product_property_definitions(
+    properties: &[GridPropertyValue],
+    schema: Option<&Schema>,
+) {
     properties.iter().for_each(|def| {
+        let definition = find_definition(schema, &def.name);
         println!(
             "\tProperty Name: {:?}\n\t Data Type: {:?}\n\t Bytes Value: {:?}\n\t Boolean Value: {:?}
-        Number Value: {:?}\n\t String Value: {:?}\n\t Enum Value: {:?}\n\t Struct Values: {:?}\n\t Lat/Lon Values: {:?}\n\t",
+        Number Value: {}\n\t String Value: {:?}\n\t Enum Value: {}\n\t Struct Values: {:?}\n\t Lat/Lon Values: {:?}\n\t",
             def.name,
             def.data_type,
             def.bytes_value,
             def.boolean_value,
-            def.number_value,
+            def.number_value
+                .map(|value| format_number(value, definition))
+                .unwrap_or_else(|| "None".into()),
             def.string_value,
-            def.enum_value,
+            def.enum_value
+                .map(|value| format_enum(value.into(), definition))
+                .unwrap_or_else(|| "None".into()),
             def.struct_values,
             def.lat_long_value,
         );
@@ -164,6 +187,101 @@ pub fn do_update_products(
     )
 }
 
+/**
+ * Normalize a GS1 product ID to its GTIN-14 form, so that the same item is not recorded as
+ * several products under GTINs of different lengths
+ *
+ * product_id - The GTIN-12, GTIN-13 or GTIN-14 of the product
+ */
+pub fn normalize_product_id(product_id: &str) -> Result<String, CliError> {
+    gtin::normalize(product_id).map_err(|err| CliError::UserError(err.to_string()))
+}
+
+/**
+ * Merge an update into a product's current properties, so that the properties the update does
+ * not include keep their current values
+ *
+ * client - Client used to fetch the product's current properties
+ * action - The update to merge
+ * service_id - Optional service ID the product is fetched from
+ */
+pub fn merge_with_current_properties(
+    client: &dyn ProductClient,
+    action: ProductUpdateAction,
+    service_id: Option<&str>,
+) -> Result<ProductUpdateAction, CliError> {
+    let product = client.get_product(action.product_id().to_string(), service_id)?;
+    let current_properties = product
+        .properties
+        .iter()
+        .map(to_property_value)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ProductUpdateActionBuilder::new()
+        .with_product_id(action.product_id().to_string())
+        .with_product_namespace(action.product_namespace().clone())
+        .with_properties(action.properties().to_vec())
+        .with_mode(UpdateMode::Merge)
+        .with_current_properties(current_properties)
+        .build()
+        .map_err(|err| CliError::PayloadError(format!("{}", err)))
+}
+
+/// Converts a property value fetched from the REST API back into its state representation.
+fn to_property_value(property: &GridPropertyValue) -> Result<PropertyValue, CliError> {
+    let data_type = match property.data_type.to_lowercase().as_str() {
+        "bytes" => StateDataType::Bytes,
+        "boolean" => StateDataType::Boolean,
+        "number" => StateDataType::Number,
+        "string" => StateDataType::String,
+        "enum" => StateDataType::Enum,
+        "latlong" | "lat_long" => StateDataType::LatLong,
+        "struct" => {
+            return Err(CliError::UserError(format!(
+                "Property {} is a struct and cannot be merged; update the product with all of \
+                its properties instead",
+                property.name
+            )))
+        }
+        unknown => {
+            return Err(CliError::UserError(format!(
+                "Property {} has unknown data type {}",
+                property.name, unknown
+            )))
+        }
+    };
+
+    let mut builder = PropertyValueBuilder::new()
+        .with_name(property.name.clone())
+        .with_data_type(data_type);
+    if let Some(value) = &property.bytes_value {
+        builder = builder.with_bytes_value(value.clone());
+    }
+    if let Some(value) = property.boolean_value {
+        builder = builder.with_boolean_value(value);
+    }
+    if let Some(value) = property.number_value {
+        builder = builder.with_number_value(value);
+    }
+    if let Some(value) = &property.string_value {
+        builder = builder.with_string_value(value.clone());
+    }
+    if let Some(value) = property.enum_value {
+        builder = builder.with_enum_value(value);
+    }
+    if let Some(value) = &property.lat_long_value {
+        let lat_long = LatLongBuilder::new()
+            .with_lat_long(value.latitude, value.longitude)
+            .build()
+            .map_err(|err| CliError::PayloadError(format!("{}", err)))?;
+        builder = builder.with_lat_long_value(lat_long);
+    }
+
+    builder
+        .build()
+        .map_err(|err| CliError::PayloadError(format!("{}", err)))
+}
+
 /**
  * Delete an existing product
  *
@@ -206,15 +324,22 @@ pub fn do_list_products(
  * Print a single product in state
  *
  * url - Url for the REST API
+ * schema_client - Client used to fetch the product's schema, to format its property values
  * product_id - e.g. GTIN
  */
 pub fn do_show_products(
     client: Box<dyn ProductClient>,
+    schema_client: Box<dyn SchemaClient>,
     product_id: String,
     service_id: Option<&str>,
 ) -> Result<(), CliError> {
     let product = client.get_product(product_id, service_id)?;
-    display_product(&product);
+    let schema = if product.product_namespace.eq_ignore_ascii_case("gs1") {
+        fetch_display_schema(&*schema_client, "gs1_product", service_id)
+    } else {
+        None
+    };
+    display_product(&product, schema.as_ref());
     Ok(())
 }
 
@@ -255,6 +380,9 @@ pub fn create_product_payloads_from_file(
 ) -> Result<Vec<ProductCreateAction>, CliError> {
     let mut total_payloads: Vec<ProductCreateAction> = Vec::new();
 
+    // Products in the same namespace share a schema, so fetch it once for all files
+    let client = CachingSchemaClient::new(client, SCHEMA_CACHE_TTL);
+
     for path in paths {
         let file_type = determine_file_type(path)?;
 
@@ -269,7 +397,7 @@ pub fn create_product_payloads_from_file(
                 create_product_payloads_from_xml(path, owner)?
             }
             ProductFileType::SchemaBasedDefinition => {
-                create_product_payloads_from_yaml(path, client.borrow(), service_id)?
+                create_product_payloads_from_yaml(path, &client, service_id)?
             }
         };
 
@@ -313,13 +441,16 @@ pub fn update_product_payloads_from_file(
 ) -> Result<Vec<ProductUpdateAction>, CliError> {
     let mut total_payloads: Vec<ProductUpdateAction> = Vec::new();
 
+    // Products in the same namespace share a schema, so fetch it once for all files
+    let client = CachingSchemaClient::new(client, SCHEMA_CACHE_TTL);
+
     for path in paths {
         let file_type = determine_file_type(path)?;
 
         let file_payloads = match file_type {
             ProductFileType::Gdsn3_1 => update_product_payloads_from_xml(path)?,
             ProductFileType::SchemaBasedDefinition => {
-                update_product_payloads_from_yaml(path, client.borrow(), service_id)?
+                update_product_payloads_from_yaml(path, &client, service_id)?
             }
         };
 
@@ -449,7 +580,7 @@ impl ProductCreateYaml {
     ) -> Result<ProductCreateAction, CliError> {
         let property_values = yaml_to_property_values(&self.properties, definitions)?;
         ProductCreateActionBuilder::new()
-            .with_product_id(self.product_id)
+            .with_product_id(normalize_product_id(&self.product_id)?)
             .with_owner(self.owner)
             .with_product_namespace(self.product_namespace.into())
             .with_properties(property_values)
@@ -491,7 +622,7 @@ impl ProductUpdateYaml {
     ) -> Result<ProductUpdateAction, CliError> {
         let property_values = yaml_to_property_values(&self.properties, definitions)?;
         ProductUpdateActionBuilder::new()
-            .with_product_id(self.product_id)
+            .with_product_id(normalize_product_id(&self.product_id)?)
             .with_product_namespace(self.product_namespace.into())
             .with_properties(property_values)
             .build()
@@ -542,10 +673,11 @@ fn yaml_to_property_values(
                 property_values.push(property_value);
             }
             DataType::Number => {
+                let number_value = parse_number(value, &def)?;
                 let property_value = PropertyValueBuilder::new()
                     .with_name(def.name.clone())
                     .with_data_type(def.data_type.into())
-                    .with_number_value(serde_yaml::from_value(value.clone())?)
+                    .with_number_value(number_value)
                     .build()
                     .map_err(|err| CliError::PayloadError(format!("{}", err)))?;
                 property_values.push(property_value);
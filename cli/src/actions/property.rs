@@ -0,0 +1,174 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+//! Formatting of property values for display, using the property definitions of their schema
+//! where one is available, and parsing of number values from YAML.
+
+use std::convert::TryFrom;
+
+use grid_sdk::client::schema::{PropertyDefinition, Schema, SchemaClient};
+use grid_sdk::schema::decimal::{format_decimal, parse_decimal};
+
+use crate::error::CliError;
+
+/// Fetches the schema used to format the properties of an entity for display.
+///
+/// Formatting falls back to raw values when the schema cannot be fetched, so failures are
+/// logged rather than returned.
+pub fn fetch_display_schema(
+    client: &dyn SchemaClient,
+    name: &str,
+    service_id: Option<&str>,
+) -> Option<Schema> {
+    match client.get_schema(name.to_string(), service_id) {
+        Ok(schema) => Some(schema),
+        Err(err) => {
+            debug!(
+                "Unable to fetch schema {}, displaying raw property values: {}",
+                name, err
+            );
+            None
+        }
+    }
+}
+
+/// Returns the definition of the named property in the schema, if there is a schema.
+pub fn find_definition<'a>(
+    schema: Option<&'a Schema>,
+    name: &str,
+) -> Option<&'a PropertyDefinition> {
+    schema.and_then(|schema| schema.properties.iter().find(|def| def.name == name))
+}
+
+/// Formats a number value with its decimal point placed according to the definition's number
+/// exponent, or as the raw stored integer if there is no definition.
+///
+/// Values are stored as the number multiplied by `10^-exponent`, so a value of `1234` with an
+/// exponent of `-2` is displayed as `12.34`.
+pub fn format_number(value: i64, definition: Option<&PropertyDefinition>) -> String {
+    format_decimal(
+        value,
+        definition.map(|def| def.number_exponent).unwrap_or(0),
+    )
+}
+
+/// Parses a number value from YAML into the integer it is stored as.
+///
+/// An integer is taken as the stored value itself. A decimal string such as `"0.1"` is scaled
+/// by the definition's number exponent without passing through a float, so it is stored
+/// exactly; it is an error for it to have more decimal places than the exponent allows. YAML
+/// floats are rejected, as they may already have been rounded.
+pub fn parse_number(
+    value: &serde_yaml::Value,
+    definition: &PropertyDefinition,
+) -> Result<i64, CliError> {
+    match value {
+        serde_yaml::Value::String(decimal) => parse_decimal(decimal, definition.number_exponent)
+            .map_err(|err| {
+                CliError::UserError(format!(
+                    "Invalid value for number property {}: {}",
+                    definition.name, err
+                ))
+            }),
+        serde_yaml::Value::Number(number) if number.is_f64() => Err(CliError::UserError(format!(
+            "Invalid value for number property {}: {} must be quoted as a decimal string to \
+            avoid rounding",
+            definition.name, number
+        ))),
+        value => Ok(serde_yaml::from_value(value.clone())?),
+    }
+}
+
+/// Formats an enum value as the label of the option at its index, or as the raw index if there
+/// is no definition or the index is not one of its options.
+pub fn format_enum(index: i64, definition: Option<&PropertyDefinition>) -> String {
+    usize::try_from(index)
+        .ok()
+        .and_then(|index| definition.and_then(|def| def.enum_options.get(index)))
+        .cloned()
+        .unwrap_or_else(|| index.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use grid_sdk::client::schema::DataType;
+
+    fn definition(number_exponent: i64) -> PropertyDefinition {
+        PropertyDefinition {
+            name: "test".to_string(),
+            schema_name: "test_schema".to_string(),
+            data_type: DataType::Number,
+            required: false,
+            description: "".to_string(),
+            number_exponent,
+            enum_options: vec!["red".to_string(), "blue".to_string()],
+            struct_properties: vec![],
+        }
+    }
+
+    /// Verifies that numbers are scaled by the definition's exponent, and displayed raw without
+    /// a definition.
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(1234, Some(&definition(-2))), "12.34");
+        assert_eq!(format_number(-5, Some(&definition(-3))), "-0.005");
+        assert_eq!(format_number(12, Some(&definition(3))), "12000");
+        assert_eq!(format_number(0, Some(&definition(3))), "0");
+        assert_eq!(format_number(1234, None), "1234");
+    }
+
+    /// Verifies that decimal strings are scaled by the definition's exponent exactly, including
+    /// values with more significant digits than a float holds, and that they round-trip through
+    /// `format_number`.
+    #[test]
+    fn test_parse_number() {
+        let parse = |value: &str, exponent| {
+            parse_number(
+                &serde_yaml::from_str(value).expect("invalid YAML"),
+                &definition(exponent),
+            )
+        };
+
+        assert_eq!(parse("\"0.1\"", -2).ok(), Some(10));
+        assert_eq!(parse("\"-0.005\"", -3).ok(), Some(-5));
+        assert_eq!(parse("\"12000\"", 3).ok(), Some(12));
+        assert_eq!(parse("\"12.50\"", -1).ok(), Some(125));
+        assert_eq!(parse("1234", -2).ok(), Some(1234));
+
+        let precise = "123456789.0123456789";
+        let value = parse(&format!("\"{}\"", precise), -10).expect("unable to parse");
+        assert_eq!(value, 1_234_567_890_123_456_789);
+        assert_eq!(format_number(value, Some(&definition(-10))), precise);
+
+        assert!(parse("0.1", -2).is_err());
+        assert!(parse("\"0.125\"", -2).is_err());
+        assert!(parse("\"12345\"", 3).is_err());
+        assert!(parse("\"1.2.3\"", -2).is_err());
+        assert!(parse("\"99999999999999999999\"", 0).is_err());
+    }
+
+    /// Verifies that enum values are displayed by label, and by index without a definition or a
+    /// matching option.
+    #[test]
+    fn test_format_enum() {
+        assert_eq!(format_enum(1, Some(&definition(0))), "blue");
+        assert_eq!(format_enum(2, Some(&definition(0))), "2");
+        assert_eq!(format_enum(1, None), "1");
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::convert::TryFrom;
+
 use crate::error::CliError;
 use crate::transaction::schema_batch_builder;
 use crate::yaml_parser::{
@@ -336,6 +338,17 @@ fn parse_property_definition(property: &Mapping) -> Result<PropertyDefinition, C
         None => property_definition,
     };
 
+    property_definition = match parse_value_as_i32(property, "access_level")? {
+        Some(access_level) => {
+            property_definition.with_access_level(u32::try_from(access_level).map_err(|_| {
+                CliError::InvalidYamlError(
+                    "Value of access_level must not be negative.".to_string(),
+                )
+            })?)
+        }
+        None => property_definition,
+    };
+
     property_definition = match data_type {
         StateDataType::Number => property_definition.with_number_exponent(
             parse_value_as_i32(property, "number_exponent")?.ok_or_else(|| {
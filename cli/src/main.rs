@@ -102,6 +102,14 @@ use log::Record;
 
 use crate::error::CliError;
 
+#[cfg(any(
+    feature = "location",
+    feature = "pike",
+    feature = "product",
+    feature = "schema",
+    feature = "purchase-order",
+))]
+use actions::batch;
 #[cfg(feature = "database")]
 use actions::database;
 use actions::keygen;
@@ -116,7 +124,7 @@ use actions::schema;
 #[cfg(feature = "xsd-downloader")]
 use actions::xsd_downloader::{self, DownloadConfig};
 #[cfg(feature = "pike")]
-use actions::{agent, organization as orgs, role};
+use actions::{agent, organization as orgs, pike, role};
 
 const APP_NAME: &str = env!("CARGO_PKG_NAME");
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -788,6 +796,58 @@ fn run() -> Result<(), CliError> {
                         )
                         .after_help(AFTER_HELP_WITHOUT_KEY),
                 ),
+        )
+        .subcommand(
+            SubCommand::with_name("pike")
+                .about("Manage Pike organizations and agents in bulk")
+                .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("service_id")
+                        .long("service-id")
+                        .takes_value(true)
+                        .global(true)
+                        .help(
+                            "The ID of the service the payload should be \
+                         sent to; required if running on Splinter. Format \
+                         <circuit-id>::<service-id>",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .long("url")
+                        .takes_value(true)
+                        .global(true)
+                        .help("URL for the REST API"),
+                )
+                .subcommand(
+                    SubCommand::with_name("import")
+                        .about(
+                            "Create the organizations and agents defined in a JSON file \
+                            in a single batch list",
+                        )
+                        .arg(
+                            Arg::with_name("file")
+                                .long("file")
+                                .short("f")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Path to a JSON file of organizations and agents"),
+                        )
+                        .arg(
+                            Arg::with_name("key")
+                                .long("key")
+                                .short("k")
+                                .takes_value(true)
+                                .help("Base name or path for private signing key file"),
+                        )
+                        .arg(
+                            Arg::with_name("wait")
+                                .long("wait")
+                                .takes_value(true)
+                                .help("How long to wait for transaction to be committed"),
+                        )
+                        .after_help(AFTER_HELP_WITH_KEY),
+                ),
         );
     }
 
@@ -884,6 +944,81 @@ fn run() -> Result<(), CliError> {
         );
     }
 
+    #[cfg(any(
+        feature = "location",
+        feature = "pike",
+        feature = "product",
+        feature = "schema",
+        feature = "purchase-order"
+    ))]
+    {
+        use clap::{Arg, SubCommand};
+
+        app = app.subcommand(
+            SubCommand::with_name("batch")
+                .about("Submit batches and show the status of submitted batches")
+                .setting(clap::AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("service_id")
+                        .long("service-id")
+                        .takes_value(true)
+                        .global(true)
+                        .help(
+                            "The ID of the service the batch was submitted to; \
+                             required if running on Splinter. Format \
+                             <circuit-id>::<service-id>",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("url")
+                        .long("url")
+                        .takes_value(true)
+                        .global(true)
+                        .help("URL for the REST API"),
+                )
+                .subcommand(
+                    SubCommand::with_name("status")
+                        .about("Show the status of a batch")
+                        .arg(
+                            Arg::with_name("batch_id")
+                                .takes_value(true)
+                                .required(true)
+                                .help("ID of the batch"),
+                        )
+                        .arg(Arg::with_name("wait").long("wait").takes_value(true).help(
+                            "How long to wait, in seconds, for the batch to be \
+                                     committed or found invalid",
+                        ))
+                        .after_help(AFTER_HELP_WITHOUT_KEY),
+                )
+                .subcommand(
+                    SubCommand::with_name("submit")
+                        .about("Submit a file containing a serialized, signed batch list")
+                        .arg(
+                            Arg::with_name("file")
+                                .takes_value(true)
+                                .required(true)
+                                .help("Path to the file containing the batch list"),
+                        )
+                        .arg(Arg::with_name("await").long("await").help(
+                            "Wait for each batch to be committed or found invalid, exiting \
+                                     with an error if any batch is invalid",
+                        ))
+                        .arg(
+                            Arg::with_name("wait")
+                                .long("wait")
+                                .takes_value(true)
+                                .default_value("300")
+                                .help(
+                                    "How long to wait, in seconds, for each batch when \
+                                     --await is given",
+                                ),
+                        )
+                        .after_help(AFTER_HELP_WITHOUT_KEY),
+                ),
+        );
+    }
+
     #[cfg(feature = "product")]
     {
         use clap::{Arg, SubCommand};
@@ -1012,6 +1147,10 @@ fn run() -> Result<(), CliError> {
                                 .number_of_values(1)
                                 .help("Path to file containing a list of products"),
                         )
+                        .arg(Arg::with_name("merge").long("merge").help(
+                            "Keep the current values of properties that are not \
+                                    updated, instead of removing them",
+                        ))
                         .arg(
                             Arg::with_name("key")
                                 .long("key")
@@ -1869,6 +2008,7 @@ DETAILS:
         feature = "pike",
         feature = "product",
         feature = "schema",
+        feature = "purchase-order",
     ))]
     let client_factory = Box::new(ReqwestClientFactory::new());
 
@@ -2200,6 +2340,28 @@ DETAILS:
             }
             _ => return Err(CliError::UserError("Subcommand not recognized".into())),
         },
+        #[cfg(feature = "pike")]
+        ("pike", Some(m)) => match m.subcommand() {
+            ("import", Some(m)) => {
+                let url = value_of_url(m)?;
+                let service_id_str = value_of_service_id(m)?;
+                let service_id = service_id_str.as_deref();
+                let pike_client = client_factory.get_pike_client(url);
+                let key = value_of_key(m)?;
+                let signer = signing::load_signer(key)?;
+                let wait = value_t!(m, "wait", u64).unwrap_or(0);
+
+                info!("Submitting request to import organizations and agents...");
+                pike::do_import(
+                    pike_client,
+                    signer,
+                    wait,
+                    value_of_required(m, "file")?,
+                    service_id,
+                )?;
+            }
+            _ => return Err(CliError::UserError("Subcommand not recognized".into())),
+        },
         #[cfg(feature = "schema")]
         ("schema", Some(m)) => match m.subcommand() {
             ("create", Some(m)) => {
@@ -2258,6 +2420,56 @@ DETAILS:
             }
             _ => return Err(CliError::UserError("Subcommand not recognized".into())),
         },
+        #[cfg(any(
+            feature = "location",
+            feature = "pike",
+            feature = "product",
+            feature = "schema",
+            feature = "purchase-order",
+        ))]
+        ("batch", Some(m)) => match m.subcommand() {
+            ("status", Some(m)) => {
+                let url = value_of_url(m)?;
+                let service_id_str = value_of_service_id(m)?;
+                let service_id = service_id_str.as_deref();
+                let batch_client = client_factory.get_batch_client(url);
+                let wait = if m.is_present("wait") {
+                    Some(value_t!(m, "wait", u64).map_err(|err| {
+                        CliError::UserError(format!("Invalid value for --wait: {}", err))
+                    })?)
+                } else {
+                    None
+                };
+
+                batch::do_show_batch_status(
+                    batch_client,
+                    value_of_required(m, "batch_id")?,
+                    service_id,
+                    wait,
+                )?
+            }
+            ("submit", Some(m)) => {
+                let url = value_of_url(m)?;
+                let service_id_str = value_of_service_id(m)?;
+                let service_id = service_id_str.as_deref();
+                let batch_client = client_factory.get_batch_client(url);
+                let wait = if m.is_present("await") {
+                    Some(value_t!(m, "wait", u64).map_err(|err| {
+                        CliError::UserError(format!("Invalid value for --wait: {}", err))
+                    })?)
+                } else {
+                    None
+                };
+
+                batch::do_submit_batch_file(
+                    batch_client,
+                    value_of_required(m, "file")?,
+                    service_id,
+                    wait,
+                )?
+            }
+            _ => return Err(CliError::UserError("Subcommand not recognized".into())),
+        },
         #[cfg(feature = "database")]
         ("database", Some(m)) => match m.subcommand() {
             ("migrate", Some(m)) => database::run_migrations(
@@ -2347,8 +2559,10 @@ DETAILS:
                     m,
                 )?;
 
+                let product_id =
+                    product::normalize_product_id(value_of_required(m, "product_id")?)?;
                 let action = ProductCreateActionBuilder::new()
-                    .with_product_id(value_of_required(m, "product_id")?.into())
+                    .with_product_id(product_id)
                     .with_owner(value_of_required(m, "owner")?.into())
                     .with_product_namespace(namespace)
                     .with_properties(properties)
@@ -2374,12 +2588,25 @@ DETAILS:
                 let signer = signing::load_signer(key)?;
                 let wait = value_t!(m, "wait", u64).unwrap_or(0);
 
-                let actions = product::update_product_payloads_from_file(
+                let mut actions = product::update_product_payloads_from_file(
                     values_of_required(m, "file")?.collect(),
                     schema_client,
                     service_id,
                 )?;
 
+                if m.is_present("merge") {
+                    actions = actions
+                        .into_iter()
+                        .map(|action| {
+                            product::merge_with_current_properties(
+                                &*product_client,
+                                action,
+                                service_id,
+                            )
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+
                 info!("Submitting request to update product...");
                 product::do_update_products(product_client, signer, wait, actions, service_id)?;
             }
@@ -2410,13 +2637,23 @@ DETAILS:
                     m,
                 )?;
 
-                let action = ProductUpdateActionBuilder::new()
-                    .with_product_id(value_of_required(m, "product_id")?.into())
+                let product_id =
+                    product::normalize_product_id(value_of_required(m, "product_id")?)?;
+                let mut action = ProductUpdateActionBuilder::new()
+                    .with_product_id(product_id)
                     .with_product_namespace(namespace)
                     .with_properties(properties)
                     .build()
                     .map_err(|err| CliError::UserError(format!("{}", err)))?;
 
+                if m.is_present("merge") {
+                    action = product::merge_with_current_properties(
+                        &*product_client,
+                        action,
+                        service_id,
+                    )?;
+                }
+
                 info!("Submitting request to update product...");
                 product::do_update_products(
                     product_client,
@@ -2445,8 +2682,10 @@ DETAILS:
                     }
                 };
 
+                let product_id =
+                    product::normalize_product_id(value_of_required(m, "product_id")?)?;
                 let action = ProductDeleteActionBuilder::new()
-                    .with_product_id(value_of_required(m, "product_id")?.into())
+                    .with_product_id(product_id)
                     .with_product_namespace(namespace)
                     .build()
                     .map_err(|err| CliError::UserError(format!("{}", err)))?;
@@ -2465,10 +2704,12 @@ DETAILS:
                 let url = value_of_url(m)?;
                 let service_id_str = value_of_service_id(m)?;
                 let service_id = service_id_str.as_deref();
-                let product_client = client_factory.get_product_client(url);
+                let product_client = client_factory.get_product_client(url.clone());
+                let schema_client = client_factory.get_schema_client(url);
                 product::do_show_products(
                     product_client,
-                    value_of_required(m, "product_id")?.into(),
+                    schema_client,
+                    product::normalize_product_id(value_of_required(m, "product_id")?)?,
                     service_id,
                 )?
             }
@@ -2640,9 +2881,11 @@ DETAILS:
                 let url = value_of_url(m)?;
                 let service_id_str = value_of_service_id(m)?;
                 let service_id = service_id_str.as_deref();
-                let location_client = client_factory.get_location_client(url);
+                let location_client = client_factory.get_location_client(url.clone());
+                let schema_client = client_factory.get_schema_client(url);
                 location::do_show_location(
                     location_client,
+                    schema_client,
                     value_of_required(m, "location_id")?,
                     service_id,
                 )?
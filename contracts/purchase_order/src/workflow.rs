@@ -492,3 +492,35 @@ impl fmt::Display for WorkflowConstraint {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use grid_sdk::workflow::WorkflowDefinitions;
+
+    /// Verify that the declarative definition of each built-in workflow builds the same workflow
+    /// that this smart contract enforces, so that the two cannot drift apart.
+    #[test]
+    fn built_in_definitions_match_workflows() {
+        let definitions = WorkflowDefinitions::built_in();
+
+        for name in &[POWorkflow::SystemOfRecord, POWorkflow::Collaborative] {
+            let name = name.to_string();
+            assert!(
+                definitions.get(&name).is_some(),
+                "{} has no definition",
+                name
+            );
+            assert_eq!(definitions.workflow(&name), get_workflow(&name), "{}", name);
+        }
+
+        for definition in &definitions.workflows {
+            assert!(
+                get_workflow(&definition.name).is_some(),
+                "{} is not a workflow of this smart contract",
+                definition.name
+            );
+        }
+    }
+}
@@ -58,6 +58,7 @@ impl Error for DaemonError {
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConfigurationError {
     MissingValue(String),
+    InvalidValue(String, String),
 }
 
 impl Error for ConfigurationError {}
@@ -68,6 +69,13 @@ impl fmt::Display for ConfigurationError {
             ConfigurationError::MissingValue(config_field_name) => {
                 write!(f, "Missing configuration for {}", config_field_name)
             }
+            ConfigurationError::InvalidValue(config_field_name, reason) => {
+                write!(
+                    f,
+                    "Invalid configuration for {}: {}",
+                    config_field_name, reason
+                )
+            }
         }
     }
 }
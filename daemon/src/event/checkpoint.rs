@@ -0,0 +1,180 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+
+use grid_sdk::commits::store::Commit;
+
+use super::CommitEvent;
+
+/// The number of recently processed commits kept to resume or resync event processing from.
+pub const MAX_CHECKPOINTS: usize = 16;
+
+/// A commit that has already been processed, from which event processing can resume
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The identifier of the commit
+    pub id: String,
+    /// The height of the commit, if the source orders its commits by height
+    pub height: Option<u64>,
+}
+
+impl From<Commit> for Checkpoint {
+    fn from(commit: Commit) -> Self {
+        Self {
+            id: commit.commit_id,
+            height: u64::try_from(commit.commit_num).ok(),
+        }
+    }
+}
+
+impl From<&CommitEvent> for Checkpoint {
+    fn from(event: &CommitEvent) -> Self {
+        Self {
+            id: event.id.clone(),
+            height: event.height,
+        }
+    }
+}
+
+/// The most recently processed commits from a source, newest first.
+pub(super) struct Checkpoints {
+    recent: VecDeque<Checkpoint>,
+}
+
+impl Checkpoints {
+    /// Creates the checkpoints from commits that were processed before a restart, which must be
+    /// ordered newest first.
+    pub fn new(checkpoints: Vec<Checkpoint>) -> Self {
+        let mut recent = VecDeque::from(checkpoints);
+        recent.truncate(MAX_CHECKPOINTS);
+        Self { recent }
+    }
+
+    /// Returns the most recently processed commit
+    pub fn latest(&self) -> Option<&Checkpoint> {
+        self.recent.front()
+    }
+
+    /// Returns the identifiers of the checkpoints, newest first
+    pub fn ids(&self) -> Vec<&str> {
+        self.recent
+            .iter()
+            .map(|checkpoint| checkpoint.id.as_str())
+            .collect()
+    }
+
+    /// Returns whether the event can be applied on top of the latest checkpoint.
+    ///
+    /// An event beyond the latest checkpoint must be at the next height and, if the source
+    /// reports it, name the latest checkpoint as its parent; otherwise commits have been missed,
+    /// or the source's chain no longer contains the latest checkpoint. Events at or below the
+    /// latest checkpoint are duplicates or forks, which the event handlers resolve, and events
+    /// from sources that do not report heights are always accepted.
+    pub fn follows(&self, event: &CommitEvent) -> bool {
+        match (self.latest(), event.height) {
+            (
+                Some(Checkpoint {
+                    id,
+                    height: Some(latest_height),
+                }),
+                Some(height),
+            ) if height > *latest_height => {
+                height == latest_height + 1
+                    && event
+                        .parent_id
+                        .as_ref()
+                        .map(|parent_id| parent_id == id)
+                        .unwrap_or(true)
+            }
+            _ => true,
+        }
+    }
+
+    /// Records that the event has been processed. Any checkpoints at or above its height are
+    /// discarded, as the event replaces them.
+    pub fn record(&mut self, event: &CommitEvent) {
+        if let Some(height) = event.height {
+            self.recent.retain(|checkpoint| {
+                checkpoint
+                    .height
+                    .map(|checkpoint_height| checkpoint_height < height)
+                    .unwrap_or(true)
+            });
+        }
+
+        self.recent.push_front(Checkpoint::from(event));
+        self.recent.truncate(MAX_CHECKPOINTS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(id: &str, height: u64, parent_id: &str) -> CommitEvent {
+        CommitEvent {
+            service_id: None,
+            id: id.into(),
+            height: Some(height),
+            parent_id: Some(parent_id.into()),
+            state_changes: vec![],
+            signers: vec![],
+        }
+    }
+
+    fn checkpoint(id: &str, height: u64) -> Checkpoint {
+        Checkpoint {
+            id: id.into(),
+            height: Some(height),
+        }
+    }
+
+    /// Verify that only an event at the next height whose parent is the latest checkpoint
+    /// follows it, and that duplicates and forks are left to the event handlers.
+    #[test]
+    fn test_follows() {
+        let checkpoints = Checkpoints::new(vec![checkpoint("b", 2), checkpoint("a", 1)]);
+
+        assert!(checkpoints.follows(&event("c", 3, "b")));
+        assert!(!checkpoints.follows(&event("c", 3, "x")));
+        assert!(!checkpoints.follows(&event("d", 4, "c")));
+        assert!(checkpoints.follows(&event("b", 2, "a")));
+        assert!(checkpoints.follows(&event("x", 2, "a")));
+
+        assert!(Checkpoints::new(vec![]).follows(&event("d", 4, "c")));
+    }
+
+    /// Verify that recording an event makes it the latest checkpoint and discards the
+    /// checkpoints it replaces.
+    #[test]
+    fn test_record() {
+        let mut checkpoints = Checkpoints::new(vec![checkpoint("b", 2), checkpoint("a", 1)]);
+
+        checkpoints.record(&event("c", 3, "b"));
+        assert_eq!(checkpoints.ids(), vec!["c", "b", "a"]);
+
+        checkpoints.record(&event("x", 2, "a"));
+        assert_eq!(checkpoints.ids(), vec!["x", "a"]);
+
+        for height in 3..(MAX_CHECKPOINTS as u64 + 3) {
+            checkpoints.record(&event(&height.to_string(), height, ""));
+        }
+        assert_eq!(checkpoints.ids().len(), MAX_CHECKPOINTS);
+    }
+}
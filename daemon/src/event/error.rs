@@ -134,6 +134,8 @@ impl From<diesel::result::Error> for EventError {
 pub enum EventIoError {
     ConnectionError(String),
     InvalidMessage(String),
+    /// The source does not know any of the checkpoints it was asked to resume from
+    UnknownCheckpoint(String),
 }
 
 impl Error for EventIoError {}
@@ -145,6 +147,7 @@ impl fmt::Display for EventIoError {
                 write!(f, "event connection encountered an error: {}", err)
             }
             Self::InvalidMessage(err) => write!(f, "connection received invalid message: {}", err),
+            Self::UnknownCheckpoint(err) => write!(f, "unknown checkpoint: {}", err),
         }
     }
 }
@@ -15,8 +15,11 @@
  * -----------------------------------------------------------------------------
  */
 
+mod checkpoint;
 pub mod db_handler;
 mod error;
+#[cfg(feature = "webhooks")]
+pub mod webhook_handler;
 
 use std::cell::RefCell;
 use std::thread;
@@ -37,8 +40,13 @@ cfg_if! {
 #[cfg(feature = "track-and-trace")]
 use grid_sdk::track_and_trace::addressing::TRACK_AND_TRACE_NAMESPACE;
 
-use grid_sdk::commits::store::{CommitEvent as DbCommitEvent, StateChange as DbStateChange};
+use grid_sdk::commits::store::{
+    CommitEvent as DbCommitEvent, CommitSigner, StateChange as DbStateChange,
+};
 
+use self::checkpoint::Checkpoints;
+
+pub use self::checkpoint::{Checkpoint, MAX_CHECKPOINTS};
 pub use self::error::{EventError, EventIoError, EventProcessorError};
 
 const ALL_GRID_NAMESPACES: &[&str] = &[
@@ -64,8 +72,12 @@ pub struct CommitEvent {
     /// May be used to provide ordering of commits from the source. If `None`, ordering is not
     /// explicitly provided, so it must be inferred from the order in which events are received.
     pub height: Option<u64>,
+    /// The identifier of the commit this one was applied on top of, if the source provides it
+    pub parent_id: Option<String>,
     /// All state changes that are included in the commit
     pub state_changes: Vec<StateChange>,
+    /// The agents that signed the transactions in the commit, if the source provides them
+    pub signers: Vec<CommitSigner>,
 }
 
 impl std::fmt::Display for CommitEvent {
@@ -90,11 +102,13 @@ impl From<DbCommitEvent> for CommitEvent {
             service_id: event.service_id,
             id: event.id,
             height: event.height,
+            parent_id: None,
             state_changes: event
                 .state_changes
                 .into_iter()
                 .map(StateChange::from)
                 .collect(),
+            signers: event.signers,
         }
     }
 }
@@ -111,6 +125,7 @@ impl From<&CommitEvent> for DbCommitEvent {
                 .into_iter()
                 .map(DbStateChange::from)
                 .collect(),
+            signers: event.signers.clone(),
         }
     }
 }
@@ -123,23 +138,32 @@ pub enum StateChange {
 }
 
 impl StateChange {
-    pub fn key_has_prefix(&self, prefix: &str) -> bool {
-        let key = match self {
+    pub fn key(&self) -> &str {
+        match self {
             Self::Set { key, .. } => key,
             Self::Delete { key, .. } => key,
-        };
-        key.get(0..prefix.len())
+        }
+    }
+
+    pub fn key_has_prefix(&self, prefix: &str) -> bool {
+        self.key()
+            .get(0..prefix.len())
             .map(|key_prefix| key_prefix == prefix)
             .unwrap_or(false)
     }
 
     pub fn is_grid_state_change(&self) -> bool {
-        ALL_GRID_NAMESPACES
-            .iter()
-            .any(|namespace| self.key_has_prefix(namespace))
+        is_grid_address(self.key())
     }
 }
 
+/// Returns whether the state address is in one of the Grid namespaces
+pub fn is_grid_address(address: &str) -> bool {
+    ALL_GRID_NAMESPACES
+        .iter()
+        .any(|namespace| address.starts_with(namespace))
+}
+
 impl From<DbStateChange> for StateChange {
     fn from(event: DbStateChange) -> StateChange {
         match event {
@@ -191,7 +215,7 @@ pub trait EventConnection: Send {
     fn subscribe(
         &mut self,
         namespaces: &[&str],
-        last_commit_id: Option<&str>,
+        checkpoints: &[&str],
     ) -> Result<Self::Unsubscriber, EventIoError>;
 
     fn close(self) -> Result<(), EventIoError>;
@@ -211,9 +235,9 @@ impl<EC: EventConnection> EventConnection for Box<EC> {
     fn subscribe(
         &mut self,
         namespaces: &[&str],
-        last_commit_id: Option<&str>,
+        checkpoints: &[&str],
     ) -> Result<Self::Unsubscriber, EventIoError> {
-        (**self).subscribe(namespaces, last_commit_id)
+        (**self).subscribe(namespaces, checkpoints)
     }
 
     fn close(self) -> Result<(), EventIoError> {
@@ -243,21 +267,47 @@ pub struct EventProcessor<Conn: EventConnection> {
 }
 
 impl<Conn: EventConnection + 'static> EventProcessor<Conn> {
+    /// Subscribes to the connection and starts handling its events.
+    ///
+    /// Events resume after the newest of `checkpoints` that the source knows of, or from the
+    /// start of its history if it knows none of them. If an event is received that does not
+    /// follow the last commit processed, the processor resubscribes from its checkpoints so that
+    /// the missed commits are replayed before any newer ones are applied.
     pub fn start(
         mut connection: Conn,
-        last_known_commit_id: Option<&str>,
+        checkpoints: Vec<Checkpoint>,
         event_handlers: Vec<Box<dyn EventHandler>>,
     ) -> Result<Self, EventProcessorError> {
-        let unsubscriber = connection
-            .subscribe(ALL_GRID_NAMESPACES, last_known_commit_id)
-            .map_err(|err| EventProcessorError(format!("Unable to unsubscribe: {}", err)))?;
+        let mut checkpoints = Checkpoints::new(checkpoints);
+        let unsubscriber = subscribe(&mut connection, &checkpoints)
+            .map_err(|err| EventProcessorError(format!("Unable to subscribe: {}", err)))?;
 
         let join_handle = thread::Builder::new()
             .name(format!("EventProcessor[{}]", connection.name()))
             .spawn(move || {
                 loop {
                     match connection.recv() {
-                        Ok(commit_event) => handle_message(commit_event, &event_handlers),
+                        Ok(commit_event) if !checkpoints.follows(&commit_event) => {
+                            warn!(
+                                "Commit {} does not follow checkpoint {}; resyncing from {}",
+                                commit_event,
+                                checkpoints
+                                    .latest()
+                                    .map(|checkpoint| checkpoint.id.as_str())
+                                    .unwrap_or_default(),
+                                connection.name()
+                            );
+                            // Resubscribing reuses the connection, so the unsubscriber held by
+                            // the shutdown handle still ends the new subscription.
+                            if let Err(err) = subscribe(&mut connection, &checkpoints) {
+                                error!("Failed to resync events; aborting: {}", err);
+                                break;
+                            }
+                        }
+                        Ok(commit_event) => {
+                            handle_message(&commit_event, &event_handlers);
+                            checkpoints.record(&commit_event);
+                        }
                         Err(EventIoError::InvalidMessage(msg)) => {
                             warn!("{}; ignoring...", msg);
                         }
@@ -304,9 +354,24 @@ impl<Conn: EventConnection + 'static> EventProcessor<Conn> {
     }
 }
 
-fn handle_message(event: CommitEvent, event_handlers: &[Box<dyn EventHandler>]) {
+/// Subscribes from the newest checkpoint known to the source, falling back to the start of the
+/// source's history if it knows none of them.
+fn subscribe<Conn: EventConnection>(
+    connection: &mut Conn,
+    checkpoints: &Checkpoints,
+) -> Result<Conn::Unsubscriber, EventIoError> {
+    match connection.subscribe(ALL_GRID_NAMESPACES, &checkpoints.ids()) {
+        Err(EventIoError::UnknownCheckpoint(msg)) => {
+            warn!("{}; resyncing from the start of {}", msg, connection.name());
+            connection.subscribe(ALL_GRID_NAMESPACES, &[])
+        }
+        result => result,
+    }
+}
+
+fn handle_message(event: &CommitEvent, event_handlers: &[Box<dyn EventHandler>]) {
     for handler in event_handlers {
-        if let Err(err) = handler.handle_event(&event) {
+        if let Err(err) = handler.handle_event(event) {
             error!("An error occurred while handling events: {}", err);
         }
     }
@@ -0,0 +1,76 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+use std::fs;
+use std::sync::Arc;
+
+use grid_sdk::backend::redact_url;
+use grid_sdk::commits::store::CommitEvent as DbCommitEvent;
+use grid_sdk::events::WebhookSender;
+
+use crate::config::WebhookConfig;
+use crate::error::DaemonError;
+
+use super::{CommitEvent, EventError, EventHandler};
+
+/// Sends the events of each commit to the configured webhooks.
+pub struct WebhookEventHandler {
+    sender: Arc<WebhookSender>,
+}
+
+impl WebhookEventHandler {
+    /// Registers the configured webhooks, reading each one's secret from its file. Surrounding
+    /// whitespace, such as a trailing newline, is not part of the secret.
+    pub fn new(webhooks: &[WebhookConfig]) -> Result<Self, DaemonError> {
+        let sender = WebhookSender::new();
+        for webhook in webhooks {
+            let secret = fs::read_to_string(&webhook.secret_file).map_err(|err| {
+                DaemonError::with_message(&format!(
+                    "Unable to read the secret of webhook {} from {}: {}",
+                    redact_url(&webhook.url),
+                    webhook.secret_file,
+                    err
+                ))
+            })?;
+            sender
+                .register(&webhook.url, secret.trim().as_bytes().to_vec())
+                .map_err(|err| DaemonError::from_source(Box::new(err)))?;
+            info!(
+                "Sending commit events to webhook {}",
+                redact_url(&webhook.url)
+            );
+        }
+
+        Ok(Self {
+            sender: Arc::new(sender),
+        })
+    }
+}
+
+impl EventHandler for WebhookEventHandler {
+    fn handle_event(&self, event: &CommitEvent) -> Result<(), EventError> {
+        self.sender
+            .publish(&DbCommitEvent::from(event))
+            .map_err(|err| EventError(format!("Unable to publish events to webhooks: {}", err)))
+    }
+
+    fn cloned_box(&self) -> Box<dyn EventHandler> {
+        Box::new(Self {
+            sender: self.sender.clone(),
+        })
+    }
+}
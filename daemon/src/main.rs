@@ -70,6 +70,15 @@ fn run() -> Result<(), DaemonError> {
         (@arg database_url: --("database-url") +takes_value
         "specifies the database URL to connect to.")
         (@arg bind: -b --bind +takes_value "connection endpoint for rest API")
+        (@arg rest_api_drain_timeout: --("rest-api-drain-timeout") +takes_value
+            "seconds in-flight batch submissions are given to complete when the rest API \
+            shuts down (default 30)")
+        (@arg rest_api_field_naming: --("rest-api-field-naming") +takes_value
+            "naming convention of the fields in rest API responses: snake_case or camelCase \
+            (default snake_case)")
+        (@arg rest_api_trust_clearance_header: --("rest-api-trust-clearance-header")
+            "read callers' clearance from the GridClearance header; only use this behind a \
+            proxy that authenticates callers and sets or strips the header")
         (@arg admin_key_dir: --("admin-key-dir") +takes_value
             "directory containing the Scabbard admin key files"));
 
@@ -85,6 +94,23 @@ fn run() -> Result<(), DaemonError> {
         );
     }
 
+    #[cfg(feature = "webhooks")]
+    {
+        use clap::Arg;
+        app = app.arg(
+            Arg::with_name("webhook")
+                .long("webhook")
+                .takes_value(true)
+                .number_of_values(2)
+                .multiple(true)
+                .value_names(&["URL", "SECRET_FILE"])
+                .help(
+                    "Webhook to send the events of every commit to, with the file holding the \
+                    secret its payloads are signed with; may be given more than once",
+                ),
+        );
+    }
+
     let matches = app.get_matches();
 
     let log_level = match matches.occurrences_of("verbose") {
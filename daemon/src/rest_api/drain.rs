@@ -0,0 +1,144 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tracking of in-flight batch submissions, so that shutdown can wait for them to complete.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Counts the batch submissions currently being handled by the REST API.
+#[derive(Clone, Default)]
+pub struct InFlightSubmissions {
+    in_flight: Arc<AtomicUsize>,
+    draining: Arc<AtomicBool>,
+}
+
+/// Marks a submission as in flight until it is dropped.
+pub struct InFlightSubmission {
+    in_flight: Arc<AtomicUsize>,
+}
+
+/// The outcome of draining in-flight submissions.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DrainSummary {
+    /// The number of submissions that completed during the drain period
+    pub drained: usize,
+    /// The number of submissions still in flight when the drain period expired
+    pub aborted: usize,
+}
+
+impl InFlightSubmissions {
+    /// Marks a new submission as in flight, or returns `None` if the submissions are being
+    /// drained and no new submissions are accepted.
+    pub fn start(&self) -> Option<InFlightSubmission> {
+        if self.draining.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+
+        Some(InFlightSubmission {
+            in_flight: self.in_flight.clone(),
+        })
+    }
+
+    /// Returns the number of submissions in flight.
+    pub fn count(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Stops accepting new submissions and waits up to `timeout` for the submissions in flight
+    /// to complete.
+    pub fn drain(&self, timeout: Duration) -> DrainSummary {
+        self.draining.store(true, Ordering::SeqCst);
+
+        let in_flight = self.count();
+        let deadline = Instant::now() + timeout;
+        while self.count() > 0 && Instant::now() < deadline {
+            thread::sleep(DRAIN_POLL_INTERVAL);
+        }
+
+        let aborted = self.count().min(in_flight);
+
+        DrainSummary {
+            drained: in_flight - aborted,
+            aborted,
+        }
+    }
+}
+
+impl Drop for InFlightSubmission {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that submissions completing within the drain period are counted as drained, that
+    /// those still in flight are counted as aborted, and that no new submissions are accepted
+    /// once draining has started.
+    #[test]
+    fn drain_counts_drained_and_aborted_submissions() {
+        let submissions = InFlightSubmissions::default();
+
+        let completing = submissions.start().expect("Submission not accepted");
+        let hanging = submissions.start().expect("Submission not accepted");
+        assert_eq!(submissions.count(), 2);
+
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            drop(completing);
+        });
+
+        let summary = submissions.drain(Duration::from_millis(500));
+        handle.join().expect("Unable to join thread");
+
+        assert_eq!(
+            summary,
+            DrainSummary {
+                drained: 1,
+                aborted: 1
+            }
+        );
+        assert!(submissions.start().is_none());
+
+        drop(hanging);
+        assert_eq!(submissions.count(), 0);
+    }
+
+    /// Verifies that draining with no submissions in flight returns immediately.
+    #[test]
+    fn drain_without_submissions_returns_immediately() {
+        let submissions = InFlightSubmissions::default();
+
+        let start = Instant::now();
+        let summary = submissions.drain(Duration::from_secs(10));
+
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            summary,
+            DrainSummary {
+                drained: 0,
+                aborted: 0
+            }
+        );
+    }
+}
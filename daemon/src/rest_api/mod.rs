@@ -13,32 +13,69 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod drain;
 pub mod error;
 
 use std::sync::mpsc;
 use std::thread;
+use std::time::Duration;
 
 pub use crate::rest_api::error::RestApiServerError;
 
 use actix_web::{
-    dev::ServerHandle,
+    dev::{ServerHandle, Service},
+    error::ErrorServiceUnavailable,
     middleware,
     rt::System,
     web::{self, Data},
     App, HttpServer, Result,
 };
 use futures::executor::block_on;
+use futures::future::{self, FutureExt};
 #[cfg(feature = "integration")]
 use grid_sdk::rest_api::actix_web_4::KeyState;
-use grid_sdk::rest_api::actix_web_4::{routes, BackendState, Endpoint, StoreState};
+#[cfg(feature = "metrics")]
+use grid_sdk::rest_api::actix_web_4::RequestTimer;
+use grid_sdk::rest_api::actix_web_4::{
+    apply_field_naming, routes, BackendState, ClearancePolicy, Endpoint, FieldNaming, StoreState,
+};
+
+use self::drain::InFlightSubmissions;
 
 pub struct RestApiShutdownHandle {
     server_handle: ServerHandle,
+    in_flight_submissions: InFlightSubmissions,
+    drain_timeout: Duration,
 }
 
 impl RestApiShutdownHandle {
+    /// Stops the REST API.
+    ///
+    /// New connections and batch submissions are refused immediately. Submissions already in
+    /// flight are given up to the drain timeout to complete before the server is forced to stop.
     pub fn shutdown(&self) {
-        block_on(self.server_handle.stop(true));
+        block_on(self.server_handle.pause());
+
+        let in_flight = self.in_flight_submissions.count();
+        if in_flight > 0 {
+            info!(
+                "Waiting up to {:?} for {} in-flight batch submissions to complete",
+                self.drain_timeout, in_flight
+            );
+        }
+
+        let summary = self.in_flight_submissions.drain(self.drain_timeout);
+        if summary.aborted > 0 {
+            warn!(
+                "Drained {} in-flight batch submissions; aborting {} that did not complete \
+                within {:?}",
+                summary.drained, summary.aborted, self.drain_timeout
+            );
+        } else {
+            info!("Drained {} in-flight batch submissions", summary.drained);
+        }
+
+        block_on(self.server_handle.stop(summary.aborted == 0));
     }
 }
 
@@ -48,6 +85,9 @@ pub fn run(
     backend_state: BackendState,
     #[cfg(feature = "integration")] key_state: KeyState,
     endpoint: Endpoint,
+    drain_timeout: Duration,
+    field_naming: FieldNaming,
+    clearance_policy: ClearancePolicy,
 ) -> Result<
     (
         RestApiShutdownHandle,
@@ -56,21 +96,59 @@ pub fn run(
     RestApiServerError,
 > {
     let bind_url = bind_url.to_owned();
+    let in_flight_submissions = InFlightSubmissions::default();
+    let server_submissions = in_flight_submissions.clone();
     let (tx, rx) = mpsc::channel();
     let join_handle = thread::Builder::new()
         .name("GridRestApi".into())
         .spawn(move || {
             let sys = System::new();
             let server = HttpServer::new(move || {
+                let submissions = server_submissions.clone();
+
                 #[allow(clippy::let_and_return)]
                 #[allow(unused_mut)]
                 let mut app = App::new()
                     .wrap(middleware::Logger::default())
+                    .wrap_fn(move |req, srv| {
+                        let res = srv.call(req);
+                        async move { apply_field_naming(field_naming, res.await?).await }
+                    })
+                    .wrap_fn(|req, srv| {
+                        #[cfg(feature = "metrics")]
+                        let timer = RequestTimer::start(&req);
+                        let res = srv.call(req);
+                        async move {
+                            let res = res.await;
+                            #[cfg(feature = "metrics")]
+                            timer.finish(&res);
+                            res
+                        }
+                    })
                     .app_data(Data::new(store_state.clone()))
                     .app_data(Data::new(backend_state.clone()))
                     .app_data(endpoint.clone())
+                    .app_data(clearance_policy)
                     .service(
-                        web::resource("/batches").route(web::post().to(routes::submit_batches)),
+                        web::resource("/batches")
+                            .wrap_fn(move |req, srv| match submissions.start() {
+                                Some(submission) => srv
+                                    .call(req)
+                                    .map(move |res| {
+                                        drop(submission);
+                                        res
+                                    })
+                                    .boxed_local(),
+                                None => future::err(ErrorServiceUnavailable(
+                                    "The REST API is shutting down",
+                                ))
+                                .boxed_local(),
+                            })
+                            .route(web::post().to(routes::submit_batches)),
+                    )
+                    .route(
+                        "/healthz/backend",
+                        web::get().to(routes::get_backend_health),
                     )
                     .service(
                         web::resource("/batch_statuses")
@@ -78,6 +156,11 @@ pub fn run(
                             .route(web::get().to(routes::get_batch_statuses)),
                     );
 
+                #[cfg(feature = "metrics")]
+                {
+                    app = app.route("/metrics", web::get().to(routes::get_metrics));
+                }
+
                 #[cfg(feature = "pike")]
                 {
                     app = app
@@ -99,14 +182,31 @@ pub fn run(
                 {
                     app = app
                         .route("/location", web::get().to(routes::list_locations))
-                        .route("/location/{id}", web::get().to(routes::get_location));
+                        .route("/location/{id}", web::get().to(routes::get_location))
+                        .route(
+                            "/location/{id}/attributes",
+                            web::get().to(routes::get_location_attributes_as_of),
+                        );
                 }
 
                 #[cfg(feature = "product")]
                 {
                     app = app
                         .route("/product", web::get().to(routes::list_products))
-                        .route("/product/{id}", web::get().to(routes::get_product));
+                        .route("/products/export", web::get().to(routes::export_products))
+                        .route("/product/{id}", web::get().to(routes::get_product))
+                        .route(
+                            "/product/{id}/recall_status",
+                            web::put().to(routes::set_product_recall_status),
+                        )
+                        .route(
+                            "/product/{id}/components",
+                            web::get().to(routes::get_bundle_components),
+                        )
+                        .route(
+                            "/product/{id}/bundles",
+                            web::get().to(routes::list_bundles_containing),
+                        );
                 }
 
                 #[cfg(feature = "schema")]
@@ -121,6 +221,10 @@ pub fn run(
                     app = app
                         .route("/record", web::get().to(routes::list_records))
                         .route("/record/{record_id}", web::get().to(routes::get_record))
+                        .route(
+                            "/record/{record_id}/custody",
+                            web::get().to(routes::get_record_custody_chain),
+                        )
                         .route(
                             "record/{record_id}/property/{property_name}",
                             web::get().to(routes::get_record_property_name),
@@ -173,6 +277,7 @@ pub fn run(
                 error!("Unable to bind !");
                 RestApiServerError::StartUpError(format!("Unable to bind to URL: {err}"))
             })?
+            .shutdown_timeout(drain_timeout.as_secs())
             .system_exit()
             .run();
             let handle = server.handle();
@@ -194,7 +299,14 @@ pub fn run(
         RestApiServerError::StartUpError(format!("Unable to receive Server Addr: {}", err))
     })?;
 
-    Ok((RestApiShutdownHandle { server_handle }, join_handle))
+    Ok((
+        RestApiShutdownHandle {
+            server_handle,
+            in_flight_submissions,
+            drain_timeout,
+        },
+        join_handle,
+    ))
 }
 
 #[cfg(all(test, feature = "stable"))]
@@ -215,9 +327,10 @@ mod test {
     use grid_sdk::backend::{
         sawtooth::{process_batch_status_response, process_validator_response, query_validator},
         BackendClient, BatchStatus, BatchStatusLink, BatchStatusResponse, BatchStatuses,
-        SubmitBatches,
+        BatchSubmission, SubmitBatches,
     };
     use grid_sdk::migrations::run_sqlite_migrations;
+    use grid_sdk::rest_api::resources::batches::v1::BackendHealthReport;
     #[cfg(feature = "track-and-trace")]
     use grid_sdk::rest_api::resources::track_and_trace::v1::*;
     use grid_sdk::rest_api::resources::{
@@ -233,9 +346,12 @@ mod test {
         pike::store::{
             Agent, AgentBuilder, DieselPikeStore, Organization, OrganizationBuilder, PikeStore,
         },
-        product::store::{
-            DieselProductStore, Product, ProductBuilder, ProductStore, PropertyValue,
-            PropertyValueBuilder,
+        product::{
+            recall::{RecallStatus, RECALL_MANAGER_ALIAS},
+            store::{
+                DieselProductStore, Product, ProductBuilder, ProductStore, PropertyValue,
+                PropertyValueBuilder,
+            },
         },
         schema::store::{DieselSchemaStore, PropertyDefinition, Schema, SchemaStore},
     };
@@ -257,6 +373,7 @@ mod test {
         ClientBatchStatusResponse_Status, ClientBatchStatus_Status, ClientBatchSubmitRequest,
         ClientBatchSubmitResponse, ClientBatchSubmitResponse_Status,
     };
+    use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
     use sawtooth_sdk::messages::validator::{Message, Message_MessageType};
     use sawtooth_sdk::messaging::stream::{MessageFuture, MessageSender, SendError};
 
@@ -279,6 +396,8 @@ mod test {
     static BATCH_ID_2: &str = "batch_2";
     static BATCH_ID_3: &str = "batch_3";
 
+    static TRANSACTION_ID_1: &str = "transaction_1";
+
     static TEST_SERVICE_ID: &str = "test_service";
 
     #[derive(Clone)]
@@ -324,7 +443,7 @@ mod test {
         fn submit_batches(
             &self,
             msg: SubmitBatches,
-        ) -> Pin<Box<dyn Future<Output = Result<BatchStatusLink, BackendClientError>> + Send>>
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
         {
             let mut client_submit_request = ClientBatchSubmitRequest::new();
             client_submit_request.set_batches(protobuf::RepeatedField::from_vec(
@@ -351,7 +470,7 @@ mod test {
                         let mut response_url = msg.response_url.clone();
                         response_url.set_query(Some(&format!("id={}", batch_query)));
 
-                        Ok(BatchStatusLink {
+                        Ok(BatchSubmission::Accepted {
                             link: response_url.to_string(),
                         })
                     }
@@ -371,7 +490,7 @@ mod test {
             match msg.wait {
                 Some(wait_time) => {
                     batch_status_request.set_wait(true);
-                    batch_status_request.set_timeout(wait_time);
+                    batch_status_request.set_timeout(wait_time.as_secs_u32());
                 }
                 None => {
                     batch_status_request.set_wait(false);
@@ -485,6 +604,10 @@ mod test {
                     .name("get_batch_statuses")
                     .route(web::get().to(routes::get_batch_statuses)),
             )
+            .route(
+                "/healthz/backend",
+                web::get().to(routes::get_backend_health),
+            )
             .route("/agent", web::get().to(routes::list_agents))
             .route("/agent/{public_key}", web::get().to(routes::get_agent))
             .route("/organization", web::get().to(routes::list_organizations))
@@ -494,7 +617,12 @@ mod test {
             )
             .route("/location", web::get().to(routes::list_locations))
             .route("/location/{id}", web::get().to(routes::get_location))
+            .route(
+                "/location/{id}/attributes",
+                web::get().to(routes::get_location_attributes_as_of),
+            )
             .route("/product", web::get().to(routes::list_products))
+            .route("/products/export", web::get().to(routes::export_products))
             .route("/product/{id}", web::get().to(routes::get_product))
             .route("/schema", web::get().to(routes::list_schemas))
             .route("/schema/{name}", web::get().to(routes::get_schema));
@@ -504,6 +632,10 @@ mod test {
             app = app
                 .route("/record", web::get().to(routes::list_records))
                 .route("/record/{record_id}", web::get().to(routes::get_record))
+                .route(
+                    "/record/{record_id}/custody",
+                    web::get().to(routes::get_record_custody_chain),
+                )
                 .route(
                     "record/{record_id}/property/{property_name}",
                     web::get().to(routes::get_record_property_name),
@@ -752,6 +884,56 @@ mod test {
             .contains(&format!("/batch_statuses?id={}", BATCH_ID_1)));
     }
 
+    /// Verifies a POST /batches with wait=0 submits the batches and returns the status link with
+    /// the batches reported as pending, without waiting for them.
+    #[actix_web::test]
+    async fn test_post_batches_wait_zero() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchSubmitResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/batches?wait=0")
+            .set_payload(get_batch_list())
+            .to_request();
+
+        let response = test::call_service(&srv, req).await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+
+        let deserialized: BatchStatusResponse = test::read_body_json(response).await;
+
+        assert!(deserialized
+            .link
+            .contains(&format!("/batch_statuses?id={}", BATCH_ID_1)));
+        assert_eq!(deserialized.data.len(), 1);
+        assert_eq!(deserialized.data[0].id, BATCH_ID_1);
+        assert_eq!(deserialized.data[0].status, "PENDING");
+    }
+
+    /// Verifies a POST /batches with an invalid wait is rejected with a BadRequest status.
+    #[actix_web::test]
+    async fn test_post_batches_wait_error() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchSubmitResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::post()
+            .uri("/batches?wait=soon")
+            .set_payload(get_batch_list())
+            .to_request();
+
+        let response = test::call_service(&srv, req).await;
+
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+    }
+
     ///
     /// Verifies a POST /batches with an INVALID_BATCH response.
     ///
@@ -806,6 +988,39 @@ mod test {
         assert_eq!(response.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    ///
+    /// Verifies a GET /healthz/backend responds with an Ok response reporting on the backend.
+    ///
+    ///    The TestServer will receive a request with no parameters
+    ///    Its backend client does not support health checks
+    ///    It should send back a JSON response with:
+    ///        - a reachable property of false
+    ///        - a last_error property explaining that health checks are not supported
+    ///
+    #[actix_web::test]
+    async fn test_get_backend_health() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/healthz/backend")
+            .to_request();
+
+        let response = test::call_service(&srv, req).await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+
+        let deserialized: BackendHealthReport = test::read_body_json(response).await;
+
+        assert!(!deserialized.reachable);
+        assert_eq!(deserialized.latency_ms, None);
+        assert!(deserialized.last_error.is_some());
+    }
+
     ///
     /// Verifies a GET /agent responds with an Ok response
     ///     with an empty Agents table
@@ -1449,6 +1664,110 @@ mod test {
         assert_eq!(test_product.properties.len(), 2);
     }
 
+    /// Verifies a GET /product leaves out recalled products unless include_recalled is set, and
+    ///     that recalled products are marked with their recall status.
+    #[actix_web::test]
+    async fn test_list_products_include_recalled() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+
+        populate_product_table(get_product(None), pool.clone());
+        DieselProductStore::new(pool)
+            .set_product_recall_status(
+                "041205707820",
+                None,
+                RecallStatus::Recalled,
+                &[RECALL_MANAGER_ALIAS.to_string()],
+            )
+            .unwrap();
+
+        let req = test::TestRequest::get().uri("/product").to_request();
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let body: ProductListSlice = test::read_body_json(response).await;
+        assert!(body.data.is_empty());
+
+        let req = test::TestRequest::get()
+            .uri("/product?include_recalled=true")
+            .to_request();
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let body: ProductListSlice = test::read_body_json(response).await;
+        assert_eq!(body.data.len(), 1);
+        assert_eq!(body.data[0].recall_status, RecallStatus::Recalled);
+    }
+
+    /// Verifies a GET /products/export streams one JSON product per line in product ID order,
+    ///     and that passing the last product ID received as the cursor resumes after it.
+    #[actix_web::test]
+    async fn test_export_products() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+
+        populate_product_table(
+            ["product-c", "product-a", "product-b"]
+                .iter()
+                .map(|id| {
+                    ProductBuilder::default()
+                        .with_product_id(id.to_string())
+                        .with_product_address(format!("address-{}", id))
+                        .with_product_namespace("Grid Product".to_string())
+                        .with_owner("phillips001".to_string())
+                        .with_start_commit_number(0)
+                        .with_end_commit_number(i64::MAX)
+                        .build()
+                        .unwrap()
+                })
+                .collect(),
+            pool,
+        );
+
+        let export_ids = |body: &[u8]| {
+            std::str::from_utf8(body)
+                .unwrap()
+                .lines()
+                .map(|line| {
+                    serde_json::from_str::<ProductSlice>(line)
+                        .unwrap()
+                        .product_id
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let req = test::TestRequest::get()
+            .uri("/products/export")
+            .to_request();
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let body = test::read_body(response).await;
+        assert_eq!(
+            export_ids(&body),
+            vec!["product-a", "product-b", "product-c"]
+        );
+
+        let req = test::TestRequest::get()
+            .uri("/products/export?cursor=product-a")
+            .to_request();
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let body = test::read_body(response).await;
+        assert_eq!(export_ids(&body), vec!["product-b", "product-c"]);
+    }
+
     /// Verifies a GET /location responds with an OK response with a
     ///     list_locations request.
     ///
@@ -2606,6 +2925,95 @@ mod test {
         assert_eq!(test_record.proposals[0].status, "CANCELED");
     }
 
+    ///
+    /// Verifies a GET /record/{record_id}/custody responds with an OK response
+    ///     and a single transfer, to the Record's creator, when the Record has
+    ///     never changed custody.
+    ///
+    #[actix_web::test]
+    #[cfg(feature = "track-and-trace")]
+    async fn test_fetch_record_custody_chain_ok() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/record/TestRecord/custody")
+            .to_request();
+
+        populate_associated_agent_table(get_associated_agents(None), pool.clone());
+        populate_record_table(get_record("TestRecord", None), pool.clone());
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let chain: Vec<CustodyTransferSlice> = test::read_body_json(response).await;
+
+        assert_eq!(chain.len(), 1);
+        assert_eq!(chain[0].agent_id, KEY2.to_string());
+        assert_eq!(chain[0].timestamp, 1);
+    }
+
+    ///
+    /// Verifies a GET /record/{record_id}/custody responds with an OK response
+    ///     and every transfer of custody, oldest first, after the Record's
+    ///     custodian has been updated.
+    ///
+    #[actix_web::test]
+    #[cfg(feature = "track-and-trace")]
+    async fn test_fetch_record_custody_chain_updated_ok() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/record/TestRecord/custody")
+            .to_request();
+
+        populate_record_table(get_updated_record(), pool.clone());
+        populate_associated_agent_table(get_associated_agents_updated(), pool.clone());
+        let response = test::call_service(&srv, req).await;
+
+        assert!(response.status().is_success());
+        let chain: Vec<CustodyTransferSlice> = test::read_body_json(response).await;
+
+        assert_eq!(
+            chain
+                .iter()
+                .map(|transfer| (transfer.agent_id.as_str(), transfer.timestamp))
+                .collect::<Vec<_>>(),
+            vec![(KEY2, 1), (KEY1, 2)]
+        );
+    }
+
+    ///
+    /// Verifies a GET /record/{record_id}/custody responds with a Not Found error
+    ///     when there is no Record with the specified record_id.
+    ///
+    #[actix_web::test]
+    #[cfg(feature = "track-and-trace")]
+    async fn test_fetch_record_custody_chain_not_found() {
+        let pool = create_connection_pool_and_migrate();
+        let srv = create_test_server(
+            Backend::Sawtooth,
+            ResponseType::ClientBatchStatusResponseOK,
+            pool.clone(),
+        )
+        .await;
+        let req = test::TestRequest::get()
+            .uri("/record/not_in_database/custody")
+            .to_request();
+
+        let response = test::call_service(&srv, req).await;
+
+        assert_eq!(response.status(), http::StatusCode::NOT_FOUND);
+    }
+
     ///
     /// Verifies a GET /record/{record_id} responds with a Not Found error
     ///     when there is no Record with the specified record_id.
@@ -3684,9 +4092,21 @@ mod test {
     }
 
     fn get_batch_list() -> Vec<u8> {
+        let mut transaction_header = TransactionHeader::new();
+        transaction_header.set_family_name("intkey".to_string());
+
+        let mut transaction = Transaction::new();
+        transaction.set_header(
+            protobuf::Message::write_to_bytes(&transaction_header)
+                .expect("Failed to write transaction header to bytes"),
+        );
+        transaction.set_header_signature(TRANSACTION_ID_1.to_string());
+
         let mut batch_list = BatchList::new();
         let mut batch = Batch::new();
+        batch.set_header(b"batch_header".to_vec());
         batch.set_header_signature(BATCH_ID_1.to_string());
+        batch.set_transactions(protobuf::RepeatedField::from_vec(vec![transaction]));
         batch_list.set_batches(protobuf::RepeatedField::from_vec(vec![batch]));
         protobuf::Message::write_to_bytes(&batch_list)
             .expect("Failed to write batch statuses to bytes")
@@ -3903,6 +4323,8 @@ mod test {
                 struct_values: None,
                 lat_long_value: None,
                 service_id: service_id.clone(),
+                effective_from: None,
+                effective_to: None,
             },
             LocationAttribute {
                 start_commit_num: 0,
@@ -3919,6 +4341,8 @@ mod test {
                 struct_values: None,
                 lat_long_value: None,
                 service_id,
+                effective_from: None,
+                effective_to: None,
             },
         ]
     }
@@ -4335,6 +4759,8 @@ mod test {
                 struct_values: None,
                 lat_long_value: None,
                 service_id: service_id.clone(),
+                effective_from: None,
+                effective_to: None,
             },
             ReportedValue {
                 id: None,
@@ -4353,6 +4779,8 @@ mod test {
                 struct_values: None,
                 lat_long_value: None,
                 service_id,
+                effective_from: None,
+                effective_to: None,
             },
         ]
     }
@@ -4422,6 +4850,7 @@ mod test {
                 data_type: "Lightbulb".to_string(),
                 required: false,
                 description: "Definition Description".to_string(),
+                access_level: 0,
                 number_exponent: 0,
                 enum_options: vec![],
                 struct_properties: vec![],
@@ -4435,6 +4864,7 @@ mod test {
                 data_type: "New Lightbulb".to_string(),
                 required: false,
                 description: "Definition Description".to_string(),
+                access_level: 0,
                 number_exponent: 0,
                 enum_options: vec![],
                 struct_properties: vec![],
@@ -4469,6 +4899,7 @@ mod test {
             data_type: "Struct".to_string(),
             required: false,
             description: "Definition Description".to_string(),
+            access_level: 0,
             number_exponent: 0,
             enum_options: vec![],
             struct_properties: vec![],
@@ -4503,6 +4934,7 @@ mod test {
                 data_type: "String".to_string(),
                 required: false,
                 description: "Definition Description".to_string(),
+                access_level: 0,
                 number_exponent: 0,
                 enum_options: vec![],
                 struct_properties: vec![],
@@ -4516,6 +4948,7 @@ mod test {
                 data_type: "Boolean".to_string(),
                 required: false,
                 description: "Definition Description".to_string(),
+                access_level: 0,
                 number_exponent: 0,
                 enum_options: vec![],
                 struct_properties: vec![],
@@ -4756,6 +5189,8 @@ mod test {
                 ]),
                 lat_long_value: None,
                 service_id: service_id.clone(),
+                effective_from: None,
+                effective_to: None,
             },
             ReportedValue {
                 id: None,
@@ -4904,6 +5339,8 @@ mod test {
                 ]),
                 lat_long_value: None,
                 service_id,
+                effective_from: None,
+                effective_to: None,
             },
         ]
     }
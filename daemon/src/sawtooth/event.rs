@@ -15,22 +15,31 @@
  * -----------------------------------------------------------------------------
  */
 
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use grid_sdk::commits::store::CommitSigner;
 use protobuf::Message as _;
 
 use sawtooth_sdk::{
     messages::{
+        client_block::{
+            ClientBlockGetByIdRequest, ClientBlockGetResponse, ClientBlockGetResponse_Status,
+        },
         client_event::{
             ClientEventsSubscribeRequest, ClientEventsSubscribeResponse,
             ClientEventsSubscribeResponse_Status,
         },
+        client_receipt::{
+            ClientReceiptGetRequest, ClientReceiptGetResponse, ClientReceiptGetResponse_Status,
+        },
         events::{
             Event as SawtoothEvent, EventFilter, EventFilter_FilterType,
             EventList as SawtoothEventList, EventSubscription,
         },
         network::PingResponse as SawtoothPingResponse,
+        transaction::TransactionHeader,
         transaction_receipt::{
             StateChange as SawtoothStateChange, StateChangeList,
             StateChange_Type as SawtoothStateChange_Type,
@@ -44,7 +53,8 @@ use sawtooth_sdk::{
 };
 
 use crate::event::{
-    CommitEvent, EventConnection, EventConnectionUnsubscriber, EventIoError, StateChange,
+    is_grid_address, CommitEvent, EventConnection, EventConnectionUnsubscriber, EventIoError,
+    StateChange,
 };
 
 use super::connection::SawtoothConnection;
@@ -54,8 +64,10 @@ const BLOCK_COMMIT_EVENT_TYPE: &str = "sawtooth/block-commit";
 const STATE_CHANGE_EVENT_TYPE: &str = "sawtooth/state-delta";
 const BLOCK_ID_ATTR: &str = "block_id";
 const BLOCK_NUM_ATTR: &str = "block_num";
+const PREVIOUS_BLOCK_ID_ATTR: &str = "previous_block_id";
 
 const SHUTDOWN_TIMEOUT: u64 = 2;
+const REQUEST_TIMEOUT: u64 = 10;
 
 impl EventConnection for SawtoothConnection {
     type Unsubscriber = SawtoothEventUnsubscriber;
@@ -67,12 +79,15 @@ impl EventConnection for SawtoothConnection {
     fn subscribe(
         &mut self,
         namespaces: &[&str],
-        last_commit_id: Option<&str>,
+        checkpoints: &[&str],
     ) -> Result<Self::Unsubscriber, EventIoError> {
         let message_sender = self.get_sender();
 
-        let request =
-            create_subscription_request(last_commit_id.unwrap_or(NULL_BLOCK_ID), namespaces);
+        let request = if checkpoints.is_empty() {
+            create_subscription_request(&[NULL_BLOCK_ID], namespaces)
+        } else {
+            create_subscription_request(checkpoints, namespaces)
+        };
         let mut future = message_sender.send(
             Message_MessageType::CLIENT_EVENTS_SUBSCRIBE_REQUEST,
             &correlation_id(),
@@ -89,6 +104,14 @@ impl EventConnection for SawtoothConnection {
             future.get()?,
         )?;
 
+        if response.get_status() == ClientEventsSubscribeResponse_Status::UNKNOWN_BLOCK {
+            return Err(EventIoError::UnknownCheckpoint(format!(
+                "{} does not know any of the blocks {:?}",
+                self.name(),
+                checkpoints
+            )));
+        }
+
         if response.get_status() != ClientEventsSubscribeResponse_Status::OK {
             return Err(EventIoError::ConnectionError(format!(
                 "Failed to subscribe for events: {:?} {}",
@@ -103,7 +126,17 @@ impl EventConnection for SawtoothConnection {
         loop {
             match self.get_receiver().recv() {
                 Ok(Ok(msg)) if msg.get_message_type() == Message_MessageType::CLIENT_EVENTS => {
-                    break extract_event(msg)
+                    break extract_event(msg).map(|mut event| {
+                        match get_signers(&self.get_sender(), &event.id) {
+                            Ok(signers) => event.signers = signers,
+                            Err(err) => warn!(
+                                "Unable to get the signers of block {}; its agent activity will \
+                                 not be recorded: {}",
+                                event.id, err
+                            ),
+                        }
+                        event
+                    })
                 }
                 Ok(Ok(msg)) if msg.get_message_type() == Message_MessageType::PING_REQUEST => {
                     self.get_sender().send(
@@ -205,8 +238,11 @@ fn content_of_type<M: protobuf::Message>(
         .map_err(|err| EventIoError::ConnectionError(err.to_string()))
 }
 
+/// Creates a request to subscribe to block commits and the state changes in the given
+/// namespaces. The validator resumes after the first of `last_known_block_ids` on its chain, so
+/// they should be ordered newest first.
 fn create_subscription_request(
-    last_known_block_id: &str,
+    last_known_block_ids: &[&str],
     namespace_filters: &[&str],
 ) -> ClientEventsSubscribeRequest {
     let mut block_info_subscription = EventSubscription::new();
@@ -220,9 +256,11 @@ fn create_subscription_request(
             .push(make_event_filter(namespace));
     }
 
-    request
-        .mut_last_known_block_ids()
-        .push(last_known_block_id.into());
+    for block_id in last_known_block_ids {
+        request
+            .mut_last_known_block_ids()
+            .push(block_id.to_string());
+    }
 
     request
 }
@@ -254,29 +292,169 @@ impl TryFrom<&[SawtoothEvent]> for CommitEvent {
 
     fn try_from(events: &[SawtoothEvent]) -> Result<Self, Self::Error> {
         let (id, height) = get_id_and_height(events)?;
+        let parent_id = get_parent_id(events)?;
         let state_changes = get_state_changes(events)?;
 
         Ok(CommitEvent {
             service_id: None, // sawtooth is identified by the null service_id
             id,
             height,
+            parent_id,
             state_changes,
+            signers: vec![],
         })
     }
 }
 
 fn sawtooth_event_to_commit_event(events: &[SawtoothEvent]) -> Result<CommitEvent, EventIoError> {
     let (id, height) = get_id_and_height(events)?;
+    let parent_id = get_parent_id(events)?;
     let state_changes = get_state_changes(events)?;
 
     Ok(CommitEvent {
         service_id: None,
         id,
         height,
+        parent_id,
         state_changes,
+        signers: vec![],
     })
 }
 
+/// Returns the agents that signed the transactions in the block, along with the Grid state each
+/// changed, read from the block's transaction headers and receipts.
+fn get_signers(
+    sender: &ZmqMessageSender,
+    block_id: &str,
+) -> Result<Vec<CommitSigner>, EventIoError> {
+    let mut block_request = ClientBlockGetByIdRequest::new();
+    block_request.set_block_id(block_id.into());
+    let mut block_response: ClientBlockGetResponse = send_request(
+        sender,
+        Message_MessageType::CLIENT_BLOCK_GET_BY_ID_REQUEST,
+        Message_MessageType::CLIENT_BLOCK_GET_RESPONSE,
+        &block_request,
+    )?;
+    if block_response.get_status() != ClientBlockGetResponse_Status::OK {
+        return Err(EventIoError::InvalidMessage(format!(
+            "Unable to get block {}: {:?}",
+            block_id,
+            block_response.get_status()
+        )));
+    }
+
+    let transactions = block_response
+        .take_block()
+        .take_batches()
+        .into_iter()
+        .flat_map(|mut batch| batch.take_transactions().into_iter())
+        .map(|transaction| {
+            let header: TransactionHeader =
+                protobuf::Message::parse_from_bytes(transaction.get_header()).map_err(|err| {
+                    EventIoError::InvalidMessage(format!(
+                        "Unable to parse transaction header: {}",
+                        err
+                    ))
+                })?;
+            Ok((transaction.header_signature, header.signer_public_key))
+        })
+        .collect::<Result<Vec<_>, EventIoError>>()?;
+
+    if transactions.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut receipt_request = ClientReceiptGetRequest::new();
+    receipt_request.set_transaction_ids(
+        transactions
+            .iter()
+            .map(|(transaction_id, _)| transaction_id.to_string())
+            .collect::<Vec<_>>()
+            .into(),
+    );
+    let receipt_response: ClientReceiptGetResponse = send_request(
+        sender,
+        Message_MessageType::CLIENT_RECEIPT_GET_REQUEST,
+        Message_MessageType::CLIENT_RECEIPT_GET_RESPONSE,
+        &receipt_request,
+    )?;
+    if receipt_response.get_status() != ClientReceiptGetResponse_Status::OK {
+        return Err(EventIoError::InvalidMessage(format!(
+            "Unable to get the transaction receipts of block {}: {:?}",
+            block_id,
+            receipt_response.get_status()
+        )));
+    }
+
+    let addresses = receipt_response
+        .receipts
+        .into_iter()
+        .map(|receipt| {
+            (
+                receipt.transaction_id,
+                receipt
+                    .state_changes
+                    .into_iter()
+                    .map(|state_change| state_change.address)
+                    .filter(|address| is_grid_address(address))
+                    .collect(),
+            )
+        })
+        .collect();
+
+    Ok(group_by_signer(transactions, addresses))
+}
+
+/// Groups the addresses changed by each transaction, given as `(transaction_id,
+/// signer_public_key)` pairs in block order, by the transaction's signer.
+fn group_by_signer(
+    transactions: Vec<(String, String)>,
+    mut addresses: HashMap<String, Vec<String>>,
+) -> Vec<CommitSigner> {
+    let mut signers: Vec<CommitSigner> = vec![];
+    for (transaction_id, public_key) in transactions {
+        let index = match signers
+            .iter()
+            .position(|signer| signer.public_key == public_key)
+        {
+            Some(index) => index,
+            None => {
+                signers.push(CommitSigner {
+                    public_key,
+                    addresses: vec![],
+                });
+                signers.len() - 1
+            }
+        };
+
+        let signer = &mut signers[index];
+        for address in addresses.remove(&transaction_id).unwrap_or_default() {
+            if !signer.addresses.contains(&address) {
+                signer.addresses.push(address);
+            }
+        }
+    }
+
+    signers
+}
+
+fn send_request<Req: protobuf::Message, Res: protobuf::Message>(
+    sender: &ZmqMessageSender,
+    request_type: Message_MessageType,
+    response_type: Message_MessageType,
+    request: &Req,
+) -> Result<Res, EventIoError> {
+    let bytes = request.write_to_bytes().map_err(|err| {
+        EventIoError::ConnectionError(format!("Failed to serialize request: {}", err))
+    })?;
+    let mut future = sender.send(request_type, &correlation_id(), &bytes)?;
+
+    content_of_type(
+        response_type,
+        future.get_timeout(Duration::from_secs(REQUEST_TIMEOUT))?,
+    )
+}
+
 fn get_id_and_height(events: &[SawtoothEvent]) -> Result<(String, Option<u64>), EventIoError> {
     let block_event = get_block_event(events)?;
     let block_id = get_required_attribute_from_event(block_event, BLOCK_ID_ATTR)?;
@@ -288,6 +466,14 @@ fn get_id_and_height(events: &[SawtoothEvent]) -> Result<(String, Option<u64>),
     Ok((block_id, Some(block_num)))
 }
 
+fn get_parent_id(events: &[SawtoothEvent]) -> Result<Option<String>, EventIoError> {
+    Ok(get_block_event(events)?
+        .get_attributes()
+        .iter()
+        .find(|attr| attr.get_key() == PREVIOUS_BLOCK_ID_ATTR)
+        .map(|attr| attr.get_value().to_string()))
+}
+
 fn get_block_event(events: &[SawtoothEvent]) -> Result<&SawtoothEvent, EventIoError> {
     events
         .iter()
@@ -402,6 +588,8 @@ mod tests {
     #[cfg(feature = "location")]
     use grid_sdk::location::addressing::GRID_LOCATION_NAMESPACE;
 
+    const PREVIOUS_BLOCK_ID: &str = "012345";
+
     /// Verify that a valid set of Sawtooth events can be converted to a `CommitEvent`.
     #[test]
     fn sawtooth_events_to_commit_event() {
@@ -430,6 +618,7 @@ mod tests {
         assert!(&commit_event.service_id.is_none());
         assert_eq!(&commit_event.id, block_id);
         assert_eq!(commit_event.height, Some(block_num));
+        assert_eq!(commit_event.parent_id.as_deref(), Some(PREVIOUS_BLOCK_ID));
         assert_eq!(commit_event.state_changes.len(), grid_state_changes.len());
         for sawtooth_state_change in grid_state_changes {
             let expected_state_change = match sawtooth_state_change.get_field_type() {
@@ -449,6 +638,41 @@ mod tests {
         }
     }
 
+    /// Verify that the addresses changed by a block's transactions are grouped by signer, in
+    /// block order and without duplicates.
+    #[test]
+    fn group_addresses_by_signer() {
+        let transactions = vec![
+            ("txn-1".to_string(), "signer-a".to_string()),
+            ("txn-2".to_string(), "signer-b".to_string()),
+            ("txn-3".to_string(), "signer-a".to_string()),
+        ];
+        let addresses = vec![
+            ("txn-1".to_string(), vec!["address-1".to_string()]),
+            ("txn-2".to_string(), vec!["address-2".to_string()]),
+            (
+                "txn-3".to_string(),
+                vec!["address-1".to_string(), "address-3".to_string()],
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            group_by_signer(transactions, addresses),
+            vec![
+                CommitSigner {
+                    public_key: "signer-a".into(),
+                    addresses: vec!["address-1".into(), "address-3".into()],
+                },
+                CommitSigner {
+                    public_key: "signer-b".into(),
+                    addresses: vec!["address-2".into()],
+                },
+            ]
+        );
+    }
+
     fn create_block_event(block_id: &str, block_num: u64) -> SawtoothEvent {
         let mut event = SawtoothEvent::new();
         event.set_event_type(BLOCK_COMMIT_EVENT_TYPE.into());
@@ -456,6 +680,7 @@ mod tests {
             vec![
                 create_attribute(BLOCK_ID_ATTR.into(), block_id.into()),
                 create_attribute(BLOCK_NUM_ATTR.into(), block_num.to_string()),
+                create_attribute(PREVIOUS_BLOCK_ID_ATTR.into(), PREVIOUS_BLOCK_ID.into()),
             ]
             .into(),
         );
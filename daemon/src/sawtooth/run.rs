@@ -33,7 +33,11 @@ use grid_sdk::store::{create_store_factory, ConnectionUri};
 use crate::config::GridConfig;
 use crate::database::ConnectionPool;
 use crate::error::DaemonError;
-use crate::event::{db_handler::DatabaseEventHandler, EventProcessor};
+#[cfg(feature = "webhooks")]
+use crate::event::webhook_handler::WebhookEventHandler;
+use crate::event::{
+    db_handler::DatabaseEventHandler, Checkpoint, EventHandler, EventProcessor, MAX_CHECKPOINTS,
+};
 use crate::rest_api;
 
 use super::connection::SawtoothConnection;
@@ -48,6 +52,13 @@ pub fn run_sawtooth(config: GridConfig) -> Result<(), DaemonError> {
     let store_factory = create_store_factory(&connection_uri)
         .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
+    #[cfg(feature = "webhooks")]
+    let webhook_handler = if config.webhooks().is_empty() {
+        None
+    } else {
+        Some(WebhookEventHandler::new(config.webhooks())?)
+    };
+
     let sawtooth_connection = SawtoothConnection::new(&sawtooth_endpoint.url());
     let backend_client = SawtoothBackendClient::new(sawtooth_connection.get_sender());
     let backend_state = BackendState::new(Arc::new(backend_client));
@@ -60,9 +71,12 @@ pub fn run_sawtooth(config: GridConfig) -> Result<(), DaemonError> {
     #[cfg(any(feature = "database-postgres", feature = "database-sqlite"))]
     let (store_state, evt_processor) = {
         let commit_store = store_factory.get_grid_commit_store();
-        let current_commit = commit_store
-            .get_current_commit_id()
-            .map_err(|err| DaemonError::from_source(Box::new(err)))?;
+        let checkpoints: Vec<Checkpoint> = commit_store
+            .list_checkpoints(None, MAX_CHECKPOINTS as i64)
+            .map_err(|err| DaemonError::from_source(Box::new(err)))?
+            .into_iter()
+            .map(Checkpoint::from)
+            .collect();
 
         match connection_uri {
             #[cfg(feature = "database-postgres")]
@@ -73,12 +87,13 @@ pub fn run_sawtooth(config: GridConfig) -> Result<(), DaemonError> {
                 let store_factory = create_store_factory(&connection_uri)
                     .map_err(|err| DaemonError::from_source(Box::new(err)))?;
                 let event_handler = DatabaseEventHandler::new(store_factory);
-                let evt_processor = EventProcessor::start(
-                    sawtooth_connection,
-                    current_commit.as_deref(),
-                    event_handlers![event_handler],
-                )
-                .map_err(|err| DaemonError::from_source(Box::new(err)))?;
+                #[allow(unused_mut)]
+                let mut event_handlers: Vec<Box<dyn EventHandler>> = event_handlers![event_handler];
+                #[cfg(feature = "webhooks")]
+                event_handlers.extend(webhook_handler.as_ref().map(|handler| handler.cloned_box()));
+                let evt_processor =
+                    EventProcessor::start(sawtooth_connection, checkpoints, event_handlers)
+                        .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
                 (
                     StoreState::with_pg_pool(connection_pool.pool),
@@ -93,12 +108,13 @@ pub fn run_sawtooth(config: GridConfig) -> Result<(), DaemonError> {
                 let store_factory = create_store_factory(&connection_uri)
                     .map_err(|err| DaemonError::from_source(Box::new(err)))?;
                 let event_handler = DatabaseEventHandler::new(store_factory);
-                let evt_processor = EventProcessor::start(
-                    sawtooth_connection,
-                    current_commit.as_deref(),
-                    event_handlers![event_handler],
-                )
-                .map_err(|err| DaemonError::from_source(Box::new(err)))?;
+                #[allow(unused_mut)]
+                let mut event_handlers: Vec<Box<dyn EventHandler>> = event_handlers![event_handler];
+                #[cfg(feature = "webhooks")]
+                event_handlers.extend(webhook_handler.as_ref().map(|handler| handler.cloned_box()));
+                let evt_processor =
+                    EventProcessor::start(sawtooth_connection, checkpoints, event_handlers)
+                        .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
                 (
                     StoreState::with_sqlite_pool(connection_pool.pool),
@@ -119,6 +135,9 @@ pub fn run_sawtooth(config: GridConfig) -> Result<(), DaemonError> {
         #[cfg(feature = "integration")]
         key_state,
         sawtooth_endpoint,
+        config.rest_api_drain_timeout(),
+        config.rest_api_field_naming(),
+        config.rest_api_clearance_policy(),
     )
     .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
@@ -0,0 +1,206 @@
+/*
+ * Copyright 2022 Cargill Incorporated
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ * -----------------------------------------------------------------------------
+ */
+
+//! Scabbard commit events do not say who signed the committed batch, so the signers of the
+//! batches submitted through this daemon are recorded as they are submitted, and looked up by
+//! batch id when the batch is committed. Batches submitted through other daemons or directly to
+//! the node are committed without signers, and so without agent activity.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use grid_sdk::backend::{
+    BackendClient, BackendClientError, BackendHealth, BatchStatus, BatchStatuses, BatchSubmission,
+    SubmitBatches,
+};
+use protobuf::Message as _;
+use sawtooth_sdk::messages::{batch::BatchList, transaction::TransactionHeader};
+
+/// The number of submitted batches whose signers are remembered while they wait to be committed
+const MAX_TRACKED_BATCHES: usize = 10_000;
+
+/// The transaction signers of submitted batches, by batch id.
+///
+/// Only the most recent `MAX_TRACKED_BATCHES` batches are kept; a batch that takes longer to be
+/// committed is committed without signers.
+#[derive(Clone, Default)]
+pub struct BatchSigners {
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    signers: HashMap<String, Vec<String>>,
+    order: VecDeque<String>,
+}
+
+impl BatchSigners {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the distinct transaction signers of each batch in the list. Transactions whose
+    /// header cannot be parsed are skipped, as the node will reject them.
+    pub fn record(&self, batch_list: &BatchList) {
+        let mut inner = match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(_) => {
+                error!("Batch signers lock was poisoned; signers will not be recorded");
+                return;
+            }
+        };
+
+        for batch in batch_list.get_batches() {
+            let mut signers: Vec<String> = vec![];
+            for transaction in batch.get_transactions() {
+                if let Ok(header) = TransactionHeader::parse_from_bytes(transaction.get_header()) {
+                    if !signers.contains(&header.signer_public_key) {
+                        signers.push(header.signer_public_key);
+                    }
+                }
+            }
+
+            let batch_id = batch.get_header_signature().to_string();
+            if inner.signers.insert(batch_id.clone(), signers).is_none() {
+                inner.order.push_back(batch_id);
+            }
+        }
+
+        while inner.order.len() > MAX_TRACKED_BATCHES {
+            if let Some(batch_id) = inner.order.pop_front() {
+                inner.signers.remove(&batch_id);
+            }
+        }
+    }
+
+    /// Removes and returns the signers recorded for the batch, or an empty list if it was not
+    /// submitted through this daemon.
+    pub fn take(&self, batch_id: &str) -> Vec<String> {
+        match self.inner.lock() {
+            Ok(mut inner) => {
+                let signers = inner.signers.remove(batch_id);
+                if signers.is_some() {
+                    inner.order.retain(|id| id != batch_id);
+                }
+                signers.unwrap_or_default()
+            }
+            Err(_) => {
+                error!("Batch signers lock was poisoned; signers will not be returned");
+                vec![]
+            }
+        }
+    }
+}
+
+/// A `BackendClient` that records the signers of the batches it submits before passing them on
+/// to the wrapped client.
+pub struct SignerRecordingBackendClient {
+    inner: Arc<dyn BackendClient>,
+    batch_signers: BatchSigners,
+}
+
+impl SignerRecordingBackendClient {
+    pub fn new(inner: Arc<dyn BackendClient>, batch_signers: BatchSigners) -> Self {
+        Self {
+            inner,
+            batch_signers,
+        }
+    }
+}
+
+impl BackendClient for SignerRecordingBackendClient {
+    fn submit_batches(
+        &self,
+        submit_batches: SubmitBatches,
+    ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>> {
+        self.batch_signers.record(&submit_batches.batch_list);
+        self.inner.submit_batches(submit_batches)
+    }
+
+    fn batch_status(
+        &self,
+        batch_statuses: BatchStatuses,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>> {
+        self.inner.batch_status(batch_statuses)
+    }
+
+    fn clone_box(&self) -> Box<dyn BackendClient> {
+        Box::new(Self {
+            inner: self.inner.clone(),
+            batch_signers: self.batch_signers.clone(),
+        })
+    }
+
+    fn requires_service_id(&self) -> bool {
+        self.inner.requires_service_id()
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = BackendHealth> + Send>> {
+        self.inner.health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::RepeatedField;
+    use sawtooth_sdk::messages::{batch::Batch, transaction::Transaction};
+
+    fn batch(batch_id: &str, signers: &[&str]) -> Batch {
+        let transactions = signers
+            .iter()
+            .map(|signer| {
+                let mut header = TransactionHeader::new();
+                header.set_signer_public_key(signer.to_string());
+
+                let mut transaction = Transaction::new();
+                transaction.set_header(header.write_to_bytes().expect("Unable to write header"));
+                transaction
+            })
+            .collect::<Vec<_>>();
+
+        let mut batch = Batch::new();
+        batch.set_header_signature(batch_id.into());
+        batch.set_transactions(RepeatedField::from_vec(transactions));
+        batch
+    }
+
+    /// Verify that the distinct signers of each submitted batch are returned once by batch id,
+    /// and that unknown batches have no signers.
+    #[test]
+    fn test_record_and_take() {
+        let batch_signers = BatchSigners::new();
+
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(vec![
+            batch("batch-1", &["signer-a", "signer-b", "signer-a"]),
+            batch("batch-2", &["signer-c"]),
+        ]));
+        batch_signers.record(&batch_list);
+
+        assert_eq!(
+            batch_signers.take("batch-1"),
+            vec!["signer-a".to_string(), "signer-b".to_string()]
+        );
+        assert!(batch_signers.take("batch-1").is_empty());
+        assert_eq!(batch_signers.take("batch-2"), vec!["signer-c".to_string()]);
+        assert!(batch_signers.take("unknown").is_empty());
+    }
+}
@@ -21,6 +21,7 @@ pub(in crate::splinter) mod processors;
 use std::cell::RefCell;
 use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 
+use grid_sdk::commits::store::CommitSigner;
 use scabbard::service::{StateChange as ScabbardStateChange, StateChangeEvent};
 use splinter::events::{Igniter, WebSocketClient, WebSocketError, WsResponse};
 
@@ -28,20 +29,25 @@ use crate::event::{
     CommitEvent, EventConnection, EventConnectionUnsubscriber, EventIoError, StateChange,
 };
 
+use super::batch_signers::BatchSigners;
+
 pub use error::ScabbardEventConnectionError;
 
 /// Constructs ScabbardEventConnections to receive events.
 pub struct ScabbardEventConnectionFactory {
     node_endpoint: String,
     igniter: Igniter,
+    batch_signers: BatchSigners,
 }
 
 impl ScabbardEventConnectionFactory {
-    /// Construct a new factory connecting to a specific splinter node.
-    pub fn new(node_endpoint: &str, igniter: Igniter) -> Self {
+    /// Construct a new factory connecting to a specific splinter node. The signers of committed
+    /// batches are looked up in `batch_signers`.
+    pub fn new(node_endpoint: &str, igniter: Igniter, batch_signers: BatchSigners) -> Self {
         Self {
             node_endpoint: node_endpoint.into(),
             igniter,
+            batch_signers,
         }
     }
 
@@ -63,6 +69,7 @@ impl ScabbardEventConnectionFactory {
             connection_url,
             self.igniter.clone(),
             authorization.to_string(),
+            self.batch_signers.clone(),
         ))
     }
 }
@@ -84,16 +91,24 @@ pub struct ScabbardEventConnection {
     igniter: Igniter,
     connection_state: RefCell<ConnectionState>,
     authorization: String,
+    batch_signers: BatchSigners,
 }
 
 impl ScabbardEventConnection {
-    fn new(name: String, connection_url: String, igniter: Igniter, authorization: String) -> Self {
+    fn new(
+        name: String,
+        connection_url: String,
+        igniter: Igniter,
+        authorization: String,
+        batch_signers: BatchSigners,
+    ) -> Self {
         Self {
             name,
             connection_url,
             igniter,
             connection_state: RefCell::new(ConnectionState::Disconnected),
             authorization,
+            batch_signers,
         }
     }
 }
@@ -108,13 +123,14 @@ impl EventConnection for ScabbardEventConnection {
     fn subscribe(
         &mut self,
         _namespaces: &[&str],
-        last_commit_id: Option<&str>,
+        checkpoints: &[&str],
     ) -> Result<Self::Unsubscriber, EventIoError> {
         let (sender, receiver) = sync_channel(128);
 
         let source = self.name.clone();
         let unsubscribe_sender = sender.clone();
-        let url = if let Some(last_commit_id) = last_commit_id {
+        // Scabbard resumes from a single event, so only the newest checkpoint is used
+        let url = if let Some(last_commit_id) = checkpoints.first() {
             format!("{}?last_seen_event={}", self.connection_url, last_commit_id)
         } else {
             self.connection_url.clone()
@@ -171,11 +187,8 @@ impl EventConnection for ScabbardEventConnection {
         let mut connection_state = self.connection_state.borrow_mut();
         match *connection_state {
             ConnectionState::Connected(ref receiver) => match receiver.recv() {
-                Ok(ConnectionCommand::Message(scabbard_evt)) => Ok(CommitEvent {
-                    service_id: Some(self.name.clone()),
-                    id: scabbard_evt.id,
-                    height: None,
-                    state_changes: scabbard_evt
+                Ok(ConnectionCommand::Message(scabbard_evt)) => {
+                    let state_changes = scabbard_evt
                         .state_changes
                         .into_iter()
                         .map(|state_change| match state_change {
@@ -184,8 +197,34 @@ impl EventConnection for ScabbardEventConnection {
                             }
                             ScabbardStateChange::Delete { key } => StateChange::Delete { key },
                         })
-                        .collect(),
-                }),
+                        .collect::<Vec<_>>();
+
+                    // Scabbard commits one batch per event, identified by the batch id, so each
+                    // of the batch's signers is credited with all of the Grid state it changed.
+                    let addresses = state_changes
+                        .iter()
+                        .filter(|state_change| state_change.is_grid_state_change())
+                        .map(|state_change| state_change.key().to_string())
+                        .collect::<Vec<_>>();
+                    let signers = self
+                        .batch_signers
+                        .take(&scabbard_evt.id)
+                        .into_iter()
+                        .map(|public_key| CommitSigner {
+                            public_key,
+                            addresses: addresses.clone(),
+                        })
+                        .collect();
+
+                    Ok(CommitEvent {
+                        service_id: Some(self.name.clone()),
+                        id: scabbard_evt.id,
+                        height: None,
+                        parent_id: None,
+                        state_changes,
+                        signers,
+                    })
+                }
                 Ok(ConnectionCommand::Shutdown) => {
                     debug!("Disconnecting event connection to {}", self.name);
 
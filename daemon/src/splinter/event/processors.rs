@@ -20,7 +20,7 @@ use std::sync::{Arc, Mutex};
 
 use grid_sdk::error::InternalError;
 
-use crate::event::{EventHandler, EventProcessor};
+use crate::event::{Checkpoint, EventHandler, EventProcessor};
 
 use super::{ScabbardEventConnection, ScabbardEventConnectionFactory};
 
@@ -97,7 +97,15 @@ impl Inner {
                 .create_connection(circuit_id, service_id, authorization)
                 .map_err(|err| InternalError::from_source(Box::new(err)))?;
 
-            let evt_processor = EventProcessor::start(event_connection, last_seen_id, factory_fn())
+            let checkpoints = last_seen_id
+                .map(|id| Checkpoint {
+                    id: id.to_string(),
+                    height: None,
+                })
+                .into_iter()
+                .collect();
+
+            let evt_processor = EventProcessor::start(event_connection, checkpoints, factory_fn())
                 .map_err(|err| InternalError::from_source(Box::new(err)))?;
 
             entry.insert(evt_processor);
@@ -16,6 +16,7 @@
  */
 
 pub mod app_auth_handler;
+mod batch_signers;
 pub mod event;
 mod run;
 
@@ -41,11 +41,16 @@ use splinter::events::Reactor;
 use crate::config::GridConfig;
 use crate::database::ConnectionPool;
 use crate::error::DaemonError;
+#[cfg(feature = "webhooks")]
+use crate::event::webhook_handler::WebhookEventHandler;
 use crate::event::{db_handler::DatabaseEventHandler, CommitEvent, EventError, EventHandler};
 use crate::rest_api;
 
 use super::{
-    app_auth_handler, event::processors::EventProcessors, event::ScabbardEventConnectionFactory,
+    app_auth_handler,
+    batch_signers::{BatchSigners, SignerRecordingBackendClient},
+    event::processors::EventProcessors,
+    event::ScabbardEventConnectionFactory,
 };
 
 enum EventCmd {
@@ -89,8 +94,13 @@ pub fn run_splinter(config: GridConfig) -> Result<(), DaemonError> {
         format!("Bearer Cylinder:{}", jwt)
     };
 
-    let scabbard_event_connection_factory =
-        ScabbardEventConnectionFactory::new(&splinter_endpoint.url(), reactor.igniter());
+    let batch_signers = BatchSigners::new();
+
+    let scabbard_event_connection_factory = ScabbardEventConnectionFactory::new(
+        &splinter_endpoint.url(),
+        reactor.igniter(),
+        batch_signers.clone(),
+    );
 
     let event_processors = EventProcessors::new(scabbard_event_connection_factory);
 
@@ -150,6 +160,13 @@ pub fn run_splinter(config: GridConfig) -> Result<(), DaemonError> {
         }
     };
 
+    #[allow(unused_mut)]
+    let mut event_handlers = vec![db_handler];
+    #[cfg(feature = "webhooks")]
+    if !config.webhooks().is_empty() {
+        event_handlers.push(Box::new(WebhookEventHandler::new(config.webhooks())?));
+    }
+
     let (event_tx, event_rx) = std::sync::mpsc::channel();
     let chan_event_handler: Box<dyn EventHandler> = Box::new(ChannelEventHandler {
         sender: event_tx.clone(),
@@ -160,8 +177,10 @@ pub fn run_splinter(config: GridConfig) -> Result<(), DaemonError> {
         .spawn(move || loop {
             match event_rx.recv() {
                 Ok(EventCmd::Event(evt)) => {
-                    if let Err(err) = db_handler.handle_event(&evt) {
-                        error!("{}", err.to_string());
+                    for handler in &event_handlers {
+                        if let Err(err) = handler.handle_event(&evt) {
+                            error!("{}", err.to_string());
+                        }
                     }
                 }
                 Ok(EventCmd::Exit) => break,
@@ -210,8 +229,12 @@ pub fn run_splinter(config: GridConfig) -> Result<(), DaemonError> {
     )
     .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
-    let backend_client = SplinterBackendClient::new(splinter_endpoint.url(), authorization);
-    let backend_state = BackendState::new(Arc::new(backend_client));
+    let backend_client = SplinterBackendClient::new(splinter_endpoint.url(), authorization)
+        .map_err(|err| DaemonError::from_source(Box::new(err)))?;
+    let backend_state = BackendState::new(Arc::new(SignerRecordingBackendClient::new(
+        Arc::new(backend_client),
+        batch_signers,
+    )));
 
     #[cfg(feature = "integration")]
     let key_state = KeyState::new(config.key_file_name());
@@ -224,6 +247,9 @@ pub fn run_splinter(config: GridConfig) -> Result<(), DaemonError> {
         #[cfg(feature = "integration")]
         key_state,
         splinter_endpoint,
+        config.rest_api_drain_timeout(),
+        config.rest_api_field_naming(),
+        config.rest_api_clearance_policy(),
     )
     .map_err(|err| DaemonError::from_source(Box::new(err)))?;
 
@@ -0,0 +1,467 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for preparing batch lists for submission.
+
+use std::error::Error;
+use std::fmt;
+
+use cylinder::{secp256k1::Secp256k1Context, Context, PublicKey, Signature, Signer, Verifier};
+use protobuf::{Message, RepeatedField};
+use sawtooth_sdk::messages::batch::{Batch, BatchHeader, BatchList};
+use sawtooth_sdk::messages::transaction::{Transaction, TransactionHeader};
+
+use crate::error::{InternalError, InvalidArgumentError};
+
+/// An error found in a batch before it was submitted.
+#[derive(Debug, PartialEq, Eq)]
+pub enum BatchError {
+    /// The header or signature of a batch is invalid
+    InvalidBatch { batch_id: String, message: String },
+    /// The header or signature of a transaction is invalid
+    InvalidTransaction {
+        batch_id: String,
+        transaction_id: String,
+        message: String,
+    },
+}
+
+impl Error for BatchError {}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::InvalidBatch { batch_id, message } => {
+                write!(f, "batch {} is invalid: {}", batch_id, message)
+            }
+            BatchError::InvalidTransaction {
+                batch_id,
+                transaction_id,
+                message,
+            } => write!(
+                f,
+                "transaction {} of batch {} is invalid: {}",
+                transaction_id, batch_id, message
+            ),
+        }
+    }
+}
+
+/// Checks the signature of every batch and transaction in a batch list.
+///
+/// A batch's header signature must be a signature of its header by the header's signer public
+/// key, and likewise for each of its transactions. This catches batches that were corrupted or
+/// signed with the wrong key before a node rejects them.
+///
+/// # Errors
+///
+/// Returns a [`BatchError`] for the first batch or transaction whose header cannot be parsed or
+/// whose signature does not match.
+pub fn verify_signatures(batch_list: &BatchList) -> Result<(), BatchError> {
+    let verifier = Secp256k1Context::new().new_verifier();
+
+    for batch in batch_list.get_batches() {
+        let batch_id = batch.get_header_signature();
+        let invalid_batch = |message: String| BatchError::InvalidBatch {
+            batch_id: batch_id.to_string(),
+            message,
+        };
+
+        let header: BatchHeader = Message::parse_from_bytes(batch.get_header())
+            .map_err(|err| invalid_batch(format!("unable to parse header: {}", err)))?;
+        verify(
+            &*verifier,
+            batch_id,
+            batch.get_header(),
+            header.get_signer_public_key(),
+        )
+        .map_err(invalid_batch)?;
+
+        for transaction in batch.get_transactions() {
+            let transaction_id = transaction.get_header_signature();
+            let invalid_transaction = |message: String| BatchError::InvalidTransaction {
+                batch_id: batch_id.to_string(),
+                transaction_id: transaction_id.to_string(),
+                message,
+            };
+
+            let header: TransactionHeader = Message::parse_from_bytes(transaction.get_header())
+                .map_err(|err| invalid_transaction(format!("unable to parse header: {}", err)))?;
+            verify(
+                &*verifier,
+                transaction_id,
+                transaction.get_header(),
+                header.get_signer_public_key(),
+            )
+            .map_err(invalid_transaction)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn verify(
+    verifier: &dyn Verifier,
+    signature: &str,
+    header: &[u8],
+    public_key: &str,
+) -> Result<(), String> {
+    let public_key = PublicKey::new_from_hex(public_key)
+        .map_err(|err| format!("invalid signer public key: {}", err))?;
+    let signature =
+        Signature::from_hex(signature).map_err(|err| format!("invalid signature: {}", err))?;
+
+    match verifier.verify(header, &signature, &public_key) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("signature does not match header".to_string()),
+        Err(err) => Err(format!("invalid signature: {}", err)),
+    }
+}
+
+/// Splits a batch list into lists that each serialize to at most `max_bytes`, so that they can
+/// be submitted separately without exceeding a node's body limit.
+///
+/// Batches are kept whole and in their original order; each list is filled with as many
+/// consecutive batches as fit. An empty batch list results in no lists.
+///
+/// # Errors
+///
+/// Returns an [`InvalidArgumentError`] if a single batch serializes to more than `max_bytes`
+/// on its own, as it cannot be submitted under the limit.
+pub fn split_by_size(
+    mut batch_list: BatchList,
+    max_bytes: usize,
+) -> Result<Vec<BatchList>, InvalidArgumentError> {
+    let mut lists = vec![];
+    let mut current: Vec<Batch> = vec![];
+    let mut current_size = 0;
+
+    for batch in batch_list.take_batches().into_iter() {
+        let size = serialized_size(&batch);
+        if size > max_bytes {
+            return Err(InvalidArgumentError::new(
+                "batch_list".to_string(),
+                format!(
+                    "batch {} is {} bytes, which exceeds the limit of {} bytes",
+                    batch.get_header_signature(),
+                    size,
+                    max_bytes
+                ),
+            ));
+        }
+
+        if current_size + size > max_bytes {
+            lists.push(batch_list_of(std::mem::take(&mut current)));
+            current_size = 0;
+        }
+
+        current_size += size;
+        current.push(batch);
+    }
+
+    if !current.is_empty() {
+        lists.push(batch_list_of(current));
+    }
+
+    Ok(lists)
+}
+
+/// Packs transactions into a batch list of batches of up to `max_per_batch` transactions each,
+/// signing each batch with `signer`.
+///
+/// Transactions are kept in their original order, which is also the order they are applied in.
+/// Fewer transactions than `max_per_batch` result in a single batch, and no transactions in an
+/// empty batch list. A `max_per_batch` of zero is treated as one.
+///
+/// The transactions must already be signed, with `signer`'s public key as their batcher public
+/// key. As a batch is applied or rejected as a whole, a larger `max_per_batch` means fewer
+/// batches to submit and track but more transactions rejected along with an invalid one.
+///
+/// # Errors
+///
+/// Returns an [`InternalError`] if a batch header cannot be serialized or signed.
+pub fn pack(
+    transactions: Vec<Transaction>,
+    max_per_batch: usize,
+    signer: &dyn Signer,
+) -> Result<BatchList, InternalError> {
+    let public_key = signer
+        .public_key()
+        .map_err(|err| InternalError::from_source(Box::new(err)))?
+        .as_hex();
+
+    let mut batches = vec![];
+    let mut transactions = transactions.into_iter().peekable();
+    while transactions.peek().is_some() {
+        let transactions = transactions
+            .by_ref()
+            .take(max_per_batch.max(1))
+            .collect::<Vec<_>>();
+
+        let mut header = BatchHeader::new();
+        header.set_signer_public_key(public_key.clone());
+        header.set_transaction_ids(RepeatedField::from_vec(
+            transactions
+                .iter()
+                .map(|transaction| transaction.get_header_signature().to_string())
+                .collect(),
+        ));
+        let header = header
+            .write_to_bytes()
+            .map_err(|err| InternalError::from_source(Box::new(err)))?;
+
+        let mut batch = Batch::new();
+        batch.set_header_signature(
+            signer
+                .sign(&header)
+                .map_err(|err| InternalError::from_source(Box::new(err)))?
+                .as_hex(),
+        );
+        batch.set_header(header);
+        batch.set_transactions(RepeatedField::from_vec(transactions));
+        batches.push(batch);
+    }
+
+    Ok(batch_list_of(batches))
+}
+
+/// Returns the number of bytes a batch adds to a serialized batch list.
+///
+/// A list's batches are encoded one after another, so this is the size of a list containing
+/// only that batch.
+fn serialized_size(batch: &Batch) -> usize {
+    batch_list_of(vec![batch.clone()]).compute_size() as usize
+}
+
+fn batch_list_of(batches: Vec<Batch>) -> BatchList {
+    let mut batch_list = BatchList::new();
+    batch_list.set_batches(RepeatedField::from_vec(batches));
+    batch_list
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch(id: &str, header_len: usize) -> Batch {
+        let mut batch = Batch::new();
+        batch.set_header_signature(id.to_string());
+        batch.set_header(vec![0; header_len]);
+        batch
+    }
+
+    fn ids(batch_list: &BatchList) -> Vec<&str> {
+        batch_list
+            .get_batches()
+            .iter()
+            .map(|batch| batch.get_header_signature())
+            .collect()
+    }
+
+    /// Verifies that batches are packed in order into lists that each serialize under the
+    /// limit.
+    #[test]
+    fn test_split_by_size() {
+        let batches = vec![
+            batch("a", 40),
+            batch("b", 40),
+            batch("c", 40),
+            batch("d", 10),
+        ];
+        let max_bytes = serialized_size(&batches[0]) * 2;
+
+        let lists = split_by_size(batch_list_of(batches), max_bytes).expect("Unable to split");
+
+        assert_eq!(
+            lists.iter().map(ids).collect::<Vec<_>>(),
+            vec![vec!["a", "b"], vec!["c", "d"]]
+        );
+        for list in &lists {
+            let bytes = list
+                .write_to_bytes()
+                .expect("Unable to serialize batch list");
+            assert!(bytes.len() <= max_bytes);
+        }
+    }
+
+    /// Verifies that a batch that exceeds the limit on its own is rejected rather than split.
+    #[test]
+    fn test_split_by_size_single_oversized_batch() {
+        let batches = vec![batch("a", 10), batch("b", 100)];
+        let max_bytes = serialized_size(&batches[1]) - 1;
+
+        let err = split_by_size(batch_list_of(batches), max_bytes).unwrap_err();
+
+        assert_eq!(err.argument(), "batch_list");
+        assert!(err.message().contains("batch b"));
+    }
+
+    /// Verifies that an empty batch list results in no lists.
+    #[test]
+    fn test_split_by_size_empty() {
+        assert!(split_by_size(BatchList::new(), 0)
+            .expect("Unable to split")
+            .is_empty());
+    }
+
+    fn signed_transaction(signer: &dyn Signer, nonce: &str) -> Transaction {
+        let public_key = signer
+            .public_key()
+            .expect("Unable to get public key")
+            .as_hex();
+
+        let mut transaction_header = TransactionHeader::new();
+        transaction_header.set_signer_public_key(public_key.clone());
+        transaction_header.set_batcher_public_key(public_key);
+        transaction_header.set_family_name("test".to_string());
+        transaction_header.set_nonce(nonce.to_string());
+        let transaction_header = transaction_header
+            .write_to_bytes()
+            .expect("Unable to serialize transaction header");
+        let mut transaction = Transaction::new();
+        transaction.set_header_signature(
+            signer
+                .sign(&transaction_header)
+                .expect("Unable to sign transaction")
+                .as_hex(),
+        );
+        transaction.set_header(transaction_header);
+        transaction
+    }
+
+    fn signed_batch(signer: &dyn Signer) -> Batch {
+        let public_key = signer
+            .public_key()
+            .expect("Unable to get public key")
+            .as_hex();
+        let transaction = signed_transaction(signer, "");
+
+        let mut batch_header = BatchHeader::new();
+        batch_header.set_signer_public_key(public_key);
+        batch_header.set_transaction_ids(RepeatedField::from_vec(vec![transaction
+            .get_header_signature()
+            .to_string()]));
+        let batch_header = batch_header
+            .write_to_bytes()
+            .expect("Unable to serialize batch header");
+        let mut batch = Batch::new();
+        batch.set_header_signature(
+            signer
+                .sign(&batch_header)
+                .expect("Unable to sign batch")
+                .as_hex(),
+        );
+        batch.set_header(batch_header);
+        batch.set_transactions(RepeatedField::from_vec(vec![transaction]));
+        batch
+    }
+
+    /// Verifies that correctly signed batches pass, and that a batch or transaction whose
+    /// header was changed after signing is reported by id.
+    #[test]
+    fn test_verify_signatures() {
+        let context = Secp256k1Context::new();
+        let signer = context.new_signer(context.new_random_private_key());
+        let signer = &*signer;
+
+        let batch_list = batch_list_of(vec![signed_batch(signer), signed_batch(signer)]);
+        assert_eq!(verify_signatures(&batch_list), Ok(()));
+
+        let mut tampered = signed_batch(signer);
+        let mut header: BatchHeader =
+            Message::parse_from_bytes(tampered.get_header()).expect("Unable to parse header");
+        header.set_transaction_ids(RepeatedField::from_vec(vec!["other".to_string()]));
+        tampered.set_header(header.write_to_bytes().expect("Unable to serialize header"));
+        let batch_id = tampered.get_header_signature().to_string();
+
+        match verify_signatures(&batch_list_of(vec![signed_batch(signer), tampered])) {
+            Err(BatchError::InvalidBatch { batch_id: id, .. }) => assert_eq!(id, batch_id),
+            res => panic!("Expected invalid batch, got {:?}", res),
+        }
+
+        let mut tampered = signed_batch(signer);
+        let transaction = &mut tampered.mut_transactions()[0];
+        transaction.set_header_signature(
+            signer
+                .sign(b"another header")
+                .expect("Unable to sign header")
+                .as_hex(),
+        );
+        let transaction_id = transaction.get_header_signature().to_string();
+
+        match verify_signatures(&batch_list_of(vec![tampered])) {
+            Err(BatchError::InvalidTransaction {
+                transaction_id: id, ..
+            }) => assert_eq!(id, transaction_id),
+            res => panic!("Expected invalid transaction, got {:?}", res),
+        }
+    }
+
+    fn transaction_ids(batch_list: &BatchList) -> Vec<Vec<String>> {
+        batch_list
+            .get_batches()
+            .iter()
+            .map(|batch| {
+                batch
+                    .get_transactions()
+                    .iter()
+                    .map(|transaction| transaction.get_header_signature().to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Verifies that transactions are packed in order into signed batches of up to the
+    /// maximum, with fewer transactions than the maximum making a single batch and no
+    /// transactions an empty list.
+    #[test]
+    fn test_pack() {
+        let context = Secp256k1Context::new();
+        let signer = context.new_signer(context.new_random_private_key());
+        let signer = &*signer;
+
+        let transactions = (0..5)
+            .map(|i| signed_transaction(signer, &i.to_string()))
+            .collect::<Vec<_>>();
+        let ids = transactions
+            .iter()
+            .map(|transaction| transaction.get_header_signature().to_string())
+            .collect::<Vec<_>>();
+
+        let batch_list = pack(transactions.clone(), 2, signer).expect("Unable to pack");
+        assert_eq!(
+            transaction_ids(&batch_list),
+            vec![ids[0..2].to_vec(), ids[2..4].to_vec(), ids[4..].to_vec()]
+        );
+        assert_eq!(verify_signatures(&batch_list), Ok(()));
+        for batch in batch_list.get_batches() {
+            let header: BatchHeader =
+                Message::parse_from_bytes(batch.get_header()).expect("Unable to parse header");
+            assert_eq!(
+                header.get_transaction_ids().to_vec(),
+                batch
+                    .get_transactions()
+                    .iter()
+                    .map(|transaction| transaction.get_header_signature().to_string())
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        let batch_list = pack(transactions, 10, signer).expect("Unable to pack");
+        assert_eq!(transaction_ids(&batch_list), vec![ids]);
+
+        let batch_list = pack(vec![], 10, signer).expect("Unable to pack");
+        assert!(batch_list.get_batches().is_empty());
+    }
+}
@@ -0,0 +1,303 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A circuit breaker that stops calling a backend which keeps failing to connect.
+
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::future::{self, Future, FutureExt};
+
+use super::{
+    BackendClient, BackendClientError, BackendHealth, BatchStatus, BatchStatuses, BatchSubmission,
+    SubmitBatches,
+};
+
+/// The state of a circuit breaker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Calls are passed to the backend
+    Closed,
+    /// Calls fail immediately until the cooldown has elapsed
+    Open,
+    /// A single trial call is being passed to the backend to test whether it has recovered
+    HalfOpen,
+}
+
+struct Breaker {
+    state: BreakerState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    cooldown: Duration,
+}
+
+/// A `BackendClient` that stops calling its backend after repeated connection failures.
+///
+/// After `failure_threshold` consecutive connection errors the breaker opens, and calls fail
+/// immediately with an internal error instead of waiting on a node that is down. Once the
+/// cooldown has elapsed, the next call is let through as a trial: if it succeeds the breaker
+/// closes, and if it fails to connect the breaker opens again with the cooldown doubled, up to
+/// the maximum cooldown.
+///
+/// Errors other than connection errors of any kind, such as rejected batches, show that the
+/// node is up and do not count as failures. Clones share the breaker's state.
+#[derive(Clone)]
+pub struct CircuitBreakerBackendClient {
+    client: Box<dyn BackendClient>,
+    breaker: Arc<Mutex<Breaker>>,
+    failure_threshold: u32,
+    cooldown: Duration,
+    max_cooldown: Duration,
+}
+
+impl CircuitBreakerBackendClient {
+    /// Wraps a client in a closed circuit breaker.
+    ///
+    /// The maximum cooldown defaults to ten times the initial cooldown.
+    pub fn new(client: Box<dyn BackendClient>, failure_threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreakerBackendClient {
+            client,
+            breaker: Arc::new(Mutex::new(Breaker {
+                state: BreakerState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                cooldown,
+            })),
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            max_cooldown: cooldown * 10,
+        }
+    }
+
+    /// Sets the longest cooldown the breaker backs off to.
+    pub fn with_max_cooldown(mut self, max_cooldown: Duration) -> Self {
+        self.max_cooldown = max_cooldown.max(self.cooldown);
+        self
+    }
+
+    /// Returns the current state of the breaker, for monitoring.
+    ///
+    /// An open breaker whose cooldown has elapsed is reported as open until the next call
+    /// tests the backend.
+    pub fn state(&self) -> BreakerState {
+        self.breaker
+            .lock()
+            .map(|breaker| breaker.state)
+            .unwrap_or(BreakerState::Open)
+    }
+
+    /// Returns whether a call may be passed to the backend, moving an open breaker whose
+    /// cooldown has elapsed to half-open.
+    fn allow_call(&self) -> Result<(), BackendClientError> {
+        let mut breaker = self.breaker.lock().map_err(|_| {
+            BackendClientError::InternalError("Circuit breaker lock was poisoned".into())
+        })?;
+
+        match breaker.state {
+            BreakerState::Closed => Ok(()),
+            BreakerState::Open
+                if breaker
+                    .opened_at
+                    .map(|opened_at| opened_at.elapsed() >= breaker.cooldown)
+                    .unwrap_or(true) =>
+            {
+                breaker.state = BreakerState::HalfOpen;
+                Ok(())
+            }
+            BreakerState::Open | BreakerState::HalfOpen => {
+                Err(BackendClientError::InternalError(format!(
+                    "The backend is unavailable after {} consecutive connection failures; \
+                    retrying after {:?}",
+                    breaker.consecutive_failures, breaker.cooldown
+                )))
+            }
+        }
+    }
+
+    /// Updates the breaker with the outcome of a call once it completes.
+    fn record<T>(
+        &self,
+        call: Pin<Box<dyn Future<Output = Result<T, BackendClientError>> + Send>>,
+    ) -> Pin<Box<dyn Future<Output = Result<T, BackendClientError>> + Send>>
+    where
+        T: Send + 'static,
+    {
+        let breaker = self.breaker.clone();
+        let failure_threshold = self.failure_threshold;
+        let (cooldown, max_cooldown) = (self.cooldown, self.max_cooldown);
+        call.map(move |res| {
+            if let Ok(mut breaker) = breaker.lock() {
+                match &res {
+                    Err(err) if err.is_connection_error() => {
+                        breaker.consecutive_failures += 1;
+                        if breaker.state == BreakerState::HalfOpen {
+                            breaker.cooldown = (breaker.cooldown * 2).min(max_cooldown);
+                            breaker.state = BreakerState::Open;
+                            breaker.opened_at = Some(Instant::now());
+                        } else if breaker.consecutive_failures >= failure_threshold {
+                            breaker.state = BreakerState::Open;
+                            breaker.opened_at = Some(Instant::now());
+                        }
+                    }
+                    _ => {
+                        breaker.consecutive_failures = 0;
+                        breaker.state = BreakerState::Closed;
+                        breaker.opened_at = None;
+                        breaker.cooldown = cooldown;
+                    }
+                }
+            }
+            res
+        })
+        .boxed()
+    }
+}
+
+impl BackendClient for CircuitBreakerBackendClient {
+    fn submit_batches(
+        &self,
+        msg: SubmitBatches,
+    ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>> {
+        if let Err(err) = self.allow_call() {
+            return future::err(err).boxed();
+        }
+        self.record(self.client.submit_batches(msg))
+    }
+
+    fn batch_status(
+        &self,
+        msg: BatchStatuses,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>> {
+        if let Err(err) = self.allow_call() {
+            return future::err(err).boxed();
+        }
+        self.record(self.client.batch_status(msg))
+    }
+
+    fn clone_box(&self) -> Box<dyn BackendClient> {
+        Box::new(self.clone())
+    }
+
+    fn requires_service_id(&self) -> bool {
+        self.client.requires_service_id()
+    }
+
+    fn health(&self) -> Pin<Box<dyn Future<Output = BackendHealth> + Send>> {
+        // Health checks bypass the breaker, so that they show whether the node has recovered
+        self.client.health()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use futures::executor::block_on;
+
+    /// A backend that fails to connect while `down` is set, and counts the calls it receives.
+    #[derive(Clone, Default)]
+    struct FlakyBackendClient {
+        down: Arc<AtomicBool>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl BackendClient for FlakyBackendClient {
+        fn submit_batches(
+            &self,
+            _: SubmitBatches,
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
+        {
+            unimplemented!()
+        }
+
+        fn batch_status(
+            &self,
+            _: BatchStatuses,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>
+        {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if self.down.load(Ordering::SeqCst) {
+                future::err(BackendClientError::ConnectionError("down".into())).boxed()
+            } else {
+                future::ok(vec![]).boxed()
+            }
+        }
+
+        fn clone_box(&self) -> Box<dyn BackendClient> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn batch_status(client: &dyn BackendClient) -> Result<Vec<BatchStatus>, BackendClientError> {
+        block_on(client.batch_status(BatchStatuses {
+            batch_ids: vec!["batch".into()],
+            wait: None,
+            service_id: None,
+            correlation_id: None,
+        }))
+    }
+
+    /// Verifies that the breaker opens after the configured number of consecutive connection
+    /// failures, and then fails calls without passing them to the backend.
+    #[test]
+    fn test_breaker_opens_after_consecutive_failures() {
+        let backend = FlakyBackendClient::default();
+        backend.down.store(true, Ordering::SeqCst);
+        let client = CircuitBreakerBackendClient::new(
+            Box::new(backend.clone()),
+            2,
+            Duration::from_secs(3600),
+        );
+
+        assert!(matches!(
+            batch_status(&client),
+            Err(BackendClientError::ConnectionError(_))
+        ));
+        assert_eq!(client.state(), BreakerState::Closed);
+        assert!(batch_status(&client).is_err());
+        assert_eq!(client.state(), BreakerState::Open);
+
+        assert!(matches!(
+            batch_status(&client),
+            Err(BackendClientError::InternalError(_))
+        ));
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 2);
+    }
+
+    /// Verifies that once the cooldown has elapsed a trial call is let through, which closes
+    /// the breaker if the backend has recovered and reopens it if not.
+    #[test]
+    fn test_breaker_half_opens_after_cooldown() {
+        let backend = FlakyBackendClient::default();
+        backend.down.store(true, Ordering::SeqCst);
+        let client = CircuitBreakerBackendClient::new(Box::new(backend.clone()), 1, Duration::ZERO);
+
+        assert!(batch_status(&client).is_err());
+        assert_eq!(client.state(), BreakerState::Open);
+
+        assert!(matches!(
+            batch_status(&client),
+            Err(BackendClientError::ConnectionError(_))
+        ));
+        assert_eq!(client.state(), BreakerState::Open);
+
+        backend.down.store(false, Ordering::SeqCst);
+        assert!(batch_status(&client).is_ok());
+        assert_eq!(client.state(), BreakerState::Closed);
+        assert_eq!(backend.calls.load(Ordering::SeqCst), 3);
+    }
+}
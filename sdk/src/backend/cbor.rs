@@ -0,0 +1,159 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of CBOR responses from a node.
+//!
+//! CBOR values are converted to their JSON equivalents and then deserialized, so the same
+//! `Deserialize` implementations are used for both formats.
+
+use std::io::Cursor;
+
+use cbor::value::{Bytes, Int, Key, Text, Value};
+use cbor::{Config, GenericDecoder};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value as JsonValue};
+
+/// Deserializes an instance of `T` from CBOR bytes.
+pub(super) fn from_slice<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, String> {
+    let value = GenericDecoder::new(Config::default(), Cursor::new(bytes))
+        .value()
+        .map_err(|err| err.to_string())?;
+
+    serde_json::from_value(to_json(value)?).map_err(|err| err.to_string())
+}
+
+/// Converts a CBOR value to JSON. Tags are dropped, and byte strings, simple values and
+/// non-finite floats, which have no JSON equivalent, are rejected.
+fn to_json(value: Value) -> Result<JsonValue, String> {
+    Ok(match value {
+        Value::Array(values) => JsonValue::Array(
+            values
+                .into_iter()
+                .map(to_json)
+                .collect::<Result<Vec<_>, _>>()?,
+        ),
+        Value::Bool(b) => JsonValue::Bool(b),
+        Value::Null | Value::Undefined => JsonValue::Null,
+        Value::F32(f) => float(f64::from(f))?,
+        Value::F64(f) => float(f)?,
+        Value::I8(i) => JsonValue::from(i),
+        Value::I16(i) => JsonValue::from(i),
+        Value::I32(i) => JsonValue::from(i),
+        Value::I64(i) => JsonValue::from(i),
+        Value::U8(u) => JsonValue::from(u),
+        Value::U16(u) => JsonValue::from(u),
+        Value::U32(u) => JsonValue::from(u),
+        Value::U64(u) => JsonValue::from(u),
+        Value::Int(int) => JsonValue::Number(int_number(&int)?),
+        Value::Map(entries) => JsonValue::Object(
+            entries
+                .into_iter()
+                .map(|(key, value)| Ok((key_string(key)?, to_json(value)?)))
+                .collect::<Result<Map<_, _>, String>>()?,
+        ),
+        Value::Tagged(_, value) => to_json(*value)?,
+        Value::Text(text) => JsonValue::String(text_string(text)),
+        Value::Bytes(_) => return Err("byte strings are not supported".into()),
+        Value::Simple(simple) => return Err(format!("unsupported simple value {:?}", simple)),
+        Value::Break => return Err("unexpected break".into()),
+    })
+}
+
+fn float(f: f64) -> Result<JsonValue, String> {
+    Number::from_f64(f)
+        .map(JsonValue::Number)
+        .ok_or_else(|| format!("unsupported float {}", f))
+}
+
+fn int_number(int: &Int) -> Result<Number, String> {
+    match (int.u64(), int.i64()) {
+        (Some(u), _) => Ok(u.into()),
+        (_, Some(i)) => Ok(i.into()),
+        _ => Err(format!("integer {:?} is out of range", int)),
+    }
+}
+
+fn key_string(key: Key) -> Result<String, String> {
+    match key {
+        Key::Text(text) => Ok(text_string(text)),
+        Key::Int(int) => Ok(int_number(&int)?.to_string()),
+        Key::Bool(b) => Ok(b.to_string()),
+        Key::Bytes(Bytes::Bytes(_)) | Key::Bytes(Bytes::Chunks(_)) => {
+            Err("byte string map keys are not supported".into())
+        }
+    }
+}
+
+fn text_string(text: Text) -> String {
+    match text {
+        Text::Text(s) => s,
+        Text::Chunks(chunks) => chunks.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use cbor::GenericEncoder;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Status {
+        id: String,
+        errors: Vec<i64>,
+    }
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut encoder = GenericEncoder::new(Vec::new());
+        encoder.value(value).unwrap();
+        encoder.into_inner().into_writer()
+    }
+
+    /// Verifies that a CBOR map is deserialized as if it were the equivalent JSON object.
+    #[test]
+    fn from_slice_decodes_structs() {
+        let mut map = BTreeMap::new();
+        map.insert(
+            Key::Text(Text::Text("id".into())),
+            Value::Text(Text::Text("abc".into())),
+        );
+        map.insert(
+            Key::Text(Text::Text("errors".into())),
+            Value::Array(vec![Value::U8(1), Value::I8(-2)]),
+        );
+
+        let status: Status = from_slice(&encode(&Value::Array(vec![Value::Map(map)])))
+            .map(|mut statuses: Vec<Status>| statuses.remove(0))
+            .unwrap();
+
+        assert_eq!(
+            status,
+            Status {
+                id: "abc".into(),
+                errors: vec![1, -2]
+            }
+        );
+    }
+
+    /// Verifies that values with no JSON equivalent, and invalid CBOR, are rejected.
+    #[test]
+    fn from_slice_rejects_unsupported_values() {
+        assert!(from_slice::<JsonValue>(&encode(&Value::Bytes(Bytes::Bytes(vec![1])))).is_err());
+        assert!(from_slice::<JsonValue>(&encode(&Value::F64(f64::NAN))).is_err());
+        assert!(from_slice::<JsonValue>(&[0xff, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Submission of batches only when the current state matches an expectation.
+
+use std::error::Error;
+use std::fmt;
+
+use futures::Future;
+
+use super::{BackendClient, BackendClientError, BatchSubmission, SubmitBatches};
+
+/// The result of checking a precondition against the current state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Precondition {
+    /// The state matches the expectation
+    Met,
+    /// The state does not match the expectation, for the given reason, e.g. "product 123 is at
+    /// version 4, expected 3"
+    Unmet(String),
+}
+
+/// An error returned by [`submit_if`].
+#[derive(Debug)]
+pub enum ConditionalSubmissionError {
+    /// The precondition was not met, so the batches were not submitted
+    Conflict(String),
+    /// The state could not be read to check the precondition, so the batches were not submitted
+    Check(BackendClientError),
+    /// The precondition was met, but the batches could not be submitted
+    Submit(BackendClientError),
+}
+
+impl Error for ConditionalSubmissionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConditionalSubmissionError::Conflict(_) => None,
+            ConditionalSubmissionError::Check(err) | ConditionalSubmissionError::Submit(err) => {
+                Some(err)
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConditionalSubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConditionalSubmissionError::Conflict(reason) => {
+                write!(f, "precondition not met: {}", reason)
+            }
+            ConditionalSubmissionError::Check(err) => {
+                write!(f, "failed to check precondition: {}", err)
+            }
+            ConditionalSubmissionError::Submit(err) => write!(f, "failed to submit: {}", err),
+        }
+    }
+}
+
+/// Submits batches only if `precondition` is met, for compare-and-swap style updates.
+///
+/// `precondition` reads the state the batches depend on, e.g. the current version of a
+/// product, and reports whether it matches what the batches were built against. If it does
+/// not, the batches are not submitted, so a client holding stale state learns of it without
+/// submitting batches that would be rejected.
+///
+/// The check and the submission are separate calls to the node, so the state can still change
+/// between them, and the batches can still be rejected or, if they do not check the state
+/// themselves, applied to state that no longer matches. This reduces the submissions made from
+/// stale state but does not replace the checks made by the smart contracts.
+///
+/// # Errors
+///
+/// Returns [`ConditionalSubmissionError::Conflict`] if the precondition is not met, and an
+/// error wrapping the [`BackendClientError`] if the check or the submission fails.
+pub async fn submit_if<F, Fut>(
+    client: &dyn BackendClient,
+    precondition: F,
+    submit_batches: SubmitBatches,
+) -> Result<BatchSubmission, ConditionalSubmissionError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<Precondition, BackendClientError>>,
+{
+    match precondition()
+        .await
+        .map_err(ConditionalSubmissionError::Check)?
+    {
+        Precondition::Met => client
+            .submit_batches(submit_batches)
+            .await
+            .map_err(ConditionalSubmissionError::Submit),
+        Precondition::Unmet(reason) => Err(ConditionalSubmissionError::Conflict(reason)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::Pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use futures::executor::block_on;
+    use futures::future::{self, FutureExt};
+    use sawtooth_sdk::messages::batch::BatchList;
+    use url::Url;
+
+    use crate::backend::{BatchStatus, BatchStatuses};
+
+    /// A backend that counts the submissions made to it.
+    #[derive(Clone, Default)]
+    struct CountingBackendClient {
+        submissions: Arc<AtomicUsize>,
+    }
+
+    impl BackendClient for CountingBackendClient {
+        fn submit_batches(
+            &self,
+            _: SubmitBatches,
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
+        {
+            self.submissions.fetch_add(1, Ordering::SeqCst);
+            future::ok(BatchSubmission::Accepted {
+                link: "/batch_statuses".into(),
+            })
+            .boxed()
+        }
+
+        fn batch_status(
+            &self,
+            _: BatchStatuses,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>
+        {
+            unimplemented!()
+        }
+
+        fn clone_box(&self) -> Box<dyn BackendClient> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn submit_batches() -> SubmitBatches {
+        SubmitBatches {
+            batch_list: BatchList::new(),
+            response_url: Url::parse("http://localhost/batch_statuses").unwrap(),
+            service_id: None,
+            correlation_id: None,
+        }
+    }
+
+    /// Verifies that the batches are submitted when the precondition is met, and not when it
+    /// is unmet or cannot be checked.
+    #[test]
+    fn test_submit_if() {
+        let client = CountingBackendClient::default();
+
+        let submission = block_on(submit_if(
+            &client,
+            || future::ok(Precondition::Met),
+            submit_batches(),
+        ))
+        .expect("Unable to submit");
+        assert_eq!(submission.link(), "/batch_statuses");
+        assert_eq!(client.submissions.load(Ordering::SeqCst), 1);
+
+        match block_on(submit_if(
+            &client,
+            || future::ok(Precondition::Unmet("version is 4, expected 3".into())),
+            submit_batches(),
+        )) {
+            Err(ConditionalSubmissionError::Conflict(reason)) => {
+                assert_eq!(reason, "version is 4, expected 3")
+            }
+            res => panic!("Expected conflict, got {:?}", res),
+        }
+
+        assert!(matches!(
+            block_on(submit_if(
+                &client,
+                || future::err(BackendClientError::ConnectionError("down".into())),
+                submit_batches(),
+            )),
+            Err(ConditionalSubmissionError::Check(
+                BackendClientError::ConnectionError(_)
+            ))
+        ));
+
+        assert_eq!(client.submissions.load(Ordering::SeqCst), 1);
+    }
+}
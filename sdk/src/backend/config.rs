@@ -0,0 +1,219 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Configuration of a backend client, loaded from environment variables.
+
+use std::env;
+use std::fmt;
+use std::time::Duration;
+
+use crate::error::InvalidArgumentError;
+
+use super::redact::{redact_url, REDACTED};
+
+/// The URL of the node's REST API. Required.
+pub const GRID_NODE_URL_ENV: &str = "GRID_NODE_URL";
+/// The authorization sent to the node with every request. Required.
+pub const GRID_AUTH_ENV: &str = "GRID_AUTH";
+/// The number of seconds to wait for the node to respond to a request. Optional; requests do not
+/// time out if it is not set.
+pub const GRID_REQUEST_TIMEOUT_ENV: &str = "GRID_REQUEST_TIMEOUT";
+/// A comma-separated list of the URLs of redundant nodes to fail over to. Optional.
+pub const GRID_FAILOVER_NODE_URLS_ENV: &str = "GRID_FAILOVER_NODE_URLS";
+/// The Grid protocol version sent to the node with every request. Optional; defaults to
+/// `DEFAULT_PROTOCOL_VERSION`.
+pub const GRID_PROTOCOL_VERSION_ENV: &str = "GRID_PROTOCOL_VERSION";
+
+/// The Grid protocol version used if none is configured.
+pub const DEFAULT_PROTOCOL_VERSION: &str = "1";
+
+/// The configuration of a backend client.
+///
+/// Its `Debug` output redacts the authorization and any credentials in the node URLs, so that
+/// the configuration can be logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct BackendConfig {
+    node_url: String,
+    authorization: String,
+    request_timeout: Option<Duration>,
+    failover_node_urls: Vec<String>,
+    protocol_version: String,
+}
+
+impl BackendConfig {
+    /// Loads the configuration from the `GRID_*` environment variables described by the
+    /// `*_ENV` constants in this module.
+    ///
+    /// Returns an error naming the variable if a required variable is not set, or a variable is
+    /// set to an invalid value.
+    pub fn from_env() -> Result<Self, InvalidArgumentError> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, InvalidArgumentError> {
+        let required = |name: &str| {
+            var(name)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| InvalidArgumentError::new(name.into(), "must be set".into()))
+        };
+
+        let request_timeout = match var(GRID_REQUEST_TIMEOUT_ENV) {
+            Some(timeout) => match timeout.parse::<u64>() {
+                Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+                _ => {
+                    return Err(InvalidArgumentError::new(
+                        GRID_REQUEST_TIMEOUT_ENV.into(),
+                        format!(
+                            "must be a number of seconds greater than 0, was {}",
+                            timeout
+                        ),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        let failover_node_urls = var(GRID_FAILOVER_NODE_URLS_ENV)
+            .map(|urls| {
+                urls.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            node_url: required(GRID_NODE_URL_ENV)?,
+            authorization: required(GRID_AUTH_ENV)?,
+            request_timeout,
+            failover_node_urls,
+            protocol_version: var(GRID_PROTOCOL_VERSION_ENV)
+                .unwrap_or_else(|| DEFAULT_PROTOCOL_VERSION.into()),
+        })
+    }
+
+    /// Returns the URL of the node's REST API
+    pub fn node_url(&self) -> &str {
+        &self.node_url
+    }
+
+    /// Returns the authorization sent to the node with every request
+    pub fn authorization(&self) -> &str {
+        &self.authorization
+    }
+
+    /// Returns how long to wait for the node to respond to a request, if requests time out
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    /// Returns the URLs of the redundant nodes to fail over to, in order
+    pub fn failover_node_urls(&self) -> &[String] {
+        &self.failover_node_urls
+    }
+
+    /// Returns the Grid protocol version sent to the node with every request
+    pub fn protocol_version(&self) -> &str {
+        &self.protocol_version
+    }
+}
+
+impl fmt::Debug for BackendConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BackendConfig")
+            .field("node_url", &redact_url(&self.node_url))
+            .field("authorization", &REDACTED)
+            .field("request_timeout", &self.request_timeout)
+            .field(
+                "failover_node_urls",
+                &self
+                    .failover_node_urls
+                    .iter()
+                    .map(|url| redact_url(url))
+                    .collect::<Vec<_>>(),
+            )
+            .field("protocol_version", &self.protocol_version)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+
+    fn from_vars(vars: &[(&str, &str)]) -> Result<BackendConfig, InvalidArgumentError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        BackendConfig::from_vars(|name| vars.get(name).cloned())
+    }
+
+    /// Verifies that every variable is loaded, and that optional variables have defaults.
+    #[test]
+    fn test_from_vars() {
+        let config = from_vars(&[
+            (GRID_NODE_URL_ENV, "http://node-1:8085"),
+            (GRID_AUTH_ENV, "Bearer token"),
+            (GRID_REQUEST_TIMEOUT_ENV, "30"),
+            (
+                GRID_FAILOVER_NODE_URLS_ENV,
+                "http://node-2:8085, http://node-3:8085",
+            ),
+            (GRID_PROTOCOL_VERSION_ENV, "2"),
+        ])
+        .expect("Unable to load config");
+
+        assert_eq!(config.node_url(), "http://node-1:8085");
+        assert_eq!(config.authorization(), "Bearer token");
+        assert_eq!(config.request_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(
+            config.failover_node_urls(),
+            ["http://node-2:8085", "http://node-3:8085"]
+        );
+        assert_eq!(config.protocol_version(), "2");
+
+        let config = from_vars(&[
+            (GRID_NODE_URL_ENV, "http://node-1:8085"),
+            (GRID_AUTH_ENV, "Bearer token"),
+        ])
+        .expect("Unable to load config");
+
+        assert_eq!(config.request_timeout(), None);
+        assert!(config.failover_node_urls().is_empty());
+        assert_eq!(config.protocol_version(), DEFAULT_PROTOCOL_VERSION);
+    }
+
+    /// Verifies that missing required variables and invalid timeouts are reported with the name
+    /// of the variable.
+    #[test]
+    fn test_from_vars_errors_name_variable() {
+        let err = from_vars(&[(GRID_AUTH_ENV, "Bearer token")]).unwrap_err();
+        assert_eq!(err.argument(), GRID_NODE_URL_ENV);
+
+        let err = from_vars(&[(GRID_NODE_URL_ENV, "http://node-1:8085")]).unwrap_err();
+        assert_eq!(err.argument(), GRID_AUTH_ENV);
+
+        let err = from_vars(&[
+            (GRID_NODE_URL_ENV, "http://node-1:8085"),
+            (GRID_AUTH_ENV, "Bearer token"),
+            (GRID_REQUEST_TIMEOUT_ENV, "soon"),
+        ])
+        .unwrap_err();
+        assert_eq!(err.argument(), GRID_REQUEST_TIMEOUT_ENV);
+    }
+}
@@ -0,0 +1,399 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Submission of a batch list that waits for its batches to be committed, within one overall
+//! deadline.
+
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use futures::future::{self, Either, Future};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    BackendClient, BackendClientError, BatchStatus, BatchStatuses, SubmitBatches, WaitTime,
+};
+
+/// The status reported for batches that have been submitted but not yet checked.
+const PENDING_STATUS: &str = "PENDING";
+
+/// The least time between the starts of two status requests, so that a node that answers
+/// without waiting is not polled in a tight loop.
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// An error returned by [`submit_and_await`].
+#[derive(Debug)]
+pub enum DeadlineSubmissionError {
+    /// The batch list could not be submitted
+    Submit(BackendClientError),
+    /// The statuses of the batches could not be read
+    Status(BackendClientError),
+    /// The deadline passed before every batch reached a final status; holds the last statuses
+    /// read, or the batches as pending if none were read. It is empty if the deadline passed
+    /// before the submission completed, in which case the node may or may not have received
+    /// the batches.
+    TimedOut(Vec<BatchStatus>),
+}
+
+impl Error for DeadlineSubmissionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeadlineSubmissionError::Submit(err) | DeadlineSubmissionError::Status(err) => {
+                Some(err)
+            }
+            DeadlineSubmissionError::TimedOut(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for DeadlineSubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeadlineSubmissionError::Submit(err) => write!(f, "failed to submit: {}", err),
+            DeadlineSubmissionError::Status(err) => {
+                write!(f, "failed to get batch status: {}", err)
+            }
+            DeadlineSubmissionError::TimedOut(statuses) if statuses.is_empty() => {
+                f.write_str("deadline passed before the batches were submitted")
+            }
+            DeadlineSubmissionError::TimedOut(statuses) => write!(
+                f,
+                "deadline passed waiting for batches to reach a final status: {}",
+                statuses
+                    .iter()
+                    .filter(|status| !is_final(status))
+                    .map(|status| status.id.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+/// Submits a batch list and waits until each of its batches is committed or invalid, all
+/// before `deadline`.
+///
+/// This gives callers a single bound on how long to wait, rather than separate timeouts for
+/// the submission and the status requests. The time left until the deadline is divided as
+/// follows:
+///
+/// * The submission may take all of it; if the deadline passes first, it is cancelled.
+/// * Each status request then asks the node to wait for the time still left, in whole seconds
+///   rounded down, so that the node answers before the deadline. A node may answer sooner,
+///   e.g. if it caps how long it waits, in which case the statuses are requested again with
+///   what is left, though no sooner than 250 milliseconds after the previous request was
+///   made. Once less than a second is left, a last request is made without waiting.
+/// * A status request that has not been answered by the deadline is abandoned.
+///
+/// Returns the final statuses of the batches, which may include invalid batches.
+///
+/// # Errors
+///
+/// Returns a [`DeadlineSubmissionError`] if the submission or a status request fails, or if
+/// the deadline passes first, in which case the error holds the last-known statuses.
+pub async fn submit_and_await(
+    client: &dyn BackendClient,
+    submit_batches: SubmitBatches,
+    deadline: Instant,
+) -> Result<Vec<BatchStatus>, DeadlineSubmissionError> {
+    if deadline <= Instant::now() {
+        return Err(DeadlineSubmissionError::TimedOut(vec![]));
+    }
+
+    let batch_ids: Vec<String> = submit_batches
+        .batch_list
+        .get_batches()
+        .iter()
+        .map(|batch| batch.get_header_signature().to_string())
+        .collect();
+    let service_id = submit_batches.service_id.clone();
+    let correlation_id = submit_batches.correlation_id.clone();
+
+    let (expired, _timer) = cancel_at(deadline);
+
+    match client
+        .submit_batches_with_cancellation(submit_batches, expired.clone())
+        .await
+    {
+        Ok(_) => (),
+        Err(_) if expired.is_cancelled() => return Err(DeadlineSubmissionError::TimedOut(vec![])),
+        Err(err) => return Err(DeadlineSubmissionError::Submit(err)),
+    }
+
+    let mut statuses: Vec<BatchStatus> = batch_ids
+        .iter()
+        .map(|id| BatchStatus {
+            id: id.clone(),
+            invalid_transactions: vec![],
+            status: PENDING_STATUS.to_string(),
+        })
+        .collect();
+
+    loop {
+        let requested_at = Instant::now();
+        let remaining = deadline.saturating_duration_since(requested_at);
+        let request = client.batch_status(BatchStatuses {
+            batch_ids: batch_ids.clone(),
+            wait: Some(WaitTime::from_secs(remaining.as_secs())),
+            service_id: service_id.clone(),
+            correlation_id: correlation_id.clone(),
+        });
+
+        statuses = match before(&expired, request).await {
+            Some(Ok(read)) => read,
+            Some(Err(err)) => return Err(DeadlineSubmissionError::Status(err)),
+            None => return Err(DeadlineSubmissionError::TimedOut(statuses)),
+        };
+
+        if statuses.iter().all(is_final) {
+            return Ok(statuses);
+        }
+        if remaining.as_secs() == 0 {
+            return Err(DeadlineSubmissionError::TimedOut(statuses));
+        }
+
+        let next_request = std::cmp::min(requested_at + MIN_POLL_INTERVAL, deadline);
+        if next_request > Instant::now() {
+            let (elapsed, _timer) = cancel_at(next_request);
+            elapsed.cancelled().await;
+        }
+    }
+}
+
+/// Returns whether a batch is committed or invalid, which it will then remain.
+fn is_final(status: &BatchStatus) -> bool {
+    status.status.eq_ignore_ascii_case("committed") || status.status.eq_ignore_ascii_case("invalid")
+}
+
+/// Returns a token that is cancelled at the deadline, along with a guard that stops the timer
+/// when dropped.
+///
+/// The timer runs on its own thread, so that deadlines are kept whichever runtime, if any, the
+/// submission is polled on.
+fn cancel_at(deadline: Instant) -> (CancellationToken, mpsc::Sender<()>) {
+    let token = CancellationToken::new();
+    let (guard, dropped) = mpsc::channel::<()>();
+
+    let timer = token.clone();
+    thread::spawn(move || {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if let Err(RecvTimeoutError::Timeout) = dropped.recv_timeout(remaining) {
+            timer.cancel();
+        }
+    });
+
+    (token, guard)
+}
+
+/// Resolves with the output of `fut`, or with `None` if `expired` is cancelled first.
+async fn before<F: Future>(expired: &CancellationToken, fut: F) -> Option<F::Output> {
+    let cancelled = expired.cancelled();
+    futures::pin_mut!(cancelled);
+    futures::pin_mut!(fut);
+
+    match future::select(fut, cancelled).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+
+    use futures::executor::block_on;
+    use futures::future::FutureExt;
+    use protobuf::RepeatedField;
+    use sawtooth_sdk::messages::batch::{Batch, BatchList};
+    use url::Url;
+
+    use crate::backend::BatchSubmission;
+
+    /// A backend that records the wait of each status request, and reports its batches as
+    /// pending until `pending_polls` requests have been made. A backend with `hang` set never
+    /// completes a submission.
+    #[derive(Clone, Default)]
+    struct PollingBackendClient {
+        waits: Arc<Mutex<Vec<u64>>>,
+        pending_polls: usize,
+        hang: bool,
+    }
+
+    impl BackendClient for PollingBackendClient {
+        fn submit_batches(
+            &self,
+            _: SubmitBatches,
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
+        {
+            if self.hang {
+                return future::pending().boxed();
+            }
+            future::ok(BatchSubmission::Accepted {
+                link: "/batch_statuses".into(),
+            })
+            .boxed()
+        }
+
+        fn batch_status(
+            &self,
+            batch_statuses: BatchStatuses,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>
+        {
+            let mut waits = self.waits.lock().unwrap();
+            waits.push(batch_statuses.wait.map(|wait| wait.as_secs()).unwrap_or(0));
+            let status = if waits.len() > self.pending_polls {
+                "COMMITTED"
+            } else {
+                PENDING_STATUS
+            };
+
+            let statuses = batch_statuses
+                .batch_ids
+                .into_iter()
+                .map(|id| BatchStatus {
+                    id,
+                    invalid_transactions: vec![],
+                    status: status.into(),
+                })
+                .collect();
+            future::ok(statuses).boxed()
+        }
+
+        fn clone_box(&self) -> Box<dyn BackendClient> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn submit_batches(batch_ids: &[&str]) -> SubmitBatches {
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(
+            batch_ids
+                .iter()
+                .map(|id| {
+                    let mut batch = Batch::new();
+                    batch.set_header_signature(id.to_string());
+                    batch
+                })
+                .collect(),
+        ));
+        SubmitBatches {
+            batch_list,
+            response_url: Url::parse("http://localhost/batch_statuses").unwrap(),
+            service_id: None,
+            correlation_id: None,
+        }
+    }
+
+    /// Verifies that the batches' statuses are requested again until they are final, each
+    /// time waiting for no longer than the time left before the deadline.
+    #[test]
+    fn test_submit_and_await() {
+        let client = PollingBackendClient {
+            pending_polls: 1,
+            ..Default::default()
+        };
+
+        let statuses = block_on(submit_and_await(
+            &client,
+            submit_batches(&["a", "b"]),
+            Instant::now() + Duration::from_millis(10_500),
+        ))
+        .expect("Unable to submit");
+
+        assert_eq!(
+            statuses.iter().map(|s| s.id.as_str()).collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert!(statuses.iter().all(|s| s.status == "COMMITTED"));
+
+        let waits = client.waits.lock().unwrap();
+        assert_eq!(waits.len(), 2);
+        assert!(waits.iter().all(|wait| *wait <= 10));
+    }
+
+    /// Verifies that a submission whose batches are still pending at the deadline times out
+    /// with their last-known statuses.
+    #[test]
+    fn test_submit_and_await_times_out_with_statuses() {
+        let client = PollingBackendClient {
+            pending_polls: usize::MAX,
+            ..Default::default()
+        };
+
+        let err = block_on(submit_and_await(
+            &client,
+            submit_batches(&["a"]),
+            Instant::now() + Duration::from_millis(50),
+        ))
+        .unwrap_err();
+
+        match err {
+            DeadlineSubmissionError::TimedOut(statuses) => {
+                assert_eq!(statuses.len(), 1);
+                assert_eq!(statuses[0].status, PENDING_STATUS);
+            }
+            err => panic!("Expected a timeout, got {}", err),
+        }
+    }
+
+    /// Verifies that a submission that has not completed by the deadline is abandoned.
+    #[test]
+    fn test_submit_and_await_times_out_submitting() {
+        let client = PollingBackendClient {
+            hang: true,
+            ..Default::default()
+        };
+
+        let err = block_on(submit_and_await(
+            &client,
+            submit_batches(&["a"]),
+            Instant::now() + Duration::from_millis(50),
+        ))
+        .unwrap_err();
+
+        assert!(
+            matches!(err, DeadlineSubmissionError::TimedOut(ref statuses) if statuses.is_empty())
+        );
+        assert!(client.waits.lock().unwrap().is_empty());
+    }
+
+    /// Verifies that the statuses of pending batches are polled no more often than the minimum
+    /// interval, even if the node answers without waiting.
+    #[test]
+    fn test_submit_and_await_bounds_polls() {
+        let client = PollingBackendClient {
+            pending_polls: usize::MAX,
+            ..Default::default()
+        };
+
+        let err = block_on(submit_and_await(
+            &client,
+            submit_batches(&["a"]),
+            Instant::now() + Duration::from_millis(1_500),
+        ))
+        .unwrap_err();
+
+        assert!(matches!(err, DeadlineSubmissionError::TimedOut(_)));
+
+        let polls = client.waits.lock().unwrap().len();
+        assert!(polls >= 2, "polled {} times", polls);
+        assert!(polls <= 7, "polled {} times", polls);
+    }
+}
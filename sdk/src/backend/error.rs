@@ -18,9 +18,32 @@ use std::fmt;
 #[derive(Debug)]
 pub enum BackendClientError {
     BadRequestError(String),
+    /// The node could not be connected to, for a reason not covered by a more specific variant
     ConnectionError(String),
+    /// The node's host name could not be resolved, e.g. because it is misconfigured
+    DnsError(String),
+    /// The node refused the connection, e.g. because it is down
+    ConnectionRefusedError(String),
+    /// A TLS session could not be established with the node, e.g. because its certificate is
+    /// not trusted
+    TlsError(String),
     InternalError(String),
     ResourceTemporarilyUnavailableError(String),
+    /// The call was cancelled by its caller before it completed
+    Cancelled(String),
+}
+
+impl BackendClientError {
+    /// Returns whether the node could not be connected to, whatever the reason.
+    pub fn is_connection_error(&self) -> bool {
+        matches!(
+            self,
+            BackendClientError::ConnectionError(_)
+                | BackendClientError::DnsError(_)
+                | BackendClientError::ConnectionRefusedError(_)
+                | BackendClientError::TlsError(_)
+        )
+    }
 }
 
 impl Error for BackendClientError {
@@ -34,8 +57,12 @@ impl fmt::Display for BackendClientError {
         match self {
             BackendClientError::BadRequestError(err) => write!(f, "{}", err),
             BackendClientError::ConnectionError(err) => write!(f, "{}", err),
+            BackendClientError::DnsError(err) => write!(f, "{}", err),
+            BackendClientError::ConnectionRefusedError(err) => write!(f, "{}", err),
+            BackendClientError::TlsError(err) => write!(f, "{}", err),
             BackendClientError::InternalError(err) => write!(f, "{}", err),
             BackendClientError::ResourceTemporarilyUnavailableError(err) => write!(f, "{}", err),
+            BackendClientError::Cancelled(err) => write!(f, "{}", err),
         }
     }
 }
@@ -0,0 +1,44 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reports of whether a backend client can reach its node.
+
+use std::time::Duration;
+
+/// The outcome of checking that a client can reach its node, for diagnosing connectivity.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BackendHealth {
+    /// Whether the node responded, even if with an error
+    pub reachable: bool,
+    /// The URL of the node checked, for backends reached over HTTP
+    pub node_url: Option<String>,
+    /// The protocol version the client requests from the node
+    pub protocol_version: Option<String>,
+    /// The version the node reported for itself, if it did
+    pub node_version: Option<String>,
+    /// The time taken for the node to respond, or for the check to fail
+    pub latency: Option<Duration>,
+    /// Why the node could not be reached, or the error it responded with
+    pub last_error: Option<String>,
+}
+
+impl BackendHealth {
+    /// Returns the report of a backend that cannot check its connectivity.
+    pub fn unsupported() -> Self {
+        BackendHealth {
+            last_error: Some("The backend does not support health checks".to_string()),
+            ..Default::default()
+        }
+    }
+}
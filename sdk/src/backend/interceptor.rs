@@ -0,0 +1,147 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hooks that see the requests sent to a node and the responses received.
+
+use reqwest::header::HeaderValue;
+use reqwest::{Request, Response};
+use uuid::Uuid;
+
+use super::redact::{redact_header, redact_url};
+
+/// The header the `RequestIdInterceptor` sends each request's id in.
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+/// Sees each request the client sends to a node, and the response to it.
+///
+/// Interceptors are called for every attempt, so a request that is retried or failed over to
+/// another node is seen once per attempt. They are called from async code, so they should
+/// return quickly. Both methods do nothing by default.
+pub trait Interceptor: Send + Sync {
+    /// Called with each request just before it is sent, after it has been signed.
+    ///
+    /// Headers added here are sent, but are not covered by a request signature.
+    fn on_request(&self, _request: &mut Request) {}
+
+    /// Called with the status and headers of each response, before its body is read.
+    ///
+    /// Requests that fail without a response, e.g. because the node cannot be connected to,
+    /// have no response to observe.
+    fn on_response(&self, _response: &Response) {}
+}
+
+/// An `Interceptor` that gives each request a unique id, so that it can be found in the logs of
+/// the node and of any proxy in between.
+///
+/// The id is a random UUID sent in the [`REQUEST_ID_HEADER`] header, unlike the correlation id,
+/// which is shared by every request made for one submission. Requests that already carry the
+/// header keep their id.
+#[derive(Clone, Debug, Default)]
+pub struct RequestIdInterceptor;
+
+impl RequestIdInterceptor {
+    pub fn new() -> Self {
+        RequestIdInterceptor
+    }
+}
+
+impl Interceptor for RequestIdInterceptor {
+    fn on_request(&self, request: &mut Request) {
+        let request_id = Uuid::new_v4().to_string();
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            request
+                .headers_mut()
+                .entry(REQUEST_ID_HEADER)
+                .or_insert(value);
+        }
+    }
+}
+
+/// An `Interceptor` that logs each request and the status of its response at debug level.
+///
+/// Credentials in URLs, sensitive query parameters such as `token`, and the values of headers
+/// such as `Authorization` are redacted before they are logged.
+#[derive(Clone, Debug, Default)]
+pub struct LoggingInterceptor;
+
+impl LoggingInterceptor {
+    pub fn new() -> Self {
+        LoggingInterceptor
+    }
+}
+
+impl Interceptor for LoggingInterceptor {
+    fn on_request(&self, request: &mut Request) {
+        debug!("Sending {}", describe_request(request));
+    }
+
+    fn on_response(&self, response: &Response) {
+        debug!(
+            "Received {} from {}",
+            response.status(),
+            redact_url(response.url().as_str())
+        );
+    }
+}
+
+/// Describes a request by its method, URL and headers, with secrets redacted.
+fn describe_request(request: &Request) -> String {
+    let headers = request
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "{}: {}",
+                name,
+                redact_header(name.as_str(), value.to_str().unwrap_or("<binary>"))
+            )
+        })
+        .collect::<Vec<_>>();
+
+    format!(
+        "{} {} [{}]",
+        request.method(),
+        redact_url(request.url().as_str()),
+        headers.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use reqwest::{Client, Method};
+
+    /// Verifies that a logged request does not contain its authorization token or the token in
+    /// its query.
+    #[test]
+    fn test_describe_request_redacts_secrets() {
+        let request = Client::new()
+            .request(Method::GET, "http://node:8080/status?token=query-secret")
+            .header("Authorization", "Bearer header-secret")
+            .header("X-Request-Id", "1234")
+            .build()
+            .expect("Unable to build request");
+
+        let description = describe_request(&request);
+
+        assert!(!description.contains("header-secret"), "{}", description);
+        assert!(!description.contains("query-secret"), "{}", description);
+        assert!(
+            description.contains("x-request-id: 1234"),
+            "{}",
+            description
+        );
+    }
+}
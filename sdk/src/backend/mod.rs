@@ -12,32 +12,133 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod batch;
+mod breaker;
+#[cfg(feature = "backend-splinter")]
+mod cbor;
+mod conditional;
+pub mod config;
+mod deadline;
 mod error;
+mod health;
+#[cfg(feature = "backend-splinter")]
+mod interceptor;
+mod ordered;
+#[cfg(feature = "backend-splinter")]
+mod receipt;
+mod redact;
+#[cfg(feature = "backend-splinter")]
+mod retry;
 #[cfg(feature = "backend-sawtooth")]
 pub mod sawtooth;
 #[cfg(feature = "backend-splinter")]
+mod serializer;
+#[cfg(feature = "backend-splinter")]
+mod signing;
+#[cfg(feature = "backend-splinter")]
 mod splinter;
+mod summary;
+#[cfg(feature = "backend-splinter")]
+mod token;
+mod wait;
 
 use std::pin::Pin;
 
+use futures::future::Either;
 use futures::prelude::*;
 use sawtooth_sdk::messages::batch::BatchList;
 use sawtooth_sdk::messages::client_batch_submit::ClientBatchStatus;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+use crate::error::InvalidArgumentError;
+
+pub use breaker::{BreakerState, CircuitBreakerBackendClient};
+pub use conditional::{submit_if, ConditionalSubmissionError, Precondition};
+pub use config::BackendConfig;
+pub use deadline::{submit_and_await, DeadlineSubmissionError};
 pub use error::BackendClientError;
+pub use health::BackendHealth;
+#[cfg(feature = "backend-splinter")]
+pub use interceptor::{Interceptor, LoggingInterceptor, RequestIdInterceptor, REQUEST_ID_HEADER};
+pub use ordered::{submit_ordered, OrderedSubmissionError, StepFailure};
+#[cfg(feature = "backend-splinter")]
+pub use receipt::{BatchReceipt, ReceiptEvent, StateChange, TransactionReceipt, TransactionResult};
+pub use redact::{redact_header, redact_url, REDACTED};
+#[cfg(feature = "backend-splinter")]
+pub use retry::RetryBudget;
 #[cfg(feature = "backend-sawtooth")]
 pub use sawtooth::SawtoothBackendClient;
 #[cfg(feature = "backend-splinter")]
-pub use splinter::SplinterBackendClient;
+pub use serializer::{
+    BatchSerializer, ProtobufBatchSerializer, SerializedBatchList, OCTET_STREAM_CONTENT_TYPE,
+};
+#[cfg(feature = "backend-splinter")]
+pub use signing::RequestSigner;
+#[cfg(feature = "backend-splinter")]
+pub use splinter::{
+    BatchReceiptResults, BatchStatusFormat, HeightWait, RedirectPolicy, SplinterBackendClient,
+    SplinterBackendClientBuilder, TlsVersion, DEFAULT_MAX_RESPONSE_BODY_SIZE,
+    DEFAULT_SCABBARD_PREFIX,
+};
+pub use summary::SubmissionSummary;
+#[cfg(feature = "backend-splinter")]
+pub use token::{StaticTokenProvider, TokenProvider};
+pub use wait::WaitTime;
 
 pub const DEFAULT_TIME_OUT: u32 = 300; // Max timeout 300 seconds == 5 minutes
 
+/// The header used to carry a request's correlation id to and from the backend.
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// The header used to tell the backend when a request, in milliseconds since the Unix epoch,
+/// must be answered by, so that it can abandon work that cannot finish in time.
+pub const DEADLINE_HEADER: &str = "Grid-Deadline";
+
+/// A client that submits batches to, and reads batch statuses from, a Grid node.
+///
+/// Clients are `Send + Sync`, and so are the futures they return, so a single client may be
+/// shared as an `Arc<dyn BackendClient>` or `Box<dyn BackendClient>` and called from any thread
+/// of a multi-threaded runtime.
 pub trait BackendClient: Send + Sync + 'static {
+    /// Submits batches to the node, returning whether it received them or only queued them.
     fn submit_batches(
         &self,
         submit_batches: SubmitBatches,
-    ) -> Pin<Box<dyn Future<Output = Result<BatchStatusLink, BackendClientError>> + Send>>;
+    ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>;
+
+    /// Submits batches as `submit_batches` does, but gives up as soon as `cancellation` is
+    /// cancelled, e.g. when shutting down.
+    ///
+    /// Cancelling drops the submission wherever it has got to, including any request in flight
+    /// to the node, and resolves with a `Cancelled` error. The node may still have received the
+    /// batches if they were sent before the submission was cancelled. A submission whose token is
+    /// already cancelled is not started. `submit_batches` behaves as this does with a token that
+    /// is never cancelled.
+    fn submit_batches_with_cancellation(
+        &self,
+        submit_batches: SubmitBatches,
+        cancellation: CancellationToken,
+    ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>> {
+        let cancelled =
+            || BackendClientError::Cancelled("The batch submission was cancelled".to_string());
+
+        if cancellation.is_cancelled() {
+            return future::err(cancelled()).boxed();
+        }
+
+        let submission = self.submit_batches(submit_batches);
+        async move {
+            let cancellation = cancellation.cancelled();
+            futures::pin_mut!(cancellation);
+
+            match future::select(submission, cancellation).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => Err(cancelled()),
+            }
+        }
+        .boxed()
+    }
 
     fn batch_status(
         &self,
@@ -45,6 +146,20 @@ pub trait BackendClient: Send + Sync + 'static {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>;
 
     fn clone_box(&self) -> Box<dyn BackendClient>;
+
+    /// Returns whether submissions to this backend must name the service they are submitted to.
+    fn requires_service_id(&self) -> bool {
+        false
+    }
+
+    /// Checks whether the node can be reached, reporting the latency and any error, so that
+    /// connectivity problems can be diagnosed.
+    ///
+    /// The check never fails: an unreachable node is reported as such. Backends that cannot
+    /// check report `BackendHealth::unsupported`.
+    fn health(&self) -> Pin<Box<dyn Future<Output = BackendHealth> + Send>> {
+        future::ready(BackendHealth::unsupported()).boxed()
+    }
 }
 
 impl Clone for Box<dyn BackendClient> {
@@ -57,26 +172,141 @@ pub struct SubmitBatches {
     pub batch_list: BatchList,
     pub response_url: Url,
     pub service_id: Option<String>,
+    /// Identifies the submission in backend requests, logs, and error messages. Backends that
+    /// support correlation ids generate one if this is not set.
+    pub correlation_id: Option<String>,
+}
+
+/// Builds a `SubmitBatches`, checking that the fields a backend needs are set.
+///
+/// A batch list and response URL are always required. A builder created with
+/// [`SubmitBatchesBuilder::for_client`] also requires a service id if the client's backend does,
+/// so that a missing service id is reported when the submission is built rather than when it is
+/// sent.
+#[derive(Default)]
+pub struct SubmitBatchesBuilder {
+    batch_list: Option<BatchList>,
+    response_url: Option<Url>,
+    service_id: Option<String>,
+    correlation_id: Option<String>,
+    requires_service_id: bool,
+    verify_signatures: bool,
+}
+
+impl SubmitBatchesBuilder {
+    /// Creates a builder that does not require a service id.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a builder that requires a service id if the given client does.
+    pub fn for_client(client: &dyn BackendClient) -> Self {
+        SubmitBatchesBuilder {
+            requires_service_id: client.requires_service_id(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_batch_list(mut self, batch_list: BatchList) -> Self {
+        self.batch_list = Some(batch_list);
+        self
+    }
+
+    pub fn with_response_url(mut self, response_url: Url) -> Self {
+        self.response_url = Some(response_url);
+        self
+    }
+
+    pub fn with_service_id(mut self, service_id: String) -> Self {
+        self.service_id = Some(service_id);
+        self
+    }
+
+    pub fn with_correlation_id(mut self, correlation_id: String) -> Self {
+        self.correlation_id = Some(correlation_id);
+        self
+    }
+
+    /// Checks the signatures of the batch list when the submission is built, so that a batch
+    /// that a node would reject as mis-signed is never sent; see [`batch::verify_signatures`].
+    pub fn with_signature_verification(mut self) -> Self {
+        self.verify_signatures = true;
+        self
+    }
+
+    /// Builds the submission.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidArgumentError`] naming the first required field that is not set, or
+    /// naming `batch_list` if signature verification is enabled and a signature is invalid.
+    pub fn build(self) -> Result<SubmitBatches, InvalidArgumentError> {
+        let missing =
+            |field: &str| InvalidArgumentError::new(field.to_string(), "is required".to_string());
+
+        let batch_list = self.batch_list.ok_or_else(|| missing("batch_list"))?;
+        let response_url = self.response_url.ok_or_else(|| missing("response_url"))?;
+        if self.requires_service_id && self.service_id.is_none() {
+            return Err(InvalidArgumentError::new(
+                "service_id".to_string(),
+                "A service id must be provided".to_string(),
+            ));
+        }
+        if self.verify_signatures {
+            batch::verify_signatures(&batch_list).map_err(|err| {
+                InvalidArgumentError::new("batch_list".to_string(), err.to_string())
+            })?;
+        }
+
+        Ok(SubmitBatches {
+            batch_list,
+            response_url,
+            service_id: self.service_id,
+            correlation_id: self.correlation_id,
+        })
+    }
 }
 
 pub struct BatchStatuses {
     pub batch_ids: Vec<String>,
-    pub wait: Option<u32>,
+    pub wait: Option<WaitTime>,
     pub service_id: Option<String>,
+    /// Identifies the status request in backend requests, logs, and error messages. Backends
+    /// that support correlation ids generate one if this is not set.
+    pub correlation_id: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BatchStatus {
     pub id: String,
     pub invalid_transactions: Vec<InvalidTransaction>,
     pub status: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InvalidTransaction {
     pub id: String,
     pub message: String,
+    /// The transaction's error data, base64-encoded
     pub extended_data: String,
+    /// The transaction's error data as text, if it is a human-readable UTF-8 string and the
+    /// backend client was configured to decode it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_text: Option<String>,
+    /// The error decoded from the error data, if the transaction was rejected by a scabbard
+    /// service that reported a structured error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scabbard_error: Option<ScabbardError>,
+}
+
+/// A structured error reported by a scabbard service in the error data of an invalid
+/// transaction.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScabbardError {
+    /// Identifies the kind of failure, for handling it programmatically
+    pub code: String,
+    /// Describes the failure
+    pub message: String,
 }
 
 impl BatchStatus {
@@ -90,6 +320,8 @@ impl BatchStatus {
                     id: txn.get_transaction_id().to_string(),
                     message: txn.get_message().to_string(),
                     extended_data: base64::encode(txn.get_extended_data()),
+                    error_text: None,
+                    scabbard_error: None,
                 })
                 .collect(),
             status: format!("{:?}", proto.get_status()),
@@ -107,3 +339,167 @@ pub struct BatchStatusResponse {
 pub struct BatchStatusLink {
     pub link: String,
 }
+
+/// The outcome of a successful `submit_batches` call.
+///
+/// Some nodes accept submissions asynchronously, acknowledging batches before they are queued
+/// for consensus. Their statuses may be reported as unknown for a short time afterwards, so
+/// callers should allow for this before treating an unknown status as lost.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchSubmission {
+    /// The node has received the batches, and their statuses can be polled at `link` right away
+    Accepted { link: String },
+    /// The node has acknowledged the batches but may not yet have queued them; the statuses at
+    /// `link` may be unknown until it has
+    Queued { link: String },
+}
+
+impl BatchSubmission {
+    /// Returns the link the statuses of the submitted batches can be polled at.
+    pub fn link(&self) -> &str {
+        match self {
+            BatchSubmission::Accepted { link } | BatchSubmission::Queued { link } => link,
+        }
+    }
+
+    /// Returns whether the node may not yet have queued the batches.
+    pub fn is_queued(&self) -> bool {
+        matches!(self, BatchSubmission::Queued { .. })
+    }
+}
+
+impl From<BatchSubmission> for BatchStatusLink {
+    fn from(submission: BatchSubmission) -> Self {
+        match submission {
+            BatchSubmission::Accepted { link } | BatchSubmission::Queued { link } => {
+                BatchStatusLink { link }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    /// Verifies at compile time that backend clients, both as trait objects and as the
+    /// concrete clients, can be shared across threads.
+    #[test]
+    fn test_backend_client_is_send_sync() {
+        assert_send_sync::<dyn BackendClient>();
+        assert_send_sync::<Box<dyn BackendClient>>();
+        assert_send_sync::<Arc<dyn BackendClient>>();
+        assert_send_sync::<CircuitBreakerBackendClient>();
+        #[cfg(feature = "backend-sawtooth")]
+        assert_send_sync::<SawtoothBackendClient>();
+        #[cfg(feature = "backend-splinter")]
+        assert_send_sync::<SplinterBackendClient>();
+    }
+
+    /// A backend whose submissions never complete.
+    #[derive(Clone)]
+    struct PendingBackendClient;
+
+    impl BackendClient for PendingBackendClient {
+        fn submit_batches(
+            &self,
+            _: SubmitBatches,
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
+        {
+            future::pending().boxed()
+        }
+
+        fn batch_status(
+            &self,
+            _: BatchStatuses,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>
+        {
+            unimplemented!()
+        }
+
+        fn clone_box(&self) -> Box<dyn BackendClient> {
+            Box::new(self.clone())
+        }
+    }
+
+    /// Verifies that a submission in progress resolves with a cancellation error once its token
+    /// is cancelled, as does one whose token was cancelled before it started.
+    #[test]
+    fn test_submit_batches_with_cancellation() {
+        let submit_batches = || {
+            SubmitBatchesBuilder::new()
+                .with_batch_list(BatchList::new())
+                .with_response_url(response_url())
+                .build()
+                .expect("Unable to build submission")
+        };
+        let client = PendingBackendClient;
+
+        let cancellation = CancellationToken::new();
+        let (result, _) = futures::executor::block_on(future::join(
+            client.submit_batches_with_cancellation(submit_batches(), cancellation.clone()),
+            async { cancellation.cancel() },
+        ));
+        assert!(matches!(result, Err(BackendClientError::Cancelled(_))));
+
+        let result = futures::executor::block_on(
+            client.submit_batches_with_cancellation(submit_batches(), cancellation),
+        );
+        assert!(matches!(result, Err(BackendClientError::Cancelled(_))));
+    }
+
+    fn response_url() -> Url {
+        Url::parse("http://localhost/batch_statuses").expect("Unable to parse URL")
+    }
+
+    /// Verifies that a submission is only built once its required fields are set, and that
+    /// the error names the missing field.
+    #[test]
+    fn test_submit_batches_builder() {
+        let err = SubmitBatchesBuilder::new()
+            .with_response_url(response_url())
+            .build()
+            .err()
+            .expect("Built without a batch list");
+        assert_eq!(err.argument(), "batch_list");
+
+        let submit_batches = SubmitBatchesBuilder::new()
+            .with_batch_list(BatchList::new())
+            .with_response_url(response_url())
+            .with_correlation_id("correlation".into())
+            .build()
+            .expect("Unable to build submission");
+        assert_eq!(submit_batches.service_id, None);
+        assert_eq!(
+            submit_batches.correlation_id.as_deref(),
+            Some("correlation")
+        );
+    }
+
+    /// Verifies that a builder for a Splinter client requires a service id.
+    #[cfg(feature = "backend-splinter")]
+    #[test]
+    fn test_submit_batches_builder_requires_service_id() {
+        let client = SplinterBackendClient::new("http://localhost".into(), "auth".into())
+            .expect("Unable to build client");
+
+        let err = SubmitBatchesBuilder::for_client(&client)
+            .with_batch_list(BatchList::new())
+            .with_response_url(response_url())
+            .build()
+            .err()
+            .expect("Built without a service id");
+        assert_eq!(err.argument(), "service_id");
+
+        assert!(SubmitBatchesBuilder::for_client(&client)
+            .with_batch_list(BatchList::new())
+            .with_response_url(response_url())
+            .with_service_id("circuit::service".into())
+            .build()
+            .is_ok());
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Submission of batch lists that depend on one another, one at a time and in order.
+
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+use super::{
+    BackendClient, BackendClientError, BatchStatus, BatchStatuses, SubmitBatches, WaitTime,
+};
+
+/// The reason a step of an ordered submission failed.
+#[derive(Debug)]
+pub enum StepFailure {
+    /// The batch list could not be submitted
+    Submit(BackendClientError),
+    /// The statuses of the step's batches could not be read
+    Status(BackendClientError),
+    /// A batch of the step was not committed, e.g. because it was invalid
+    NotCommitted(BatchStatus),
+    /// The step's batches were not all committed within the step timeout; holds the IDs of
+    /// those that were still pending
+    TimedOut(Vec<String>),
+}
+
+/// An error returned by [`submit_ordered`], identifying the step that failed.
+///
+/// Every step before `step` was committed; `step` and the steps after it were not, or not
+/// fully, committed.
+#[derive(Debug)]
+pub struct OrderedSubmissionError {
+    /// The index of the failed step in the submitted list
+    pub step: usize,
+    pub failure: StepFailure,
+}
+
+impl Error for OrderedSubmissionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.failure {
+            StepFailure::Submit(err) | StepFailure::Status(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for OrderedSubmissionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.failure {
+            StepFailure::Submit(err) => write!(f, "step {}: failed to submit: {}", self.step, err),
+            StepFailure::Status(err) => {
+                write!(f, "step {}: failed to get batch status: {}", self.step, err)
+            }
+            StepFailure::NotCommitted(status) => write!(
+                f,
+                "step {}: batch {} was not committed, status is {}",
+                self.step, status.id, status.status
+            ),
+            StepFailure::TimedOut(pending) => write!(
+                f,
+                "step {}: timed out waiting for batches to commit: {}",
+                self.step,
+                pending.join(", ")
+            ),
+        }
+    }
+}
+
+/// Submits batch lists strictly in order, waiting for each list's batches to be committed
+/// before the next list is sent.
+///
+/// This is for submissions where a later batch depends on state written by an earlier one,
+/// which submitting the lists concurrently could reorder; it trades throughput for that
+/// guarantee. Each step waits up to `step_timeout` for its batches to commit.
+///
+/// Returns the statuses of each step's batches, in step order.
+///
+/// # Errors
+///
+/// Stops at the first step that fails to submit, has a batch that is not committed, or does
+/// not commit within the timeout, and returns an [`OrderedSubmissionError`] identifying it.
+/// The steps after it are not submitted.
+pub async fn submit_ordered(
+    client: &dyn BackendClient,
+    steps: Vec<SubmitBatches>,
+    step_timeout: Duration,
+) -> Result<Vec<Vec<BatchStatus>>, OrderedSubmissionError> {
+    let mut committed = Vec::with_capacity(steps.len());
+
+    for (step, submit_batches) in steps.into_iter().enumerate() {
+        let fail = |failure| OrderedSubmissionError { step, failure };
+
+        let batch_ids: Vec<String> = submit_batches
+            .batch_list
+            .get_batches()
+            .iter()
+            .map(|batch| batch.get_header_signature().to_string())
+            .collect();
+        let service_id = submit_batches.service_id.clone();
+        let correlation_id = submit_batches.correlation_id.clone();
+
+        client
+            .submit_batches(submit_batches)
+            .await
+            .map_err(|err| fail(StepFailure::Submit(err)))?;
+
+        let statuses = client
+            .batch_status(BatchStatuses {
+                batch_ids,
+                wait: Some(WaitTime::new(step_timeout)),
+                service_id,
+                correlation_id,
+            })
+            .await
+            .map_err(|err| fail(StepFailure::Status(err)))?;
+
+        let mut pending = vec![];
+        for status in &statuses {
+            if status.status.eq_ignore_ascii_case("pending") {
+                pending.push(status.id.clone());
+            } else if !status.status.eq_ignore_ascii_case("committed") {
+                return Err(fail(StepFailure::NotCommitted(status.clone())));
+            }
+        }
+        if !pending.is_empty() {
+            return Err(fail(StepFailure::TimedOut(pending)));
+        }
+
+        committed.push(statuses);
+    }
+
+    Ok(committed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+
+    use futures::executor::block_on;
+    use futures::future::{self, Future, FutureExt};
+    use protobuf::RepeatedField;
+    use sawtooth_sdk::messages::batch::{Batch, BatchList};
+    use url::Url;
+
+    use crate::backend::BatchSubmission;
+
+    /// A backend that records the batches submitted to it, and reports every batch as committed
+    /// except for the one named in `invalid`.
+    #[derive(Clone, Default)]
+    struct RecordingBackendClient {
+        submitted: Arc<Mutex<Vec<String>>>,
+        invalid: Option<String>,
+    }
+
+    impl BackendClient for RecordingBackendClient {
+        fn submit_batches(
+            &self,
+            submit_batches: SubmitBatches,
+        ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>>
+        {
+            self.submitted.lock().unwrap().extend(
+                submit_batches
+                    .batch_list
+                    .get_batches()
+                    .iter()
+                    .map(|batch| batch.get_header_signature().to_string()),
+            );
+            future::ok(BatchSubmission::Accepted {
+                link: "/batch_statuses".into(),
+            })
+            .boxed()
+        }
+
+        fn batch_status(
+            &self,
+            batch_statuses: BatchStatuses,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<BatchStatus>, BackendClientError>> + Send>>
+        {
+            let statuses = batch_statuses
+                .batch_ids
+                .into_iter()
+                .map(|id| BatchStatus {
+                    status: if self.invalid.as_ref() == Some(&id) {
+                        "INVALID".into()
+                    } else {
+                        "COMMITTED".into()
+                    },
+                    id,
+                    invalid_transactions: vec![],
+                })
+                .collect();
+            future::ok(statuses).boxed()
+        }
+
+        fn clone_box(&self) -> Box<dyn BackendClient> {
+            Box::new(self.clone())
+        }
+    }
+
+    fn step(batch_ids: &[&str]) -> SubmitBatches {
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(
+            batch_ids
+                .iter()
+                .map(|id| {
+                    let mut batch = Batch::new();
+                    batch.set_header_signature(id.to_string());
+                    batch
+                })
+                .collect(),
+        ));
+        SubmitBatches {
+            batch_list,
+            response_url: Url::parse("http://localhost/batch_statuses").unwrap(),
+            service_id: None,
+            correlation_id: None,
+        }
+    }
+
+    /// Verifies that steps are submitted in order and their statuses returned per step.
+    #[test]
+    fn test_submit_ordered() {
+        let client = RecordingBackendClient::default();
+
+        let statuses = block_on(submit_ordered(
+            &client,
+            vec![step(&["a", "b"]), step(&["c"])],
+            Duration::from_secs(1),
+        ))
+        .expect("Unable to submit");
+
+        assert_eq!(*client.submitted.lock().unwrap(), vec!["a", "b", "c"]);
+        assert_eq!(
+            statuses
+                .iter()
+                .map(|step| step.iter().map(|s| s.id.as_str()).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![vec!["a", "b"], vec!["c"]]
+        );
+    }
+
+    /// Verifies that submission stops at the first step with a batch that is not committed,
+    /// and the error identifies that step.
+    #[test]
+    fn test_submit_ordered_stops_at_failed_step() {
+        let client = RecordingBackendClient {
+            invalid: Some("b".into()),
+            ..Default::default()
+        };
+
+        let err = block_on(submit_ordered(
+            &client,
+            vec![step(&["a"]), step(&["b"]), step(&["c"])],
+            Duration::from_secs(1),
+        ))
+        .unwrap_err();
+
+        assert_eq!(err.step, 1);
+        assert!(matches!(err.failure, StepFailure::NotCommitted(ref status) if status.id == "b"));
+        assert_eq!(*client.submitted.lock().unwrap(), vec!["a", "b"]);
+    }
+}
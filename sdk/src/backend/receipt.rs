@@ -0,0 +1,66 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The complete receipt of a committed batch, as reported by the node.
+
+/// The receipt of a committed batch, with the receipt of each of its transactions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchReceipt {
+    pub batch_id: String,
+    pub transaction_receipts: Vec<TransactionReceipt>,
+}
+
+/// The receipt of a single transaction: its result, and the events, state changes and data it
+/// produced.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    pub transaction_id: String,
+    pub result: TransactionResult,
+    #[serde(default)]
+    pub events: Vec<ReceiptEvent>,
+    #[serde(default)]
+    pub state_changes: Vec<StateChange>,
+    #[serde(default)]
+    pub data: Vec<Vec<u8>>,
+}
+
+/// Whether a transaction was applied, or why it was not.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum TransactionResult {
+    Valid,
+    Invalid {
+        error_message: String,
+        #[serde(default)]
+        error_data: Vec<u8>,
+    },
+}
+
+/// An event emitted by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReceiptEvent {
+    pub event_type: String,
+    #[serde(default)]
+    pub attributes: Vec<(String, String)>,
+    #[serde(default)]
+    pub data: Vec<u8>,
+}
+
+/// A change a transaction made to state.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StateChange {
+    Set { key: String, value: Vec<u8> },
+    Delete { key: String },
+}
@@ -0,0 +1,135 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A budget shared by the retries of many calls, so that they cannot amplify an outage.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// A token bucket limiting how many retries may be made over a window of time.
+///
+/// Each retry spends a token, and tokens are refilled at a steady rate of `max_retries` per
+/// `window`, up to `max_retries`. When failures are isolated the budget stays full and every
+/// retry is made, but when many calls fail at once the budget runs out, and further calls fail
+/// fast on their first error instead of adding retries to a struggling backend.
+///
+/// Clones share the budget, so one budget can be given to several clients to limit their
+/// retries together.
+#[derive(Clone)]
+pub struct RetryBudget {
+    bucket: Arc<Mutex<Bucket>>,
+    max_retries: u32,
+    window: Duration,
+}
+
+impl RetryBudget {
+    /// Creates a full budget allowing up to `max_retries` retries per `window`.
+    pub fn new(max_retries: u32, window: Duration) -> Self {
+        RetryBudget {
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: f64::from(max_retries),
+                refilled_at: Instant::now(),
+            })),
+            max_retries,
+            window,
+        }
+    }
+
+    /// Returns the largest number of retries the budget allows per window.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Returns the window the budget is refilled over.
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Returns the number of retries that may currently be made, for monitoring.
+    pub fn remaining(&self) -> u32 {
+        self.bucket
+            .lock()
+            .map(|mut bucket| {
+                self.refill(&mut bucket);
+                bucket.tokens as u32
+            })
+            .unwrap_or(0)
+    }
+
+    /// Spends a token for a retry, returning whether the retry may be made.
+    pub(crate) fn try_spend(&self) -> bool {
+        match self.bucket.lock() {
+            Ok(mut bucket) => {
+                self.refill(&mut bucket);
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    true
+                } else {
+                    false
+                }
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Adds the tokens earned since the bucket was last refilled.
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let max_tokens = f64::from(self.max_retries);
+
+        if self.window.is_zero() {
+            bucket.tokens = max_tokens;
+        } else {
+            let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+            bucket.tokens =
+                (bucket.tokens + elapsed * max_tokens / self.window.as_secs_f64()).min(max_tokens);
+        }
+        bucket.refilled_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that retries are allowed until the budget is spent, and that clones spend the
+    /// same budget.
+    #[test]
+    fn test_retry_budget_is_exhausted() {
+        let budget = RetryBudget::new(2, Duration::from_secs(3600));
+        let shared = budget.clone();
+
+        assert_eq!(budget.remaining(), 2);
+        assert!(budget.try_spend());
+        assert!(shared.try_spend());
+        assert!(!budget.try_spend());
+        assert_eq!(shared.remaining(), 0);
+    }
+
+    /// Verifies that a spent budget is refilled over its window.
+    #[test]
+    fn test_retry_budget_refills() {
+        let budget = RetryBudget::new(1, Duration::from_millis(10));
+
+        assert!(budget.try_spend());
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(budget.remaining(), 1);
+        assert!(budget.try_spend());
+    }
+}
@@ -27,7 +27,7 @@ use sawtooth_sdk::messaging::zmq_stream::ZmqMessageSender;
 use uuid::Uuid;
 
 use super::{
-    BackendClient, BackendClientError, BatchStatus, BatchStatusLink, BatchStatuses, SubmitBatches,
+    BackendClient, BackendClientError, BatchStatus, BatchStatuses, BatchSubmission, SubmitBatches,
     DEFAULT_TIME_OUT,
 };
 
@@ -55,7 +55,7 @@ impl BackendClient for SawtoothBackendClient {
     fn submit_batches(
         &self,
         msg: SubmitBatches,
-    ) -> Pin<Box<dyn Future<Output = Result<BatchStatusLink, BackendClientError>> + Send>> {
+    ) -> Pin<Box<dyn Future<Output = Result<BatchSubmission, BackendClientError>> + Send>> {
         let mut client_submit_request = ClientBatchSubmitRequest::new();
         client_submit_request.set_batches(protobuf::RepeatedField::from_vec(
             msg.batch_list.get_batches().to_vec(),
@@ -80,7 +80,7 @@ impl BackendClient for SawtoothBackendClient {
                 let mut response_url = msg.response_url;
                 response_url.set_query(Some(&format!("id={}", batch_query)));
 
-                BatchStatusLink {
+                BatchSubmission::Accepted {
                     link: response_url.to_string(),
                 }
             }),
@@ -97,7 +97,7 @@ impl BackendClient for SawtoothBackendClient {
         match msg.wait {
             Some(wait_time) => {
                 batch_status_request.set_wait(true);
-                batch_status_request.set_timeout(wait_time);
+                batch_status_request.set_timeout(wait_time.as_secs_u32());
             }
             None => {
                 batch_status_request.set_wait(false);
@@ -0,0 +1,65 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Encodings of the batch lists submitted to a node.
+
+use protobuf::Message;
+use sawtooth_sdk::messages::batch::BatchList;
+
+use super::BackendClientError;
+
+/// The content type the Splinter batches endpoint expects.
+pub const OCTET_STREAM_CONTENT_TYPE: &str = "octet-stream";
+
+/// The body of a batch submission, and the content type it is sent with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SerializedBatchList {
+    pub body: Vec<u8>,
+    pub content_type: String,
+}
+
+/// Encodes the batch list of each submission into the body of the request sent to the node.
+///
+/// A serializer is called once per submission, and its output is reused for every attempt to
+/// send it. The default, `ProtobufBatchSerializer`, sends the batch list as raw protobuf, which
+/// is what a Splinter node expects; other serializers allow submitting through gateways that
+/// expect a different encoding.
+pub trait BatchSerializer: Send + Sync {
+    /// Encodes the batch list, returning a `BadRequestError` if it cannot be encoded.
+    fn serialize(&self, batch_list: &BatchList) -> Result<SerializedBatchList, BackendClientError>;
+}
+
+/// A `BatchSerializer` that sends the batch list as raw protobuf bytes, with the
+/// `octet-stream` content type.
+#[derive(Clone, Debug, Default)]
+pub struct ProtobufBatchSerializer;
+
+impl ProtobufBatchSerializer {
+    pub fn new() -> Self {
+        ProtobufBatchSerializer
+    }
+}
+
+impl BatchSerializer for ProtobufBatchSerializer {
+    fn serialize(&self, batch_list: &BatchList) -> Result<SerializedBatchList, BackendClientError> {
+        let body = batch_list.write_to_bytes().map_err(|err| {
+            BackendClientError::BadRequestError(format!("Malformed batch list: {}", err))
+        })?;
+
+        Ok(SerializedBatchList {
+            body,
+            content_type: OCTET_STREAM_CONTENT_TYPE.into(),
+        })
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! HMAC signing of requests sent to a node.
+
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Request;
+
+use crate::error::InvalidArgumentError;
+use crate::hex::to_hex;
+
+/// Signs requests with an HMAC-SHA256 over a canonical form of the request.
+///
+/// The canonical form is the concatenation of:
+///
+/// * the request method, in upper case (e.g. `POST`)
+/// * a newline (`\n`)
+/// * the request path including its query string, exactly as sent and without the scheme or
+///   host (e.g. `/scabbard/circuit/service/batch_statuses?ids=abc`)
+/// * a newline (`\n`)
+/// * the raw request body, which is empty for requests without one
+///
+/// The signature is the lower-case hex encoding of the HMAC of the canonical form, and is sent
+/// in the configured header.
+#[derive(Clone)]
+pub struct RequestSigner {
+    key: Vec<u8>,
+    header_name: HeaderName,
+}
+
+impl RequestSigner {
+    /// Constructs a signer using the given secret key, sending signatures in the header
+    /// `header_name`.
+    pub fn new(key: Vec<u8>, header_name: &str) -> Result<Self, InvalidArgumentError> {
+        if key.is_empty() {
+            return Err(InvalidArgumentError::new(
+                "key".into(),
+                "must not be empty".into(),
+            ));
+        }
+
+        let header_name = HeaderName::from_bytes(header_name.as_bytes())
+            .map_err(|err| InvalidArgumentError::new("header_name".into(), format!("{}", err)))?;
+
+        Ok(Self { key, header_name })
+    }
+
+    /// Returns the name of the header the signature is sent in.
+    pub fn header_name(&self) -> &str {
+        self.header_name.as_str()
+    }
+
+    /// Returns the signature of a request with the given method, path and query, and body.
+    pub fn signature(&self, method: &str, path_and_query: &str, body: &[u8]) -> String {
+        let mut hmac = Hmac::new(Sha256::new(), &self.key);
+        hmac.input(method.to_uppercase().as_bytes());
+        hmac.input(b"\n");
+        hmac.input(path_and_query.as_bytes());
+        hmac.input(b"\n");
+        hmac.input(body);
+
+        to_hex(hmac.result().code())
+    }
+
+    /// Adds the signature header to the given request.
+    pub(super) fn sign(&self, request: &mut Request) {
+        let url = request.url();
+        let path_and_query = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .unwrap_or_default();
+
+        let signature = self.signature(request.method().as_str(), &path_and_query, body);
+
+        // A hex string is always a valid header value
+        if let Ok(value) = HeaderValue::from_str(&signature) {
+            request
+                .headers_mut()
+                .insert(self.header_name.clone(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the signature is the hex HMAC-SHA256 of the documented canonical form, and
+    /// that the method is upper-cased before signing.
+    #[test]
+    fn signature_is_hmac_of_canonical_form() {
+        let signer = RequestSigner::new(b"Jefe".to_vec(), "X-Signature").unwrap();
+
+        let mut hmac = Hmac::new(Sha256::new(), b"Jefe");
+        hmac.input(b"POST\n/batches?wait=10\nbody");
+
+        assert_eq!(
+            signer.signature("POST", "/batches?wait=10", b"body"),
+            to_hex(hmac.result().code())
+        );
+        assert_eq!(
+            signer.signature("post", "/batches?wait=10", b"body"),
+            signer.signature("POST", "/batches?wait=10", b"body")
+        );
+    }
+
+    /// Verifies that the same request always yields the same signature, and that changing the
+    /// method, path, query, body or key changes it.
+    #[test]
+    fn signature_is_deterministic() {
+        let signer = RequestSigner::new(b"secret".to_vec(), "X-Signature").unwrap();
+        let signature = signer.signature("POST", "/batches?wait=10", b"body");
+
+        assert_eq!(
+            signature,
+            signer.signature("POST", "/batches?wait=10", b"body")
+        );
+        assert_eq!(signature.len(), 64);
+
+        assert_ne!(
+            signature,
+            signer.signature("GET", "/batches?wait=10", b"body")
+        );
+        assert_ne!(signature, signer.signature("POST", "/batch", b"body"));
+        assert_ne!(
+            signature,
+            signer.signature("POST", "/batches?wait=11", b"body")
+        );
+        assert_ne!(signature, signer.signature("POST", "/batches?wait=10", b""));
+        assert_ne!(
+            signature,
+            RequestSigner::new(b"other".to_vec(), "X-Signature")
+                .unwrap()
+                .signature("POST", "/batches?wait=10", b"body")
+        );
+    }
+
+    /// Verifies that signing a request adds the header computed from its canonical form.
+    #[test]
+    fn sign_adds_signature_header() {
+        let signer = RequestSigner::new(b"secret".to_vec(), "X-Signature").unwrap();
+        let mut request = reqwest::Client::new()
+            .post("http://localhost:8080/scabbard/a/b/batches?wait=10")
+            .body(b"body".to_vec())
+            .build()
+            .unwrap();
+
+        signer.sign(&mut request);
+
+        assert_eq!(
+            request.headers()["x-signature"],
+            signer
+                .signature("POST", "/scabbard/a/b/batches?wait=10", b"body")
+                .as_str()
+        );
+    }
+
+    /// Verifies that an empty key or an invalid header name is rejected.
+    #[test]
+    fn new_rejects_invalid_arguments() {
+        assert!(RequestSigner::new(vec![], "X-Signature").is_err());
+        assert!(RequestSigner::new(b"secret".to_vec(), "bad header").is_err());
+    }
+}
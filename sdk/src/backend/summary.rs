@@ -0,0 +1,125 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt;
+
+use super::BatchStatus;
+
+/// Aggregate outcome of a submission, computed from the statuses of its batches.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SubmissionSummary {
+    /// The number of batches reported on
+    pub batches: usize,
+    /// The number of batches that were committed
+    pub committed_batches: usize,
+    /// The number of transactions reported invalid, summed across all batches
+    pub invalid_transactions: usize,
+}
+
+impl SubmissionSummary {
+    /// Counts the committed batches and invalid transactions in `statuses`.
+    ///
+    /// Status names are compared ignoring case, as backends differ in how they spell them.
+    pub fn from_batch_statuses(statuses: &[BatchStatus]) -> Self {
+        statuses
+            .iter()
+            .fold(SubmissionSummary::default(), |mut summary, status| {
+                summary.batches += 1;
+                if status.status.eq_ignore_ascii_case("committed") {
+                    summary.committed_batches += 1;
+                }
+                summary.invalid_transactions += status.invalid_transactions.len();
+                summary
+            })
+    }
+
+    /// Logs the summary as a single line, tagged with the correlation id if one is given.
+    pub fn log(&self, correlation_id: Option<&str>) {
+        match correlation_id {
+            Some(correlation_id) => info!(
+                "Submission summary: {} correlation_id={}",
+                self, correlation_id
+            ),
+            None => info!("Submission summary: {}", self),
+        }
+    }
+}
+
+impl fmt::Display for SubmissionSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "batches={} committed_batches={} invalid_transactions={}",
+            self.batches, self.committed_batches, self.invalid_transactions
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::backend::InvalidTransaction;
+
+    fn status(status: &str, invalid_transactions: usize) -> BatchStatus {
+        BatchStatus {
+            id: "batch".to_string(),
+            invalid_transactions: (0..invalid_transactions)
+                .map(|i| InvalidTransaction {
+                    id: format!("txn-{}", i),
+                    message: "invalid".to_string(),
+                    extended_data: "".to_string(),
+                    error_text: None,
+                    scabbard_error: None,
+                })
+                .collect(),
+            status: status.to_string(),
+        }
+    }
+
+    /// Verifies that committed batches are counted regardless of how the backend spells the
+    /// status, and that invalid transactions are summed across batches.
+    #[test]
+    fn test_summary_from_batch_statuses() {
+        let summary = SubmissionSummary::from_batch_statuses(&[
+            status("COMMITTED", 0),
+            status("Committed", 0),
+            status("INVALID", 2),
+            status("Invalid", 1),
+            status("PENDING", 0),
+        ]);
+
+        assert_eq!(
+            summary,
+            SubmissionSummary {
+                batches: 5,
+                committed_batches: 2,
+                invalid_transactions: 3,
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "batches=5 committed_batches=2 invalid_transactions=3"
+        );
+    }
+
+    /// Verifies that an empty status list produces an empty summary.
+    #[test]
+    fn test_summary_from_no_batch_statuses() {
+        assert_eq!(
+            SubmissionSummary::from_batch_statuses(&[]),
+            SubmissionSummary::default()
+        );
+    }
+}
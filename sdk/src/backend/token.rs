@@ -0,0 +1,51 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sources of the authorization sent to a node.
+
+use crate::error::InternalError;
+
+/// Provides the authorization sent to the node with each request.
+///
+/// The provider is asked for a token before every request, including retries, so that a
+/// rotating or short-lived credential is always current. It is called from async code, so it
+/// should return quickly, e.g. from a cached value refreshed in the background, rather than
+/// blocking on a remote secret store.
+pub trait TokenProvider: Send + Sync {
+    /// Returns the value of the `Authorization` header for the next request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InternalError`] if no token is available, which fails the request without
+    /// contacting the node.
+    fn token(&self) -> Result<String, InternalError>;
+}
+
+/// A `TokenProvider` that always provides the same token.
+#[derive(Clone, Debug)]
+pub struct StaticTokenProvider {
+    token: String,
+}
+
+impl StaticTokenProvider {
+    pub fn new(token: String) -> Self {
+        Self { token }
+    }
+}
+
+impl TokenProvider for StaticTokenProvider {
+    fn token(&self) -> Result<String, InternalError> {
+        Ok(self.token.clone())
+    }
+}
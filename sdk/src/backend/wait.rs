@@ -0,0 +1,91 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+use std::time::Duration;
+
+/// How long a batch status request waits for the batches to be committed before responding.
+///
+/// Nodes take the wait time as a whole number of seconds. A partial second is rounded up, so a
+/// non-zero wait is never sent as no wait at all.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct WaitTime(Duration);
+
+impl WaitTime {
+    pub fn new(duration: Duration) -> Self {
+        WaitTime(duration)
+    }
+
+    pub fn from_secs(secs: u64) -> Self {
+        WaitTime(Duration::from_secs(secs))
+    }
+
+    /// Returns the wait time as a duration.
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+
+    /// Returns the wait time in whole seconds, as expected by nodes.
+    pub fn as_secs(&self) -> u64 {
+        if self.0.subsec_nanos() > 0 {
+            self.0.as_secs().saturating_add(1)
+        } else {
+            self.0.as_secs()
+        }
+    }
+
+    /// Returns the wait time in whole seconds, saturating at the largest timeout a Sawtooth
+    /// validator accepts.
+    pub fn as_secs_u32(&self) -> u32 {
+        u32::try_from(self.as_secs()).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the `wait` query parameter of a batch status URL.
+    pub fn to_query_param(&self) -> String {
+        format!("wait={}", self.as_secs())
+    }
+}
+
+impl From<Duration> for WaitTime {
+    fn from(duration: Duration) -> Self {
+        WaitTime(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the wait time is sent as whole seconds.
+    #[test]
+    fn test_wait_time_query_param() {
+        assert_eq!(
+            WaitTime::from(Duration::from_secs(5)).to_query_param(),
+            "wait=5"
+        );
+        assert_eq!(
+            WaitTime::new(Duration::from_millis(1500)).to_query_param(),
+            "wait=2"
+        );
+        assert_eq!(WaitTime::new(Duration::ZERO).to_query_param(), "wait=0");
+    }
+
+    /// Verifies that a wait time too long for a Sawtooth validator is capped rather than
+    /// wrapped.
+    #[test]
+    fn test_wait_time_as_secs_u32() {
+        assert_eq!(WaitTime::from_secs(5).as_secs_u32(), 5);
+        assert_eq!(WaitTime::from_secs(u64::MAX).as_secs_u32(), u32::MAX);
+    }
+}
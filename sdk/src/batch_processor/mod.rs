@@ -25,7 +25,7 @@ use std::{
 
 use sawtooth_sdk::messages::batch::BatchList;
 
-use crate::batches::store::BatchStore;
+use crate::batches::store::{BatchStore, BatchSubmitInfo, SubmissionOutcome, SubmissionRecord};
 use crate::error::InternalError;
 use crate::hex;
 
@@ -161,6 +161,12 @@ impl BatchProcessorBuilder {
                                     ) {
                                         error!("Failed to update error status: {}", err);
                                     }
+                                    record_submission(
+                                        &*store,
+                                        &batch_submit_info,
+                                        SubmissionOutcome::Rejected,
+                                        Some(err.to_string()),
+                                    );
                                     continue;
                                 }
                             };
@@ -177,13 +183,19 @@ impl BatchProcessorBuilder {
                                         ) {
                                             error!("Failed to update error status: {}", err);
                                         }
+                                        record_submission(
+                                            &*store,
+                                            &batch_submit_info,
+                                            SubmissionOutcome::Rejected,
+                                            Some(err.to_string()),
+                                        );
                                         continue;
                                     }
                                 };
 
                             match submitter.submit_batches(SubmitBatches {
                                 batch_list,
-                                service_id: batch_submit_info.service_id,
+                                service_id: batch_submit_info.service_id.clone(),
                             }) {
                                 Ok(()) => {
                                     info!(
@@ -197,6 +209,12 @@ impl BatchProcessorBuilder {
                                     } else {
                                         info!("Batch status updated to submitted");
                                     }
+                                    record_submission(
+                                        &*store,
+                                        &batch_submit_info,
+                                        SubmissionOutcome::Submitted,
+                                        None,
+                                    );
                                 }
                                 Err(BatchSubmitterError::BadRequestError(ref msg))
                                 | Err(BatchSubmitterError::NotFound(ref msg)) => {
@@ -207,6 +225,12 @@ impl BatchProcessorBuilder {
                                     ) {
                                         error!("Failed to update error status: {}", err);
                                     }
+                                    record_submission(
+                                        &*store,
+                                        &batch_submit_info,
+                                        SubmissionOutcome::Rejected,
+                                        Some(msg.to_string()),
+                                    );
                                 }
                                 Err(BatchSubmitterError::ConnectionError(ref msg))
                                 | Err(BatchSubmitterError::InternalError(ref msg))
@@ -221,6 +245,12 @@ impl BatchProcessorBuilder {
                                     } else {
                                         info!("Batch claim relinquished");
                                     }
+                                    record_submission(
+                                        &*store,
+                                        &batch_submit_info,
+                                        SubmissionOutcome::Failed,
+                                        Some(msg.to_string()),
+                                    );
                                 }
                             };
                         }
@@ -248,6 +278,26 @@ impl BatchProcessorBuilder {
     }
 }
 
+/// Adds a batch's submission to the submission history. A failure to record it is logged
+/// rather than returned, as the submission itself has already been handled.
+fn record_submission(
+    store: &dyn BatchStore,
+    batch_submit_info: &BatchSubmitInfo,
+    outcome: SubmissionOutcome,
+    error_message: Option<String>,
+) {
+    if let Err(err) = store.add_submission_record(SubmissionRecord {
+        batch_id: batch_submit_info.header_signature.clone(),
+        submitter: batch_submit_info.signer_public_key.clone(),
+        service_id: batch_submit_info.service_id.clone(),
+        submitted_at: chrono::Utc::now().naive_utc(),
+        outcome,
+        error_message,
+    }) {
+        error!("Failed to record batch submission: {}", err);
+    }
+}
+
 #[derive(Clone)]
 pub struct ShutdownSignaler {
     sender: Sender<BatchProcessorMessage>,
@@ -20,16 +20,22 @@ use diesel::connection::AnsiTransactionManager;
 use diesel::r2d2::{ConnectionManager, Pool};
 
 use super::diesel::models::{BatchModel, TransactionModel};
-use super::{Batch, BatchList, BatchStore, BatchStoreError, BatchSubmitInfo};
+use super::{
+    Batch, BatchList, BatchStore, BatchStoreError, BatchSubmitInfo, SubmissionHistoryFilter,
+    SubmissionOutcome, SubmissionRecord, SubmissionRecordList,
+};
 use crate::error::ResourceTemporarilyUnavailableError;
 
 use operations::add_batch::AddBatchOperation as _;
+use operations::add_submission_record::AddSubmissionRecordOperation as _;
 use operations::change_batch_to_submitted::ChangeBatchToSubmittedOperation as _;
 use operations::get_batch::GetBatchOperation as _;
 use operations::get_unclaimed_batches::GetUnclaimedBatchesOperation as _;
 use operations::list_batches::ListBatchesOperation as _;
+use operations::list_submission_history::ListSubmissionHistoryOperation as _;
 use operations::relinquish_claim::RelinquishClaimOperation as _;
 use operations::update_submission_error_info::UpdateSubmissionErrorInfoOperation as _;
+use operations::update_submission_outcome::UpdateSubmissionOutcomeOperation as _;
 use operations::BatchStoreOperations;
 
 #[derive(Clone)]
@@ -117,6 +123,43 @@ impl BatchStore for DieselBatchStore<diesel::pg::PgConnection> {
         })?)
         .relinquish_claim(id)
     }
+
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .add_submission_record(record.into())
+    }
+
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .update_submission_outcome(batch_id, outcome, error_message)
+    }
+
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_submission_history(filter, offset, limit)
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -192,6 +235,43 @@ impl BatchStore for DieselBatchStore<diesel::sqlite::SqliteConnection> {
         })?)
         .relinquish_claim(id)
     }
+
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .add_submission_record(record.into())
+    }
+
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .update_submission_outcome(batch_id, outcome, error_message)
+    }
+
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        BatchStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            BatchStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_submission_history(filter, offset, limit)
+    }
 }
 
 pub struct DieselConnectionBatchStore<'a, C>
@@ -254,6 +334,32 @@ impl<'a> BatchStore for DieselConnectionBatchStore<'a, diesel::pg::PgConnection>
     fn relinquish_claim(&self, id: &str) -> Result<(), BatchStoreError> {
         BatchStoreOperations::new(self.connection).relinquish_claim(id)
     }
+
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(self.connection).add_submission_record(record.into())
+    }
+
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(self.connection).update_submission_outcome(
+            batch_id,
+            outcome,
+            error_message,
+        )
+    }
+
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        BatchStoreOperations::new(self.connection).list_submission_history(filter, offset, limit)
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -298,4 +404,30 @@ impl<'a> BatchStore for DieselConnectionBatchStore<'a, diesel::sqlite::SqliteCon
     fn relinquish_claim(&self, id: &str) -> Result<(), BatchStoreError> {
         BatchStoreOperations::new(self.connection).relinquish_claim(id)
     }
+
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(self.connection).add_submission_record(record.into())
+    }
+
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        BatchStoreOperations::new(self.connection).update_submission_outcome(
+            batch_id,
+            outcome,
+            error_message,
+        )
+    }
+
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        BatchStoreOperations::new(self.connection).list_submission_history(filter, offset, limit)
+    }
 }
@@ -12,7 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::batches::store::{diesel::schema::*, Batch, BatchSubmitInfo, Transaction};
+use std::convert::TryFrom;
+
+use crate::batches::store::{
+    diesel::schema::*, Batch, BatchStoreError, BatchSubmitInfo, SubmissionOutcome,
+    SubmissionRecord, Transaction,
+};
+use crate::error::InternalError;
 use chrono::NaiveDateTime;
 
 #[derive(Insertable, Queryable, PartialEq, Eq, Debug)]
@@ -54,6 +60,28 @@ pub struct TransactionReceiptModel {
     pub external_error_message: Option<String>,
 }
 
+#[derive(Insertable, PartialEq, Eq, Debug)]
+#[table_name = "submission_history"]
+pub struct NewSubmissionRecordModel {
+    pub batch_id: String,
+    pub submitter: String,
+    pub service_id: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub outcome: String,
+    pub error_message: Option<String>,
+}
+
+#[derive(Queryable, PartialEq, Eq, Debug)]
+pub struct SubmissionRecordModel {
+    pub id: i64,
+    pub batch_id: String,
+    pub submitter: String,
+    pub service_id: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub outcome: String,
+    pub error_message: Option<String>,
+}
+
 impl From<(BatchModel, Vec<TransactionModel>)> for Batch {
     fn from((batch_model, transaction_models): (BatchModel, Vec<TransactionModel>)) -> Self {
         Self {
@@ -132,8 +160,41 @@ impl From<BatchModel> for BatchSubmitInfo {
     fn from(model: BatchModel) -> Self {
         Self {
             header_signature: model.header_signature,
+            signer_public_key: model.signer_public_key,
             serialized_batch: model.serialized_batch,
             service_id: model.service_id,
         }
     }
 }
+
+impl From<SubmissionRecord> for NewSubmissionRecordModel {
+    fn from(record: SubmissionRecord) -> Self {
+        Self {
+            batch_id: record.batch_id,
+            submitter: record.submitter,
+            service_id: record.service_id,
+            submitted_at: record.submitted_at,
+            outcome: record.outcome.as_str().to_string(),
+            error_message: record.error_message,
+        }
+    }
+}
+
+impl TryFrom<SubmissionRecordModel> for SubmissionRecord {
+    type Error = BatchStoreError;
+
+    fn try_from(model: SubmissionRecordModel) -> Result<Self, Self::Error> {
+        let outcome = model.outcome.parse::<SubmissionOutcome>().map_err(|err| {
+            BatchStoreError::InternalError(InternalError::from_source(Box::new(err)))
+        })?;
+
+        Ok(Self {
+            batch_id: model.batch_id,
+            submitter: model.submitter,
+            service_id: model.service_id,
+            submitted_at: model.submitted_at,
+            outcome,
+            error_message: model.error_message,
+        })
+    }
+}
@@ -0,0 +1,58 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::BatchStoreOperations;
+use crate::batches::store::{
+    diesel::{models::NewSubmissionRecordModel, schema::submission_history},
+    BatchStoreError,
+};
+
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::batches::store::diesel) trait AddSubmissionRecordOperation {
+    fn add_submission_record(
+        &self,
+        record: NewSubmissionRecordModel,
+    ) -> Result<(), BatchStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> AddSubmissionRecordOperation for BatchStoreOperations<'a, diesel::pg::PgConnection> {
+    fn add_submission_record(
+        &self,
+        record: NewSubmissionRecordModel,
+    ) -> Result<(), BatchStoreError> {
+        insert_into(submission_history::table)
+            .values(record)
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(BatchStoreError::from)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> AddSubmissionRecordOperation
+    for BatchStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_submission_record(
+        &self,
+        record: NewSubmissionRecordModel,
+    ) -> Result<(), BatchStoreError> {
+        insert_into(submission_history::table)
+            .values(record)
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(BatchStoreError::from)
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryFrom;
+
+use super::BatchStoreOperations;
+use crate::batches::store::{
+    diesel::{models::SubmissionRecordModel, schema::submission_history},
+    BatchStoreError, SubmissionHistoryFilter, SubmissionRecord, SubmissionRecordList,
+};
+use crate::error::InternalError;
+use crate::paging::Paging;
+
+use diesel::prelude::*;
+
+pub(in crate::batches::store::diesel) trait ListSubmissionHistoryOperation {
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListSubmissionHistoryOperation for BatchStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        self.conn.transaction::<_, BatchStoreError, _>(|| {
+            let models = pg::matching(filter)
+                .select(submission_history::all_columns)
+                .order((
+                    submission_history::submitted_at.asc(),
+                    submission_history::id.asc(),
+                ))
+                .offset(offset)
+                .limit(limit)
+                .load::<SubmissionRecordModel>(self.conn)
+                .map_err(|err| {
+                    BatchStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            let total = pg::matching(filter)
+                .count()
+                .get_result(self.conn)
+                .map_err(|err| {
+                    BatchStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            Ok(SubmissionRecordList::new(
+                models
+                    .into_iter()
+                    .map(SubmissionRecord::try_from)
+                    .collect::<Result<_, _>>()?,
+                Paging::new(offset, limit, total),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListSubmissionHistoryOperation
+    for BatchStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        self.conn.transaction::<_, BatchStoreError, _>(|| {
+            let models = sqlite::matching(filter)
+                .select(submission_history::all_columns)
+                .order((
+                    submission_history::submitted_at.asc(),
+                    submission_history::id.asc(),
+                ))
+                .offset(offset)
+                .limit(limit)
+                .load::<SubmissionRecordModel>(self.conn)
+                .map_err(|err| {
+                    BatchStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            let total = sqlite::matching(filter)
+                .count()
+                .get_result(self.conn)
+                .map_err(|err| {
+                    BatchStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            Ok(SubmissionRecordList::new(
+                models
+                    .into_iter()
+                    .map(SubmissionRecord::try_from)
+                    .collect::<Result<_, _>>()?,
+                Paging::new(offset, limit, total),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the submission records that match every criterion of the filter.
+    pub fn matching(filter: &SubmissionHistoryFilter) -> submission_history::BoxedQuery<'_, Pg> {
+        let mut query = submission_history::table.into_boxed();
+
+        if let Some(submitted_after) = filter.submitted_after {
+            query = query.filter(submission_history::submitted_at.ge(submitted_after));
+        }
+        if let Some(submitted_before) = filter.submitted_before {
+            query = query.filter(submission_history::submitted_at.lt(submitted_before));
+        }
+        if let Some(service_id) = &filter.service_id {
+            query = query.filter(submission_history::service_id.eq(service_id));
+        }
+        if let Some(submitter) = &filter.submitter {
+            query = query.filter(submission_history::submitter.eq(submitter));
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the submission records that match every criterion of the filter.
+    pub fn matching(
+        filter: &SubmissionHistoryFilter,
+    ) -> submission_history::BoxedQuery<'_, Sqlite> {
+        let mut query = submission_history::table.into_boxed();
+
+        if let Some(submitted_after) = filter.submitted_after {
+            query = query.filter(submission_history::submitted_at.ge(submitted_after));
+        }
+        if let Some(submitted_before) = filter.submitted_before {
+            query = query.filter(submission_history::submitted_at.lt(submitted_before));
+        }
+        if let Some(service_id) = &filter.service_id {
+            query = query.filter(submission_history::service_id.eq(service_id));
+        }
+        if let Some(submitter) = &filter.submitter {
+            query = query.filter(submission_history::submitter.eq(submitter));
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use chrono::{NaiveDate, NaiveDateTime};
+
+    use crate::batches::store::diesel::operations::add_submission_record::AddSubmissionRecordOperation as _;
+    use crate::batches::store::SubmissionOutcome;
+    use crate::migrations::run_sqlite_migrations;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2022, 6, 22)
+            .and_then(|date| date.and_hms_opt(hour, 0, 0))
+            .expect("Invalid time")
+    }
+
+    fn record(
+        batch_id: &str,
+        submitter: &str,
+        hour: u32,
+        service_id: Option<&str>,
+    ) -> SubmissionRecord {
+        SubmissionRecord {
+            batch_id: batch_id.into(),
+            submitter: submitter.into(),
+            service_id: service_id.map(String::from),
+            submitted_at: at(hour),
+            outcome: SubmissionOutcome::Submitted,
+            error_message: None,
+        }
+    }
+
+    fn batch_ids(list: &SubmissionRecordList) -> Vec<&str> {
+        list.data
+            .iter()
+            .map(|record| record.batch_id.as_str())
+            .collect()
+    }
+
+    /// Verify that submissions are listed in submission order, limited to those matching each
+    /// criterion of the filter, and that the paging total counts every match.
+    #[test]
+    fn test_list_submission_history() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = BatchStoreOperations::new(&conn);
+        let mut rejected = record("batch-3", "alice", 12, Some("circuit::service"));
+        rejected.outcome = SubmissionOutcome::Rejected;
+        rejected.error_message = Some("Bad Request".into());
+        for record in [
+            rejected.clone(),
+            record("batch-1", "alice", 10, None),
+            record("batch-2", "bob", 11, Some("circuit::service")),
+        ] {
+            ops.add_submission_record(record.into())?;
+        }
+
+        let all = ops.list_submission_history(&SubmissionHistoryFilter::default(), 0, 10)?;
+        assert_eq!(batch_ids(&all), vec!["batch-1", "batch-2", "batch-3"]);
+        assert_eq!(all.data[2], rejected);
+
+        let by_submitter = SubmissionHistoryFilter {
+            submitter: Some("alice".into()),
+            ..Default::default()
+        };
+        let page = ops.list_submission_history(&by_submitter, 0, 1)?;
+        assert_eq!(batch_ids(&page), vec!["batch-1"]);
+        assert_eq!(page.paging, Paging::new(0, 1, 2));
+
+        let by_service_and_time = SubmissionHistoryFilter {
+            service_id: Some("circuit::service".into()),
+            submitted_after: Some(at(11)),
+            submitted_before: Some(at(12)),
+            ..Default::default()
+        };
+        assert_eq!(
+            batch_ids(&ops.list_submission_history(&by_service_and_time, 0, 10)?),
+            vec!["batch-2"]
+        );
+
+        Ok(())
+    }
+}
@@ -13,12 +13,15 @@
 // limitations under the License.
 
 pub(super) mod add_batch;
+pub(super) mod add_submission_record;
 pub(super) mod change_batch_to_submitted;
 pub(super) mod get_batch;
 pub(super) mod get_unclaimed_batches;
 pub(super) mod list_batches;
+pub(super) mod list_submission_history;
 pub(super) mod relinquish_claim;
 pub(super) mod update_submission_error_info;
+pub(super) mod update_submission_outcome;
 
 pub(super) struct BatchStoreOperations<'a, C> {
     conn: &'a C,
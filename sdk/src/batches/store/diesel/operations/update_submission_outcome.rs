@@ -0,0 +1,146 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::BatchStoreOperations;
+use crate::batches::store::{
+    diesel::schema::submission_history, BatchStoreError, SubmissionOutcome,
+};
+
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::batches::store::diesel) trait UpdateSubmissionOutcomeOperation {
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> UpdateSubmissionOutcomeOperation for BatchStoreOperations<'a, diesel::pg::PgConnection> {
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        update(submission_history::table)
+            .filter(
+                submission_history::batch_id
+                    .eq(batch_id)
+                    .and(submission_history::outcome.eq(SubmissionOutcome::Submitted.as_str())),
+            )
+            .set((
+                submission_history::outcome.eq(outcome.as_str()),
+                submission_history::error_message.eq(error_message),
+            ))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(BatchStoreError::from)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> UpdateSubmissionOutcomeOperation
+    for BatchStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        update(submission_history::table)
+            .filter(
+                submission_history::batch_id
+                    .eq(batch_id)
+                    .and(submission_history::outcome.eq(SubmissionOutcome::Submitted.as_str())),
+            )
+            .set((
+                submission_history::outcome.eq(outcome.as_str()),
+                submission_history::error_message.eq(error_message),
+            ))
+            .execute(self.conn)
+            .map(|_| ())
+            .map_err(BatchStoreError::from)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use chrono::NaiveDate;
+
+    use crate::batches::store::diesel::operations::add_submission_record::AddSubmissionRecordOperation as _;
+    use crate::batches::store::diesel::operations::list_submission_history::ListSubmissionHistoryOperation as _;
+    use crate::batches::store::{SubmissionHistoryFilter, SubmissionRecord};
+    use crate::migrations::run_sqlite_migrations;
+
+    fn record(batch_id: &str, outcome: SubmissionOutcome) -> SubmissionRecord {
+        SubmissionRecord {
+            batch_id: batch_id.into(),
+            submitter: "alice".into(),
+            service_id: None,
+            submitted_at: NaiveDate::from_ymd_opt(2022, 6, 22)
+                .and_then(|date| date.and_hms_opt(10, 0, 0))
+                .expect("Invalid time"),
+            outcome,
+            error_message: None,
+        }
+    }
+
+    /// Verify that the final outcome of a batch replaces the outcome of its accepted
+    /// submissions only, leaving those that failed as they were.
+    #[test]
+    fn test_update_submission_outcome() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = BatchStoreOperations::new(&conn);
+        for record in [
+            record("batch-1", SubmissionOutcome::Failed),
+            record("batch-1", SubmissionOutcome::Submitted),
+            record("batch-2", SubmissionOutcome::Submitted),
+        ] {
+            ops.add_submission_record(record.into())?;
+        }
+
+        ops.update_submission_outcome("batch-1", SubmissionOutcome::Invalid, Some("bad payload"))?;
+        ops.update_submission_outcome("batch-2", SubmissionOutcome::Committed, None)?;
+
+        let outcomes = ops
+            .list_submission_history(&SubmissionHistoryFilter::default(), 0, 10)?
+            .data
+            .into_iter()
+            .map(|record| (record.batch_id, record.outcome, record.error_message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("batch-1".to_string(), SubmissionOutcome::Failed, None),
+                (
+                    "batch-1".to_string(),
+                    SubmissionOutcome::Invalid,
+                    Some("bad payload".to_string())
+                ),
+                ("batch-2".to_string(), SubmissionOutcome::Committed, None),
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -51,3 +51,15 @@ table! {
         external_error_message -> Nullable<Text>,
     }
 }
+
+table! {
+    submission_history (id) {
+        id -> Int8,
+        batch_id -> Text,
+        submitter -> Text,
+        service_id -> Nullable<Text>,
+        submitted_at -> Timestamp,
+        outcome -> Text,
+        error_message -> Nullable<Text>,
+    }
+}
@@ -16,8 +16,12 @@
 pub(crate) mod diesel;
 mod error;
 
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::NaiveDateTime;
 
+use crate::error::InvalidArgumentError;
 use crate::hex;
 use crate::paging::Paging;
 
@@ -87,6 +91,7 @@ impl Batch {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BatchSubmitInfo {
     pub header_signature: String,
+    pub signer_public_key: String,
     pub serialized_batch: String,
     pub service_id: Option<String>,
 }
@@ -126,6 +131,92 @@ impl BatchList {
     }
 }
 
+/// The outcome of a single batch submission
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionOutcome {
+    /// The batch was accepted by the node
+    Submitted,
+    /// The batch was refused and will not be submitted again
+    Rejected,
+    /// The batch could not be submitted and will be retried
+    Failed,
+    /// The submitted batch was committed
+    Committed,
+    /// The submitted batch was found to be invalid
+    Invalid,
+}
+
+impl SubmissionOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionOutcome::Submitted => "submitted",
+            SubmissionOutcome::Rejected => "rejected",
+            SubmissionOutcome::Failed => "failed",
+            SubmissionOutcome::Committed => "committed",
+            SubmissionOutcome::Invalid => "invalid",
+        }
+    }
+}
+
+impl FromStr for SubmissionOutcome {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "submitted" => Ok(SubmissionOutcome::Submitted),
+            "rejected" => Ok(SubmissionOutcome::Rejected),
+            "failed" => Ok(SubmissionOutcome::Failed),
+            "committed" => Ok(SubmissionOutcome::Committed),
+            "invalid" => Ok(SubmissionOutcome::Invalid),
+            _ => Err(InvalidArgumentError::new(
+                "outcome".to_string(),
+                format!("unknown submission outcome {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SubmissionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A record of an attempt to submit a batch, kept for auditing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionRecord {
+    pub batch_id: String,
+    /// The public key of the batch's signer
+    pub submitter: String,
+    pub service_id: Option<String>,
+    pub submitted_at: NaiveDateTime,
+    pub outcome: SubmissionOutcome,
+    pub error_message: Option<String>,
+}
+
+/// Limits the submission records that are listed. Unset criteria match every record.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubmissionHistoryFilter {
+    /// The earliest submission time to include
+    pub submitted_after: Option<NaiveDateTime>,
+    /// The submission time at which to stop, exclusive
+    pub submitted_before: Option<NaiveDateTime>,
+    pub service_id: Option<String>,
+    pub submitter: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubmissionRecordList {
+    pub data: Vec<SubmissionRecord>,
+    pub paging: Paging,
+}
+
+impl SubmissionRecordList {
+    pub fn new(data: Vec<SubmissionRecord>, paging: Paging) -> Self {
+        Self { data, paging }
+    }
+}
+
 pub trait BatchStore {
     /// Adds a batch to the underlying storage
     ///
@@ -193,6 +284,42 @@ pub trait BatchStore {
     ///
     ///  * `id` - The id of the batch to update
     fn relinquish_claim(&self, id: &str) -> Result<(), BatchStoreError>;
+
+    /// Records an attempt to submit a batch and its outcome
+    ///
+    /// # Arguments
+    ///
+    ///  * `record` - The submission to be recorded
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError>;
+
+    /// Records the final outcome of a batch on its accepted submissions, once the batch has
+    /// been committed or found to be invalid
+    ///
+    /// # Arguments
+    ///
+    ///  * `batch_id` - The id of the submitted batch
+    ///  * `outcome` - The final outcome of the batch
+    ///  * `error_message` - The explanation of an invalid batch
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError>;
+
+    /// Lists the recorded submissions that match a filter, ordered by submission time
+    ///
+    /// # Arguments
+    ///
+    ///  * `filter` - The criteria the listed submissions must match
+    ///  * `offset` - The index of the first in storage to retrieve
+    ///  * `limit` - The number of items to retrieve from the offset
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError>;
 }
 
 impl<BS> BatchStore for Box<BS>
@@ -235,4 +362,26 @@ where
     fn relinquish_claim(&self, id: &str) -> Result<(), BatchStoreError> {
         (**self).relinquish_claim(id)
     }
+
+    fn add_submission_record(&self, record: SubmissionRecord) -> Result<(), BatchStoreError> {
+        (**self).add_submission_record(record)
+    }
+
+    fn update_submission_outcome(
+        &self,
+        batch_id: &str,
+        outcome: SubmissionOutcome,
+        error_message: Option<&str>,
+    ) -> Result<(), BatchStoreError> {
+        (**self).update_submission_outcome(batch_id, outcome, error_message)
+    }
+
+    fn list_submission_history(
+        &self,
+        filter: &SubmissionHistoryFilter,
+        offset: i64,
+        limit: i64,
+    ) -> Result<SubmissionRecordList, BatchStoreError> {
+        (**self).list_submission_history(filter, offset, limit)
+    }
 }
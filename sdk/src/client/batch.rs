@@ -0,0 +1,167 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use sawtooth_sdk::messages::batch::{Batch, BatchList};
+
+use crate::error::{ClientError, InvalidArgumentError};
+
+/// The client representation of an invalid transaction in a batch
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidTransaction {
+    pub id: String,
+    pub message: String,
+}
+
+/// The client representation of the status of a batch
+#[derive(Debug, PartialEq, Eq)]
+pub struct BatchStatus {
+    pub id: String,
+    pub status: String,
+    pub invalid_transactions: Vec<InvalidTransaction>,
+}
+
+impl BatchStatus {
+    /// Returns whether the batch has been committed or found invalid, after which its status
+    /// will not change.
+    ///
+    /// Status names are compared ignoring case, as backends differ in how they spell them.
+    pub fn is_terminal(&self) -> bool {
+        self.is_committed() || self.is_invalid()
+    }
+
+    /// Returns whether the batch has been committed
+    pub fn is_committed(&self) -> bool {
+        self.status.eq_ignore_ascii_case("committed")
+    }
+
+    /// Returns whether the batch contained invalid transactions
+    pub fn is_invalid(&self) -> bool {
+        self.status.eq_ignore_ascii_case("invalid")
+    }
+}
+
+/// Returns the header signature of a built batch, which is the ID the batch is tracked and
+/// reported by once it is submitted.
+///
+/// Returns an error if the batch has not been signed.
+pub fn header_signature(batch: &Batch) -> Result<&str, InvalidArgumentError> {
+    if batch.get_header().is_empty() || batch.get_header_signature().is_empty() {
+        return Err(InvalidArgumentError::new(
+            "batch".into(),
+            "batch has not been signed".into(),
+        ));
+    }
+
+    Ok(batch.get_header_signature())
+}
+
+/// Returns the header signatures of the batches in a batch list, in the order they will be
+/// submitted.
+///
+/// Returns an error if any of the batches has not been signed.
+pub fn header_signatures(batch_list: &BatchList) -> Result<Vec<&str>, InvalidArgumentError> {
+    batch_list
+        .get_batches()
+        .iter()
+        .map(header_signature)
+        .collect()
+}
+
+pub trait BatchClient {
+    /// Submits a list of signed batches without waiting for them to be committed
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_list` - the `BatchList` to be submitted
+    /// * `service_id` - optional - the service ID to submit the batches to if running splinter
+    fn submit_batches(
+        &self,
+        batch_list: &BatchList,
+        service_id: Option<&str>,
+    ) -> Result<(), ClientError>;
+
+    /// Fetches the current status of a batch
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the batch
+    /// * `service_id` - optional - the service ID the batch was submitted to if running splinter
+    fn get_batch_status(
+        &self,
+        id: &str,
+        service_id: Option<&str>,
+    ) -> Result<BatchStatus, ClientError>;
+
+    /// Polls the status of a batch until it is committed or invalid, or until `wait` has
+    /// elapsed, returning the last status received
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the batch
+    /// * `service_id` - optional - the service ID the batch was submitted to if running splinter
+    /// * `wait` - how long to wait for the batch to be committed or invalid
+    /// * `on_poll` - called with each status received while polling
+    fn await_batch_status(
+        &self,
+        id: &str,
+        service_id: Option<&str>,
+        wait: Duration,
+        on_poll: &mut dyn FnMut(&BatchStatus),
+    ) -> Result<BatchStatus, ClientError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::RepeatedField;
+
+    fn batch(header: &[u8], header_signature: &str) -> Batch {
+        let mut batch = Batch::new();
+        batch.set_header(header.to_vec());
+        batch.set_header_signature(header_signature.to_string());
+        batch
+    }
+
+    /// Verifies that the header signatures of every batch in a list are returned in order.
+    #[test]
+    fn test_header_signatures() {
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(vec![
+            batch(b"header-1", "batch-1"),
+            batch(b"header-2", "batch-2"),
+            batch(b"header-3", "batch-3"),
+        ]));
+
+        assert_eq!(
+            header_signatures(&batch_list).expect("Unable to get header signatures"),
+            vec!["batch-1", "batch-2", "batch-3"]
+        );
+    }
+
+    /// Verifies that a list containing an unsigned batch is rejected.
+    #[test]
+    fn test_header_signatures_unsigned_batch() {
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(vec![
+            batch(b"header-1", "batch-1"),
+            batch(b"header-2", ""),
+        ]));
+
+        assert!(header_signatures(&batch_list).is_err());
+        assert!(header_signature(&batch(b"", "batch-1")).is_err());
+    }
+}
@@ -14,6 +14,7 @@
 
 //! Traits and implementations useful for interacting with the REST API.
 
+pub mod batch;
 #[cfg(feature = "location")]
 pub mod location;
 #[cfg(feature = "location")]
@@ -57,6 +58,9 @@ pub trait Client {
 }
 
 pub trait ClientFactory {
+    /// Retrieves a client for showing the statuses of submitted batches
+    fn get_batch_client(&self, url: String) -> Box<dyn batch::BatchClient>;
+
     /// Retrieves a client for listing and showing locations
     #[cfg(feature = "location")]
     fn get_location_client(&self, url: String) -> Box<dyn location::LocationClient>;
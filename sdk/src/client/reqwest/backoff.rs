@@ -0,0 +1,180 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use crate::error::InvalidArgumentError;
+
+/// Implements `with_poll_backoff` for a reqwest client, which keeps its backoff in a
+/// `poll_backoff` field.
+macro_rules! impl_with_poll_backoff {
+    ($client:ident) => {
+        impl $client {
+            /// Sets how often batch statuses are polled while waiting for submitted batches.
+            pub fn with_poll_backoff(mut self, poll_backoff: PollBackoff) -> Self {
+                self.poll_backoff = poll_backoff;
+                self
+            }
+        }
+    };
+}
+
+const DEFAULT_INITIAL_INTERVAL: Duration = Duration::from_millis(250);
+const DEFAULT_GROWTH_FACTOR: f64 = 2.0;
+const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Controls how often batch statuses are polled while waiting for submitted batches.
+///
+/// Polling starts at the initial interval, which is multiplied by the growth factor after each
+/// poll that finds the batches still pending, up to the maximum interval. When a poll finds that
+/// more batches have left the pending state than before, the interval is reset to the initial
+/// interval.
+///
+/// Each reqwest client polls with the default backoff unless another is given to its
+/// `with_poll_backoff` method.
+#[derive(Clone, Debug)]
+pub struct PollBackoff {
+    initial_interval: Duration,
+    growth_factor: f64,
+    max_interval: Duration,
+}
+
+impl PollBackoff {
+    /// Constructs a new backoff.
+    ///
+    /// The initial interval must be greater than zero, the growth factor must be at least 1, and
+    /// the maximum interval must not be less than the initial interval.
+    pub fn new(
+        initial_interval: Duration,
+        growth_factor: f64,
+        max_interval: Duration,
+    ) -> Result<Self, InvalidArgumentError> {
+        if initial_interval == Duration::from_secs(0) {
+            return Err(InvalidArgumentError::new(
+                "initial_interval".into(),
+                "must be greater than 0".into(),
+            ));
+        }
+
+        if !(growth_factor >= 1.0 && growth_factor.is_finite()) {
+            return Err(InvalidArgumentError::new(
+                "growth_factor".into(),
+                "must be a finite number of at least 1".into(),
+            ));
+        }
+
+        if max_interval < initial_interval {
+            return Err(InvalidArgumentError::new(
+                "max_interval".into(),
+                "must not be less than the initial interval".into(),
+            ));
+        }
+
+        Ok(Self {
+            initial_interval,
+            growth_factor,
+            max_interval,
+        })
+    }
+
+    /// Returns the interval before the first poll, and after a poll that observed progress.
+    pub fn initial_interval(&self) -> Duration {
+        self.initial_interval
+    }
+
+    /// Returns the factor the interval is multiplied by after a poll that observed no progress.
+    pub fn growth_factor(&self) -> f64 {
+        self.growth_factor
+    }
+
+    /// Returns the longest interval between polls.
+    pub fn max_interval(&self) -> Duration {
+        self.max_interval
+    }
+
+    pub(super) fn intervals(&self) -> PollIntervals {
+        PollIntervals {
+            backoff: self.clone(),
+            next: self.initial_interval,
+        }
+    }
+}
+
+impl Default for PollBackoff {
+    fn default() -> Self {
+        Self {
+            initial_interval: DEFAULT_INITIAL_INTERVAL,
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            max_interval: DEFAULT_MAX_INTERVAL,
+        }
+    }
+}
+
+/// The sequence of intervals produced by a `PollBackoff` while waiting on a set of batches.
+pub(super) struct PollIntervals {
+    backoff: PollBackoff,
+    next: Duration,
+}
+
+impl PollIntervals {
+    /// Returns how long to wait before the next poll, given whether the previous poll observed
+    /// progress.
+    pub fn next(&mut self, progressed: bool) -> Duration {
+        if progressed {
+            self.next = self.backoff.initial_interval;
+        }
+
+        let interval = self.next;
+        self.next = std::cmp::min(
+            interval.mul_f64(self.backoff.growth_factor),
+            self.backoff.max_interval,
+        );
+
+        interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that intervals grow by the growth factor up to the maximum, and reset to the
+    /// initial interval when progress is observed.
+    #[test]
+    fn intervals_grow_until_capped_and_reset_on_progress() {
+        let backoff =
+            PollBackoff::new(Duration::from_millis(100), 3.0, Duration::from_secs(1)).unwrap();
+        let mut intervals = backoff.intervals();
+
+        assert_eq!(intervals.next(false), Duration::from_millis(100));
+        assert_eq!(intervals.next(false), Duration::from_millis(300));
+        assert_eq!(intervals.next(false), Duration::from_millis(900));
+        assert_eq!(intervals.next(false), Duration::from_secs(1));
+        assert_eq!(intervals.next(false), Duration::from_secs(1));
+        assert_eq!(intervals.next(true), Duration::from_millis(100));
+        assert_eq!(intervals.next(false), Duration::from_millis(300));
+    }
+
+    /// Verifies that invalid intervals and growth factors are rejected.
+    #[test]
+    fn new_rejects_invalid_arguments() {
+        let second = Duration::from_secs(1);
+
+        assert!(PollBackoff::new(Duration::from_secs(0), 2.0, second).is_err());
+        assert!(PollBackoff::new(second, 0.5, second).is_err());
+        assert!(PollBackoff::new(second, f64::NAN, second).is_err());
+        assert!(PollBackoff::new(second, 2.0, Duration::from_millis(500)).is_err());
+        assert!(PollBackoff::new(second, 1.0, second).is_ok());
+    }
+}
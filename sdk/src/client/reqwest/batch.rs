@@ -0,0 +1,217 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use reqwest::blocking::Client as BlockingClient;
+use sawtooth_sdk::messages::batch::BatchList;
+
+use crate::client::batch::{
+    BatchClient, BatchStatus as ClientBatchStatus, InvalidTransaction as ClientInvalidTransaction,
+};
+use crate::error::ClientError;
+
+use super::{
+    await_terminal, fetch_entity, post_batches, BatchStatus, BatchStatusResponse, PollBackoff,
+};
+
+/// The Reqwest implementation of the batch client
+pub struct ReqwestBatchClient {
+    url: String,
+    poll_backoff: PollBackoff,
+}
+
+impl ReqwestBatchClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+        }
+    }
+}
+
+impl_with_poll_backoff!(ReqwestBatchClient);
+
+impl BatchClient for ReqwestBatchClient {
+    /// Submits a list of signed batches without waiting for them to be committed
+    ///
+    /// # Arguments
+    ///
+    /// * `batch_list` - the `BatchList` to be submitted
+    /// * `service_id` - optional - the service ID to submit the batches to if running splinter
+    fn submit_batches(
+        &self,
+        batch_list: &BatchList,
+        service_id: Option<&str>,
+    ) -> Result<(), ClientError> {
+        post_batches(&self.url, 0, batch_list, service_id, &self.poll_backoff)
+    }
+
+    /// Fetches the current status of a batch
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the batch
+    /// * `service_id` - optional - the service ID the batch was submitted to if running splinter
+    fn get_batch_status(
+        &self,
+        id: &str,
+        service_id: Option<&str>,
+    ) -> Result<ClientBatchStatus, ClientError> {
+        let response: BatchStatusResponse =
+            fetch_entity(&self.url, format!("batch_statuses?id={}", id), service_id)?;
+
+        find_batch_status(id, &response.data)
+    }
+
+    /// Polls the status of a batch until it is committed or invalid, or until `wait` has
+    /// elapsed, returning the last status received
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the batch
+    /// * `service_id` - optional - the service ID the batch was submitted to if running splinter
+    /// * `wait` - how long to wait for the batch to be committed or invalid
+    /// * `on_poll` - called with each status received while polling
+    fn await_batch_status(
+        &self,
+        id: &str,
+        service_id: Option<&str>,
+        wait: Duration,
+        on_poll: &mut dyn FnMut(&ClientBatchStatus),
+    ) -> Result<ClientBatchStatus, ClientError> {
+        let url = match service_id {
+            Some(service_id) => format!(
+                "{}/batch_statuses?id={}&service_id={}",
+                self.url, id, service_id
+            ),
+            None => format!("{}/batch_statuses?id={}", self.url, id),
+        };
+
+        let response = await_terminal(
+            &BlockingClient::new(),
+            &url,
+            wait,
+            &self.poll_backoff,
+            |status| ClientBatchStatus::from(status).is_terminal(),
+            &mut |statuses| {
+                if let Ok(status) = find_batch_status(id, statuses) {
+                    on_poll(&status)
+                }
+            },
+        )?;
+
+        find_batch_status(id, &response.data)
+    }
+}
+
+fn find_batch_status(id: &str, statuses: &[BatchStatus]) -> Result<ClientBatchStatus, ClientError> {
+    statuses
+        .iter()
+        .find(|status| status.id == id)
+        .or_else(|| statuses.first())
+        .map(ClientBatchStatus::from)
+        .ok_or_else(|| ClientError::InternalError(format!("No status found for batch {}", id)))
+}
+
+impl From<&BatchStatus> for ClientBatchStatus {
+    fn from(d: &BatchStatus) -> Self {
+        Self {
+            id: d.id.to_string(),
+            status: d.status.to_string(),
+            invalid_transactions: d
+                .invalid_transactions
+                .iter()
+                .map(|txn| ClientInvalidTransaction {
+                    id: txn.get("id").cloned().unwrap_or_default(),
+                    message: txn.get("message").cloned().unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockito::{mock, Matcher};
+    use protobuf::Message;
+    use sawtooth_sdk::messages::batch::Batch;
+
+    /// Verifies that a batch list is posted to the batches endpoint as its serialized bytes.
+    #[test]
+    fn test_submit_batches() {
+        let mut batch = Batch::new();
+        batch.set_header(b"header".to_vec());
+        batch.set_header_signature("batch-2".into());
+        let mut batch_list = BatchList::new();
+        batch_list.mut_batches().push(batch);
+
+        let submit = mock("POST", "/batches")
+            .match_body(batch_list.write_to_bytes().expect("Unable to serialize"))
+            .with_body(r#"{"link": "/batch_statuses?id=batch-2"}"#)
+            .create();
+
+        ReqwestBatchClient::new(mockito::server_url())
+            .submit_batches(&batch_list, None)
+            .expect("Unable to submit batches");
+
+        submit.assert();
+    }
+
+    /// Verifies that the batch status is polled until the batch is no longer pending, that each
+    /// status received is reported, and that the invalid transactions are returned.
+    #[test]
+    fn test_await_batch_status_until_invalid() {
+        let pending = mock("GET", "/batch_statuses")
+            .match_query(Matcher::UrlEncoded("id".into(), "batch-1".into()))
+            .with_body(
+                r#"{"data": [{"id": "batch-1", "status": "PENDING", "invalid_transactions": []}]}"#,
+            )
+            .expect(1)
+            .create();
+        let invalid = mock("GET", "/batch_statuses")
+            .match_query(Matcher::UrlEncoded("id".into(), "batch-1".into()))
+            .with_body(
+                r#"{"data": [{"id": "batch-1", "status": "INVALID",
+                "invalid_transactions": [{"id": "txn-1", "message": "bad"}]}]}"#,
+            )
+            .create();
+
+        let client = ReqwestBatchClient::new(mockito::server_url()).with_poll_backoff(
+            PollBackoff::new(Duration::from_millis(10), 1.0, Duration::from_millis(10))
+                .expect("Unable to build backoff"),
+        );
+
+        let mut polled = Vec::new();
+        let status = client
+            .await_batch_status("batch-1", None, Duration::from_secs(5), &mut |status| {
+                polled.push(status.status.clone())
+            })
+            .expect("Unable to await batch status");
+
+        pending.assert();
+        invalid.assert();
+        assert_eq!(polled, vec!["PENDING".to_string(), "INVALID".to_string()]);
+        assert!(status.is_invalid());
+        assert_eq!(
+            status.invalid_transactions,
+            vec![ClientInvalidTransaction {
+                id: "txn-1".into(),
+                message: "bad".into(),
+            }]
+        );
+    }
+}
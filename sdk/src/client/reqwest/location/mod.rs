@@ -15,7 +15,7 @@
 mod data;
 
 use crate::client::location::{Location, LocationClient};
-use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches};
+use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches, PollBackoff};
 use crate::client::Client;
 use crate::error::ClientError;
 
@@ -26,14 +26,20 @@ const LOCATION_ROUTE: &str = "location";
 /// The Reqwest implementation of the Location client
 pub struct ReqwestLocationClient {
     url: String,
+    poll_backoff: PollBackoff,
 }
 
 impl ReqwestLocationClient {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+        }
     }
 }
 
+impl_with_poll_backoff!(ReqwestLocationClient);
+
 impl Client for ReqwestLocationClient {
     /// Submits a list of batches
     ///
@@ -48,7 +54,7 @@ impl Client for ReqwestLocationClient {
         batch_list: &BatchList,
         service_id: Option<&str>,
     ) -> Result<(), ClientError> {
-        post_batches(&self.url, wait, batch_list, service_id)
+        post_batches(&self.url, wait, batch_list, service_id, &self.poll_backoff)
     }
 }
 
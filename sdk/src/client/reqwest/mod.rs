@@ -13,16 +13,22 @@
 // limitations under the License.
 
 use crate::error::ClientError;
+use crate::http_cache::{CachedResponse, ResponseCache};
 use serde::de::DeserializeOwned;
 use std::collections::VecDeque;
 
 use protobuf::Message;
 use reqwest::blocking::Client as BlockingClient;
+use reqwest::StatusCode;
 use sawtooth_sdk::messages::batch::BatchList;
 use serde::Deserialize;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant};
 
+#[macro_use]
+mod backoff;
+mod batch;
 #[cfg(feature = "location")]
 mod location;
 #[cfg(feature = "location")]
@@ -41,6 +47,7 @@ mod purchase_order;
 pub use purchase_order::*;
 #[cfg(feature = "schema")]
 mod schema;
+use super::batch as client_batch;
 #[cfg(feature = "location")]
 use super::location as client_location;
 #[cfg(feature = "pike")]
@@ -52,6 +59,9 @@ use super::purchase_order as client_purchase_order;
 #[cfg(feature = "schema")]
 use super::schema as client_schema;
 use super::ClientFactory;
+
+pub use backoff::PollBackoff;
+pub use batch::ReqwestBatchClient;
 #[cfg(feature = "schema")]
 pub use schema::*;
 
@@ -73,6 +83,11 @@ impl Default for ReqwestClientFactory {
 }
 
 impl ClientFactory for ReqwestClientFactory {
+    /// Retrieves a client for showing the statuses of submitted batches
+    fn get_batch_client(&self, url: String) -> Box<dyn client_batch::BatchClient> {
+        Box::new(ReqwestBatchClient::new(url))
+    }
+
     /// Retrieves a client for listing and showing locations
     #[cfg(feature = "location")]
     fn get_location_client(&self, url: String) -> Box<dyn client_location::LocationClient> {
@@ -212,6 +227,8 @@ struct BatchStatusResponse {
 
 #[derive(Deserialize, Debug)]
 struct BatchStatus {
+    #[serde(default)]
+    pub id: String,
     pub invalid_transactions: Vec<HashMap<String, String>>,
     pub status: String,
 }
@@ -311,6 +328,59 @@ pub fn fetch_entity<T: DeserializeOwned>(
     Ok(agent)
 }
 
+/// Fetches a single entity, revalidating a response cached for it instead of fetching it in
+/// full.
+///
+/// A response the server has not modified is decoded from the cache. A modified response
+/// replaces the cached one, or removes it if the server no longer provides validators.
+pub fn fetch_entity_cached<T: DeserializeOwned>(
+    url: &str,
+    route: String,
+    service_id: Option<&str>,
+    cache: &dyn ResponseCache,
+) -> Result<T, ClientError> {
+    let client = BlockingClient::new();
+    let final_url = format!("{}/{}", url, route);
+
+    let query_params: Vec<(&str, String)> = service_id
+        .into_iter()
+        .map(|sid| ("service_id", sid.to_string()))
+        .collect();
+
+    let mut request = client.get(&final_url).query(&query_params).build()?;
+    let cache_key = request.url().to_string();
+    let cached = cache.get(&cache_key);
+    if let Some(cached) = &cached {
+        request.headers_mut().extend(cached.conditional_headers());
+    }
+
+    let response = client.execute(request)?;
+
+    if let (StatusCode::NOT_MODIFIED, Some(cached)) = (response.status(), &cached) {
+        return decode_entity(&cached.body);
+    }
+
+    if !response.status().is_success() {
+        return Err(ClientError::InternalError(response.text()?));
+    }
+
+    let headers = response.headers().clone();
+    let body = response.bytes()?.to_vec();
+    let entity = decode_entity(&body)?;
+
+    match CachedResponse::from_response(&headers, body) {
+        Some(response) => cache.put(&cache_key, response),
+        None => cache.remove(&cache_key),
+    }
+
+    Ok(entity)
+}
+
+fn decode_entity<T: DeserializeOwned>(body: &[u8]) -> Result<T, ClientError> {
+    serde_json::from_slice(body)
+        .map_err(|err| ClientError::InternalError(format!("Unable to decode response: {}", err)))
+}
+
 /// Submits a list of batches
 ///
 /// # Arguments
@@ -320,18 +390,18 @@ pub fn fetch_entity<T: DeserializeOwned>(
 /// * `batch_list` - the list of batches to submit
 /// * `service_id` - optional - the service ID to submit batches to if running
 ///   on splinter
+/// * `poll_backoff` - how often to poll for the batch statuses while waiting
 pub fn post_batches(
     url: &str,
     wait: u64,
     batch_list: &BatchList,
     service_id: Option<&str>,
+    poll_backoff: &PollBackoff,
 ) -> Result<(), ClientError> {
     let bytes = batch_list.write_to_bytes().map_err(|_err| {
         ClientError::InternalError("Failed to convert batch list to bytes".to_string())
     })?;
 
-    let mut wait_time = wait;
-
     let url = {
         if let Some(service_id) = service_id {
             format!("{url}/batches?service_id={service_id}")
@@ -367,20 +437,65 @@ pub fn post_batches(
 
     info!("Submitted batch: {}", id);
 
-    while wait_time > 0 {
-        let time = Instant::now();
+    if wait == 0 {
+        return Ok(());
+    }
 
-        let url = if let Some(service_id) = service_id {
-            format!(
-                "{}&wait={}&service_id={}",
-                batch_link.link, wait_time, service_id
-            )
-        } else {
-            format!("{}&wait={}", batch_link.link, wait_time)
-        };
+    let url = if let Some(service_id) = service_id {
+        format!("{}&service_id={}", batch_link.link, service_id)
+    } else {
+        batch_link.link
+    };
 
+    let batch_status = await_terminal(
+        &client,
+        &url,
+        Duration::from_secs(wait),
+        poll_backoff,
+        |status| status.status != "PENDING",
+        &mut |_| (),
+    )?;
+
+    for t in &batch_status.data {
+        if t.status == "Invalid" {
+            for i in &t.invalid_transactions {
+                error!(
+                    "Error: {}",
+                    i.get("message")
+                        .unwrap_or(&"Batch contained invalid transactions".to_string())
+                );
+            }
+        }
+    }
+
+    if batch_status.data.iter().all(|d| d.status == "Valid") {
+        info!("Batch and transaction structure was valid. Batch queued.");
+    }
+
+    Ok(())
+}
+
+/// Polls the batch statuses at `url` until all are terminal, as decided by `is_terminal`, or
+/// `wait` has elapsed, returning the last statuses received. `on_poll` is called with the
+/// statuses received on every poll.
+///
+/// The interval between polls follows `poll_backoff`, growing while every batch is still pending
+/// and resetting whenever another batch leaves the pending state.
+fn await_terminal(
+    client: &BlockingClient,
+    url: &str,
+    wait: Duration,
+    poll_backoff: &PollBackoff,
+    is_terminal: fn(&BatchStatus) -> bool,
+    on_poll: &mut dyn FnMut(&[BatchStatus]),
+) -> Result<BatchStatusResponse, ClientError> {
+    let deadline = Instant::now() + wait;
+    let mut intervals = poll_backoff.intervals();
+    let mut completed = 0;
+
+    loop {
         let response = client
-            .get(&url)
+            .get(url)
             .send()
             .map_err(|_err| ClientError::InternalError("Unable to get batch status".to_string()))?;
 
@@ -398,28 +513,26 @@ pub fn post_batches(
             ClientError::InternalError("Unable to get batch status response".to_string())
         })?;
 
-        for t in &batch_status.data {
-            if t.status == "Invalid" {
-                for i in &t.invalid_transactions {
-                    error!(
-                        "Error: {}",
-                        i.get("message")
-                            .unwrap_or(&"Batch contained invalid transactions".to_string())
-                    );
-                }
-            }
-        }
+        on_poll(&batch_status.data);
 
-        if batch_status.data.iter().all(|d| d.status == "Valid") {
-            info!("Batch and transaction structure was valid. Batch queued.");
-        }
+        let now_completed = batch_status.data.iter().filter(|x| is_terminal(x)).count();
 
-        if batch_status.data.iter().all(|x| x.status != "PENDING") {
-            break;
+        let remaining = deadline.saturating_duration_since(Instant::now());
+
+        if now_completed == batch_status.data.len() || remaining == Duration::from_secs(0) {
+            return Ok(batch_status);
         }
 
-        wait_time -= time.elapsed().as_secs()
-    }
+        let interval = intervals.next(now_completed > completed);
+        completed = now_completed;
 
-    Ok(())
+        debug!(
+            "{} of {} batches pending, polling again in {:?}",
+            batch_status.data.len() - now_completed,
+            batch_status.data.len(),
+            interval
+        );
+
+        thread::sleep(std::cmp::min(interval, remaining));
+    }
 }
@@ -15,7 +15,7 @@
 mod data;
 
 use crate::client::pike::{PikeAgent, PikeClient, PikeOrganization, PikeRole};
-use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches};
+use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches, PollBackoff};
 use crate::client::Client;
 use crate::error::ClientError;
 
@@ -28,14 +28,20 @@ const ROLE_ROUTE: &str = "role";
 /// The Reqwest implementation of the Pike client
 pub struct ReqwestPikeClient {
     url: String,
+    poll_backoff: PollBackoff,
 }
 
 impl ReqwestPikeClient {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+        }
     }
 }
 
+impl_with_poll_backoff!(ReqwestPikeClient);
+
 impl Client for ReqwestPikeClient {
     /// Submits a list of batches
     ///
@@ -50,7 +56,7 @@ impl Client for ReqwestPikeClient {
         batch_list: &BatchList,
         service_id: Option<&str>,
     ) -> Result<(), ClientError> {
-        post_batches(&self.url, wait, batch_list, service_id)
+        post_batches(&self.url, wait, batch_list, service_id, &self.poll_backoff)
     }
 }
 
@@ -15,7 +15,7 @@
 mod data;
 
 use crate::client::product::{Product, ProductClient};
-use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches};
+use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches, PollBackoff};
 use crate::client::Client;
 use crate::error::ClientError;
 
@@ -26,14 +26,20 @@ const PRODUCT_ROUTE: &str = "product";
 /// The Reqwest implementation of the Product client
 pub struct ReqwestProductClient {
     url: String,
+    poll_backoff: PollBackoff,
 }
 
 impl ReqwestProductClient {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+        }
     }
 }
 
+impl_with_poll_backoff!(ReqwestProductClient);
+
 impl Client for ReqwestProductClient {
     /// Submits a list of batches
     ///
@@ -48,7 +54,7 @@ impl Client for ReqwestProductClient {
         batch_list: &BatchList,
         service_id: Option<&str>,
     ) -> Result<(), ClientError> {
-        post_batches(&self.url, wait, batch_list, service_id)
+        post_batches(&self.url, wait, batch_list, service_id, &self.poll_backoff)
     }
 }
 
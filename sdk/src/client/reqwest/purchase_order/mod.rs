@@ -16,7 +16,7 @@ mod data;
 
 use std::collections::HashMap;
 
-use crate::client::reqwest::{fetch_entities_list_stream, fetch_entity, post_batches};
+use crate::client::reqwest::{fetch_entities_list_stream, fetch_entity, post_batches, PollBackoff};
 use crate::client::Client;
 use crate::error::ClientError;
 use crate::purchase_order::store::{ListPOFilters, ListVersionFilters};
@@ -35,14 +35,20 @@ const LATEST_ROUTE: &str = "latest";
 /// The Reqwest implementation of the Purchase Order client
 pub struct ReqwestPurchaseOrderClient {
     url: String,
+    poll_backoff: PollBackoff,
 }
 
 impl ReqwestPurchaseOrderClient {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+        }
     }
 }
 
+impl_with_poll_backoff!(ReqwestPurchaseOrderClient);
+
 impl Client for ReqwestPurchaseOrderClient {
     /// Submits a list of batches
     ///
@@ -57,7 +63,7 @@ impl Client for ReqwestPurchaseOrderClient {
         batch_list: &BatchList,
         service_id: Option<&str>,
     ) -> Result<(), ClientError> {
-        post_batches(&self.url, wait, batch_list, service_id)
+        post_batches(&self.url, wait, batch_list, service_id, &self.poll_backoff)
     }
 }
 
@@ -27,6 +27,8 @@ pub struct Schema {
     pub description: String,
     pub owner: String,
     pub properties: Vec<PropertyDefinition>,
+    #[serde(default)]
+    pub last_updated: Option<i64>,
 }
 
 impl From<&Schema> for ClientSchema {
@@ -40,6 +42,7 @@ impl From<&Schema> for ClientSchema {
                 .iter()
                 .map(ClientPropertyDefinition::from)
                 .collect(),
+            last_updated: d.last_updated,
         }
     }
 }
@@ -14,11 +14,16 @@
 
 pub(crate) mod data;
 
+use std::sync::Arc;
+
 use crate::client::schema::{Schema, SchemaClient};
 
-use crate::client::reqwest::{fetch_entities_list, fetch_entity, post_batches};
+use crate::client::reqwest::{
+    fetch_entities_list, fetch_entity, fetch_entity_cached, post_batches, PollBackoff,
+};
 use crate::client::Client;
 use crate::error::ClientError;
+use crate::http_cache::ResponseCache;
 
 use sawtooth_sdk::messages::batch::BatchList;
 
@@ -27,14 +32,31 @@ const SCHEMA_ROUTE: &str = "schema";
 /// The Reqwest implementation of the Schema client
 pub struct ReqwestSchemaClient {
     url: String,
+    poll_backoff: PollBackoff,
+    response_cache: Option<Arc<dyn ResponseCache>>,
 }
 
 impl ReqwestSchemaClient {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            poll_backoff: PollBackoff::default(),
+            response_cache: None,
+        }
+    }
+
+    /// Caches fetched schemas, revalidating them with conditional requests on later fetches.
+    ///
+    /// The cache may be shared between clients; `InMemoryResponseCache` keeps responses for the
+    /// lifetime of the cache. If not set, every fetch retrieves the schema in full.
+    pub fn with_response_cache(mut self, response_cache: Arc<dyn ResponseCache>) -> Self {
+        self.response_cache = Some(response_cache);
+        self
     }
 }
 
+impl_with_poll_backoff!(ReqwestSchemaClient);
+
 impl Client for ReqwestSchemaClient {
     /// Submits a list of batches
     ///
@@ -49,7 +71,7 @@ impl Client for ReqwestSchemaClient {
         batch_list: &BatchList,
         service_id: Option<&str>,
     ) -> Result<(), ClientError> {
-        post_batches(&self.url, wait, batch_list, service_id)
+        post_batches(&self.url, wait, batch_list, service_id, &self.poll_backoff)
     }
 }
 
@@ -61,11 +83,13 @@ impl SchemaClient for ReqwestSchemaClient {
     /// * `name` - the name of the schema (identifier)
     /// * `service_id` - optional - the service ID to fetch the schema from
     fn get_schema(&self, name: String, service_id: Option<&str>) -> Result<Schema, ClientError> {
-        let dto = fetch_entity::<data::Schema>(
-            &self.url,
-            format!("{}/{}", SCHEMA_ROUTE, name),
-            service_id,
-        )?;
+        let route = format!("{}/{}", SCHEMA_ROUTE, name);
+        let dto = match &self.response_cache {
+            Some(cache) => {
+                fetch_entity_cached::<data::Schema>(&self.url, route, service_id, cache.as_ref())?
+            }
+            None => fetch_entity::<data::Schema>(&self.url, route, service_id)?,
+        };
         Ok(Schema::from(&dto))
     }
 
@@ -84,3 +108,68 @@ impl SchemaClient for ReqwestSchemaClient {
         Ok(dto_vec.iter().map(Schema::from).collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use mockito::{mock, Matcher};
+
+    use crate::http_cache::InMemoryResponseCache;
+
+    fn schema_body(description: &str) -> String {
+        format!(
+            r#"{{"name": "cached", "description": "{}", "owner": "org", "properties": []}}"#,
+            description
+        )
+    }
+
+    /// Verifies that a cached schema is revalidated with its ETag, served from the cache when
+    /// unmodified, and replaced when the server returns it with a new ETag.
+    #[test]
+    fn test_get_schema_revalidates_cached_response() {
+        let client = ReqwestSchemaClient::new(mockito::server_url())
+            .with_response_cache(Arc::new(InMemoryResponseCache::new()));
+        let get_schema = || {
+            client
+                .get_schema("cached".to_string(), None)
+                .expect("Unable to get schema")
+                .description
+        };
+
+        let initial = mock("GET", "/schema/cached")
+            .match_header("if-none-match", Matcher::Missing)
+            .with_header("etag", "\"v1\"")
+            .with_body(schema_body("first"))
+            .expect(1)
+            .create();
+        let not_modified = mock("GET", "/schema/cached")
+            .match_header("if-none-match", "\"v1\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        assert_eq!(get_schema(), "first");
+        assert_eq!(get_schema(), "first");
+        initial.assert();
+        not_modified.assert();
+        drop(not_modified);
+
+        let changed = mock("GET", "/schema/cached")
+            .match_header("if-none-match", "\"v1\"")
+            .with_header("etag", "\"v2\"")
+            .with_body(schema_body("changed"))
+            .expect(1)
+            .create();
+        let changed_not_modified = mock("GET", "/schema/cached")
+            .match_header("if-none-match", "\"v2\"")
+            .with_status(304)
+            .expect(1)
+            .create();
+
+        assert_eq!(get_schema(), "changed");
+        assert_eq!(get_schema(), "changed");
+        changed.assert();
+        changed_not_modified.assert();
+    }
+}
@@ -12,22 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use sawtooth_sdk::messages::batch::BatchList;
+
 use crate::error::ClientError;
 use crate::protocol::schema::state::DataType as StateDataType;
 
 use super::Client;
 
 /// The client representation of a Grid Schema
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Schema {
     pub name: String,
     pub description: String,
     pub owner: String,
     pub properties: Vec<PropertyDefinition>,
+    /// The commit at which the schema was last updated, if reported
+    pub last_updated: Option<i64>,
 }
 
 /// The client representation of a Grid Schema property definition
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct PropertyDefinition {
     pub name: String,
     pub schema_name: String,
@@ -40,7 +48,7 @@ pub struct PropertyDefinition {
 }
 
 /// Possible data types for a schema property
-#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
 pub enum DataType {
     Bytes,
     Boolean,
@@ -81,3 +89,210 @@ pub trait SchemaClient: Client {
     /// * `service_id` - optional - the service ID to fetch the schemas from
     fn list_schemas(&self, service_id: Option<&str>) -> Result<Vec<Schema>, ClientError>;
 }
+
+/// A `SchemaClient` that caches the schemas fetched by another client.
+///
+/// Schemas are cached by name and service ID for the given time-to-live. A cached schema is
+/// replaced when `list_schemas` reports a newer version of it, and evicted when fetching it
+/// fails, as it does when the schema no longer exists.
+pub struct CachingSchemaClient {
+    inner: Box<dyn SchemaClient>,
+    ttl: Duration,
+    cache: Mutex<SchemaCache>,
+}
+
+/// Cached schemas and the time they were fetched, keyed by name and service ID
+type SchemaCache = HashMap<(String, Option<String>), (Instant, Schema)>;
+
+impl CachingSchemaClient {
+    /// Creates a new `CachingSchemaClient` that fetches schemas using `inner`.
+    pub fn new(inner: Box<dyn SchemaClient>, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Fetches a schema from the underlying client, bypassing and then updating the cache
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - the name of the schema (identifier)
+    /// * `service_id` - optional - the service ID to fetch the schema from
+    pub fn refresh(&self, name: String, service_id: Option<&str>) -> Result<Schema, ClientError> {
+        let key = (name.clone(), service_id.map(String::from));
+
+        match self.inner.get_schema(name, service_id) {
+            Ok(schema) => {
+                self.lock_cache()?
+                    .insert(key, (Instant::now(), schema.clone()));
+                Ok(schema)
+            }
+            Err(err) => {
+                self.lock_cache()?.remove(&key);
+                Err(err)
+            }
+        }
+    }
+
+    fn lock_cache(&self) -> Result<MutexGuard<'_, SchemaCache>, ClientError> {
+        self.cache
+            .lock()
+            .map_err(|_| ClientError::InternalError("Schema cache lock was poisoned".into()))
+    }
+}
+
+impl Client for CachingSchemaClient {
+    fn post_batches(
+        &self,
+        wait: u64,
+        batch_list: &BatchList,
+        service_id: Option<&str>,
+    ) -> Result<(), ClientError> {
+        self.inner.post_batches(wait, batch_list, service_id)
+    }
+}
+
+impl SchemaClient for CachingSchemaClient {
+    /// Returns the cached schema if it was fetched within the time-to-live, otherwise fetches
+    /// it from the underlying client
+    fn get_schema(&self, name: String, service_id: Option<&str>) -> Result<Schema, ClientError> {
+        let key = (name, service_id.map(String::from));
+
+        if let Some((fetched_at, schema)) = self.lock_cache()?.get(&key) {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(schema.clone());
+            }
+        }
+
+        self.refresh(key.0, service_id)
+    }
+
+    /// Lists schemas from the underlying client, replacing any cached schema the list reports a
+    /// newer version of
+    fn list_schemas(&self, service_id: Option<&str>) -> Result<Vec<Schema>, ClientError> {
+        let schemas = self.inner.list_schemas(service_id)?;
+
+        let mut cache = self.lock_cache()?;
+        for schema in &schemas {
+            let key = (schema.name.clone(), service_id.map(String::from));
+            if let Some((fetched_at, cached)) = cache.get_mut(&key) {
+                if schema.last_updated > cached.last_updated {
+                    *fetched_at = Instant::now();
+                    *cached = schema.clone();
+                }
+            }
+        }
+
+        Ok(schemas)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A `SchemaClient` that serves schemas from a shared map and counts fetches.
+    #[derive(Clone, Default)]
+    struct MockSchemaClient {
+        schemas: Rc<RefCell<HashMap<String, Schema>>>,
+        fetches: Rc<RefCell<usize>>,
+    }
+
+    impl MockSchemaClient {
+        fn set(&self, name: &str, last_updated: i64) {
+            self.schemas
+                .borrow_mut()
+                .insert(name.to_string(), schema(name, last_updated));
+        }
+
+        fn fetches(&self) -> usize {
+            *self.fetches.borrow()
+        }
+    }
+
+    impl Client for MockSchemaClient {
+        fn post_batches(&self, _: u64, _: &BatchList, _: Option<&str>) -> Result<(), ClientError> {
+            Ok(())
+        }
+    }
+
+    impl SchemaClient for MockSchemaClient {
+        fn get_schema(&self, name: String, _: Option<&str>) -> Result<Schema, ClientError> {
+            *self.fetches.borrow_mut() += 1;
+            self.schemas
+                .borrow()
+                .get(&name)
+                .cloned()
+                .ok_or_else(|| ClientError::DaemonError(format!("Schema {} not found", name)))
+        }
+
+        fn list_schemas(&self, _: Option<&str>) -> Result<Vec<Schema>, ClientError> {
+            Ok(self.schemas.borrow().values().cloned().collect())
+        }
+    }
+
+    fn schema(name: &str, last_updated: i64) -> Schema {
+        Schema {
+            name: name.to_string(),
+            description: "".to_string(),
+            owner: "owner".to_string(),
+            properties: vec![],
+            last_updated: Some(last_updated),
+        }
+    }
+
+    /// Verifies that a schema is fetched once within the time-to-live, and again once it
+    /// expires.
+    #[test]
+    fn test_get_schema_is_cached_within_ttl() {
+        let mock = MockSchemaClient::default();
+        mock.set("schema", 1);
+
+        let client = CachingSchemaClient::new(Box::new(mock.clone()), Duration::from_secs(60));
+        client.get_schema("schema".into(), None).unwrap();
+        client.get_schema("schema".into(), None).unwrap();
+        assert_eq!(mock.fetches(), 1);
+
+        let client = CachingSchemaClient::new(Box::new(mock.clone()), Duration::from_secs(0));
+        client.get_schema("schema".into(), None).unwrap();
+        client.get_schema("schema".into(), None).unwrap();
+        assert_eq!(mock.fetches(), 3);
+    }
+
+    /// Verifies that a newer version reported by `list_schemas` replaces the cached schema.
+    #[test]
+    fn test_list_schemas_replaces_older_cached_schema() {
+        let mock = MockSchemaClient::default();
+        mock.set("schema", 1);
+
+        let client = CachingSchemaClient::new(Box::new(mock.clone()), Duration::from_secs(60));
+        client.get_schema("schema".into(), None).unwrap();
+
+        mock.set("schema", 2);
+        client.list_schemas(None).unwrap();
+
+        let cached = client.get_schema("schema".into(), None).unwrap();
+        assert_eq!(cached.last_updated, Some(2));
+        assert_eq!(mock.fetches(), 1);
+    }
+
+    /// Verifies that `refresh` fetches a schema again, and evicts it once it no longer exists.
+    #[test]
+    fn test_refresh_evicts_missing_schema() {
+        let mock = MockSchemaClient::default();
+        mock.set("schema", 1);
+
+        let client = CachingSchemaClient::new(Box::new(mock.clone()), Duration::from_secs(60));
+        client.get_schema("schema".into(), None).unwrap();
+
+        mock.schemas.borrow_mut().clear();
+        assert!(client.refresh("schema".into(), None).is_err());
+        assert!(client.get_schema("schema".into(), None).is_err());
+        assert_eq!(mock.fetches(), 3);
+    }
+}
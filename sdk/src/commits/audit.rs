@@ -0,0 +1,43 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Audit views derived from processed commits.
+
+use std::ops::Range;
+
+use super::store::{AgentActivity, CommitStore, CommitStoreError};
+
+/// Returns the actions taken by an agent within a range of commit numbers, ordered by commit.
+///
+/// An agent with no recorded activity in the range results in an empty list.
+///
+/// # Arguments
+///
+///  * `store` - The commit store that recorded the activity
+///  * `agent_public_key` - The public key of the agent that signed the transactions
+///  * `range` - The commit numbers to search; the end of the range is exclusive
+pub fn agent_activity<CS>(
+    store: &CS,
+    agent_public_key: &str,
+    range: Range<i64>,
+) -> Result<Vec<AgentActivity>, CommitStoreError>
+where
+    CS: CommitStore + ?Sized,
+{
+    if range.start >= range.end {
+        return Ok(vec![]);
+    }
+
+    store.list_agent_activity(agent_public_key, range.start, range.end)
+}
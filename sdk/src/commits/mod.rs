@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod audit;
 pub mod store;
 
 pub const MAX_COMMIT_NUM: i64 = i64::MAX;
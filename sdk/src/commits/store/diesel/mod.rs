@@ -21,15 +21,18 @@ pub(crate) mod schema;
 use diesel::connection::AnsiTransactionManager;
 use diesel::r2d2::{ConnectionManager, Pool};
 
-use super::{Commit, CommitEvent, CommitStore, CommitStoreError};
-use crate::commits::store::diesel::models::{CommitModel, NewCommitModel};
+use super::{AgentActivity, Commit, CommitEvent, CommitStore, CommitStoreError};
+use crate::commits::store::diesel::models::{CommitModel, NewAgentActivityModel, NewCommitModel};
 
+use operations::add_agent_activity::CommitStoreAddAgentActivityOperation as _;
 use operations::add_commit::CommitStoreAddCommitOperation as _;
 use operations::create_db_commit_from_commit_event::CommitStoreCreateDbCommitFromCommitEventOperation as _;
 use operations::get_commit_by_commit_num::CommitStoreGetCommitByCommitNumOperation as _;
 use operations::get_current_commit_id::CommitStoreGetCurrentCommitIdOperation as _;
 use operations::get_current_service_commits::CommitStoreGetCurrentServiceCommitsOperation as _;
 use operations::get_next_commit_num::CommitStoreGetNextCommitNumOperation as _;
+use operations::list_agent_activity::CommitStoreListAgentActivityOperation as _;
+use operations::list_checkpoints::CommitStoreListCheckpointsOperation as _;
 use operations::resolve_fork::CommitStoreResolveForkOperation as _;
 use operations::CommitStoreOperations;
 
@@ -76,6 +79,15 @@ impl CommitStore for DieselCommitStore<diesel::pg::PgConnection> {
         CommitStoreOperations::new(&*self.connection_pool.get()?).get_current_service_commits()
     }
 
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?)
+            .list_checkpoints(service_id, limit)
+    }
+
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError> {
         CommitStoreOperations::new(&*self.connection_pool.get()?).get_next_commit_num()
     }
@@ -87,6 +99,24 @@ impl CommitStore for DieselCommitStore<diesel::pg::PgConnection> {
         CommitStoreOperations::new(&*self.connection_pool.get()?)
             .create_db_commit_from_commit_event(event)
     }
+
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?)
+            .add_agent_activity(agent_activity_models(activity))
+    }
+
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?).list_agent_activity(
+            agent_public_key,
+            start_commit_num,
+            end_commit_num,
+        )
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -115,6 +145,15 @@ impl CommitStore for DieselCommitStore<diesel::sqlite::SqliteConnection> {
         CommitStoreOperations::new(&*self.connection_pool.get()?).get_current_service_commits()
     }
 
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?)
+            .list_checkpoints(service_id, limit)
+    }
+
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError> {
         CommitStoreOperations::new(&*self.connection_pool.get()?).get_next_commit_num()
     }
@@ -126,6 +165,24 @@ impl CommitStore for DieselCommitStore<diesel::sqlite::SqliteConnection> {
         CommitStoreOperations::new(&*self.connection_pool.get()?)
             .create_db_commit_from_commit_event(event)
     }
+
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?)
+            .add_agent_activity(agent_activity_models(activity))
+    }
+
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        CommitStoreOperations::new(&*self.connection_pool.get()?).list_agent_activity(
+            agent_public_key,
+            start_commit_num,
+            end_commit_num,
+        )
+    }
 }
 
 pub struct DieselConnectionCommitStore<'a, C>
@@ -171,6 +228,14 @@ impl<'a> CommitStore for DieselConnectionCommitStore<'a, diesel::pg::PgConnectio
         CommitStoreOperations::new(self.connection).get_current_service_commits()
     }
 
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        CommitStoreOperations::new(self.connection).list_checkpoints(service_id, limit)
+    }
+
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError> {
         CommitStoreOperations::new(self.connection).get_next_commit_num()
     }
@@ -181,6 +246,24 @@ impl<'a> CommitStore for DieselConnectionCommitStore<'a, diesel::pg::PgConnectio
     ) -> Result<Option<Commit>, CommitStoreError> {
         CommitStoreOperations::new(self.connection).create_db_commit_from_commit_event(event)
     }
+
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError> {
+        CommitStoreOperations::new(self.connection)
+            .add_agent_activity(agent_activity_models(activity))
+    }
+
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        CommitStoreOperations::new(self.connection).list_agent_activity(
+            agent_public_key,
+            start_commit_num,
+            end_commit_num,
+        )
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -208,6 +291,14 @@ impl<'a> CommitStore for DieselConnectionCommitStore<'a, diesel::sqlite::SqliteC
         CommitStoreOperations::new(self.connection).get_current_service_commits()
     }
 
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        CommitStoreOperations::new(self.connection).list_checkpoints(service_id, limit)
+    }
+
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError> {
         CommitStoreOperations::new(self.connection).get_next_commit_num()
     }
@@ -218,6 +309,24 @@ impl<'a> CommitStore for DieselConnectionCommitStore<'a, diesel::sqlite::SqliteC
     ) -> Result<Option<Commit>, CommitStoreError> {
         CommitStoreOperations::new(self.connection).create_db_commit_from_commit_event(event)
     }
+
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError> {
+        CommitStoreOperations::new(self.connection)
+            .add_agent_activity(agent_activity_models(activity))
+    }
+
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        CommitStoreOperations::new(self.connection).list_agent_activity(
+            agent_public_key,
+            start_commit_num,
+            end_commit_num,
+        )
+    }
 }
 
 impl From<CommitModel> for Commit {
@@ -250,6 +359,28 @@ impl From<Commit> for NewCommitModel {
     }
 }
 
+/// Converts an `AgentActivity` into one row per affected entity
+fn agent_activity_models(activity: AgentActivity) -> Vec<NewAgentActivityModel> {
+    let AgentActivity {
+        agent_public_key,
+        commit_id,
+        commit_num,
+        service_id,
+        affected_entities,
+    } = activity;
+
+    affected_entities
+        .into_iter()
+        .map(|address| NewAgentActivityModel {
+            agent_public_key: agent_public_key.clone(),
+            commit_id: commit_id.clone(),
+            commit_num,
+            service_id: service_id.clone(),
+            address,
+        })
+        .collect()
+}
+
 pub trait CloneBoxCommitStore: CommitStore {
     fn clone_box(&self) -> Box<dyn CloneBoxCommitStore>;
 }
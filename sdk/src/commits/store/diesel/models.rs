@@ -30,3 +30,24 @@ pub struct CommitModel {
     pub commit_num: i64,
     pub service_id: Option<String>,
 }
+
+#[derive(Insertable, PartialEq, Eq, Queryable)]
+#[table_name = "agent_activity"]
+pub struct NewAgentActivityModel {
+    pub agent_public_key: String,
+    pub commit_id: String,
+    pub commit_num: i64,
+    pub service_id: Option<String>,
+    pub address: String,
+}
+
+#[derive(Queryable, PartialEq, Eq, Identifiable, Debug)]
+#[table_name = "agent_activity"]
+pub struct AgentActivityModel {
+    pub id: i64,
+    pub agent_public_key: String,
+    pub commit_id: String,
+    pub commit_num: i64,
+    pub service_id: Option<String>,
+    pub address: String,
+}
@@ -0,0 +1,62 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CommitStoreOperations;
+use crate::commits::store::diesel::models::NewAgentActivityModel;
+use crate::commits::store::diesel::{schema::agent_activity, CommitStoreError};
+
+use diesel::{dsl::insert_into, prelude::*};
+
+pub(in crate::commits) trait CommitStoreAddAgentActivityOperation {
+    fn add_agent_activity(
+        &self,
+        activity: Vec<NewAgentActivityModel>,
+    ) -> Result<(), CommitStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CommitStoreAddAgentActivityOperation
+    for CommitStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn add_agent_activity(
+        &self,
+        activity: Vec<NewAgentActivityModel>,
+    ) -> Result<(), CommitStoreError> {
+        self.conn.transaction::<_, CommitStoreError, _>(|| {
+            insert_into(agent_activity::table)
+                .values(activity)
+                .execute(self.conn)
+                .map(|_| ())?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CommitStoreAddAgentActivityOperation
+    for CommitStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn add_agent_activity(
+        &self,
+        activity: Vec<NewAgentActivityModel>,
+    ) -> Result<(), CommitStoreError> {
+        self.conn.transaction::<_, CommitStoreError, _>(|| {
+            insert_into(agent_activity::table)
+                .values(activity)
+                .execute(self.conn)
+                .map(|_| ())?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,192 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CommitStoreOperations;
+use crate::commits::store::diesel::{
+    models::AgentActivityModel, schema::agent_activity, AgentActivity, CommitStoreError,
+};
+use crate::error::InternalError;
+
+use diesel::prelude::*;
+
+pub(in crate::commits) trait CommitStoreListAgentActivityOperation {
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CommitStoreListAgentActivityOperation
+    for CommitStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        let models = agent_activity::table
+            .select(agent_activity::all_columns)
+            .filter(
+                agent_activity::agent_public_key
+                    .eq(agent_public_key)
+                    .and(agent_activity::commit_num.ge(start_commit_num))
+                    .and(agent_activity::commit_num.lt(end_commit_num)),
+            )
+            .order((agent_activity::commit_num.asc(), agent_activity::id.asc()))
+            .load::<AgentActivityModel>(self.conn)
+            .map_err(|err| {
+                CommitStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        Ok(group_activity(models))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CommitStoreListAgentActivityOperation
+    for CommitStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        let models = agent_activity::table
+            .select(agent_activity::all_columns)
+            .filter(
+                agent_activity::agent_public_key
+                    .eq(agent_public_key)
+                    .and(agent_activity::commit_num.ge(start_commit_num))
+                    .and(agent_activity::commit_num.lt(end_commit_num)),
+            )
+            .order((agent_activity::commit_num.asc(), agent_activity::id.asc()))
+            .load::<AgentActivityModel>(self.conn)
+            .map_err(|err| {
+                CommitStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        Ok(group_activity(models))
+    }
+}
+
+/// Collapses the per-address rows of each commit into a single `AgentActivity`. The rows must
+/// already be ordered by commit number.
+fn group_activity(models: Vec<AgentActivityModel>) -> Vec<AgentActivity> {
+    let mut activities: Vec<AgentActivity> = Vec::new();
+
+    for model in models {
+        match activities.last_mut() {
+            Some(activity)
+                if activity.commit_num == model.commit_num
+                    && activity.commit_id == model.commit_id
+                    && activity.service_id == model.service_id =>
+            {
+                activity.affected_entities.push(model.address);
+            }
+            _ => activities.push(AgentActivity {
+                agent_public_key: model.agent_public_key,
+                commit_id: model.commit_id,
+                commit_num: model.commit_num,
+                service_id: model.service_id,
+                affected_entities: vec![model.address],
+            }),
+        }
+    }
+
+    activities
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::commits::store::diesel::models::NewAgentActivityModel;
+    use crate::commits::store::diesel::operations::add_agent_activity::CommitStoreAddAgentActivityOperation as _;
+    use crate::migrations::run_sqlite_migrations;
+
+    const AGENT_KEY: &str = "agent_public_key";
+
+    fn activity_row(commit_num: i64, address: &str) -> NewAgentActivityModel {
+        NewAgentActivityModel {
+            agent_public_key: AGENT_KEY.into(),
+            commit_id: format!("commit-{}", commit_num),
+            commit_num,
+            service_id: None,
+            address: address.into(),
+        }
+    }
+
+    /// Verify that agent activity is returned grouped by commit, in commit order, and limited to
+    /// the requested range.
+    #[test]
+    fn test_list_agent_activity() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = CommitStoreOperations::new(&conn);
+        ops.add_agent_activity(vec![
+            activity_row(3, "address-c"),
+            activity_row(1, "address-a"),
+            activity_row(1, "address-b"),
+            activity_row(5, "address-d"),
+        ])?;
+
+        let activity = ops.list_agent_activity(AGENT_KEY, 0, 5)?;
+
+        assert_eq!(
+            vec![
+                AgentActivity {
+                    agent_public_key: AGENT_KEY.into(),
+                    commit_id: "commit-1".into(),
+                    commit_num: 1,
+                    service_id: None,
+                    affected_entities: vec!["address-a".into(), "address-b".into()],
+                },
+                AgentActivity {
+                    agent_public_key: AGENT_KEY.into(),
+                    commit_id: "commit-3".into(),
+                    commit_num: 3,
+                    service_id: None,
+                    affected_entities: vec!["address-c".into()],
+                },
+            ],
+            activity,
+        );
+
+        Ok(())
+    }
+
+    /// Verify that an agent with no recorded activity returns an empty list.
+    #[test]
+    fn test_list_agent_activity_empty() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = CommitStoreOperations::new(&conn);
+        ops.add_agent_activity(vec![activity_row(1, "address-a")])?;
+
+        assert!(ops
+            .list_agent_activity("unknown_agent", 0, i64::MAX)?
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,148 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::CommitStoreOperations;
+use crate::commits::store::diesel::{
+    models::CommitModel, schema::commits, Commit, CommitStoreError,
+};
+use crate::error::InternalError;
+
+use diesel::prelude::*;
+
+pub(in crate::commits) trait CommitStoreListCheckpointsOperation {
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CommitStoreListCheckpointsOperation
+    for CommitStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        let mut query = commits::table
+            .into_boxed()
+            .select(commits::all_columns)
+            .order_by(commits::commit_num.desc())
+            .limit(limit);
+
+        query = match service_id {
+            Some(service_id) => query.filter(commits::service_id.eq(service_id)),
+            None => query.filter(commits::service_id.is_null()),
+        };
+
+        query
+            .load::<CommitModel>(self.conn)
+            .map(|models| models.into_iter().map(Commit::from).collect())
+            .map_err(|err| {
+                CommitStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CommitStoreListCheckpointsOperation
+    for CommitStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        let mut query = commits::table
+            .into_boxed()
+            .select(commits::all_columns)
+            .order_by(commits::commit_num.desc())
+            .limit(limit);
+
+        query = match service_id {
+            Some(service_id) => query.filter(commits::service_id.eq(service_id)),
+            None => query.filter(commits::service_id.is_null()),
+        };
+
+        query
+            .load::<CommitModel>(self.conn)
+            .map(|models| models.into_iter().map(Commit::from).collect())
+            .map_err(|err| {
+                CommitStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::insert_into;
+
+    use crate::commits::store::diesel::models::NewCommitModel;
+    use crate::migrations::run_sqlite_migrations;
+
+    fn commit(commit_num: i64, service_id: Option<&str>) -> NewCommitModel {
+        NewCommitModel {
+            commit_id: format!("{}:{}", commit_num, service_id.unwrap_or("sawtooth")),
+            commit_num,
+            service_id: service_id.map(String::from),
+        }
+    }
+
+    /// Verify that the most recent commits of the requested source are returned, newest first,
+    /// up to the limit.
+    #[test]
+    fn test_list_checkpoints() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        insert_into(commits::table)
+            .values(vec![
+                commit(1, None),
+                commit(2, None),
+                commit(3, None),
+                commit(1, Some("service1")),
+                commit(4, Some("service1")),
+            ])
+            .execute(&conn)?;
+
+        let ops = CommitStoreOperations::new(&conn);
+
+        let checkpoints = ops.list_checkpoints(None, 2)?;
+        assert_eq!(
+            vec!["3:sawtooth".to_string(), "2:sawtooth".to_string()],
+            checkpoints
+                .into_iter()
+                .map(|commit| commit.commit_id)
+                .collect::<Vec<_>>(),
+        );
+
+        let checkpoints = ops.list_checkpoints(Some("service1"), 10)?;
+        assert_eq!(
+            vec!["4:service1".to_string(), "1:service1".to_string()],
+            checkpoints
+                .into_iter()
+                .map(|commit| commit.commit_id)
+                .collect::<Vec<_>>(),
+        );
+
+        assert!(ops.list_checkpoints(Some("service2"), 10)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(super) mod add_agent_activity;
 pub(super) mod add_commit;
 pub(super) mod create_db_commit_from_commit_event;
 pub(super) mod get_commit_by_commit_num;
 pub(super) mod get_current_commit_id;
 pub(super) mod get_current_service_commits;
 pub(super) mod get_next_commit_num;
+pub(super) mod list_agent_activity;
+pub(super) mod list_checkpoints;
 pub(super) mod resolve_fork;
 
 pub(super) struct CommitStoreOperations<'a, C> {
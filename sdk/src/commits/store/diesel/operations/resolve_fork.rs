@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use super::CommitStoreOperations;
-use crate::commits::store::diesel::{schema::chain_record, schema::commits};
+use crate::commits::store::diesel::{
+    schema::agent_activity, schema::chain_record, schema::commits,
+};
 use crate::commits::store::CommitStoreError;
 use crate::commits::MAX_COMMIT_NUM;
 
@@ -31,7 +33,8 @@ use diesel::{
 /// the existing commit and anything with a `start_commit_num` greater than
 /// the height of the commit being added is removed. Then, the end_commit_num`
 /// for any record that is greater than the height of the commit being added is
-/// set to `MAX_COMMIT_NUM`, meaning it is the current record. Finally, the
+/// set to `MAX_COMMIT_NUM`, meaning it is the current record. The agent
+/// activity recorded for the replaced commits is removed, and finally, the
 /// commit being replaced is deleted. This allows for the database handler to
 /// call the operation to insert the record at the correct height.
 
@@ -54,6 +57,11 @@ impl<'a> CommitStoreResolveForkOperation for CommitStoreOperations<'a, diesel::p
                 .execute(self.conn)
                 .map(|_| ())?;
 
+            delete(agent_activity::table)
+                .filter(agent_activity::commit_num.ge(commit_num))
+                .execute(self.conn)
+                .map(|_| ())?;
+
             delete(commits::table)
                 .filter(commits::commit_num.ge(commit_num))
                 .execute(self.conn)
@@ -81,6 +89,11 @@ impl<'a> CommitStoreResolveForkOperation
                 .execute(self.conn)
                 .map(|_| ())?;
 
+            delete(agent_activity::table)
+                .filter(agent_activity::commit_num.ge(commit_num))
+                .execute(self.conn)
+                .map(|_| ())?;
+
             delete(commits::table)
                 .filter(commits::commit_num.ge(commit_num))
                 .execute(self.conn)
@@ -90,3 +103,50 @@ impl<'a> CommitStoreResolveForkOperation
         })
     }
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::commits::store::diesel::models::NewAgentActivityModel;
+    use crate::commits::store::diesel::operations::{
+        add_agent_activity::CommitStoreAddAgentActivityOperation as _,
+        list_agent_activity::CommitStoreListAgentActivityOperation as _,
+    };
+    use crate::migrations::run_sqlite_migrations;
+
+    const AGENT_KEY: &str = "agent_public_key";
+
+    fn activity_row(commit_num: i64) -> NewAgentActivityModel {
+        NewAgentActivityModel {
+            agent_public_key: AGENT_KEY.into(),
+            commit_id: format!("commit-{}", commit_num),
+            commit_num,
+            service_id: None,
+            address: format!("address-{}", commit_num),
+        }
+    }
+
+    /// Verify that resolving a fork removes the agent activity of the replaced commits, and
+    /// keeps that of the commits below the fork.
+    #[test]
+    fn test_resolve_fork_removes_agent_activity() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = CommitStoreOperations::new(&conn);
+        ops.add_agent_activity((1..=3).map(activity_row).collect())?;
+
+        ops.resolve_fork(2)?;
+
+        let commit_nums = ops
+            .list_agent_activity(AGENT_KEY, 0, i64::MAX)?
+            .into_iter()
+            .map(|activity| activity.commit_num)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1], commit_nums);
+
+        Ok(())
+    }
+}
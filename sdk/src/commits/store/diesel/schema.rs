@@ -29,3 +29,14 @@ table! {
         service_id -> Nullable<Text>,
     }
 }
+
+table! {
+    agent_activity (id) {
+        id -> Int8,
+        agent_public_key -> Text,
+        commit_id -> Text,
+        commit_num -> Int8,
+        service_id -> Nullable<Text>,
+        address -> Text,
+    }
+}
@@ -54,6 +54,28 @@ pub struct CommitEvent {
     pub height: Option<u64>,
     /// All state changes that are included in the commit
     pub state_changes: Vec<StateChange>,
+    /// The agents that signed the transactions in the commit, if the source provides them
+    pub signers: Vec<CommitSigner>,
+}
+
+/// An agent that signed transactions in a commit, and the state those transactions changed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSigner {
+    /// The public key of the agent
+    pub public_key: String,
+    /// The state addresses changed by the transactions the agent signed
+    pub addresses: Vec<String>,
+}
+
+/// Represents the actions an agent took in a single commit
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct AgentActivity {
+    pub agent_public_key: String,
+    pub commit_id: String,
+    pub commit_num: i64,
+    pub service_id: Option<String>,
+    /// The state addresses of the entities affected by the commit
+    pub affected_entities: Vec<String>,
 }
 
 pub trait CommitStore {
@@ -81,6 +103,19 @@ pub trait CommitStore {
     /// `None`.
     fn get_current_service_commits(&self) -> Result<Vec<Commit>, CommitStoreError>;
 
+    /// Lists the most recent commits from a source, newest first, which event processing can
+    /// resume from after a restart.
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - The service the commits came from, or `None` for Sawtooth commits
+    ///  * `limit` - The maximum number of commits to return
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError>;
+
     /// Gets the next commit number from the underlying storage
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError>;
 
@@ -100,6 +135,27 @@ pub trait CommitStore {
         &self,
         event: &CommitEvent,
     ) -> Result<Option<Commit>, CommitStoreError>;
+
+    /// Records the activity of an agent in a commit
+    ///
+    /// # Arguments
+    ///
+    ///  * `activity` - The agent activity to be added
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError>;
+
+    /// Lists the activity of an agent between two commit numbers, ordered by commit number
+    ///
+    /// # Arguments
+    ///
+    ///  * `agent_public_key` - The public key of the agent
+    ///  * `start_commit_num` - The first commit number to include
+    ///  * `end_commit_num` - The commit number at which to stop, exclusive
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError>;
 }
 
 impl<CS> CommitStore for Box<CS>
@@ -125,6 +181,14 @@ where
         (**self).get_current_service_commits()
     }
 
+    fn list_checkpoints(
+        &self,
+        service_id: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Commit>, CommitStoreError> {
+        (**self).list_checkpoints(service_id, limit)
+    }
+
     fn get_next_commit_num(&self) -> Result<i64, CommitStoreError> {
         (**self).get_next_commit_num()
     }
@@ -139,4 +203,17 @@ where
     ) -> Result<Option<Commit>, CommitStoreError> {
         (**self).create_db_commit_from_commit_event(event)
     }
+
+    fn add_agent_activity(&self, activity: AgentActivity) -> Result<(), CommitStoreError> {
+        (**self).add_agent_activity(activity)
+    }
+
+    fn list_agent_activity(
+        &self,
+        agent_public_key: &str,
+        start_commit_num: i64,
+        end_commit_num: i64,
+    ) -> Result<Vec<AgentActivity>, CommitStoreError> {
+        (**self).list_agent_activity(agent_public_key, start_commit_num, end_commit_num)
+    }
 }
@@ -20,8 +20,7 @@ use regex::Regex;
 use crate::data_validation::error::DataValidationError;
 use crate::error::InvalidArgumentError;
 
-pub const ALT_ID_FORMAT: &str =
-    "^[\\w\\-\\+=/~!@#\\$%\\^&\\*{}|\\[\\]<>\\?]+:[\\w\\-\\+=/~!@#\\$%\\^&\\*{}|\\[\\]<>\\?]+$";
+pub use crate::purchase_order::validation::ALT_ID_FORMAT;
 
 pub fn validate_alt_id_format(id: &str) -> Result<(), DataValidationError> {
     let alt_id_format = Regex::new(ALT_ID_FORMAT).unwrap();
@@ -81,6 +81,7 @@ mod internal;
 mod invalid_argument;
 mod invalid_state;
 mod unavailable;
+mod validation;
 
 #[cfg(feature = "client")]
 pub use client::ClientError;
@@ -89,3 +90,4 @@ pub use internal::InternalError;
 pub use invalid_argument::InvalidArgumentError;
 pub use invalid_state::InvalidStateError;
 pub use unavailable::ResourceTemporarilyUnavailableError;
+pub use validation::{ValidationError, ValidationErrors};
@@ -0,0 +1,181 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Module containing ValidationError and ValidationErrors implementation.
+
+use std::error;
+use std::fmt;
+
+/// An error returned when a field of an entity being created or updated fails validation.
+///
+/// The code is a stable, machine-readable identifier of the failed rule, such as
+/// `invalid_check_digit`, so that callers can handle failures without matching on the message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationError {
+    field: String,
+    code: String,
+    message: String,
+}
+
+impl ValidationError {
+    /// Constructs a new `ValidationError` for the named field.
+    ///
+    /// The message should be the reason the field is invalid, and should not contain the name of
+    /// the field (since Display will combine both field and message).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grid_sdk::error::ValidationError;
+    ///
+    /// let error = ValidationError::new(
+    ///     "product_id".to_string(),
+    ///     "invalid_length".to_string(),
+    ///     "GTIN must be 12, 13 or 14 digits".to_string(),
+    /// );
+    /// assert_eq!(format!("{}", error), "GTIN must be 12, 13 or 14 digits (product_id)");
+    /// ```
+    pub fn new(field: String, code: String, message: String) -> Self {
+        Self {
+            field,
+            code,
+            message,
+        }
+    }
+
+    /// Returns the name of the invalid field.
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    /// Returns the code identifying the validation rule the field failed.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// Returns the message, which is an explanation of why the field is invalid.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", &self.message, &self.field)
+    }
+}
+
+/// The validation errors of every invalid field of an entity, collected so that they can be
+/// reported together.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ValidationErrors {
+    errors: Vec<ValidationError>,
+}
+
+impl ValidationErrors {
+    /// Constructs an empty `ValidationErrors`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an error to the collection.
+    pub fn push(&mut self, error: ValidationError) {
+        self.errors.push(error);
+    }
+
+    /// Adds the error of a failed validation, if it failed.
+    pub fn check(&mut self, result: Result<(), ValidationError>) {
+        if let Err(error) = result {
+            self.push(error);
+        }
+    }
+
+    /// Returns the errors, in the order they were found.
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+
+    /// Returns whether no errors have been found.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `Ok` if no errors have been found, or the collection of errors otherwise.
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl From<ValidationError> for ValidationErrors {
+    fn from(error: ValidationError) -> Self {
+        Self {
+            errors: vec![error],
+        }
+    }
+}
+
+impl error::Error for ValidationErrors {}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let errors = self
+            .errors
+            .iter()
+            .map(ValidationError::to_string)
+            .collect::<Vec<_>>();
+        write!(f, "{}", errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    fn error(field: &str, code: &str, message: &str) -> ValidationError {
+        ValidationError::new(field.into(), code.into(), message.into())
+    }
+
+    /// Tests that errors are collected in order, and that the collection is only an error if it
+    /// is not empty.
+    #[test]
+    fn test_into_result() {
+        assert_eq!(ValidationErrors::new().into_result(), Ok(()));
+
+        let mut errors = ValidationErrors::new();
+        errors.check(Ok(()));
+        errors.check(Err(error("owner", "required", "must be set")));
+        errors.push(error("product_id", "invalid_format", "must be numeric"));
+
+        let errors = errors.into_result().unwrap_err();
+        assert_eq!(
+            errors
+                .errors()
+                .iter()
+                .map(ValidationError::field)
+                .collect::<Vec<_>>(),
+            vec!["owner", "product_id"]
+        );
+        assert_eq!(
+            errors.to_string(),
+            "must be set (owner); must be numeric (product_id)"
+        );
+    }
+}
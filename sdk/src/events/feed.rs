@@ -0,0 +1,135 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A feed that publishes the events of commits to any number of subscribers.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::channel::mpsc::{unbounded, UnboundedSender};
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::commits::store::CommitEvent;
+use crate::error::InternalError;
+
+use super::{decode_commit, GridEvent};
+
+#[derive(Default)]
+struct FeedState {
+    history: Vec<GridEvent>,
+    subscribers: Vec<UnboundedSender<GridEvent>>,
+}
+
+/// Publishes the events of commits to subscribers as they are committed.
+///
+/// The feed keeps every event it has published, so a subscriber that joins late can replay the
+/// events it missed before receiving new ones. Cloning the feed shares its history and
+/// subscribers.
+#[derive(Clone, Default)]
+pub struct EventFeed {
+    state: Arc<Mutex<FeedState>>,
+}
+
+impl EventFeed {
+    /// Creates a feed with no history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decodes a commit and sends its events to every subscriber.
+    ///
+    /// If the commit cannot be decoded, none of its events are published. Subscribers whose
+    /// streams have been dropped are removed.
+    pub fn publish(&self, commit: &CommitEvent) -> Result<(), InternalError> {
+        let events = decode_commit(commit)?;
+
+        let mut state = self.lock()?;
+        for event in events {
+            state
+                .subscribers
+                .retain(|subscriber| subscriber.unbounded_send(event.clone()).is_ok());
+            state.history.push(event);
+        }
+
+        Ok(())
+    }
+
+    /// Returns a stream of the events published after this call.
+    pub fn subscribe(&self) -> Result<impl Stream<Item = GridEvent>, InternalError> {
+        let (sender, receiver) = unbounded();
+        self.lock()?.subscribers.push(sender);
+
+        Ok(receiver)
+    }
+
+    /// Returns a stream of every event published so far, followed by the events published after
+    /// this call.
+    ///
+    /// No event is missed or repeated between the replayed events and the new ones.
+    pub fn replay(&self) -> Result<impl Stream<Item = GridEvent>, InternalError> {
+        let (sender, receiver) = unbounded();
+
+        let mut state = self.lock()?;
+        let history = state.history.clone();
+        state.subscribers.push(sender);
+
+        Ok(stream::iter(history).chain(receiver))
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, FeedState>, InternalError> {
+        self.state
+            .lock()
+            .map_err(|_| InternalError::with_message("Event feed lock was poisoned".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    use crate::events::tests::{agent, agent_set, commit};
+
+    /// Verifies that a subscriber only receives events published after it subscribed, while a
+    /// replay also receives those published before.
+    #[test]
+    fn test_subscribe_and_replay() {
+        let feed = EventFeed::new();
+        feed.publish(&commit("commit-1", vec![agent_set(&agent())]))
+            .expect("Unable to publish commit");
+
+        let subscription = feed.subscribe().expect("Unable to subscribe");
+        let replay = feed.replay().expect("Unable to replay");
+
+        feed.publish(&commit("commit-2", vec![agent_set(&agent())]))
+            .expect("Unable to publish commit");
+        drop(feed);
+
+        let commit_ids = |events: Vec<GridEvent>| {
+            events
+                .into_iter()
+                .map(|event| event.commit().commit_id.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            commit_ids(block_on(subscription.collect())),
+            vec!["commit-2"]
+        );
+        assert_eq!(
+            commit_ids(block_on(replay.collect())),
+            vec!["commit-1", "commit-2"]
+        );
+    }
+}
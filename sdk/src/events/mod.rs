@@ -0,0 +1,478 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Typed events describing the Grid entities changed by commits.
+//!
+//! Commits carry their changes as raw state entries. This module decodes those entries into
+//! [`GridEvent`]s, so consumers can react to changes to products, agents and locations without
+//! handling protobufs or state addresses themselves. The receipts of committed batches can be
+//! decoded the same way with `decode_batch_events`, and events can be delivered to webhooks with
+//! `WebhookSender`.
+
+mod feed;
+#[cfg(feature = "events-webhooks")]
+mod webhook;
+
+use futures::stream::{self, Stream, StreamExt};
+
+#[cfg(feature = "backend-splinter")]
+use crate::backend::{self, BatchReceipt, TransactionResult};
+use crate::commits::store::{CommitEvent, StateChange};
+use crate::error::InternalError;
+use crate::location::addressing::GRID_LOCATION_NAMESPACE;
+use crate::pike::addressing::{GRID_PIKE_AGENT_NAMESPACE, GRID_PIKE_NAMESPACE};
+use crate::product::addressing::GRID_PRODUCT_NAMESPACE;
+use crate::protocol::location::state::{Location, LocationList};
+use crate::protocol::pike::state::{Agent, AgentList};
+use crate::protocol::product::state::{Product, ProductList};
+use crate::protos::FromBytes;
+
+pub use feed::EventFeed;
+#[cfg(feature = "events-webhooks")]
+pub use webhook::{
+    sign_payload, WebhookPayload, WebhookSender, EVENT_TYPE_HEADER, SIGNATURE_HEADER,
+};
+
+/// The commit an event was decoded from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitInfo {
+    /// The ID of the commit
+    pub commit_id: String,
+    /// The height of the commit, if its source provides one
+    pub height: Option<u64>,
+    /// The service the commit came from, if it came from a service
+    pub service_id: Option<String>,
+}
+
+impl From<&CommitEvent> for CommitInfo {
+    fn from(event: &CommitEvent) -> Self {
+        CommitInfo {
+            commit_id: event.id.clone(),
+            height: event.height,
+            service_id: event.service_id.clone(),
+        }
+    }
+}
+
+/// A change to a Grid entity made by a commit.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GridEvent {
+    /// An agent was created or updated
+    AgentSet { commit: CommitInfo, agent: Agent },
+    /// A product was created or updated
+    ProductSet {
+        commit: CommitInfo,
+        product: Product,
+    },
+    /// The product stored at `address` was deleted
+    ProductDeleted { commit: CommitInfo, address: String },
+    /// A location was created or updated
+    LocationSet {
+        commit: CommitInfo,
+        location: Location,
+    },
+    /// The location stored at `address` was deleted
+    LocationDeleted { commit: CommitInfo, address: String },
+    /// An event emitted by a transaction that has no typed equivalent, kept as it was reported
+    Other {
+        commit: CommitInfo,
+        event_type: String,
+        attributes: Vec<(String, String)>,
+        data: Vec<u8>,
+    },
+}
+
+impl GridEvent {
+    /// Returns the commit the event was decoded from.
+    pub fn commit(&self) -> &CommitInfo {
+        match self {
+            GridEvent::AgentSet { commit, .. }
+            | GridEvent::ProductSet { commit, .. }
+            | GridEvent::ProductDeleted { commit, .. }
+            | GridEvent::LocationSet { commit, .. }
+            | GridEvent::LocationDeleted { commit, .. }
+            | GridEvent::Other { commit, .. } => commit,
+        }
+    }
+}
+
+/// Decodes the events of a single commit, in the order of its state changes.
+///
+/// Changes to entities that have no event, such as organizations or schemas, are ignored. A
+/// state change in a supported namespace that cannot be parsed results in an error.
+pub fn decode_commit(commit: &CommitEvent) -> Result<Vec<GridEvent>, InternalError> {
+    let info = CommitInfo::from(commit);
+    let mut events = vec![];
+
+    for state_change in &commit.state_changes {
+        decode_state_change(state_change, &info, &mut events)?;
+    }
+
+    Ok(events)
+}
+
+/// Decodes the events of a committed batch from its receipt.
+///
+/// The state changes of each valid transaction are decoded as by [`decode_commit`], followed by
+/// the events the transaction emitted, which are kept as [`GridEvent::Other`]. Invalid
+/// transactions changed no state, so they contribute no events. Receipts do not identify the
+/// commit that included the batch, so the events' commit is identified by the batch ID.
+#[cfg(feature = "backend-splinter")]
+pub fn decode_batch_events(receipt: &BatchReceipt) -> Result<Vec<GridEvent>, InternalError> {
+    let info = CommitInfo {
+        commit_id: receipt.batch_id.clone(),
+        height: None,
+        service_id: None,
+    };
+    let mut events = vec![];
+
+    for transaction in &receipt.transaction_receipts {
+        if transaction.result != TransactionResult::Valid {
+            continue;
+        }
+
+        for state_change in &transaction.state_changes {
+            let state_change = match state_change {
+                backend::StateChange::Set { key, value } => StateChange::Set {
+                    key: key.clone(),
+                    value: value.clone(),
+                },
+                backend::StateChange::Delete { key } => StateChange::Delete { key: key.clone() },
+            };
+            decode_state_change(&state_change, &info, &mut events)?;
+        }
+
+        events.extend(transaction.events.iter().map(|event| GridEvent::Other {
+            commit: info.clone(),
+            event_type: event.event_type.clone(),
+            attributes: event.attributes.clone(),
+            data: event.data.clone(),
+        }));
+    }
+
+    Ok(events)
+}
+
+/// Turns a stream of commits into a stream of the events they contain.
+///
+/// The events of each commit are yielded in order, so replaying the same commits produces the
+/// same events. Commits that cannot be decoded are logged and skipped; use [`decode_commit`] to
+/// handle decoding errors directly.
+pub fn grid_events<S>(commits: S) -> impl Stream<Item = GridEvent>
+where
+    S: Stream<Item = CommitEvent>,
+{
+    commits.flat_map(|commit| stream::iter(decode_or_skip(&commit)))
+}
+
+/// Decodes a commit's events, logging and returning no events if it cannot be decoded.
+fn decode_or_skip(commit: &CommitEvent) -> Vec<GridEvent> {
+    decode_commit(commit).unwrap_or_else(|err| {
+        warn!("Skipping events of commit {}: {}", commit.id, err);
+        vec![]
+    })
+}
+
+fn decode_state_change(
+    state_change: &StateChange,
+    commit: &CommitInfo,
+    events: &mut Vec<GridEvent>,
+) -> Result<(), InternalError> {
+    match state_change {
+        StateChange::Set { key, value } => {
+            match namespace_of(key) {
+                Some(GRID_PIKE_NAMESPACE) if key.starts_with(GRID_PIKE_AGENT_NAMESPACE) => {
+                    let agents = AgentList::from_bytes(value).map_err(|err| {
+                        InternalError::from_source_with_message(
+                            Box::new(err),
+                            format!("Failed to parse agent list at {}", key),
+                        )
+                    })?;
+                    events.extend(agents.agents().iter().map(|agent| GridEvent::AgentSet {
+                        commit: commit.clone(),
+                        agent: agent.clone(),
+                    }));
+                }
+                Some(GRID_PRODUCT_NAMESPACE) => {
+                    let products = ProductList::from_bytes(value).map_err(|err| {
+                        InternalError::from_source_with_message(
+                            Box::new(err),
+                            format!("Failed to parse product list at {}", key),
+                        )
+                    })?;
+                    events.extend(products.products().iter().map(|product| {
+                        GridEvent::ProductSet {
+                            commit: commit.clone(),
+                            product: product.clone(),
+                        }
+                    }));
+                }
+                Some(GRID_LOCATION_NAMESPACE) => {
+                    let locations = LocationList::from_bytes(value).map_err(|err| {
+                        InternalError::from_source_with_message(
+                            Box::new(err),
+                            format!("Failed to parse location list at {}", key),
+                        )
+                    })?;
+                    events.extend(locations.locations().iter().map(|location| {
+                        GridEvent::LocationSet {
+                            commit: commit.clone(),
+                            location: location.clone(),
+                        }
+                    }));
+                }
+                _ => (),
+            }
+        }
+        StateChange::Delete { key } => match namespace_of(key) {
+            Some(GRID_PRODUCT_NAMESPACE) => events.push(GridEvent::ProductDeleted {
+                commit: commit.clone(),
+                address: key.clone(),
+            }),
+            Some(GRID_LOCATION_NAMESPACE) => events.push(GridEvent::LocationDeleted {
+                commit: commit.clone(),
+                address: key.clone(),
+            }),
+            _ => (),
+        },
+    }
+
+    Ok(())
+}
+
+/// Returns the Grid namespace prefix of a state address.
+fn namespace_of(key: &str) -> Option<&str> {
+    key.get(0..8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+
+    use crate::location::addressing::compute_gs1_location_address;
+    use crate::pike::addressing::{compute_agent_address, compute_organization_address};
+    use crate::product::addressing::compute_gs1_product_address;
+    use crate::protocol::location::state::{
+        LocationBuilder, LocationListBuilder, LocationNamespace,
+    };
+    use crate::protocol::pike::state::{AgentBuilder, AgentListBuilder};
+    use crate::protocol::product::state::{ProductBuilder, ProductListBuilder, ProductNamespace};
+    use crate::protos::IntoBytes;
+
+    pub(super) fn agent() -> Agent {
+        AgentBuilder::new()
+            .with_org_id("org".to_string())
+            .with_public_key("agent_public_key".to_string())
+            .with_active(true)
+            .build()
+            .expect("Unable to build agent")
+    }
+
+    pub(super) fn agent_set(agent: &Agent) -> StateChange {
+        StateChange::Set {
+            key: compute_agent_address(agent.public_key()),
+            value: AgentListBuilder::new()
+                .with_agents(vec![agent.clone()])
+                .build()
+                .expect("Unable to build agent list")
+                .into_bytes()
+                .expect("Unable to serialize agent list"),
+        }
+    }
+
+    pub(super) fn commit(id: &str, state_changes: Vec<StateChange>) -> CommitEvent {
+        CommitEvent {
+            service_id: None,
+            id: id.to_string(),
+            height: None,
+            state_changes,
+            signers: vec![],
+        }
+    }
+
+    /// Verifies that product, agent and location changes are decoded in order, and that changes
+    /// to entities without events are ignored.
+    #[test]
+    fn test_decode_commit() {
+        let product = ProductBuilder::new()
+            .with_product_id("00012345600012".to_string())
+            .with_product_namespace(ProductNamespace::Gs1)
+            .with_owner("org".to_string())
+            .with_properties(vec![])
+            .build()
+            .expect("Unable to build product");
+        let location = LocationBuilder::new()
+            .with_location_id("0614141000012".to_string())
+            .with_namespace(LocationNamespace::Gs1)
+            .with_owner("org".to_string())
+            .with_properties(vec![])
+            .build()
+            .expect("Unable to build location");
+        let product_address = compute_gs1_product_address(product.product_id());
+        let location_address = compute_gs1_location_address(location.location_id());
+
+        let commit = commit(
+            "commit-1",
+            vec![
+                agent_set(&agent()),
+                StateChange::Set {
+                    key: compute_organization_address("org"),
+                    value: vec![],
+                },
+                StateChange::Set {
+                    key: product_address.clone(),
+                    value: ProductListBuilder::new()
+                        .with_products(vec![product.clone()])
+                        .build()
+                        .expect("Unable to build product list")
+                        .into_bytes()
+                        .expect("Unable to serialize product list"),
+                },
+                StateChange::Set {
+                    key: location_address.clone(),
+                    value: LocationListBuilder::new()
+                        .with_locations(vec![location.clone()])
+                        .build()
+                        .expect("Unable to build location list")
+                        .into_bytes()
+                        .expect("Unable to serialize location list"),
+                },
+                StateChange::Delete {
+                    key: product_address.clone(),
+                },
+                StateChange::Delete {
+                    key: location_address.clone(),
+                },
+            ],
+        );
+        let info = CommitInfo::from(&commit);
+
+        assert_eq!(
+            decode_commit(&commit).expect("Unable to decode commit"),
+            vec![
+                GridEvent::AgentSet {
+                    commit: info.clone(),
+                    agent: agent(),
+                },
+                GridEvent::ProductSet {
+                    commit: info.clone(),
+                    product,
+                },
+                GridEvent::LocationSet {
+                    commit: info.clone(),
+                    location,
+                },
+                GridEvent::ProductDeleted {
+                    commit: info.clone(),
+                    address: product_address,
+                },
+                GridEvent::LocationDeleted {
+                    commit: info,
+                    address: location_address,
+                },
+            ]
+        );
+    }
+
+    /// Verifies that the state changes of a batch's valid transactions are decoded into typed
+    /// events, that the events the transactions emitted are kept as generic events, and that
+    /// invalid transactions are ignored.
+    #[cfg(feature = "backend-splinter")]
+    #[test]
+    fn test_decode_batch_events() {
+        use crate::backend::{ReceiptEvent, TransactionReceipt};
+
+        let agent_state_change = match agent_set(&agent()) {
+            StateChange::Set { key, value } => backend::StateChange::Set { key, value },
+            StateChange::Delete { .. } => unreachable!(),
+        };
+        let event = ReceiptEvent {
+            event_type: "grid/custom".to_string(),
+            attributes: vec![("key".to_string(), "value".to_string())],
+            data: vec![1, 2, 3],
+        };
+        let receipt = BatchReceipt {
+            batch_id: "batch-1".to_string(),
+            transaction_receipts: vec![
+                TransactionReceipt {
+                    transaction_id: "txn-1".to_string(),
+                    result: TransactionResult::Valid,
+                    events: vec![event.clone()],
+                    state_changes: vec![agent_state_change.clone()],
+                    data: vec![],
+                },
+                TransactionReceipt {
+                    transaction_id: "txn-2".to_string(),
+                    result: TransactionResult::Invalid {
+                        error_message: "invalid".to_string(),
+                        error_data: vec![],
+                    },
+                    events: vec![event.clone()],
+                    state_changes: vec![agent_state_change],
+                    data: vec![],
+                },
+            ],
+        };
+        let info = CommitInfo {
+            commit_id: "batch-1".to_string(),
+            height: None,
+            service_id: None,
+        };
+
+        assert_eq!(
+            decode_batch_events(&receipt).expect("Unable to decode receipt"),
+            vec![
+                GridEvent::AgentSet {
+                    commit: info.clone(),
+                    agent: agent(),
+                },
+                GridEvent::Other {
+                    commit: info,
+                    event_type: event.event_type,
+                    attributes: event.attributes,
+                    data: event.data,
+                },
+            ]
+        );
+    }
+
+    /// Verifies that a commit which cannot be decoded is skipped by the event stream, without
+    /// affecting the commits around it.
+    #[test]
+    fn test_grid_events_skips_undecodable_commits() {
+        let commits = vec![
+            commit("commit-1", vec![agent_set(&agent())]),
+            commit(
+                "commit-2",
+                vec![StateChange::Set {
+                    key: compute_agent_address("other"),
+                    value: vec![0xff],
+                }],
+            ),
+            commit("commit-3", vec![agent_set(&agent())]),
+        ];
+
+        let events = block_on(grid_events(stream::iter(commits)).collect::<Vec<_>>());
+
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.commit().commit_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["commit-1", "commit-3"]
+        );
+    }
+}
@@ -0,0 +1,551 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Delivery of events to webhooks.
+//!
+//! Each registered endpoint is sent every event as a JSON [`WebhookPayload`] in its own `POST`
+//! request. The body is signed with the endpoint's secret: the `X-Grid-Signature` header holds
+//! the lower-case hex encoding of the HMAC-SHA256 of the body, prefixed with `sha256=`.
+//!
+//! Events are delivered to an endpoint in the order they were published, by a thread of its
+//! own, so a slow or unavailable endpoint does not hold up the others. A failed delivery is
+//! logged and retried with exponential backoff until it succeeds or the sender is shut down.
+//! Endpoint URLs are logged with any credentials they carry redacted.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crypto::hmac::Hmac;
+use crypto::mac::Mac;
+use crypto::sha2::Sha256;
+use reqwest::blocking::Client;
+
+use crate::backend::redact_url;
+use crate::commits::store::CommitEvent;
+use crate::error::{InternalError, InvalidArgumentError};
+use crate::hex::to_hex;
+use crate::protos::IntoBytes;
+
+use super::{decode_commit, GridEvent};
+
+/// The header the signature of a payload is sent in.
+pub const SIGNATURE_HEADER: &str = "X-Grid-Signature";
+
+/// The header the type of the event is sent in, e.g. `product_set`.
+pub const EVENT_TYPE_HEADER: &str = "X-Grid-Event";
+
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// The JSON body sent to a webhook for a single event.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WebhookPayload {
+    /// The type of the event, e.g. `agent_set` or `location_deleted`
+    pub event_type: String,
+    pub commit_id: String,
+    pub height: Option<u64>,
+    pub service_id: Option<String>,
+    /// The public key of the agent, the ID of the product or location, or the state address of
+    /// the deleted entity, depending on the type of the event
+    pub entity_id: Option<String>,
+    /// The base64 encoding of the entity as a protobuf message, or of the data of an event with
+    /// no typed equivalent
+    pub data: Option<String>,
+    /// The attributes of an event with no typed equivalent
+    #[serde(default)]
+    pub attributes: Vec<(String, String)>,
+}
+
+impl WebhookPayload {
+    /// Creates the payload of an event.
+    pub fn from_event(event: &GridEvent) -> Result<Self, InternalError> {
+        let commit = event.commit();
+        let mut payload = WebhookPayload {
+            event_type: String::new(),
+            commit_id: commit.commit_id.clone(),
+            height: commit.height,
+            service_id: commit.service_id.clone(),
+            entity_id: None,
+            data: None,
+            attributes: vec![],
+        };
+
+        let (event_type, entity_id, data) = match event {
+            GridEvent::AgentSet { agent, .. } => (
+                "agent_set".to_string(),
+                agent.public_key().to_string(),
+                Some(encode(agent.clone())?),
+            ),
+            GridEvent::ProductSet { product, .. } => (
+                "product_set".to_string(),
+                product.product_id().to_string(),
+                Some(encode(product.clone())?),
+            ),
+            GridEvent::ProductDeleted { address, .. } => {
+                ("product_deleted".to_string(), address.clone(), None)
+            }
+            GridEvent::LocationSet { location, .. } => (
+                "location_set".to_string(),
+                location.location_id().to_string(),
+                Some(encode(location.clone())?),
+            ),
+            GridEvent::LocationDeleted { address, .. } => {
+                ("location_deleted".to_string(), address.clone(), None)
+            }
+            GridEvent::Other {
+                event_type,
+                attributes,
+                data,
+                ..
+            } => {
+                payload.event_type = event_type.clone();
+                payload.attributes = attributes.clone();
+                payload.data = Some(base64::encode(data));
+                return Ok(payload);
+            }
+        };
+
+        payload.event_type = event_type;
+        payload.entity_id = Some(entity_id);
+        payload.data = data;
+        Ok(payload)
+    }
+}
+
+fn encode<T: IntoBytes>(entity: T) -> Result<String, InternalError> {
+    entity
+        .into_bytes()
+        .map(base64::encode)
+        .map_err(|err| InternalError::from_source(Box::new(err)))
+}
+
+/// Returns the value of the signature header for a payload body signed with `secret`.
+pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+    let mut hmac = Hmac::new(Sha256::new(), secret);
+    hmac.input(body);
+    format!("sha256={}", to_hex(hmac.result().code()))
+}
+
+/// A serialized payload waiting to be delivered.
+struct Delivery {
+    event_type: String,
+    body: Vec<u8>,
+}
+
+struct Endpoint {
+    url: String,
+    sender: Sender<Delivery>,
+    join_handle: JoinHandle<()>,
+}
+
+/// Sends events to the registered webhooks.
+pub struct WebhookSender {
+    endpoints: Mutex<Vec<Endpoint>>,
+    client: Client,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl Default for WebhookSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WebhookSender {
+    /// Creates a sender with no endpoints, which retries failed deliveries after a second at
+    /// first and at most every five minutes.
+    pub fn new() -> Self {
+        Self::with_backoff(DEFAULT_INITIAL_BACKOFF, DEFAULT_MAX_BACKOFF)
+    }
+
+    /// Creates a sender with no endpoints, which waits `initial_backoff` before retrying a failed
+    /// delivery and doubles the wait after each further failure, up to `max_backoff`.
+    pub fn with_backoff(initial_backoff: Duration, max_backoff: Duration) -> Self {
+        WebhookSender {
+            endpoints: Mutex::new(vec![]),
+            client: Client::new(),
+            initial_backoff,
+            max_backoff: max_backoff.max(initial_backoff),
+            shutdown: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Registers an endpoint, which is sent every event published after this call, signed with
+    /// `secret`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidArgumentError`] if the URL is not an HTTP(S) URL or the secret is
+    /// empty.
+    pub fn register(&self, url: &str, secret: Vec<u8>) -> Result<(), InvalidArgumentError> {
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(InvalidArgumentError::new(
+                "url".to_string(),
+                format!("expected an HTTP or HTTPS URL, found {}", redact_url(url)),
+            ));
+        }
+        if secret.is_empty() {
+            return Err(InvalidArgumentError::new(
+                "secret".to_string(),
+                "must not be empty".to_string(),
+            ));
+        }
+
+        let (sender, receiver) = channel();
+        let worker = Worker {
+            url: url.to_string(),
+            secret,
+            client: self.client.clone(),
+            initial_backoff: self.initial_backoff,
+            max_backoff: self.max_backoff,
+            shutdown: self.shutdown.clone(),
+        };
+        let join_handle = thread::Builder::new()
+            .name(format!("Webhook {}", redact_url(url)))
+            .spawn(move || worker.run(receiver))
+            .map_err(|err| {
+                InvalidArgumentError::new(
+                    "url".to_string(),
+                    format!("unable to start delivery thread: {}", err),
+                )
+            })?;
+
+        self.lock_endpoints()
+            .map_err(|err| InvalidArgumentError::new("url".to_string(), err.to_string()))?
+            .push(Endpoint {
+                url: url.to_string(),
+                sender,
+                join_handle,
+            });
+
+        Ok(())
+    }
+
+    /// Returns the URLs of the registered endpoints, in the order they were registered.
+    pub fn endpoints(&self) -> Vec<String> {
+        self.lock_endpoints()
+            .map(|endpoints| {
+                endpoints
+                    .iter()
+                    .map(|endpoint| endpoint.url.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Decodes a commit and queues its events for delivery to every endpoint.
+    ///
+    /// If the commit cannot be decoded, none of its events are sent.
+    pub fn publish(&self, commit: &CommitEvent) -> Result<(), InternalError> {
+        self.send(&decode_commit(commit)?)
+    }
+
+    /// Queues events for delivery to every endpoint.
+    ///
+    /// If any event cannot be serialized, none of the events are sent.
+    pub fn send(&self, events: &[GridEvent]) -> Result<(), InternalError> {
+        let deliveries = events
+            .iter()
+            .map(|event| {
+                let payload = WebhookPayload::from_event(event)?;
+                let body = serde_json::to_vec(&payload)
+                    .map_err(|err| InternalError::from_source(Box::new(err)))?;
+                Ok((payload.event_type, body))
+            })
+            .collect::<Result<Vec<_>, InternalError>>()?;
+
+        let endpoints = self.lock_endpoints()?;
+        for (event_type, body) in deliveries {
+            for endpoint in endpoints.iter() {
+                if endpoint
+                    .sender
+                    .send(Delivery {
+                        event_type: event_type.clone(),
+                        body: body.clone(),
+                    })
+                    .is_err()
+                {
+                    error!(
+                        "Unable to queue {} event for webhook {}: delivery thread has stopped",
+                        event_type,
+                        redact_url(&endpoint.url)
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stops delivering events and waits for the delivery threads to exit.
+    ///
+    /// Each endpoint is sent the events queued before this call unless a delivery fails, in
+    /// which case it is not retried and the events still queued for the endpoint are logged as
+    /// undelivered.
+    pub fn shutdown(self) {
+        let endpoints = match self.endpoints.into_inner() {
+            Ok(endpoints) => endpoints,
+            Err(_) => {
+                error!("Webhook endpoint lock was poisoned; delivery threads were not joined");
+                return;
+            }
+        };
+
+        self.shutdown.store(true, Ordering::SeqCst);
+        for endpoint in endpoints {
+            drop(endpoint.sender);
+            endpoint.join_handle.thread().unpark();
+            if endpoint.join_handle.join().is_err() {
+                error!(
+                    "Webhook delivery thread for {} panicked",
+                    redact_url(&endpoint.url)
+                );
+            }
+        }
+    }
+
+    fn lock_endpoints(&self) -> Result<std::sync::MutexGuard<'_, Vec<Endpoint>>, InternalError> {
+        self.endpoints.lock().map_err(|_| {
+            InternalError::with_message("Webhook endpoint lock was poisoned".to_string())
+        })
+    }
+}
+
+/// Delivers the events queued for a single endpoint.
+struct Worker {
+    url: String,
+    secret: Vec<u8>,
+    client: Client,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl Worker {
+    fn run(self, receiver: Receiver<Delivery>) {
+        for delivery in receiver.iter() {
+            let mut backoff = self.initial_backoff;
+            loop {
+                match self.deliver(&delivery) {
+                    Ok(()) => break,
+                    Err(err) if self.shutdown.load(Ordering::SeqCst) => {
+                        error!(
+                            "Unable to deliver {} event to webhook {}; not retrying as the \
+                            sender is shutting down: {}",
+                            delivery.event_type,
+                            redact_url(&self.url),
+                            err
+                        );
+                        self.log_undelivered(&receiver);
+                        return;
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Unable to deliver {} event to webhook {}, retrying in {:?}: {}",
+                            delivery.event_type,
+                            redact_url(&self.url),
+                            backoff,
+                            err
+                        );
+                        // Shutting down unparks the thread, so the wait is cut short
+                        thread::park_timeout(backoff);
+                        backoff = (backoff * 2).min(self.max_backoff);
+                    }
+                }
+            }
+        }
+    }
+
+    fn deliver(&self, delivery: &Delivery) -> Result<(), String> {
+        let res = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header(EVENT_TYPE_HEADER, &delivery.event_type)
+            .header(SIGNATURE_HEADER, sign_payload(&self.secret, &delivery.body))
+            .body(delivery.body.clone())
+            .send()
+            .map_err(|err| err.without_url().to_string())?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("webhook responded with {}", res.status()))
+        }
+    }
+
+    fn log_undelivered(&self, receiver: &Receiver<Delivery>) {
+        for delivery in receiver.try_iter() {
+            error!(
+                "Webhook {} was not sent {} event: {}",
+                redact_url(&self.url),
+                delivery.event_type,
+                String::from_utf8_lossy(&delivery.body)
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    use crate::events::tests::{agent, agent_set, commit};
+
+    /// A request received by the test server.
+    struct Received {
+        headers: Vec<(String, String)>,
+        body: Vec<u8>,
+    }
+
+    impl Received {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(header, _)| header.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        }
+    }
+
+    /// Starts a server that answers requests with the given statuses in turn, and returns its
+    /// URL and the requests it received once it has answered them all.
+    fn serve(statuses: Vec<u16>) -> (String, JoinHandle<Vec<Received>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind test server");
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut received = vec![];
+            for status in statuses {
+                let (stream, _) = listener.accept().expect("Unable to accept request");
+                let mut reader = BufReader::new(stream);
+
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut headers = vec![];
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    match line.trim_end().split_once(": ") {
+                        Some((name, value)) => headers.push((name.into(), value.into())),
+                        None => break,
+                    }
+                }
+
+                let length = headers
+                    .iter()
+                    .find(|(name, _): &&(String, String)| {
+                        name.eq_ignore_ascii_case("content-length")
+                    })
+                    .and_then(|(_, value)| value.parse().ok())
+                    .unwrap_or(0);
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {} Test\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                )
+                .unwrap();
+                received.push(Received { headers, body });
+            }
+            received
+        });
+
+        (url, handle)
+    }
+
+    /// Verifies that an event is posted as a typed payload, signed with the endpoint's secret.
+    #[test]
+    fn test_event_delivered_with_signature() {
+        let (url, server) = serve(vec![200]);
+        let sender = WebhookSender::new();
+        sender
+            .register(&url, b"secret".to_vec())
+            .expect("Unable to register endpoint");
+
+        sender
+            .publish(&commit("commit-1", vec![agent_set(&agent())]))
+            .expect("Unable to publish commit");
+
+        let received = server.join().expect("Test server panicked");
+        sender.shutdown();
+
+        let request = &received[0];
+        assert_eq!(
+            request.header(SIGNATURE_HEADER),
+            Some(sign_payload(b"secret", &request.body).as_str())
+        );
+        assert_eq!(request.header(EVENT_TYPE_HEADER), Some("agent_set"));
+
+        let payload: WebhookPayload =
+            serde_json::from_slice(&request.body).expect("Payload is not JSON");
+        assert_eq!(payload.event_type, "agent_set");
+        assert_eq!(payload.commit_id, "commit-1");
+        assert_eq!(payload.entity_id.as_deref(), Some(agent().public_key()));
+    }
+
+    /// Verifies that a failed delivery is retried until the endpoint accepts it.
+    #[test]
+    fn test_failed_delivery_retried() {
+        let (url, server) = serve(vec![500, 503, 200]);
+        let sender =
+            WebhookSender::with_backoff(Duration::from_millis(10), Duration::from_millis(20));
+        sender
+            .register(&url, b"secret".to_vec())
+            .expect("Unable to register endpoint");
+
+        sender
+            .publish(&commit("commit-1", vec![agent_set(&agent())]))
+            .expect("Unable to publish commit");
+
+        let received = server.join().expect("Test server panicked");
+        sender.shutdown();
+
+        assert_eq!(received.len(), 3);
+        assert!(received
+            .iter()
+            .all(|request| request.body == received[0].body));
+    }
+
+    /// Verifies that endpoints are only registered with an HTTP(S) URL and a secret.
+    #[test]
+    fn test_register_validates_endpoint() {
+        let sender = WebhookSender::new();
+
+        assert_eq!(
+            sender
+                .register("ftp://example.com", b"secret".to_vec())
+                .unwrap_err()
+                .argument(),
+            "url"
+        );
+        assert_eq!(
+            sender
+                .register("https://example.com", vec![])
+                .unwrap_err()
+                .argument(),
+            "secret"
+        );
+        assert!(sender.endpoints().is_empty());
+    }
+}
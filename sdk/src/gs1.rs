@@ -0,0 +1,39 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+/// Parses a GS1 identifier, such as a GTIN or GLN, into its digits, returning `None` if it
+/// contains anything but digits.
+pub fn digits(id: &str) -> Option<Vec<u32>> {
+    id.chars().map(|c| c.to_digit(10)).collect()
+}
+
+/// Returns whether the last of the digits is the check digit of those before it.
+///
+/// Digits are weighted 3 and 1 alternately, starting with 3 for the digit left of the check
+/// digit. See https://www.gs1.org/services/how-calculate-check-digit-manually
+pub fn has_valid_check_digit(digits: &[u32]) -> bool {
+    match digits.split_last() {
+        Some((check_digit, digits)) => {
+            let sum: u32 = digits
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+                .sum();
+
+            (10 - sum % 10) % 10 == *check_digit
+        }
+        None => false,
+    }
+}
@@ -14,7 +14,7 @@
 
 use std::error::Error;
 use std::fmt;
-#[cfg(feature = "batch-store")]
+#[cfg(any(feature = "backend-splinter", feature = "batch-store"))]
 use std::fmt::Write;
 
 use serde::de;
@@ -25,7 +25,7 @@ use serde::Deserializer;
 /// # Arguments
 ///
 ///  * `bytes`: the byte array to convert
-#[cfg(feature = "batch-store")]
+#[cfg(any(feature = "backend-splinter", feature = "batch-store"))]
 pub fn to_hex(bytes: &[u8]) -> String {
     let mut buf = String::new();
     for b in bytes {
@@ -0,0 +1,149 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Caching of HTTP responses for reads that rarely change, such as schemas and the receipts of
+//! committed batches.
+//!
+//! A cached response is kept with the `ETag` and `Last-Modified` validators it was served with.
+//! Later reads of the same URL send them as `If-None-Match` and `If-Modified-Since`, and a
+//! `304 Not Modified` response is served from the cache. When the resource has changed, the
+//! server returns it in full with new validators, which replace the cached entry.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+
+/// A response body stored with the validators it was served with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl CachedResponse {
+    /// Creates a cached response from the headers and body of a successful response.
+    ///
+    /// Returns `None` if the response has neither an `ETag` nor a `Last-Modified` header, as it
+    /// could not be revalidated.
+    pub fn from_response(headers: &HeaderMap, body: Vec<u8>) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(String::from)
+        };
+
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+
+        Some(CachedResponse {
+            etag,
+            last_modified,
+            body,
+        })
+    }
+
+    /// Returns the headers that make a request conditional on this response being stale.
+    pub fn conditional_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+
+        let values = [
+            (IF_NONE_MATCH, &self.etag),
+            (IF_MODIFIED_SINCE, &self.last_modified),
+        ];
+        for (name, value) in values {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
+
+        headers
+    }
+}
+
+/// Storage for cached responses, keyed by the URL they were fetched from.
+///
+/// Implementations may keep responses in memory or persist them, e.g. to disk, so that they
+/// outlive the client.
+pub trait ResponseCache: Send + Sync {
+    /// Returns the response cached for the URL, if there is one.
+    fn get(&self, url: &str) -> Option<CachedResponse>;
+
+    /// Caches a response for the URL, replacing any response already cached for it.
+    fn put(&self, url: &str, response: CachedResponse);
+
+    /// Removes the response cached for the URL, if there is one.
+    fn remove(&self, url: &str);
+}
+
+/// A `ResponseCache` that keeps responses in memory for the lifetime of the cache.
+#[derive(Default)]
+pub struct InMemoryResponseCache {
+    responses: Mutex<HashMap<String, CachedResponse>>,
+}
+
+impl InMemoryResponseCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ResponseCache for InMemoryResponseCache {
+    fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.responses.lock().ok()?.get(url).cloned()
+    }
+
+    fn put(&self, url: &str, response: CachedResponse) {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses.insert(url.to_string(), response);
+        }
+    }
+
+    fn remove(&self, url: &str) {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses.remove(url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that only responses with a validator are cached, and that a cached response's
+    /// validators are sent as the matching conditional headers.
+    #[test]
+    fn test_cached_response_validators() {
+        assert!(CachedResponse::from_response(&HeaderMap::new(), vec![]).is_none());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let cached =
+            CachedResponse::from_response(&headers, b"{}".to_vec()).expect("Response not cached");
+
+        let conditional = cached.conditional_headers();
+        assert_eq!(
+            conditional.get(IF_NONE_MATCH),
+            Some(&HeaderValue::from_static("\"v1\""))
+        );
+        assert!(conditional.get(IF_MODIFIED_SINCE).is_none());
+    }
+}
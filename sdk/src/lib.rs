@@ -48,9 +48,17 @@ pub mod commits;
 #[cfg(feature = "data-validation")]
 pub mod data_validation;
 pub mod error;
+#[cfg(feature = "events")]
+pub mod events;
+#[cfg(any(feature = "location", feature = "product"))]
+mod gs1;
 mod hex;
+#[cfg(any(feature = "backend-splinter", feature = "client-reqwest"))]
+pub mod http_cache;
 #[cfg(feature = "location")]
 pub mod location;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod migrations;
 pub mod paging;
 #[cfg(feature = "pike")]
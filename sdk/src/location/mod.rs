@@ -14,3 +14,4 @@
 
 pub mod addressing;
 pub mod store;
+pub mod validation;
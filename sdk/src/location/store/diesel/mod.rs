@@ -29,8 +29,10 @@ use crate::commits::MAX_COMMIT_NUM;
 use crate::error::ResourceTemporarilyUnavailableError;
 
 use operations::add_location::LocationStoreAddLocationOperation as _;
+use operations::count_locations::LocationStoreCountLocationsOperation as _;
 use operations::delete_location::LocationStoreDeleteLocationOperation as _;
 use operations::get_location::LocationStoreGetLocationOperation as _;
+use operations::get_location_attributes_as_of::LocationStoreGetLocationAttributesAsOfOperation as _;
 use operations::list_locations::LocationStoreListLocationsOperation as _;
 use operations::LocationStoreOperations;
 
@@ -77,6 +79,20 @@ impl LocationStore for DieselLocationStore<diesel::pg::PgConnection> {
         .get_location(location_id, service_id)
     }
 
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        LocationStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            LocationStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_location_attributes_as_of(location_id, as_of, service_id)
+    }
+
     fn list_locations(
         &self,
         service_id: Option<&str>,
@@ -91,6 +107,15 @@ impl LocationStore for DieselLocationStore<diesel::pg::PgConnection> {
         .list_locations(service_id, offset, limit)
     }
 
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        LocationStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            LocationStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_locations(service_id)
+    }
+
     fn delete_location(
         &self,
         address: &str,
@@ -131,6 +156,20 @@ impl LocationStore for DieselLocationStore<diesel::sqlite::SqliteConnection> {
         .get_location(location_id, service_id)
     }
 
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        LocationStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            LocationStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_location_attributes_as_of(location_id, as_of, service_id)
+    }
+
     fn list_locations(
         &self,
         service_id: Option<&str>,
@@ -145,6 +184,15 @@ impl LocationStore for DieselLocationStore<diesel::sqlite::SqliteConnection> {
         .list_locations(service_id, offset, limit)
     }
 
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        LocationStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            LocationStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_locations(service_id)
+    }
+
     fn delete_location(
         &self,
         address: &str,
@@ -197,6 +245,19 @@ impl<'a> LocationStore for DieselConnectionLocationStore<'a, diesel::pg::PgConne
         LocationStoreOperations::new(self.connection).get_location(location_id, service_id)
     }
 
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        LocationStoreOperations::new(self.connection).get_location_attributes_as_of(
+            location_id,
+            as_of,
+            service_id,
+        )
+    }
+
     fn list_locations(
         &self,
         service_id: Option<&str>,
@@ -206,6 +267,10 @@ impl<'a> LocationStore for DieselConnectionLocationStore<'a, diesel::pg::PgConne
         LocationStoreOperations::new(self.connection).list_locations(service_id, offset, limit)
     }
 
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        LocationStoreOperations::new(self.connection).count_locations(service_id)
+    }
+
     fn delete_location(
         &self,
         address: &str,
@@ -235,6 +300,19 @@ impl<'a> LocationStore for DieselConnectionLocationStore<'a, diesel::sqlite::Sql
         LocationStoreOperations::new(self.connection).get_location(location_id, service_id)
     }
 
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        LocationStoreOperations::new(self.connection).get_location_attributes_as_of(
+            location_id,
+            as_of,
+            service_id,
+        )
+    }
+
     fn list_locations(
         &self,
         service_id: Option<&str>,
@@ -244,6 +322,10 @@ impl<'a> LocationStore for DieselConnectionLocationStore<'a, diesel::sqlite::Sql
         LocationStoreOperations::new(self.connection).list_locations(service_id, offset, limit)
     }
 
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        LocationStoreOperations::new(self.connection).count_locations(service_id)
+    }
+
     fn delete_location(
         &self,
         address: &str,
@@ -291,6 +373,8 @@ pub fn make_location_attribute_models(
             start_commit_num: attr.start_commit_num,
             end_commit_num: MAX_COMMIT_NUM,
             service_id: attr.service_id.clone(),
+            effective_from: attr.effective_from,
+            effective_to: attr.effective_to,
         });
 
         if attr.struct_values.is_some() {
@@ -330,6 +414,8 @@ impl From<LocationAttributeModel> for LocationAttribute {
             start_commit_num: model.start_commit_num,
             end_commit_num: model.end_commit_num,
             service_id: model.service_id,
+            effective_from: model.effective_from,
+            effective_to: model.effective_to,
         }
     }
 }
@@ -351,6 +437,8 @@ impl From<(LocationAttributeModel, Vec<LocationAttribute>)> for LocationAttribut
             start_commit_num: model.start_commit_num,
             end_commit_num: model.end_commit_num,
             service_id: model.service_id,
+            effective_from: model.effective_from,
+            effective_to: model.effective_to,
         }
     }
 }
@@ -69,6 +69,11 @@ pub struct NewLocationAttributeModel {
     pub end_commit_num: i64,
 
     pub service_id: Option<String>,
+
+    /// The time, in seconds since the epoch, from which the value is in effect
+    pub effective_from: Option<i64>,
+    /// The time, in seconds since the epoch, at which the value stops being in effect
+    pub effective_to: Option<i64>,
 }
 
 #[derive(Insertable, PartialEq, Eq, Queryable, Debug)]
@@ -93,4 +98,9 @@ pub struct LocationAttributeModel {
     pub end_commit_num: i64,
 
     pub service_id: Option<String>,
+
+    /// The time, in seconds since the epoch, from which the value is in effect
+    pub effective_from: Option<i64>,
+    /// The time, in seconds since the epoch, at which the value stops being in effect
+    pub effective_to: Option<i64>,
 }
@@ -0,0 +1,145 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::LocationStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::location::store::diesel::{schema::location, LocationStoreError};
+
+use diesel::prelude::*;
+
+pub(in crate::location::store::diesel) trait LocationStoreCountLocationsOperation {
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> LocationStoreCountLocationsOperation
+    for LocationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        pg::locations(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> LocationStoreCountLocationsOperation
+    for LocationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        sqlite::locations(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current locations.
+    pub fn locations<'a>(service_id: Option<&'a str>) -> location::BoxedQuery<'a, Pg> {
+        let mut query = location::table
+            .into_boxed()
+            .filter(location::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(location::service_id.eq(service_id));
+        } else {
+            query = query.filter(location::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current locations.
+    pub fn locations<'a>(service_id: Option<&'a str>) -> location::BoxedQuery<'a, Sqlite> {
+        let mut query = location::table
+            .into_boxed()
+            .filter(location::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(location::service_id.eq(service_id));
+        } else {
+            query = query.filter(location::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::location::store::{DieselConnectionLocationStore, Location, LocationStore};
+    use crate::migrations::run_sqlite_migrations;
+
+    fn location(location_id: &str, service_id: Option<&str>) -> Location {
+        Location {
+            location_id: location_id.to_string(),
+            location_address: format!("address-{}", location_id),
+            location_namespace: "Gs1".to_string(),
+            owner: "owner".to_string(),
+            attributes: vec![],
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: service_id.map(String::from),
+            last_updated: None,
+        }
+    }
+
+    /// Verifies that locations are counted with the same filters they are listed with, and that
+    /// the total of a page of locations is the unpaged count.
+    #[test]
+    fn test_count_locations() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionLocationStore::new(&conn);
+
+        store.add_location(location("0123456789012", None))?;
+        store.add_location(location("0123456789029", None))?;
+        store.add_location(location("0123456789036", None))?;
+        store.add_location(location("0123456789043", Some("service")))?;
+
+        for (service_id, expected) in [(None, 3), (Some("service"), 1), (Some("other"), 0)] {
+            assert_eq!(store.count_locations(service_id)?, expected);
+            assert_eq!(
+                store.list_locations(service_id, 0, 100)?.data.len() as i64,
+                expected
+            );
+
+            let page = store.list_locations(service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,335 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+
+use super::LocationStoreOperations;
+use crate::location::store::diesel::{schema::location_attribute, LocationStoreError};
+
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InvalidStateError;
+use crate::location::store::diesel::models::LocationAttributeModel;
+use crate::location::store::LocationAttribute;
+use diesel::prelude::*;
+
+pub(in crate::location::store::diesel) trait LocationStoreGetLocationAttributesAsOfOperation<
+    C: Connection,
+>
+{
+    fn get_location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError>;
+    fn get_effective_attributes(
+        conn: &C,
+        location_id: &str,
+        parent_property_name: Option<&str>,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> LocationStoreGetLocationAttributesAsOfOperation<diesel::pg::PgConnection>
+    for LocationStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn get_location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        self.conn.transaction::<_, LocationStoreError, _>(|| {
+            Self::get_effective_attributes(self.conn, location_id, None, as_of, service_id)
+        })
+    }
+
+    fn get_effective_attributes(
+        conn: &PgConnection,
+        location_id: &str,
+        parent_property_name: Option<&str>,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        let mut query = location_attribute::table
+            .into_boxed()
+            .select(location_attribute::all_columns)
+            .filter(
+                location_attribute::location_id
+                    .eq(location_id)
+                    .and(location_attribute::end_commit_num.eq(MAX_COMMIT_NUM))
+                    .and(
+                        location_attribute::effective_from
+                            .is_null()
+                            .or(location_attribute::effective_from.le(as_of)),
+                    )
+                    .and(
+                        location_attribute::effective_to
+                            .is_null()
+                            .or(location_attribute::effective_to.gt(as_of)),
+                    ),
+            );
+
+        if let Some(parent_property_name) = parent_property_name {
+            query = query.filter(location_attribute::parent_property_name.eq(parent_property_name));
+        } else {
+            query = query.filter(location_attribute::parent_property_name.is_null());
+        }
+
+        if let Some(service_id) = service_id {
+            query = query.filter(location_attribute::service_id.eq(service_id));
+        } else {
+            query = query.filter(location_attribute::service_id.is_null());
+        }
+
+        let models = query
+            .order(location_attribute::property_name)
+            .load::<LocationAttributeModel>(conn)?;
+        check_overlaps(location_id, &models, as_of)?;
+
+        let mut attrs = Vec::with_capacity(models.len());
+        for model in models {
+            let children = Self::get_effective_attributes(
+                conn,
+                location_id,
+                Some(&model.property_name),
+                as_of,
+                service_id,
+            )?;
+            if children.is_empty() {
+                attrs.push(LocationAttribute::from(model));
+            } else {
+                attrs.push(LocationAttribute::from((model, children)));
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> LocationStoreGetLocationAttributesAsOfOperation<diesel::sqlite::SqliteConnection>
+    for LocationStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn get_location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        self.conn.transaction::<_, LocationStoreError, _>(|| {
+            Self::get_effective_attributes(self.conn, location_id, None, as_of, service_id)
+        })
+    }
+
+    fn get_effective_attributes(
+        conn: &SqliteConnection,
+        location_id: &str,
+        parent_property_name: Option<&str>,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        let mut query = location_attribute::table
+            .into_boxed()
+            .select(location_attribute::all_columns)
+            .filter(
+                location_attribute::location_id
+                    .eq(location_id)
+                    .and(location_attribute::end_commit_num.eq(MAX_COMMIT_NUM))
+                    .and(
+                        location_attribute::effective_from
+                            .is_null()
+                            .or(location_attribute::effective_from.le(as_of)),
+                    )
+                    .and(
+                        location_attribute::effective_to
+                            .is_null()
+                            .or(location_attribute::effective_to.gt(as_of)),
+                    ),
+            );
+
+        if let Some(parent_property_name) = parent_property_name {
+            query = query.filter(location_attribute::parent_property_name.eq(parent_property_name));
+        } else {
+            query = query.filter(location_attribute::parent_property_name.is_null());
+        }
+
+        if let Some(service_id) = service_id {
+            query = query.filter(location_attribute::service_id.eq(service_id));
+        } else {
+            query = query.filter(location_attribute::service_id.is_null());
+        }
+
+        let models = query
+            .order(location_attribute::property_name)
+            .load::<LocationAttributeModel>(conn)?;
+        check_overlaps(location_id, &models, as_of)?;
+
+        let mut attrs = Vec::with_capacity(models.len());
+        for model in models {
+            let children = Self::get_effective_attributes(
+                conn,
+                location_id,
+                Some(&model.property_name),
+                as_of,
+                service_id,
+            )?;
+            if children.is_empty() {
+                attrs.push(LocationAttribute::from(model));
+            } else {
+                attrs.push(LocationAttribute::from((model, children)));
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// Returns an error if more than one value of a property is in effect, which means the
+/// property's effective ranges overlap.
+fn check_overlaps(
+    location_id: &str,
+    models: &[LocationAttributeModel],
+    as_of: i64,
+) -> Result<(), LocationStoreError> {
+    let mut seen = HashSet::new();
+    for model in models {
+        if !seen.insert(model.property_name.as_str()) {
+            return Err(LocationStoreError::InvalidStateError(
+                InvalidStateError::with_message(format!(
+                    "Location {} has overlapping effective ranges for property {} at {}",
+                    location_id, model.property_name, as_of
+                )),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::location::store::{
+        DieselConnectionLocationStore, Location, LocationAttribute, LocationStore,
+        LocationStoreError,
+    };
+    use crate::migrations::run_sqlite_migrations;
+
+    fn attribute(
+        property_name: &str,
+        value: &str,
+        effective_from: Option<i64>,
+        effective_to: Option<i64>,
+    ) -> LocationAttribute {
+        LocationAttribute {
+            location_id: "0123456789012".into(),
+            location_address: "address".into(),
+            property_name: property_name.into(),
+            data_type: "STRING".into(),
+            bytes_value: None,
+            boolean_value: None,
+            number_value: None,
+            string_value: Some(value.into()),
+            enum_value: None,
+            struct_values: None,
+            lat_long_value: None,
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: None,
+            effective_from,
+            effective_to,
+        }
+    }
+
+    fn add_location(store: &dyn LocationStore, attributes: Vec<LocationAttribute>) {
+        store
+            .add_location(Location {
+                location_id: "0123456789012".into(),
+                location_address: "address".into(),
+                location_namespace: "GS1".into(),
+                owner: "owner".into(),
+                attributes,
+                start_commit_num: 1,
+                end_commit_num: MAX_COMMIT_NUM,
+                service_id: None,
+                last_updated: None,
+            })
+            .expect("Unable to add location");
+    }
+
+    fn string_values(attributes: &[LocationAttribute]) -> Vec<&str> {
+        attributes
+            .iter()
+            .filter_map(|attr| attr.string_value.as_deref())
+            .collect()
+    }
+
+    /// Verifies that the values returned are those whose effective range includes the given
+    /// time, with the start of a range included and its end excluded, and that values without
+    /// a range are always returned.
+    #[test]
+    fn test_location_attributes_as_of() {
+        let conn = SqliteConnection::establish(":memory:").expect("Unable to connect");
+        run_sqlite_migrations(&conn).expect("Unable to run migrations");
+        let store = DieselConnectionLocationStore::new(&conn);
+
+        add_location(
+            &store,
+            vec![
+                attribute("address", "1 Old Road", None, Some(100)),
+                attribute("address", "2 New Road", Some(100), None),
+                attribute("name", "Warehouse", None, None),
+            ],
+        );
+
+        let attrs = store
+            .location_attributes_as_of("0123456789012", 99, None)
+            .expect("Unable to get attributes");
+        assert_eq!(string_values(&attrs), vec!["1 Old Road", "Warehouse"]);
+
+        let attrs = store
+            .location_attributes_as_of("0123456789012", 100, None)
+            .expect("Unable to get attributes");
+        assert_eq!(string_values(&attrs), vec!["2 New Road", "Warehouse"]);
+    }
+
+    /// Verifies that a property with more than one value in effect at the given time is
+    /// reported as an invalid state.
+    #[test]
+    fn test_location_attributes_as_of_overlap() {
+        let conn = SqliteConnection::establish(":memory:").expect("Unable to connect");
+        run_sqlite_migrations(&conn).expect("Unable to run migrations");
+        let store = DieselConnectionLocationStore::new(&conn);
+
+        add_location(
+            &store,
+            vec![
+                attribute("address", "1 Old Road", None, Some(200)),
+                attribute("address", "2 New Road", Some(100), None),
+            ],
+        );
+
+        assert!(store
+            .location_attributes_as_of("0123456789012", 50, None)
+            .is_ok());
+        assert!(matches!(
+            store.location_attributes_as_of("0123456789012", 150, None),
+            Err(LocationStoreError::InvalidStateError(_))
+        ));
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::count_locations::{self, LocationStoreCountLocationsOperation};
 use super::LocationStoreOperations;
 use crate::commits::MAX_COMMIT_NUM;
 use crate::location::store::diesel::{
@@ -25,7 +26,6 @@ use crate::location::store::{Location, LocationAttribute, LocationList};
 use crate::paging::Paging;
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::location::store::diesel) trait LocationStoreListLocationsOperation<C: Connection> {
     fn list_locations(
@@ -56,26 +56,16 @@ impl<'a> LocationStoreListLocationsOperation<diesel::pg::PgConnection>
         limit: i64,
     ) -> Result<LocationList, LocationStoreError> {
         self.conn.transaction::<_, LocationStoreError, _>(|| {
-            let mut query = location::table
-                .into_boxed()
+            let query = count_locations::pg::locations(service_id)
                 .select(location::all_columns)
                 .limit(limit)
-                .offset(offset)
-                .filter(location::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(location::service_id.eq(service_id));
-            } else {
-                query = query.filter(location::service_id.is_null());
-            }
+                .offset(offset);
 
             let locs = query.load::<LocationModel>(self.conn).map_err(|err| {
                 LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = locs.len().try_into().map_err(|err| {
-                LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_locations(service_id)?;
 
             let mut locations = Vec::new();
 
@@ -168,26 +158,16 @@ impl<'a> LocationStoreListLocationsOperation<diesel::sqlite::SqliteConnection>
         limit: i64,
     ) -> Result<LocationList, LocationStoreError> {
         self.conn.transaction::<_, LocationStoreError, _>(|| {
-            let mut query = location::table
-                .into_boxed()
+            let query = count_locations::sqlite::locations(service_id)
                 .select(location::all_columns)
                 .limit(limit)
-                .offset(offset)
-                .filter(location::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(location::service_id.eq(service_id));
-            } else {
-                query = query.filter(location::service_id.is_null());
-            }
+                .offset(offset);
 
             let locs = query.load::<LocationModel>(self.conn).map_err(|err| {
                 LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = locs.len().try_into().map_err(|err| {
-                LocationStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_locations(service_id)?;
 
             let mut locations = Vec::new();
 
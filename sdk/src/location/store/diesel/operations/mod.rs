@@ -13,8 +13,10 @@
 // limitations under the License.
 
 pub(super) mod add_location;
+pub(super) mod count_locations;
 pub(super) mod delete_location;
 pub(super) mod get_location;
+pub(super) mod get_location_attributes_as_of;
 pub(super) mod list_locations;
 
 pub(super) struct LocationStoreOperations<'a, C> {
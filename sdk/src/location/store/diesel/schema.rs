@@ -45,5 +45,7 @@ table! {
         start_commit_num -> Int8,
         end_commit_num -> Int8,
         service_id -> Nullable<Text>,
+        effective_from -> Nullable<Int8>,
+        effective_to -> Nullable<Int8>,
     }
 }
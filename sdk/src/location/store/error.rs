@@ -17,13 +17,16 @@ use std::fmt;
 
 #[cfg(feature = "diesel")]
 use crate::error::ConstraintViolationType;
-use crate::error::{ConstraintViolationError, InternalError, ResourceTemporarilyUnavailableError};
+use crate::error::{
+    ConstraintViolationError, InternalError, InvalidStateError, ResourceTemporarilyUnavailableError,
+};
 
 /// Represents LocationStore errors
 #[derive(Debug)]
 pub enum LocationStoreError {
     InternalError(InternalError),
     ConstraintViolationError(ConstraintViolationError),
+    InvalidStateError(InvalidStateError),
     ResourceTemporarilyUnavailableError(ResourceTemporarilyUnavailableError),
     NotFoundError(String),
 }
@@ -33,6 +36,7 @@ impl Error for LocationStoreError {
         match self {
             LocationStoreError::InternalError(err) => Some(err),
             LocationStoreError::ConstraintViolationError(err) => Some(err),
+            LocationStoreError::InvalidStateError(err) => Some(err),
             LocationStoreError::ResourceTemporarilyUnavailableError(err) => Some(err),
             LocationStoreError::NotFoundError(_) => None,
         }
@@ -44,6 +48,7 @@ impl fmt::Display for LocationStoreError {
         match self {
             LocationStoreError::InternalError(err) => err.fmt(f),
             LocationStoreError::ConstraintViolationError(err) => err.fmt(f),
+            LocationStoreError::InvalidStateError(err) => err.fmt(f),
             LocationStoreError::ResourceTemporarilyUnavailableError(err) => err.fmt(f),
             LocationStoreError::NotFoundError(ref s) => write!(f, "Commit not found: {}", s),
         }
@@ -67,6 +67,12 @@ pub struct LocationAttribute {
     pub start_commit_num: i64,
     pub end_commit_num: i64,
     pub service_id: Option<String>,
+    /// The time, in seconds since the epoch, from which the value is in effect; `None` if the
+    /// value has always been in effect
+    pub effective_from: Option<i64>,
+    /// The time, in seconds since the epoch, at which the value stops being in effect; `None` if
+    /// the value remains in effect
+    pub effective_to: Option<i64>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -95,6 +101,28 @@ pub trait LocationStore {
         service_id: Option<&str>,
     ) -> Result<Option<Location>, LocationStoreError>;
 
+    /// Fetches the attributes of a location that are in effect at the given time
+    ///
+    /// Only attributes whose effective range includes `as_of` are returned; attributes without
+    /// an effective range are always in effect.
+    ///
+    /// # Arguments
+    ///
+    ///  * `location_id` - The ID of the location to fetch the attributes of
+    ///  * `as_of` - The time, in seconds since the epoch, to fetch the attributes at
+    ///  * `service_id` - optional - The service ID to fetch the attributes from
+    ///
+    /// # Errors
+    ///
+    /// Returns an `InvalidStateError` if more than one value of a property is in effect at
+    /// `as_of`, i.e. the property's effective ranges overlap.
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError>;
+
     /// Gets locations from the underlying storage
     ///
     /// # Arguments
@@ -109,6 +137,14 @@ pub trait LocationStore {
         limit: i64,
     ) -> Result<LocationList, LocationStoreError>;
 
+    /// Counts the locations in the underlying storage, applying the same filters as
+    /// `list_locations`
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - optional - The service ID to count the locations for
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError>;
+
     /// Deletes a location from the underlying storage
     ///
     /// # Arguments
@@ -138,6 +174,15 @@ where
         (**self).get_location(location_id, service_id)
     }
 
+    fn location_attributes_as_of(
+        &self,
+        location_id: &str,
+        as_of: i64,
+        service_id: Option<&str>,
+    ) -> Result<Vec<LocationAttribute>, LocationStoreError> {
+        (**self).location_attributes_as_of(location_id, as_of, service_id)
+    }
+
     fn list_locations(
         &self,
         service_id: Option<&str>,
@@ -147,6 +192,10 @@ where
         (**self).list_locations(service_id, offset, limit)
     }
 
+    fn count_locations(&self, service_id: Option<&str>) -> Result<i64, LocationStoreError> {
+        (**self).count_locations(service_id)
+    }
+
     fn delete_location(
         &self,
         address: &str,
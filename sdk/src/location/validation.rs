@@ -0,0 +1,112 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of location create and update actions before they are submitted, applying the same
+//! rules as the location smart contract.
+
+use crate::error::{ValidationError, ValidationErrors};
+use crate::gs1;
+use crate::protocol::location::payload::{
+    LocationCreateAction, LocationNamespace, LocationUpdateAction,
+};
+use crate::protocol::schema::state::PropertyDefinition;
+use crate::schema::validation::{validate_properties, validate_required_properties};
+
+/// Checks that a GLN is 13 digits with a valid check digit.
+pub fn validate_gln(gln: &str) -> Result<(), ValidationError> {
+    let invalid = |code: &str, message: String| {
+        Err(ValidationError::new(
+            "location_id".into(),
+            code.into(),
+            message,
+        ))
+    };
+
+    match gs1::digits(gln) {
+        None => invalid(
+            "invalid_format",
+            format!("GLN {} may only contain digits", gln),
+        ),
+        Some(digits) if digits.len() != 13 => {
+            invalid("invalid_length", format!("GLN {} must be 13 digits", gln))
+        }
+        Some(digits) if !gs1::has_valid_check_digit(&digits) => invalid(
+            "invalid_check_digit",
+            format!("GLN {} has an invalid check digit", gln),
+        ),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Validates a location create action against the property definitions of the location's
+/// schema, returning every problem found.
+pub fn validate_location_create(
+    action: &LocationCreateAction,
+    definitions: &[PropertyDefinition],
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    match action.namespace() {
+        LocationNamespace::Gs1 => errors.check(validate_gln(action.location_id())),
+    }
+
+    if action.owner().is_empty() {
+        errors.push(ValidationError::new(
+            "owner".into(),
+            "required".into(),
+            "owner is not set".into(),
+        ));
+    }
+
+    validate_properties(definitions, action.properties(), &mut errors);
+    validate_required_properties(definitions, action.properties(), &mut errors);
+
+    errors.into_result()
+}
+
+/// Validates a location update action against the property definitions of the location's
+/// schema, returning every problem found.
+///
+/// Required properties are not checked, as an update only carries the properties it changes.
+pub fn validate_location_update(
+    action: &LocationUpdateAction,
+    definitions: &[PropertyDefinition],
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    match action.namespace() {
+        LocationNamespace::Gs1 => errors.check(validate_gln(action.location_id())),
+    }
+
+    validate_properties(definitions, action.properties(), &mut errors);
+
+    errors.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that a GLN with a valid check digit is accepted, and that each invalid GLN is
+    /// rejected with the code of the rule it fails.
+    #[test]
+    fn test_validate_gln() {
+        let code = |gln| validate_gln(gln).unwrap_err().code().to_string();
+
+        assert!(validate_gln("0614141000012").is_ok());
+        assert_eq!(code("061414100001a"), "invalid_format");
+        assert_eq!(code("061414100001"), "invalid_length");
+        assert_eq!(code("0614141000013"), "invalid_check_digit");
+    }
+}
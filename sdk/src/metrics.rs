@@ -0,0 +1,271 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal metrics facade.
+//!
+//! Metrics are recorded by name into a process-wide [`Registry`] with [`increment_counter`] and
+//! [`observe_duration`], and rendered in the Prometheus text exposition format with [`render`].
+//!
+//! Every distinct set of label values is kept as its own series for the lifetime of the
+//! process, so labels should only take a small, bounded set of values, e.g. a route pattern
+//! rather than the path of a request or the ID of an entity.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// The upper bounds, in seconds, of the buckets durations are counted in.
+const DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+    /// The number of observations at or under each of `DURATION_BUCKETS`
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; DURATION_BUCKETS.len()];
+        }
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if value <= *bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Default)]
+struct Metrics {
+    counters: BTreeMap<&'static str, BTreeMap<Labels, u64>>,
+    histograms: BTreeMap<&'static str, BTreeMap<Labels, Histogram>>,
+}
+
+/// A set of counters and histograms, keyed by name and label values.
+#[derive(Default)]
+pub struct Registry {
+    metrics: Mutex<Metrics>,
+}
+
+impl Registry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Increments the counter with the given name and labels by one.
+    pub fn increment_counter(&self, name: &'static str, labels: &[(&'static str, &str)]) {
+        self.add_to_counter(name, labels, 1)
+    }
+
+    /// Increments the counter with the given name and labels by `value`.
+    pub fn add_to_counter(&self, name: &'static str, labels: &[(&'static str, &str)], value: u64) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            *metrics
+                .counters
+                .entry(name)
+                .or_default()
+                .entry(to_labels(labels))
+                .or_default() += value;
+        }
+    }
+
+    /// Records a duration in the histogram with the given name and labels.
+    pub fn observe_duration(
+        &self,
+        name: &'static str,
+        labels: &[(&'static str, &str)],
+        duration: Duration,
+    ) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics
+                .histograms
+                .entry(name)
+                .or_default()
+                .entry(to_labels(labels))
+                .or_default()
+                .observe(duration.as_secs_f64());
+        }
+    }
+
+    /// Renders every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        let metrics = match self.metrics.lock() {
+            Ok(metrics) => metrics,
+            Err(_) => return output,
+        };
+
+        for (name, series) in &metrics.counters {
+            let _ = writeln!(output, "# TYPE {} counter", name);
+            for (labels, value) in series {
+                let _ = writeln!(output, "{}{} {}", name, format_labels(labels, None), value);
+            }
+        }
+
+        for (name, series) in &metrics.histograms {
+            let _ = writeln!(output, "# TYPE {} histogram", name);
+            for (labels, histogram) in series {
+                for (bound, count) in DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                    let _ = writeln!(
+                        output,
+                        "{}_bucket{} {}",
+                        name,
+                        format_labels(labels, Some(&bound.to_string())),
+                        count
+                    );
+                }
+                let _ = writeln!(
+                    output,
+                    "{}_bucket{} {}",
+                    name,
+                    format_labels(labels, Some("+Inf")),
+                    histogram.count
+                );
+                let _ = writeln!(
+                    output,
+                    "{}_sum{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.sum
+                );
+                let _ = writeln!(
+                    output,
+                    "{}_count{} {}",
+                    name,
+                    format_labels(labels, None),
+                    histogram.count
+                );
+            }
+        }
+
+        output
+    }
+}
+
+/// Returns the process-wide registry the functions of this module record into.
+pub fn global() -> &'static Registry {
+    static GLOBAL: OnceLock<Registry> = OnceLock::new();
+    GLOBAL.get_or_init(Registry::new)
+}
+
+/// Increments a counter in the global registry by one.
+pub fn increment_counter(name: &'static str, labels: &[(&'static str, &str)]) {
+    global().increment_counter(name, labels)
+}
+
+/// Increments a counter in the global registry by `value`.
+pub fn add_to_counter(name: &'static str, labels: &[(&'static str, &str)], value: u64) {
+    global().add_to_counter(name, labels, value)
+}
+
+/// Records a duration in a histogram of the global registry.
+pub fn observe_duration(name: &'static str, labels: &[(&'static str, &str)], duration: Duration) {
+    global().observe_duration(name, labels, duration)
+}
+
+/// Renders the global registry in the Prometheus text exposition format.
+pub fn render() -> String {
+    global().render()
+}
+
+fn to_labels(labels: &[(&'static str, &str)]) -> Labels {
+    let mut labels: Labels = labels
+        .iter()
+        .map(|(name, value)| (*name, value.to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn format_labels(labels: &[(&'static str, String)], le: Option<&str>) -> String {
+    let mut pairs: Vec<String> = labels
+        .iter()
+        .map(|(name, value)| format!("{}=\"{}\"", name, escape(value)))
+        .collect();
+    if let Some(le) = le {
+        pairs.push(format!("le=\"{}\"", le));
+    }
+
+    if pairs.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that counters are kept per set of labels, regardless of the order the labels
+    /// are given in, and rendered with escaped label values.
+    #[test]
+    fn test_render_counters() {
+        let registry = Registry::new();
+        registry.increment_counter("requests_total", &[("method", "GET"), ("status", "200")]);
+        registry.increment_counter("requests_total", &[("status", "200"), ("method", "GET")]);
+        registry.add_to_counter(
+            "requests_total",
+            &[("method", "P\"OST"), ("status", "400")],
+            3,
+        );
+        registry.increment_counter("restarts_total", &[]);
+
+        assert_eq!(
+            registry.render(),
+            "# TYPE requests_total counter\n\
+             requests_total{method=\"GET\",status=\"200\"} 2\n\
+             requests_total{method=\"P\\\"OST\",status=\"400\"} 3\n\
+             # TYPE restarts_total counter\n\
+             restarts_total 1\n"
+        );
+    }
+
+    /// Verifies that durations are counted in every bucket at or above them, and rendered with
+    /// their sum and count.
+    #[test]
+    fn test_render_histogram() {
+        let registry = Registry::new();
+        registry.observe_duration("latency_seconds", &[], Duration::from_millis(20));
+        registry.observe_duration("latency_seconds", &[], Duration::from_secs(20));
+
+        let output = registry.render();
+
+        assert!(output.starts_with("# TYPE latency_seconds histogram\n"));
+        assert!(output.contains("latency_seconds_bucket{le=\"0.01\"} 0\n"));
+        assert!(output.contains("latency_seconds_bucket{le=\"0.025\"} 1\n"));
+        assert!(output.contains("latency_seconds_bucket{le=\"10\"} 1\n"));
+        assert!(output.contains("latency_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(output.contains("latency_seconds_sum 20.02\n"));
+        assert!(output.contains("latency_seconds_count 2\n"));
+    }
+}
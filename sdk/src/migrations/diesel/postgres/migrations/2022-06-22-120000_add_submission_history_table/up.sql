@@ -0,0 +1,26 @@
+-- Copyright 2022 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE submission_history (
+    id BIGSERIAL PRIMARY KEY,
+    batch_id VARCHAR(128) NOT NULL,
+    submitter VARCHAR(70) NOT NULL,
+    service_id TEXT,
+    submitted_at TIMESTAMP NOT NULL,
+    outcome TEXT NOT NULL,
+    error_message TEXT
+);
+
+CREATE INDEX idx_submission_history_submitted_at ON submission_history(submitted_at);
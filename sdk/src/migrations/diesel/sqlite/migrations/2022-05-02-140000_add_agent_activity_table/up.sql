@@ -0,0 +1,25 @@
+-- Copyright 2022 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE agent_activity (
+    id INTEGER PRIMARY KEY,
+    agent_public_key VARCHAR(70) NOT NULL,
+    commit_id VARCHAR(128) NOT NULL,
+    commit_num BIGINT NOT NULL,
+    service_id TEXT,
+    address VARCHAR(70) NOT NULL
+);
+
+CREATE INDEX idx_agent_activity_public_key ON agent_activity(agent_public_key, commit_num);
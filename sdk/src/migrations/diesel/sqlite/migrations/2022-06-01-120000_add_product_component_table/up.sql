@@ -0,0 +1,27 @@
+-- Copyright 2022 Cargill Incorporated
+--
+-- Licensed under the Apache License, Version 2.0 (the "License");
+-- you may not use this file except in compliance with the License.
+-- You may obtain a copy of the License at
+--
+--     http://www.apache.org/licenses/LICENSE-2.0
+--
+-- Unless required by applicable law or agreed to in writing, software
+-- distributed under the License is distributed on an "AS IS" BASIS,
+-- WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+-- See the License for the specific language governing permissions and
+-- limitations under the License.
+-- -----------------------------------------------------------------------------
+
+CREATE TABLE product_component (
+    id INTEGER PRIMARY KEY,
+    product_id VARCHAR(256) NOT NULL,
+    product_address VARCHAR(70) NOT NULL,
+    component_id VARCHAR(256) NOT NULL,
+    service_id TEXT,
+    start_commit_num BIGINT NOT NULL,
+    end_commit_num BIGINT NOT NULL
+);
+
+CREATE INDEX idx_product_component_product_id ON product_component(product_id);
+CREATE INDEX idx_product_component_component_id ON product_component(component_id);
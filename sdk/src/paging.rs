@@ -12,6 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! Paging information shared by store listing results and REST API responses, and iteration
+//! over every record of a store list method a page at a time.
+
+use std::cmp;
+use std::collections::VecDeque;
+
+/// The largest number of records that may be requested in a single page.
+pub const MAX_LIMIT: i64 = 1024;
+
+/// The number of records read at a time by a `PageStream` by default
+const DEFAULT_PAGE_SIZE: i64 = 100;
+
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
 pub struct Paging {
     pub offset: i64,
@@ -20,11 +32,218 @@ pub struct Paging {
 }
 
 impl Paging {
+    /// Creates a new `Paging`.
+    ///
+    /// Negative offsets and totals are clamped to 0, and the limit is clamped to between 1 and
+    /// `MAX_LIMIT`.
     pub fn new(offset: i64, limit: i64, total: i64) -> Self {
         Paging {
-            offset,
-            limit,
-            total,
+            offset: cmp::max(offset, 0),
+            limit: limit.clamp(1, MAX_LIMIT),
+            total: cmp::max(total, 0),
+        }
+    }
+
+    /// Returns the offset of the first page of records.
+    pub fn first_offset(&self) -> i64 {
+        0
+    }
+
+    /// Returns the offset of the last page of records.
+    pub fn last_offset(&self) -> i64 {
+        cmp::max(((self.total - 1) / self.limit) * self.limit, 0)
+    }
+
+    /// Returns the offset of the previous page of records, if there is one.
+    ///
+    /// An offset past the end of the records has the last page as its previous page.
+    pub fn prev_offset(&self) -> Option<i64> {
+        if self.offset == 0 {
+            None
+        } else if self.offset > self.total {
+            Some(self.last_offset())
+        } else {
+            Some(cmp::max(self.offset - self.limit, 0))
+        }
+    }
+
+    /// Returns the offset of the next page of records, if there is one.
+    pub fn next_offset(&self) -> Option<i64> {
+        let last_offset = self.last_offset();
+
+        if self.offset >= last_offset {
+            None
+        } else {
+            Some(cmp::min(self.offset + self.limit, last_offset))
+        }
+    }
+}
+
+/// An iterator over the records of a store list method, read a page at a time.
+///
+/// Diesel loads the whole result of a query at once, so records are not read from a database
+/// cursor. Instead each page is read by its own query, so no more than one page is held in
+/// memory however many records there are, and no database connection is held between pages.
+///
+/// `read_page` is passed the number of records to read, and keeps track of where the previous
+/// page ended. A page shorter than that is taken to be the last. A failed read is returned as
+/// the next item, after which the iterator ends.
+pub struct PageStream<T, F> {
+    read_page: F,
+    page_size: i64,
+    page: VecDeque<T>,
+    done: bool,
+}
+
+impl<T, E, F> PageStream<T, F>
+where
+    F: FnMut(i64) -> Result<Vec<T>, E>,
+{
+    /// Creates an iterator over the records returned by successive calls to `read_page`.
+    pub fn new(read_page: F) -> Self {
+        PageStream {
+            read_page,
+            page_size: DEFAULT_PAGE_SIZE,
+            page: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Sets the number of records read at a time, between 1 and `MAX_LIMIT`.
+    pub fn with_page_size(mut self, page_size: i64) -> Self {
+        self.page_size = page_size.clamp(1, MAX_LIMIT);
+        self
+    }
+}
+
+impl<T, E, F> Iterator for PageStream<T, F>
+where
+    F: FnMut(i64) -> Result<Vec<T>, E>,
+{
+    type Item = Result<T, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.page.is_empty() && !self.done {
+            match (self.read_page)(self.page_size) {
+                Ok(page) => {
+                    self.done = (page.len() as i64) < self.page_size;
+                    self.page.extend(page);
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
         }
+
+        self.page.pop_front().map(Ok)
+    }
+}
+
+/// Creates a `PageStream` over a list method that takes an offset and a limit, such as
+/// `LocationStore::list_locations`.
+///
+/// Records added or removed during the iteration move the records after them to other offsets,
+/// so that a record may be skipped or returned twice. List methods that start after a key, such
+/// as `ProductStore::list_products_after`, do not have this problem.
+pub fn stream_by_offset<T, E, L>(mut list: L) -> PageStream<T, impl FnMut(i64) -> Result<Vec<T>, E>>
+where
+    L: FnMut(i64, i64) -> Result<Vec<T>, E>,
+{
+    let mut offset = 0;
+    PageStream::new(move |limit| {
+        let page = list(offset, limit)?;
+        offset += page.len() as i64;
+        Ok(page)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the first page has a next page but no previous page.
+    #[test]
+    fn test_first_page() {
+        let paging = Paging::new(0, 10, 80);
+
+        assert_eq!(paging.prev_offset(), None);
+        assert_eq!(paging.next_offset(), Some(10));
+        assert_eq!(paging.last_offset(), 70);
+    }
+
+    /// Verifies that the last page has a previous page but no next page.
+    #[test]
+    fn test_last_page() {
+        let paging = Paging::new(70, 10, 80);
+
+        assert_eq!(paging.prev_offset(), Some(60));
+        assert_eq!(paging.next_offset(), None);
+        assert_eq!(paging.last_offset(), 70);
+    }
+
+    /// Verifies that an offset past the end of the records points back to the last page.
+    #[test]
+    fn test_out_of_range_offset() {
+        let paging = Paging::new(100, 10, 80);
+
+        assert_eq!(paging.prev_offset(), Some(70));
+        assert_eq!(paging.next_offset(), None);
+    }
+
+    /// Verifies that negative values are clamped and the limit is capped.
+    #[test]
+    fn test_new_clamps_values() {
+        assert_eq!(
+            Paging::new(-5, 5000, -1),
+            Paging {
+                offset: 0,
+                limit: MAX_LIMIT,
+                total: 0
+            }
+        );
+        assert_eq!(Paging::new(0, -3, 10).limit, 1);
+    }
+
+    /// Verifies that every record is returned once across several pages, including when the
+    /// last page is full, and that a failed read ends the iteration.
+    #[test]
+    fn test_stream_by_offset() {
+        let records = (0..6).collect::<Vec<i64>>();
+        let list = |offset: i64, limit: i64| -> Result<Vec<i64>, String> {
+            Ok(records
+                .iter()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        };
+
+        assert_eq!(
+            stream_by_offset(list)
+                .with_page_size(4)
+                .collect::<Result<Vec<_>, _>>(),
+            Ok(records.clone())
+        );
+        assert_eq!(
+            stream_by_offset(list)
+                .with_page_size(3)
+                .collect::<Result<Vec<_>, _>>(),
+            Ok(records.clone())
+        );
+
+        let mut reads = 0;
+        let failing = stream_by_offset(|offset, limit| {
+            reads += 1;
+            if offset == 0 {
+                list(offset, limit)
+            } else {
+                Err("unavailable".to_string())
+            }
+        })
+        .with_page_size(2)
+        .collect::<Vec<_>>();
+        assert_eq!(failing, vec![Ok(0), Ok(1), Err("unavailable".to_string())]);
+        assert_eq!(reads, 2);
     }
 }
@@ -41,11 +41,16 @@ use models::{
 use operations::add_agent::PikeStoreAddAgentOperation as _;
 use operations::add_organization::PikeStoreAddOrganizationOperation as _;
 use operations::add_role::PikeStoreAddRoleOperation as _;
+use operations::count_agents::PikeStoreCountAgentsOperation as _;
+use operations::count_organizations::PikeStoreCountOrganizationsOperation as _;
+use operations::count_roles_for_organization::PikeStoreCountRolesForOrganizationOperation as _;
 use operations::delete_role::PikeStoreDeleteRoleOperation as _;
 use operations::get_agent::PikeStoreGetAgentOperation as _;
 use operations::get_organization::PikeStoreGetOrganizationOperation as _;
 use operations::get_role::PikeStoreGetRoleOperation as _;
+use operations::list_agent_history::PikeStoreListAgentHistoryOperation as _;
 use operations::list_agents::PikeStoreListAgentsOperation as _;
+use operations::list_agents_by_role::PikeStoreListAgentsByRoleOperation as _;
 use operations::list_organizations::PikeStoreListOrganizationsOperation as _;
 use operations::list_roles_for_organization::PikeStoreListRolesForOrganizationOperation as _;
 use operations::update_agent::PikeStoreUpdateAgentOperation as _;
@@ -107,6 +112,31 @@ impl PikeStore for DieselPikeStore<diesel::pg::PgConnection> {
         .list_agents(service_id, offset, limit)
     }
 
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_agents(service_id)
+    }
+
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_agents_by_role(org_id, role_name, service_id, offset, limit)
+    }
+
     fn list_roles_for_organization(
         &self,
         org_id: &str,
@@ -122,6 +152,19 @@ impl PikeStore for DieselPikeStore<diesel::pg::PgConnection> {
         .list_roles_for_organization(org_id, service_id, offset, limit)
     }
 
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_roles_for_organization(org_id, service_id)
+    }
+
     fn get_agent(
         &self,
         pub_key: &str,
@@ -135,6 +178,19 @@ impl PikeStore for DieselPikeStore<diesel::pg::PgConnection> {
         .get_agent(pub_key, service_id)
     }
 
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_agent_history(pub_key, service_id)
+    }
+
     fn get_role(
         &self,
         name: &str,
@@ -186,6 +242,15 @@ impl PikeStore for DieselPikeStore<diesel::pg::PgConnection> {
         .list_organizations(service_id, offset, limit)
     }
 
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_organizations(service_id)
+    }
+
     fn get_organization(
         &self,
         org_id: &str,
@@ -248,6 +313,31 @@ impl PikeStore for DieselPikeStore<diesel::sqlite::SqliteConnection> {
         .list_agents(service_id, offset, limit)
     }
 
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_agents(service_id)
+    }
+
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_agents_by_role(org_id, role_name, service_id, offset, limit)
+    }
+
     fn list_roles_for_organization(
         &self,
         org_id: &str,
@@ -263,6 +353,19 @@ impl PikeStore for DieselPikeStore<diesel::sqlite::SqliteConnection> {
         .list_roles_for_organization(org_id, service_id, offset, limit)
     }
 
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_roles_for_organization(org_id, service_id)
+    }
+
     fn get_agent(
         &self,
         pub_key: &str,
@@ -276,6 +379,19 @@ impl PikeStore for DieselPikeStore<diesel::sqlite::SqliteConnection> {
         .get_agent(pub_key, service_id)
     }
 
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_agent_history(pub_key, service_id)
+    }
+
     fn get_role(
         &self,
         name: &str,
@@ -327,6 +443,15 @@ impl PikeStore for DieselPikeStore<diesel::sqlite::SqliteConnection> {
         .list_organizations(service_id, offset, limit)
     }
 
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PikeStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_organizations(service_id)
+    }
+
     fn get_organization(
         &self,
         org_id: &str,
@@ -393,6 +518,22 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::pg::PgConnection> {
         PikeStoreOperations::new(self.connection).list_agents(service_id, offset, limit)
     }
 
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_agents(service_id)
+    }
+
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        PikeStoreOperations::new(self.connection)
+            .list_agents_by_role(org_id, role_name, service_id, offset, limit)
+    }
+
     fn list_roles_for_organization(
         &self,
         org_id: &str,
@@ -404,6 +545,14 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::pg::PgConnection> {
             .list_roles_for_organization(org_id, service_id, offset, limit)
     }
 
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_roles_for_organization(org_id, service_id)
+    }
+
     fn get_agent(
         &self,
         pub_key: &str,
@@ -412,6 +561,14 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::pg::PgConnection> {
         PikeStoreOperations::new(self.connection).get_agent(pub_key, service_id)
     }
 
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).list_agent_history(pub_key, service_id)
+    }
+
     fn get_role(
         &self,
         name: &str,
@@ -444,6 +601,10 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::pg::PgConnection> {
         PikeStoreOperations::new(self.connection).list_organizations(service_id, offset, limit)
     }
 
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_organizations(service_id)
+    }
+
     fn get_organization(
         &self,
         org_id: &str,
@@ -482,6 +643,22 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::sqlite::SqliteConne
         PikeStoreOperations::new(self.connection).list_agents(service_id, offset, limit)
     }
 
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_agents(service_id)
+    }
+
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        PikeStoreOperations::new(self.connection)
+            .list_agents_by_role(org_id, role_name, service_id, offset, limit)
+    }
+
     fn list_roles_for_organization(
         &self,
         org_id: &str,
@@ -493,6 +670,14 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::sqlite::SqliteConne
             .list_roles_for_organization(org_id, service_id, offset, limit)
     }
 
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_roles_for_organization(org_id, service_id)
+    }
+
     fn get_agent(
         &self,
         pub_key: &str,
@@ -501,6 +686,14 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::sqlite::SqliteConne
         PikeStoreOperations::new(self.connection).get_agent(pub_key, service_id)
     }
 
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).list_agent_history(pub_key, service_id)
+    }
+
     fn get_role(
         &self,
         name: &str,
@@ -533,6 +726,10 @@ impl<'a> PikeStore for DieselConnectionPikeStore<'a, diesel::sqlite::SqliteConne
         PikeStoreOperations::new(self.connection).list_organizations(service_id, offset, limit)
     }
 
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        PikeStoreOperations::new(self.connection).count_organizations(service_id)
+    }
+
     fn get_organization(
         &self,
         org_id: &str,
@@ -0,0 +1,144 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "count agents" operation for the `DieselPikeStore`.
+
+use super::PikeStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::pike::store::diesel::{schema::pike_agent, PikeStoreError};
+
+use diesel::prelude::*;
+
+pub(in crate::pike::store::diesel) trait PikeStoreCountAgentsOperation {
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PikeStoreCountAgentsOperation for PikeStoreOperations<'a, diesel::pg::PgConnection> {
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        pg::agents(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PikeStoreCountAgentsOperation
+    for PikeStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        sqlite::agents(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current agents.
+    pub fn agents<'a>(service_id: Option<&'a str>) -> pike_agent::BoxedQuery<'a, Pg> {
+        let mut query = pike_agent::table
+            .into_boxed()
+            .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_agent::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_agent::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current agents.
+    pub fn agents<'a>(service_id: Option<&'a str>) -> pike_agent::BoxedQuery<'a, Sqlite> {
+        let mut query = pike_agent::table
+            .into_boxed()
+            .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_agent::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_agent::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{Agent, AgentBuilder, DieselConnectionPikeStore, PikeStore};
+
+    fn agent(
+        public_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Agent, Box<dyn std::error::Error>> {
+        let mut builder = AgentBuilder::new()
+            .with_public_key(public_key.to_string())
+            .with_org_id("org".to_string())
+            .with_active(true)
+            .with_start_commit_num(1)
+            .with_end_commit_num(MAX_COMMIT_NUM);
+        if let Some(service_id) = service_id {
+            builder = builder.with_service_id(service_id.to_string());
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Verifies that agents are counted with the same filters they are listed with, and that
+    /// the total of a page of agents is the unpaged count.
+    #[test]
+    fn test_count_agents() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+
+        store.add_agent(agent("key-1", None)?)?;
+        store.add_agent(agent("key-2", None)?)?;
+        store.add_agent(agent("key-3", None)?)?;
+        store.add_agent(agent("key-4", Some("service"))?)?;
+
+        for (service_id, expected) in [(None, 3), (Some("service"), 1), (Some("other"), 0)] {
+            assert_eq!(store.count_agents(service_id)?, expected);
+            assert_eq!(
+                store.list_agents(service_id, 0, 100)?.data.len() as i64,
+                expected
+            );
+
+            let page = store.list_agents(service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "count organizations" operation for the `DieselPikeStore`.
+
+use super::PikeStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::pike::store::diesel::{schema::pike_organization, PikeStoreError};
+
+use diesel::prelude::*;
+
+pub(in crate::pike::store::diesel) trait PikeStoreCountOrganizationsOperation {
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PikeStoreCountOrganizationsOperation
+    for PikeStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        pg::organizations(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PikeStoreCountOrganizationsOperation
+    for PikeStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        sqlite::organizations(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current organizations.
+    pub fn organizations<'a>(service_id: Option<&'a str>) -> pike_organization::BoxedQuery<'a, Pg> {
+        let mut query = pike_organization::table
+            .into_boxed()
+            .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_organization::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_organization::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current organizations.
+    pub fn organizations<'a>(
+        service_id: Option<&'a str>,
+    ) -> pike_organization::BoxedQuery<'a, Sqlite> {
+        let mut query = pike_organization::table
+            .into_boxed()
+            .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_organization::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_organization::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{
+        DieselConnectionPikeStore, Organization, OrganizationBuilder, PikeStore,
+    };
+
+    fn organization(
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Organization, Box<dyn std::error::Error>> {
+        let mut builder = OrganizationBuilder::new()
+            .with_org_id(org_id.to_string())
+            .with_name(format!("{} name", org_id))
+            .with_start_commit_num(1)
+            .with_end_commit_num(MAX_COMMIT_NUM);
+        if let Some(service_id) = service_id {
+            builder = builder.with_service_id(service_id.to_string());
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Verifies that organizations are counted with the same filters they are listed with, and
+    /// that the total of a page of organizations is the unpaged count.
+    #[test]
+    fn test_count_organizations() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+
+        store.add_organization(organization("org-1", None)?)?;
+        store.add_organization(organization("org-2", None)?)?;
+        store.add_organization(organization("org-3", None)?)?;
+        store.add_organization(organization("org-4", Some("service"))?)?;
+
+        for (service_id, expected) in [(None, 3), (Some("service"), 1), (Some("other"), 0)] {
+            assert_eq!(store.count_organizations(service_id)?, expected);
+            assert_eq!(
+                store.list_organizations(service_id, 0, 100)?.data.len() as i64,
+                expected
+            );
+
+            let page = store.list_organizations(service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "count roles for an organization" operation for the `DieselPikeStore`.
+
+use super::PikeStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::pike::store::diesel::{schema::pike_role, PikeStoreError};
+
+use diesel::prelude::*;
+
+pub(in crate::pike::store::diesel) trait PikeStoreCountRolesForOrganizationOperation {
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PikeStoreCountRolesForOrganizationOperation
+    for PikeStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        pg::roles_for_organization(org_id, service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PikeStoreCountRolesForOrganizationOperation
+    for PikeStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        sqlite::roles_for_organization(org_id, service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| PikeStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current roles of the organization.
+    pub fn roles_for_organization<'a>(
+        org_id: &'a str,
+        service_id: Option<&'a str>,
+    ) -> pike_role::BoxedQuery<'a, Pg> {
+        let mut query = pike_role::table
+            .into_boxed()
+            .filter(pike_role::end_commit_num.eq(MAX_COMMIT_NUM))
+            .filter(pike_role::org_id.eq(org_id));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_role::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_role::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current roles of the organization.
+    pub fn roles_for_organization<'a>(
+        org_id: &'a str,
+        service_id: Option<&'a str>,
+    ) -> pike_role::BoxedQuery<'a, Sqlite> {
+        let mut query = pike_role::table
+            .into_boxed()
+            .filter(pike_role::end_commit_num.eq(MAX_COMMIT_NUM))
+            .filter(pike_role::org_id.eq(org_id));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_role::service_id.eq(service_id));
+        } else {
+            query = query.filter(pike_role::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{DieselConnectionPikeStore, PikeStore, Role, RoleBuilder};
+
+    fn role(
+        name: &str,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Role, Box<dyn std::error::Error>> {
+        let mut builder = RoleBuilder::new()
+            .with_name(name.to_string())
+            .with_org_id(org_id.to_string())
+            .with_active(true)
+            .with_start_commit_num(1)
+            .with_end_commit_num(MAX_COMMIT_NUM);
+        if let Some(service_id) = service_id {
+            builder = builder.with_service_id(service_id.to_string());
+        }
+
+        Ok(builder.build()?)
+    }
+
+    /// Verifies that the roles of an organization are counted with the same filters they are
+    /// listed with, and that the total of a page of roles is the unpaged count.
+    #[test]
+    fn test_count_roles_for_organization() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+
+        store.add_role(role("admin", "org-1", None)?)?;
+        store.add_role(role("member", "org-1", None)?)?;
+        store.add_role(role("admin", "org-2", None)?)?;
+        store.add_role(role("auditor", "org-1", Some("service"))?)?;
+
+        for (org_id, service_id, expected) in [
+            ("org-1", None, 2),
+            ("org-2", None, 1),
+            ("org-1", Some("service"), 1),
+            ("org-2", Some("service"), 0),
+        ] {
+            assert_eq!(
+                store.count_roles_for_organization(org_id, service_id)?,
+                expected
+            );
+            assert_eq!(
+                store
+                    .list_roles_for_organization(org_id, service_id, 0, 100)?
+                    .data
+                    .len() as i64,
+                expected
+            );
+
+            let page = store.list_roles_for_organization(org_id, service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
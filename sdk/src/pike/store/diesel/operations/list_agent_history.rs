@@ -0,0 +1,219 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list agent history" operation for the `DieselPikeStore`.
+
+use super::PikeStoreOperations;
+use crate::pike::store::diesel::{
+    schema::{pike_agent, pike_agent_role_assoc},
+    Agent, PikeStoreError,
+};
+
+use crate::pike::store::diesel::models::{AgentModel, RoleAssociationModel};
+use diesel::prelude::*;
+
+pub(in crate::pike::store::diesel) trait PikeStoreListAgentHistoryOperation {
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PikeStoreListAgentHistoryOperation for PikeStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        self.conn.transaction::<_, PikeStoreError, _>(|| {
+            let mut query = pike_agent::table
+                .into_boxed()
+                .select(pike_agent::all_columns)
+                .filter(pike_agent::public_key.eq(pub_key));
+
+            if let Some(service_id) = service_id {
+                query = query.filter(pike_agent::service_id.eq(service_id));
+            } else {
+                query = query.filter(pike_agent::service_id.is_null());
+            }
+
+            let versions = query
+                .order(pike_agent::start_commit_num)
+                .load::<AgentModel>(self.conn)?;
+
+            versions
+                .into_iter()
+                .map(|agent| {
+                    let mut query = pike_agent_role_assoc::table
+                        .into_boxed()
+                        .select(pike_agent_role_assoc::all_columns)
+                        .filter(
+                            pike_agent_role_assoc::agent_public_key
+                                .eq(pub_key)
+                                .and(pike_agent_role_assoc::org_id.eq(&agent.org_id))
+                                .and(
+                                    pike_agent_role_assoc::start_commit_num
+                                        .le(agent.start_commit_num),
+                                )
+                                .and(
+                                    pike_agent_role_assoc::end_commit_num
+                                        .gt(agent.start_commit_num),
+                                ),
+                        );
+
+                    if let Some(service_id) = service_id {
+                        query = query.filter(pike_agent_role_assoc::service_id.eq(service_id));
+                    } else {
+                        query = query.filter(pike_agent_role_assoc::service_id.is_null());
+                    }
+
+                    let roles = query
+                        .order(pike_agent_role_assoc::id)
+                        .load::<RoleAssociationModel>(self.conn)?;
+
+                    Ok(Agent::from((agent, roles)))
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PikeStoreListAgentHistoryOperation
+    for PikeStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        self.conn.transaction::<_, PikeStoreError, _>(|| {
+            let mut query = pike_agent::table
+                .into_boxed()
+                .select(pike_agent::all_columns)
+                .filter(pike_agent::public_key.eq(pub_key));
+
+            if let Some(service_id) = service_id {
+                query = query.filter(pike_agent::service_id.eq(service_id));
+            } else {
+                query = query.filter(pike_agent::service_id.is_null());
+            }
+
+            let versions = query
+                .order(pike_agent::start_commit_num)
+                .load::<AgentModel>(self.conn)?;
+
+            versions
+                .into_iter()
+                .map(|agent| {
+                    let mut query = pike_agent_role_assoc::table
+                        .into_boxed()
+                        .select(pike_agent_role_assoc::all_columns)
+                        .filter(
+                            pike_agent_role_assoc::agent_public_key
+                                .eq(pub_key)
+                                .and(pike_agent_role_assoc::org_id.eq(&agent.org_id))
+                                .and(
+                                    pike_agent_role_assoc::start_commit_num
+                                        .le(agent.start_commit_num),
+                                )
+                                .and(
+                                    pike_agent_role_assoc::end_commit_num
+                                        .gt(agent.start_commit_num),
+                                ),
+                        );
+
+                    if let Some(service_id) = service_id {
+                        query = query.filter(pike_agent_role_assoc::service_id.eq(service_id));
+                    } else {
+                        query = query.filter(pike_agent_role_assoc::service_id.is_null());
+                    }
+
+                    let roles = query
+                        .order(pike_agent_role_assoc::id)
+                        .load::<RoleAssociationModel>(self.conn)?;
+
+                    Ok(Agent::from((agent, roles)))
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::diesel::models::make_role_association_models;
+    use crate::pike::store::diesel::operations::add_agent::PikeStoreAddAgentOperation as _;
+    use crate::pike::store::AgentBuilder;
+
+    fn add_agent(
+        ops: &PikeStoreOperations<diesel::sqlite::SqliteConnection>,
+        start_commit_num: i64,
+        active: bool,
+        roles: &[&str],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let agent = AgentBuilder::new()
+            .with_public_key("agent".into())
+            .with_org_id("org".into())
+            .with_active(active)
+            .with_metadata(b"metadata".to_vec())
+            .with_roles(roles.iter().map(|role| role.to_string()).collect())
+            .with_start_commit_num(start_commit_num)
+            .with_end_commit_num(MAX_COMMIT_NUM)
+            .build()?;
+
+        ops.add_agent(agent.clone().into(), make_role_association_models(&agent))?;
+
+        Ok(())
+    }
+
+    /// Verifies that every version of an agent is listed in commit order, each with the roles it
+    /// held at that commit and the commit that superseded it.
+    #[test]
+    fn test_list_agent_history() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = PikeStoreOperations::new(&conn);
+
+        add_agent(&ops, 1, true, &["buyer"])?;
+        add_agent(&ops, 4, false, &["buyer"])?;
+
+        let history = ops.list_agent_history("agent", None)?;
+
+        assert_eq!(
+            history
+                .iter()
+                .map(|agent| (
+                    *agent.start_commit_num(),
+                    *agent.end_commit_num(),
+                    agent.active(),
+                    agent.roles().to_vec()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 4, true, vec!["buyer".to_string()]),
+                (4, MAX_COMMIT_NUM, false, vec!["buyer".to_string()]),
+            ]
+        );
+        assert!(ops.list_agent_history("unknown", None)?.is_empty());
+
+        Ok(())
+    }
+}
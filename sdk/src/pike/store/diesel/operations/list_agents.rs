@@ -14,6 +14,7 @@
 
 //! Provides the "list agents" operation for the `DieselPikeStore`.
 
+use super::count_agents::{self, PikeStoreCountAgentsOperation};
 use super::PikeStoreOperations;
 use crate::paging::Paging;
 use crate::pike::store::diesel::{
@@ -26,7 +27,6 @@ use crate::error::InternalError;
 use crate::pike::store::diesel::models::{AgentModel, RoleAssociationModel};
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::pike::store::diesel) trait PikeStoreListAgentsOperation {
     fn list_agents(
@@ -46,26 +46,16 @@ impl<'a> PikeStoreListAgentsOperation for PikeStoreOperations<'a, diesel::pg::Pg
         limit: i64,
     ) -> Result<AgentList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_agent::table
-                .into_boxed()
+            let query = count_agents::pg::agents(service_id)
                 .select(pike_agent::all_columns)
                 .offset(offset)
-                .limit(limit)
-                .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_agent::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_agent::service_id.is_null());
-            }
+                .limit(limit);
 
             let agent_models = query.load::<AgentModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = agent_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_agents(service_id)?;
 
             let mut agents = Vec::new();
 
@@ -111,26 +101,16 @@ impl<'a> PikeStoreListAgentsOperation
         limit: i64,
     ) -> Result<AgentList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_agent::table
-                .into_boxed()
+            let query = count_agents::sqlite::agents(service_id)
                 .select(pike_agent::all_columns)
                 .offset(offset)
-                .limit(limit)
-                .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_agent::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_agent::service_id.is_null());
-            }
+                .limit(limit);
 
             let agent_models = query.load::<AgentModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = agent_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_agents(service_id)?;
 
             let mut agents = Vec::new();
 
@@ -0,0 +1,332 @@
+// Copyright 2018-2021 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Provides the "list agents by role" operation for the `DieselPikeStore`.
+
+use super::PikeStoreOperations;
+use crate::paging::Paging;
+use crate::pike::store::diesel::{
+    schema::{pike_agent, pike_agent_role_assoc},
+    Agent, AgentList, PikeStoreError,
+};
+
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::pike::store::diesel::models::{AgentModel, RoleAssociationModel};
+
+use diesel::prelude::*;
+
+pub(in crate::pike::store::diesel) trait PikeStoreListAgentsByRoleOperation {
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PikeStoreListAgentsByRoleOperation for PikeStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        self.conn.transaction::<_, PikeStoreError, _>(|| {
+            let qualified_name = format!("{}.{}", org_id, role_name);
+
+            let agent_models = pg::agents_with_role(org_id, role_name, &qualified_name, service_id)
+                .select(pike_agent::all_columns)
+                .order(pike_agent::public_key)
+                .offset(offset)
+                .limit(limit)
+                .load::<AgentModel>(self.conn)
+                .map_err(|err| {
+                    PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            let total = pg::agents_with_role(org_id, role_name, &qualified_name, service_id)
+                .count()
+                .get_result(self.conn)
+                .map_err(|err| {
+                    PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            let mut agents = Vec::new();
+
+            for a in agent_models {
+                let mut query = pike_agent_role_assoc::table
+                    .into_boxed()
+                    .select(pike_agent_role_assoc::all_columns)
+                    .filter(
+                        pike_agent_role_assoc::agent_public_key
+                            .eq(&a.public_key)
+                            .and(pike_agent_role_assoc::org_id.eq(&a.org_id))
+                            .and(pike_agent_role_assoc::end_commit_num.eq(MAX_COMMIT_NUM)),
+                    );
+
+                if let Some(service_id) = service_id {
+                    query = query.filter(pike_agent_role_assoc::service_id.eq(service_id));
+                } else {
+                    query = query.filter(pike_agent_role_assoc::service_id.is_null());
+                }
+
+                let roles = query
+                    .load::<RoleAssociationModel>(self.conn)
+                    .map_err(|err| {
+                        PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                    })?;
+
+                agents.push(Agent::from((a, roles)));
+            }
+
+            Ok(AgentList::new(agents, Paging::new(offset, limit, total)))
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PikeStoreListAgentsByRoleOperation
+    for PikeStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        self.conn.transaction::<_, PikeStoreError, _>(|| {
+            let qualified_name = format!("{}.{}", org_id, role_name);
+
+            let agent_models =
+                sqlite::agents_with_role(org_id, role_name, &qualified_name, service_id)
+                    .select(pike_agent::all_columns)
+                    .order(pike_agent::public_key)
+                    .offset(offset)
+                    .limit(limit)
+                    .load::<AgentModel>(self.conn)
+                    .map_err(|err| {
+                        PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                    })?;
+
+            let total = sqlite::agents_with_role(org_id, role_name, &qualified_name, service_id)
+                .count()
+                .get_result(self.conn)
+                .map_err(|err| {
+                    PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                })?;
+
+            let mut agents = Vec::new();
+
+            for a in agent_models {
+                let mut query = pike_agent_role_assoc::table
+                    .into_boxed()
+                    .select(pike_agent_role_assoc::all_columns)
+                    .filter(
+                        pike_agent_role_assoc::agent_public_key
+                            .eq(&a.public_key)
+                            .and(pike_agent_role_assoc::org_id.eq(&a.org_id))
+                            .and(pike_agent_role_assoc::end_commit_num.eq(MAX_COMMIT_NUM)),
+                    );
+
+                if let Some(service_id) = service_id {
+                    query = query.filter(pike_agent_role_assoc::service_id.eq(service_id));
+                } else {
+                    query = query.filter(pike_agent_role_assoc::service_id.is_null());
+                }
+
+                let roles = query
+                    .load::<RoleAssociationModel>(self.conn)
+                    .map_err(|err| {
+                        PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
+                    })?;
+
+                agents.push(Agent::from((a, roles)));
+            }
+
+            Ok(AgentList::new(agents, Paging::new(offset, limit, total)))
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current agents that are assigned the role, either by its name from within
+    /// the role's organization or by its qualified name from another organization.
+    pub fn agents_with_role<'a>(
+        org_id: &'a str,
+        role_name: &'a str,
+        qualified_name: &'a str,
+        service_id: Option<&'a str>,
+    ) -> pike_agent::BoxedQuery<'a, Pg> {
+        let mut holders = pike_agent_role_assoc::table
+            .into_boxed()
+            .select(pike_agent_role_assoc::agent_public_key)
+            .filter(pike_agent_role_assoc::end_commit_num.eq(MAX_COMMIT_NUM))
+            .filter(
+                pike_agent_role_assoc::org_id
+                    .eq(org_id)
+                    .and(pike_agent_role_assoc::role_name.eq(role_name))
+                    .or(pike_agent_role_assoc::role_name.eq(qualified_name)),
+            );
+
+        let mut query = pike_agent::table
+            .into_boxed()
+            .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            holders = holders.filter(pike_agent_role_assoc::service_id.eq(service_id));
+            query = query.filter(pike_agent::service_id.eq(service_id));
+        } else {
+            holders = holders.filter(pike_agent_role_assoc::service_id.is_null());
+            query = query.filter(pike_agent::service_id.is_null());
+        }
+
+        query.filter(pike_agent::public_key.eq_any(holders))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current agents that are assigned the role, either by its name from within
+    /// the role's organization or by its qualified name from another organization.
+    pub fn agents_with_role<'a>(
+        org_id: &'a str,
+        role_name: &'a str,
+        qualified_name: &'a str,
+        service_id: Option<&'a str>,
+    ) -> pike_agent::BoxedQuery<'a, Sqlite> {
+        let mut holders = pike_agent_role_assoc::table
+            .into_boxed()
+            .select(pike_agent_role_assoc::agent_public_key)
+            .filter(pike_agent_role_assoc::end_commit_num.eq(MAX_COMMIT_NUM))
+            .filter(
+                pike_agent_role_assoc::org_id
+                    .eq(org_id)
+                    .and(pike_agent_role_assoc::role_name.eq(role_name))
+                    .or(pike_agent_role_assoc::role_name.eq(qualified_name)),
+            );
+
+        let mut query = pike_agent::table
+            .into_boxed()
+            .filter(pike_agent::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            holders = holders.filter(pike_agent_role_assoc::service_id.eq(service_id));
+            query = query.filter(pike_agent::service_id.eq(service_id));
+        } else {
+            holders = holders.filter(pike_agent_role_assoc::service_id.is_null());
+            query = query.filter(pike_agent::service_id.is_null());
+        }
+
+        query.filter(pike_agent::public_key.eq_any(holders))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::diesel::models::make_role_association_models;
+    use crate::pike::store::diesel::operations::add_agent::PikeStoreAddAgentOperation as _;
+    use crate::pike::store::AgentBuilder;
+
+    fn add_agent(
+        ops: &PikeStoreOperations<diesel::sqlite::SqliteConnection>,
+        public_key: &str,
+        org_id: &str,
+        roles: &[&str],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let agent = AgentBuilder::new()
+            .with_public_key(public_key.into())
+            .with_org_id(org_id.into())
+            .with_active(true)
+            .with_metadata(b"metadata".to_vec())
+            .with_roles(roles.iter().map(|role| role.to_string()).collect())
+            .with_start_commit_num(1)
+            .with_end_commit_num(MAX_COMMIT_NUM)
+            .build()?;
+
+        ops.add_agent(agent.clone().into(), make_role_association_models(&agent))?;
+
+        Ok(())
+    }
+
+    /// Verify that the agents holding a role are listed, whether assigned the role from within
+    /// its organization or by its qualified name from another, and that a role's name is not
+    /// confused with a role of the same name in another organization.
+    #[test]
+    fn test_list_agents_by_role() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = PikeStoreOperations::new(&conn);
+        add_agent(&ops, "agent_a", "org_1", &["auditor", "admin"])?;
+        add_agent(&ops, "agent_b", "org_2", &["org_1.auditor"])?;
+        add_agent(&ops, "agent_c", "org_2", &["auditor"])?;
+        add_agent(&ops, "agent_d", "org_1", &["admin"])?;
+
+        let agents = ops.list_agents_by_role("org_1", "auditor", None, 0, 10)?;
+
+        assert_eq!(
+            agents
+                .data
+                .iter()
+                .map(|agent| agent.public_key())
+                .collect::<Vec<_>>(),
+            vec!["agent_a", "agent_b"]
+        );
+        assert_eq!(agents.paging.total, 2);
+
+        let page = ops.list_agents_by_role("org_1", "auditor", None, 1, 1)?;
+        assert_eq!(page.data.len(), 1);
+        assert_eq!(page.data[0].public_key(), "agent_b");
+        assert_eq!(page.paging.total, 2);
+
+        Ok(())
+    }
+
+    /// Verify that an organization with no agents holding the role returns an empty page.
+    #[test]
+    fn test_list_agents_by_role_empty() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = PikeStoreOperations::new(&conn);
+        add_agent(&ops, "agent_a", "org_1", &["admin"])?;
+
+        let agents = ops.list_agents_by_role("org_1", "auditor", None, 0, 10)?;
+
+        assert!(agents.data.is_empty());
+        assert_eq!(agents.paging.total, 0);
+
+        Ok(())
+    }
+}
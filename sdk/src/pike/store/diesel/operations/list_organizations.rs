@@ -14,6 +14,7 @@
 
 //! Provides the "list organizations" operation for the `DieselPikeStore`.
 
+use super::count_organizations::{self, PikeStoreCountOrganizationsOperation};
 use super::PikeStoreOperations;
 use crate::commits::MAX_COMMIT_NUM;
 use crate::error::InternalError;
@@ -31,7 +32,6 @@ use crate::pike::store::diesel::{
 use crate::pike::store::{Organization, OrganizationList};
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::pike::store::diesel) trait PikeStoreListOrganizationsOperation {
     fn list_organizations(
@@ -51,24 +51,14 @@ impl<'a> PikeStoreListOrganizationsOperation for PikeStoreOperations<'a, diesel:
         limit: i64,
     ) -> Result<OrganizationList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_organization::table
-                .into_boxed()
-                .select(pike_organization::all_columns)
-                .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_organization::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_organization::service_id.is_null());
-            }
+            let query = count_organizations::pg::organizations(service_id)
+                .select(pike_organization::all_columns);
 
             let org_models = query.load::<OrganizationModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = org_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_organizations(service_id)?;
 
             let mut orgs = Vec::new();
 
@@ -165,24 +155,14 @@ impl<'a> PikeStoreListOrganizationsOperation
         limit: i64,
     ) -> Result<OrganizationList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_organization::table
-                .into_boxed()
-                .select(pike_organization::all_columns)
-                .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_organization::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_organization::service_id.is_null());
-            }
+            let query = count_organizations::sqlite::organizations(service_id)
+                .select(pike_organization::all_columns);
 
             let org_models = query.load::<OrganizationModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = org_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_organizations(service_id)?;
 
             let mut orgs = Vec::new();
 
@@ -14,6 +14,7 @@
 
 //! Provides the "list roles for an organization" operation for the `DieselPikeStore`.
 
+use super::count_roles_for_organization::{self, PikeStoreCountRolesForOrganizationOperation};
 use super::PikeStoreOperations;
 use crate::commits::MAX_COMMIT_NUM;
 use crate::error::InternalError;
@@ -28,7 +29,6 @@ use crate::pike::store::diesel::{
 use crate::pike::store::{Role, RoleList};
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::pike::store::diesel) trait PikeStoreListRolesForOrganizationOperation {
     fn list_roles_for_organization(
@@ -52,28 +52,15 @@ impl<'a> PikeStoreListRolesForOrganizationOperation
         limit: i64,
     ) -> Result<RoleList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_role::table
-                .into_boxed()
-                .select(pike_role::all_columns)
-                .filter(
-                    pike_role::end_commit_num
-                        .eq(MAX_COMMIT_NUM)
-                        .and(pike_role::org_id.eq(org_id)),
-                );
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_role::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_role::service_id.is_null());
-            }
+            let query =
+                count_roles_for_organization::pg::roles_for_organization(org_id, service_id)
+                    .select(pike_role::all_columns);
 
             let role_models = query.load::<RoleModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = role_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_roles_for_organization(org_id, service_id)?;
 
             let mut roles = Vec::new();
 
@@ -158,28 +145,15 @@ impl<'a> PikeStoreListRolesForOrganizationOperation
         limit: i64,
     ) -> Result<RoleList, PikeStoreError> {
         self.conn.transaction::<_, PikeStoreError, _>(|| {
-            let mut query = pike_role::table
-                .into_boxed()
-                .select(pike_role::all_columns)
-                .filter(
-                    pike_role::end_commit_num
-                        .eq(MAX_COMMIT_NUM)
-                        .and(pike_role::org_id.eq(org_id)),
-                );
-
-            if let Some(service_id) = service_id {
-                query = query.filter(pike_role::service_id.eq(service_id));
-            } else {
-                query = query.filter(pike_role::service_id.is_null());
-            }
+            let query =
+                count_roles_for_organization::sqlite::roles_for_organization(org_id, service_id)
+                    .select(pike_role::all_columns);
 
             let role_models = query.load::<RoleModel>(self.conn).map_err(|err| {
                 PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
             })?;
 
-            let total = role_models.len().try_into().map_err(|err| {
-                PikeStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_roles_for_organization(org_id, service_id)?;
 
             let mut roles = Vec::new();
 
@@ -17,11 +17,16 @@
 pub(super) mod add_agent;
 pub(super) mod add_organization;
 pub(super) mod add_role;
+pub(super) mod count_agents;
+pub(super) mod count_organizations;
+pub(super) mod count_roles_for_organization;
 pub(super) mod delete_role;
 pub(super) mod get_agent;
 pub(super) mod get_organization;
 pub(super) mod get_role;
+pub(super) mod list_agent_history;
 pub(super) mod list_agents;
+pub(super) mod list_agents_by_role;
 pub(super) mod list_organizations;
 pub(super) mod list_roles_for_organization;
 pub(super) mod update_agent;
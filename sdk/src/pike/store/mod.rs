@@ -29,6 +29,7 @@ mod builder;
 #[cfg(feature = "diesel")]
 pub(crate) mod diesel;
 mod error;
+mod resolve;
 
 use crate::paging::Paging;
 
@@ -38,6 +39,7 @@ pub use builder::{
     AgentBuilder, AlternateIdBuilder, OrganizationBuilder, OrganizationMetadataBuilder, RoleBuilder,
 };
 pub use error::PikeStoreError;
+pub use resolve::{resolve_agent_permissions, AgentPermissions};
 
 /// Represents a Grid Agent
 #[derive(Clone, Debug, Serialize, PartialEq, Eq)]
@@ -381,6 +383,36 @@ pub trait PikeStore {
         limit: i64,
     ) -> Result<AgentList, PikeStoreError>;
 
+    /// Counts the agents in the underlying storage, applying the same filters as `list_agents`
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - The service id to count agents for
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError>;
+
+    /// Lists the agents that are assigned a role, from the underlying storage
+    ///
+    /// An agent holds the role if it is assigned it by name from within the role's organization,
+    /// or by its qualified `<org_id>.<name>` name from another organization. Only direct
+    /// assignments are listed; agents that hold the role's permissions through a role that
+    /// inherits from it are not.
+    ///
+    /// # Arguments
+    ///
+    ///  * `org_id` - The organization the role belongs to
+    ///  * `role_name` - The name of the role
+    ///  * `service_id` - The service id to list agents for
+    ///  * `offset` - The index of the first in storage to retrieve
+    ///  * `limit` - The number of items to retrieve from the offset
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError>;
+
     ///  Lists roles from the underlying storage
     ///
     /// # Arguments
@@ -397,6 +429,19 @@ pub trait PikeStore {
         limit: i64,
     ) -> Result<RoleList, PikeStoreError>;
 
+    /// Counts the roles in the underlying storage, applying the same filters as
+    /// `list_roles_for_organization`
+    ///
+    /// # Arguments
+    ///
+    ///  * `org_id` - The organization id to count roles for
+    ///  * `service_id` - The service id to count roles for
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError>;
+
     /// Fetches an agent from the underlying storage
     ///
     /// # Arguments
@@ -409,6 +454,22 @@ pub trait PikeStore {
         service_id: Option<&str>,
     ) -> Result<Option<Agent>, PikeStoreError>;
 
+    /// Lists every version of an agent from the underlying storage, oldest first
+    ///
+    /// Each version's `start_commit_num` is the commit height it was written at, and its
+    /// `end_commit_num` the height it was replaced or removed at, or `MAX_COMMIT_NUM` for the
+    /// current version. Returns an empty list if the agent has never existed.
+    ///
+    /// # Arguments
+    ///
+    ///  * `pub_key` - This public key of the agent to fetch
+    ///  * `service_id` - The service id of the agent to fetch
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError>;
+
     /// Fetches a role from the underlying storage
     ///
     /// # Arguments
@@ -458,6 +519,14 @@ pub trait PikeStore {
         limit: i64,
     ) -> Result<OrganizationList, PikeStoreError>;
 
+    /// Counts the organizations in the underlying storage, applying the same filters as
+    /// `list_organizations`
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - The service ID to count organizations for
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError>;
+
     /// Fetches an organization from the underlying storage
     ///
     /// # Arguments
@@ -492,6 +561,21 @@ where
         (**self).list_agents(service_id, offset, limit)
     }
 
+    fn count_agents(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        (**self).count_agents(service_id)
+    }
+
+    fn list_agents_by_role(
+        &self,
+        org_id: &str,
+        role_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<AgentList, PikeStoreError> {
+        (**self).list_agents_by_role(org_id, role_name, service_id, offset, limit)
+    }
+
     fn list_roles_for_organization(
         &self,
         org_id: &str,
@@ -502,6 +586,14 @@ where
         (**self).list_roles_for_organization(org_id, service_id, offset, limit)
     }
 
+    fn count_roles_for_organization(
+        &self,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<i64, PikeStoreError> {
+        (**self).count_roles_for_organization(org_id, service_id)
+    }
+
     fn get_agent(
         &self,
         pub_key: &str,
@@ -510,6 +602,14 @@ where
         (**self).get_agent(pub_key, service_id)
     }
 
+    fn list_agent_history(
+        &self,
+        pub_key: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Agent>, PikeStoreError> {
+        (**self).list_agent_history(pub_key, service_id)
+    }
+
     fn get_role(
         &self,
         name: &str,
@@ -540,6 +640,10 @@ where
         (**self).list_organizations(service_id, offset, limit)
     }
 
+    fn count_organizations(&self, service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+        (**self).count_organizations(service_id)
+    }
+
     fn get_organization(
         &self,
         org_id: &str,
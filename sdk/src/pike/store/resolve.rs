@@ -0,0 +1,352 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resolution of the permissions an agent holds through its roles.
+
+use std::collections::{BTreeSet, HashSet};
+
+use super::{Agent, PikeStore, PikeStoreError, Role};
+
+/// An agent and the permissions granted to it by its roles.
+#[derive(Clone, Debug)]
+pub struct AgentPermissions {
+    agent: Agent,
+    permissions: BTreeSet<String>,
+}
+
+impl AgentPermissions {
+    /// Returns the agent the permissions were resolved for
+    pub fn agent(&self) -> &Agent {
+        &self.agent
+    }
+
+    /// Returns the agent's effective permissions, in sorted order
+    pub fn permissions(&self) -> impl Iterator<Item = &str> {
+        self.permissions.iter().map(String::as_str)
+    }
+
+    /// Returns whether the agent holds the given permission
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.contains(permission)
+    }
+}
+
+/// Resolves the effective permissions of the agent with the given public key.
+///
+/// Roles are resolved the same way the Pike permission checks do: a role name of the form
+/// `<org_id>.<name>` refers to a role of that organization, and any other name to a role of the
+/// agent's organization. A role also grants the permissions of the roles it inherits from, as
+/// long as the inherited role belongs to or allows the agent's organization. An inactive agent
+/// has no permissions.
+///
+/// The permissions are not scoped to the organizations that own the records they are used on;
+/// that check is left to the smart contracts.
+///
+/// Returns `None` if there is no agent with the public key.
+///
+/// # Arguments
+///
+///  * `store` - The store to read the agent and roles from
+///  * `public_key` - The public key of the agent
+///  * `service_id` - The service ID to read the agent and roles from
+pub fn resolve_agent_permissions(
+    store: &dyn PikeStore,
+    public_key: &str,
+    service_id: Option<&str>,
+) -> Result<Option<AgentPermissions>, PikeStoreError> {
+    let agent = match store.get_agent(public_key, service_id)? {
+        Some(agent) => agent,
+        None => return Ok(None),
+    };
+
+    let mut permissions = BTreeSet::new();
+
+    if agent.active() {
+        let mut visited = HashSet::new();
+        let mut pending = agent
+            .roles()
+            .iter()
+            .map(|role| (role.to_string(), false))
+            .collect::<Vec<_>>();
+
+        while let Some((name, inherited)) = pending.pop() {
+            let role = match get_role(store, &name, agent.org_id(), service_id)? {
+                Some(role) => role,
+                None => continue,
+            };
+
+            if !visited.insert((role.org_id().to_string(), role.name().to_string())) {
+                continue;
+            }
+
+            if inherited
+                && role.org_id() != agent.org_id()
+                && !role
+                    .allowed_organizations()
+                    .iter()
+                    .any(|org| org == agent.org_id())
+            {
+                continue;
+            }
+
+            permissions.extend(role.permissions().iter().cloned());
+            pending.extend(
+                role.inherit_from()
+                    .iter()
+                    .map(|inherited| (inherited.to_string(), true)),
+            );
+        }
+    }
+
+    Ok(Some(AgentPermissions { agent, permissions }))
+}
+
+fn get_role(
+    store: &dyn PikeStore,
+    name: &str,
+    agent_org_id: &str,
+    service_id: Option<&str>,
+) -> Result<Option<Role>, PikeStoreError> {
+    match name.split_once('.') {
+        Some((org_id, name)) => store.get_role(name, org_id, service_id),
+        None => store.get_role(name, agent_org_id, service_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::pike::store::{
+        AgentBuilder, AgentList, Organization, OrganizationList, RoleBuilder, RoleList,
+    };
+
+    /// A store that only supports looking up the agents and roles it was constructed with.
+    struct TestPikeStore {
+        agents: Vec<Agent>,
+        roles: Vec<Role>,
+    }
+
+    impl PikeStore for TestPikeStore {
+        fn add_agent(&self, _agent: Agent) -> Result<(), PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn add_role(&self, _role: Role) -> Result<(), PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn list_agents(
+            &self,
+            _service_id: Option<&str>,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<AgentList, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn count_agents(&self, _service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn list_agents_by_role(
+            &self,
+            _org_id: &str,
+            _role_name: &str,
+            _service_id: Option<&str>,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<AgentList, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn list_roles_for_organization(
+            &self,
+            _org_id: &str,
+            _service_id: Option<&str>,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<RoleList, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn count_roles_for_organization(
+            &self,
+            _org_id: &str,
+            _service_id: Option<&str>,
+        ) -> Result<i64, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn get_agent(
+            &self,
+            pub_key: &str,
+            _service_id: Option<&str>,
+        ) -> Result<Option<Agent>, PikeStoreError> {
+            Ok(self
+                .agents
+                .iter()
+                .find(|agent| agent.public_key() == pub_key)
+                .cloned())
+        }
+
+        fn list_agent_history(
+            &self,
+            _pub_key: &str,
+            _service_id: Option<&str>,
+        ) -> Result<Vec<Agent>, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn get_role(
+            &self,
+            name: &str,
+            org_id: &str,
+            _service_id: Option<&str>,
+        ) -> Result<Option<Role>, PikeStoreError> {
+            Ok(self
+                .roles
+                .iter()
+                .find(|role| role.name() == name && role.org_id() == org_id)
+                .cloned())
+        }
+
+        fn update_agent(&self, _agent: Agent) -> Result<(), PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn delete_role(
+            &self,
+            _address: &str,
+            _current_commit_num: i64,
+        ) -> Result<(), PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn add_organization(&self, _org: Organization) -> Result<(), PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn list_organizations(
+            &self,
+            _service_id: Option<&str>,
+            _offset: i64,
+            _limit: i64,
+        ) -> Result<OrganizationList, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn count_organizations(&self, _service_id: Option<&str>) -> Result<i64, PikeStoreError> {
+            unimplemented!()
+        }
+
+        fn get_organization(
+            &self,
+            _org_id: &str,
+            _service_id: Option<&str>,
+        ) -> Result<Option<Organization>, PikeStoreError> {
+            unimplemented!()
+        }
+    }
+
+    fn role(
+        org_id: &str,
+        name: &str,
+        permissions: &[&str],
+        inherit_from: &[&str],
+        allowed_organizations: &[&str],
+    ) -> Role {
+        RoleBuilder::new()
+            .with_name(name.to_string())
+            .with_org_id(org_id.to_string())
+            .with_description("test role".to_string())
+            .with_active(true)
+            .with_permissions(permissions.iter().map(|p| p.to_string()).collect())
+            .with_inherit_from(inherit_from.iter().map(|r| r.to_string()).collect())
+            .with_allowed_organizations(
+                allowed_organizations
+                    .iter()
+                    .map(|o| o.to_string())
+                    .collect(),
+            )
+            .with_start_commit_num(1)
+            .with_end_commit_num(i64::MAX)
+            .build()
+            .expect("Unable to build role")
+    }
+
+    fn agent(public_key: &str, active: bool, roles: &[&str]) -> Agent {
+        AgentBuilder::new()
+            .with_public_key(public_key.to_string())
+            .with_org_id("org-1".to_string())
+            .with_active(active)
+            .with_roles(roles.iter().map(|r| r.to_string()).collect())
+            .with_start_commit_num(1)
+            .with_end_commit_num(i64::MAX)
+            .build()
+            .expect("Unable to build agent")
+    }
+
+    /// Verifies that an agent's permissions include those of its roles and of inherited roles
+    /// that allow its organization, but not of inherited roles that do not, and that inactive
+    /// and unknown agents are handled.
+    #[test]
+    fn test_resolve_agent_permissions() -> Result<(), Box<dyn std::error::Error>> {
+        let store = TestPikeStore {
+            agents: vec![
+                agent("active-key", true, &["editor"]),
+                agent("inactive-key", false, &["editor"]),
+            ],
+            roles: vec![
+                role(
+                    "org-1",
+                    "editor",
+                    &["product::can-update-product"],
+                    &["org-2.creator", "org-2.deleter"],
+                    &[],
+                ),
+                role(
+                    "org-2",
+                    "creator",
+                    &["product::can-create-product"],
+                    &[],
+                    &["org-1"],
+                ),
+                role(
+                    "org-2",
+                    "deleter",
+                    &["product::can-delete-product"],
+                    &[],
+                    &[],
+                ),
+            ],
+        };
+
+        let resolved =
+            resolve_agent_permissions(&store, "active-key", None)?.expect("Agent not found");
+        assert_eq!(
+            resolved.permissions().collect::<Vec<_>>(),
+            vec!["product::can-create-product", "product::can-update-product"]
+        );
+        assert!(!resolved.has_permission("product::can-delete-product"));
+
+        let inactive =
+            resolve_agent_permissions(&store, "inactive-key", None)?.expect("Agent not found");
+        assert_eq!(inactive.permissions().count(), 0);
+
+        assert!(resolve_agent_permissions(&store, "unknown-key", None)?.is_none());
+
+        Ok(())
+    }
+}
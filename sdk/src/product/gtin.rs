@@ -0,0 +1,189 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Normalization of GTINs to the canonical GTIN-14 form.
+//!
+//! The same trade item may be identified by its GTIN-12 (UPC-A), GTIN-13 (EAN-13) or GTIN-14,
+//! which differ only by leading zeros. Normalizing GTINs before storing or comparing products
+//! keeps one item from being recorded as several products.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::gs1;
+
+/// The length of a GTIN in its canonical form.
+const GTIN_14_LENGTH: usize = 14;
+
+/// An error returned when a GTIN cannot be normalized.
+#[derive(Debug, PartialEq, Eq)]
+pub enum GtinError {
+    /// The GTIN contains characters other than digits
+    InvalidFormat(String),
+    /// The GTIN is not a GTIN-12, GTIN-13 or GTIN-14
+    InvalidLength(String),
+    /// The GTIN's last digit is not the check digit of the digits before it
+    InvalidCheckDigit(String),
+}
+
+impl Error for GtinError {}
+
+impl fmt::Display for GtinError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GtinError::InvalidFormat(gtin) => {
+                write!(f, "GTIN {} may only contain digits", gtin)
+            }
+            GtinError::InvalidLength(gtin) => {
+                write!(f, "GTIN {} must be 12, 13 or 14 digits", gtin)
+            }
+            GtinError::InvalidCheckDigit(gtin) => {
+                write!(f, "GTIN {} has an invalid check digit", gtin)
+            }
+        }
+    }
+}
+
+/// Normalizes a GTIN-12, GTIN-13 or GTIN-14 to its canonical GTIN-14 form, zero-padding it on
+/// the left.
+///
+/// Surrounding whitespace is ignored. Leading zeros do not change the check digit, so it is
+/// validated the same way for every length. GTIN-8 is not supported, matching product
+/// validation.
+///
+/// # Errors
+///
+/// Returns a [`GtinError`] if the GTIN contains anything but digits, has an unsupported length
+/// or has an invalid check digit.
+pub fn normalize(gtin: &str) -> Result<String, GtinError> {
+    let gtin = gtin.trim();
+
+    let digits = gs1::digits(gtin).ok_or_else(|| GtinError::InvalidFormat(gtin.to_string()))?;
+
+    if !(12..=GTIN_14_LENGTH).contains(&digits.len()) {
+        return Err(GtinError::InvalidLength(gtin.to_string()));
+    }
+
+    if !gs1::has_valid_check_digit(&digits) {
+        return Err(GtinError::InvalidCheckDigit(gtin.to_string()));
+    }
+
+    Ok(format!("{:0>width$}", gtin, width = GTIN_14_LENGTH))
+}
+
+/// Returns every representation of a GTIN that identifies the same trade item: its GTIN-14
+/// form, followed by its GTIN-13 and GTIN-12 forms where its leading zeros allow them.
+///
+/// # Errors
+///
+/// Returns a [`GtinError`] if the GTIN cannot be normalized.
+pub fn representations(gtin: &str) -> Result<Vec<String>, GtinError> {
+    let gtin_14 = normalize(gtin)?;
+
+    Ok((12..=GTIN_14_LENGTH)
+        .rev()
+        .map(|length| GTIN_14_LENGTH - length)
+        .filter(|&padding| gtin_14[..padding].bytes().all(|digit| digit == b'0'))
+        .map(|padding| gtin_14[padding..].to_string())
+        .collect())
+}
+
+/// Returns the product ids a product with the given id may be stored under, so that a lookup
+/// by one GTIN representation finds a product stored under another.
+///
+/// The id as given comes first, followed by its other representations if it is a GTIN. An id
+/// that is not a GTIN is returned as-is.
+pub fn product_ids(product_id: &str) -> Vec<String> {
+    let mut product_ids = vec![product_id.to_string()];
+
+    if let Ok(representations) = representations(product_id) {
+        product_ids.extend(
+            representations
+                .into_iter()
+                .filter(|representation| representation != product_id),
+        );
+    }
+
+    product_ids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that a UPC-A, its EAN-13 form and its GTIN-14 form all normalize to the same
+    /// GTIN-14.
+    #[test]
+    fn test_normalize_pads_to_gtin_14() {
+        assert_eq!(normalize("012345678905"), Ok("00012345678905".to_string()));
+        assert_eq!(normalize("0012345678905"), Ok("00012345678905".to_string()));
+        assert_eq!(
+            normalize(" 00012345678905\n"),
+            Ok("00012345678905".to_string())
+        );
+    }
+
+    /// Verifies that GTINs that are malformed, of an unsupported length or with an invalid
+    /// check digit are rejected.
+    #[test]
+    fn test_normalize_invalid() {
+        assert_eq!(
+            normalize("01234567890a"),
+            Err(GtinError::InvalidFormat("01234567890a".to_string()))
+        );
+        assert_eq!(
+            normalize("12345670"),
+            Err(GtinError::InvalidLength("12345670".to_string()))
+        );
+        assert_eq!(
+            normalize("012345678906"),
+            Err(GtinError::InvalidCheckDigit("012345678906".to_string()))
+        );
+    }
+
+    /// Verifies that a GTIN's shorter representations are only those its leading zeros allow.
+    #[test]
+    fn test_representations() {
+        assert_eq!(
+            representations("012345678905"),
+            Ok(vec![
+                "00012345678905".to_string(),
+                "0012345678905".to_string(),
+                "012345678905".to_string(),
+            ])
+        );
+        assert_eq!(
+            representations("4006381333931"),
+            Ok(vec![
+                "04006381333931".to_string(),
+                "4006381333931".to_string()
+            ])
+        );
+    }
+
+    /// Verifies that the id as given is looked up first, and that an id that is not a GTIN is
+    /// looked up as-is.
+    #[test]
+    fn test_product_ids() {
+        assert_eq!(
+            product_ids("0012345678905"),
+            vec![
+                "0012345678905".to_string(),
+                "00012345678905".to_string(),
+                "012345678905".to_string(),
+            ]
+        );
+        assert_eq!(product_ids("product"), vec!["product".to_string()]);
+    }
+}
@@ -13,7 +13,10 @@
 // limitations under the License.
 
 pub mod addressing;
+pub mod gtin;
+pub mod recall;
 pub mod store;
+pub mod validation;
 
 pub const MAX_COMMIT_NUM: i64 = i64::MAX;
 
@@ -0,0 +1,196 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recalls and withdrawals of products from the market.
+//!
+//! A product is withdrawn when it is taken off the market as a precaution, and recalled when it
+//! must also be returned by those who hold it. The recall status of a product moves through the
+//! `recall` subworkflow, so that, for instance, a recalled product cannot quietly be downgraded
+//! to a withdrawal.
+//!
+//! Recall statuses are not part of the products' on-chain state: they are set through the REST
+//! API by agents of the product's owner, and kept only in the database of the daemon that
+//! received the request. They are lost if that database is rebuilt from the chain, and are not
+//! shared with other daemons.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{InvalidArgumentError, InvalidStateError};
+use crate::workflow::{PermissionAlias, SubWorkflow, SubWorkflowBuilder, WorkflowStateBuilder};
+
+/// The permission alias allowed to make every transition of the `recall` subworkflow.
+pub const RECALL_MANAGER_ALIAS: &str = "product::recall-manager";
+
+/// Whether a product is on the market, or has been withdrawn or recalled from it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecallStatus {
+    Active,
+    Withdrawn,
+    Recalled,
+}
+
+impl RecallStatus {
+    /// Returns the name of the status, which is also the name of its workflow state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecallStatus::Active => "active",
+            RecallStatus::Withdrawn => "withdrawn",
+            RecallStatus::Recalled => "recalled",
+        }
+    }
+
+    /// Returns whether the product has been taken off the market, by a withdrawal or a recall.
+    pub fn is_recalled(&self) -> bool {
+        *self != RecallStatus::Active
+    }
+}
+
+impl Default for RecallStatus {
+    fn default() -> Self {
+        RecallStatus::Active
+    }
+}
+
+impl fmt::Display for RecallStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RecallStatus {
+    type Err = InvalidArgumentError;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status {
+            "active" => Ok(RecallStatus::Active),
+            "withdrawn" => Ok(RecallStatus::Withdrawn),
+            "recalled" => Ok(RecallStatus::Recalled),
+            _ => Err(InvalidArgumentError::new(
+                "recall_status".to_string(),
+                format!(
+                    "'{}' is not one of 'active', 'withdrawn' or 'recalled'",
+                    status
+                ),
+            )),
+        }
+    }
+}
+
+/// Returns the `recall` subworkflow.
+///
+/// An active product may be withdrawn or recalled, and a withdrawn product may be recalled or
+/// returned to the market. A recall can only be lifted, returning the product to the market.
+/// Every transition is made by the [`RECALL_MANAGER_ALIAS`] permission alias.
+pub fn recall_workflow() -> SubWorkflow {
+    let state = |status: RecallStatus, transitions: &[RecallStatus]| {
+        let mut manager = PermissionAlias::new(RECALL_MANAGER_ALIAS);
+        let mut builder = WorkflowStateBuilder::new(status.as_str());
+        for transition in transitions {
+            manager.add_transition(transition.as_str());
+            builder = builder.add_transition(transition.as_str());
+        }
+
+        builder.add_permission_alias(manager).build()
+    };
+
+    SubWorkflowBuilder::new("recall")
+        .add_state(state(
+            RecallStatus::Active,
+            &[RecallStatus::Withdrawn, RecallStatus::Recalled],
+        ))
+        .add_state(state(
+            RecallStatus::Withdrawn,
+            &[RecallStatus::Active, RecallStatus::Recalled],
+        ))
+        .add_state(state(RecallStatus::Recalled, &[RecallStatus::Active]))
+        .build()
+}
+
+/// Checks that the holder of the given permission aliases may move a product from one recall
+/// status to another, following the `recall` subworkflow.
+///
+/// # Arguments
+///
+///  * `from` - The current recall status of the product
+///  * `to` - The recall status to move the product to
+///  * `permission_aliases` - The permission aliases held by the agent making the transition
+pub fn check_recall_transition(
+    from: RecallStatus,
+    to: RecallStatus,
+    permission_aliases: &[String],
+) -> Result<(), InvalidStateError> {
+    let allowed = recall_workflow()
+        .state(from.as_str())
+        .map(|state| state.can_transition(to.as_str().to_string(), permission_aliases))
+        .unwrap_or(false);
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(InvalidStateError::with_message(format!(
+            "A {} product cannot be {}",
+            from,
+            match to {
+                RecallStatus::Active => "returned to the market",
+                RecallStatus::Withdrawn => "withdrawn",
+                RecallStatus::Recalled => "recalled",
+            }
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the recall manager may make the transitions of the `recall` subworkflow,
+    /// and only those.
+    #[test]
+    fn test_check_recall_transition() {
+        let manager = vec![RECALL_MANAGER_ALIAS.to_string()];
+
+        assert!(
+            check_recall_transition(RecallStatus::Active, RecallStatus::Recalled, &manager).is_ok()
+        );
+        assert!(
+            check_recall_transition(RecallStatus::Withdrawn, RecallStatus::Recalled, &manager)
+                .is_ok()
+        );
+        assert!(
+            check_recall_transition(RecallStatus::Recalled, RecallStatus::Active, &manager).is_ok()
+        );
+        assert!(
+            check_recall_transition(RecallStatus::Recalled, RecallStatus::Withdrawn, &manager)
+                .is_err()
+        );
+        assert!(
+            check_recall_transition(RecallStatus::Active, RecallStatus::Recalled, &[]).is_err()
+        );
+    }
+
+    /// Verifies that recall statuses round-trip through their names.
+    #[test]
+    fn test_recall_status_from_str() {
+        for status in &[
+            RecallStatus::Active,
+            RecallStatus::Withdrawn,
+            RecallStatus::Recalled,
+        ] {
+            assert_eq!(status.as_str().parse::<RecallStatus>().ok(), Some(*status));
+        }
+        assert!("lost".parse::<RecallStatus>().is_err());
+    }
+}
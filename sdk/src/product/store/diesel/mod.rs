@@ -19,15 +19,26 @@ pub(crate) mod schema;
 use crate::error::ResourceTemporarilyUnavailableError;
 
 use operations::{
-    add_product::AddProductOperation, delete_product::DeleteProductOperation,
-    get_product::GetProductOperation, list_products::ListProductsOperation,
-    update_product::UpdateProductOperation, ProductStoreOperations,
+    add_product::AddProductOperation, count_products::CountProductsOperation,
+    delete_product::DeleteProductOperation, get_bundle_components::GetBundleComponentsOperation,
+    get_product::GetProductOperation, list_bundles_containing::ListBundlesContainingOperation,
+    list_dangling_references::ListDanglingReferencesOperation,
+    list_product_history::ListProductHistoryOperation, list_products::ListProductsOperation,
+    list_products_after::ListProductsAfterOperation,
+    list_products_missing_required::ListProductsMissingRequiredOperation,
+    set_product_recall_status::SetProductRecallStatusOperation,
+    update_product::UpdateProductOperation, upsert_products::UpsertProductsOperation,
+    ProductStoreOperations,
 };
 
 use diesel::connection::AnsiTransactionManager;
 use diesel::r2d2::{ConnectionManager, Pool};
 
-use super::{Product, ProductList, ProductStore, ProductStoreError};
+use crate::product::recall::RecallStatus;
+
+use super::{
+    BundleComponent, DanglingReference, Product, ProductList, ProductStore, ProductStoreError,
+};
 
 #[derive(Clone)]
 pub struct DieselProductStore<C: diesel::Connection + 'static> {
@@ -51,6 +62,15 @@ impl ProductStore for DieselProductStore<diesel::pg::PgConnection> {
         .add_product(product)
     }
 
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .upsert_products(products)
+    }
+
     fn get_product(
         &self,
         product_id: &str,
@@ -64,9 +84,23 @@ impl ProductStore for DieselProductStore<diesel::pg::PgConnection> {
         .get_product(product_id, service_id)
     }
 
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_product_history(product_id, service_id)
+    }
+
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
@@ -75,13 +109,94 @@ impl ProductStore for DieselProductStore<diesel::pg::PgConnection> {
                 ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
             )
         })?)
-        .list_products(service_id, offset, limit)
+        .list_products(service_id, include_recalled, offset, limit)
+    }
+
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_products_after(service_id, after, limit)
+    }
+
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_products_missing_required(schema_name, service_id, offset, limit)
+    }
+
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_bundle_components(product_id, service_id)
+    }
+
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_bundles_containing(product_id, service_id)
+    }
+
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_products(service_id, include_recalled)
+    }
+
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_dangling_references(service_id)
     }
 
     fn update_product(
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
@@ -89,7 +204,32 @@ impl ProductStore for DieselProductStore<diesel::pg::PgConnection> {
                 ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
             )
         })?)
-        .update_product(product_id, service_id, current_commit_num)
+        .update_product(
+            product_id,
+            service_id,
+            expected_commit_num,
+            current_commit_num,
+        )
+    }
+
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .set_product_recall_status(
+            product_id,
+            service_id,
+            recall_status,
+            permission_aliases,
+        )
     }
 
     fn delete_product(
@@ -117,6 +257,15 @@ impl ProductStore for DieselProductStore<diesel::sqlite::SqliteConnection> {
         .add_product(product)
     }
 
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .upsert_products(products)
+    }
+
     fn get_product(
         &self,
         product_id: &str,
@@ -130,9 +279,52 @@ impl ProductStore for DieselProductStore<diesel::sqlite::SqliteConnection> {
         .get_product(product_id, service_id)
     }
 
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_product_history(product_id, service_id)
+    }
+
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_products(service_id, include_recalled, offset, limit)
+    }
+
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_products_after(service_id, after, limit)
+    }
+
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
@@ -141,13 +333,65 @@ impl ProductStore for DieselProductStore<diesel::sqlite::SqliteConnection> {
                 ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
             )
         })?)
-        .list_products(service_id, offset, limit)
+        .list_products_missing_required(schema_name, service_id, offset, limit)
+    }
+
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_bundle_components(product_id, service_id)
+    }
+
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_bundles_containing(product_id, service_id)
+    }
+
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_products(service_id, include_recalled)
+    }
+
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_dangling_references(service_id)
     }
 
     fn update_product(
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
@@ -155,7 +399,32 @@ impl ProductStore for DieselProductStore<diesel::sqlite::SqliteConnection> {
                 ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
             )
         })?)
-        .update_product(product_id, service_id, current_commit_num)
+        .update_product(
+            product_id,
+            service_id,
+            expected_commit_num,
+            current_commit_num,
+        )
+    }
+
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            ProductStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .set_product_recall_status(
+            product_id,
+            service_id,
+            recall_status,
+            permission_aliases,
+        )
     }
 
     fn delete_product(
@@ -196,6 +465,10 @@ impl<'a> ProductStore for DieselConnectionProductStore<'a, diesel::pg::PgConnect
         ProductStoreOperations::new(self.connection).add_product(product)
     }
 
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(self.connection).upsert_products(products)
+    }
+
     fn get_product(
         &self,
         product_id: &str,
@@ -204,28 +477,114 @@ impl<'a> ProductStore for DieselConnectionProductStore<'a, diesel::pg::PgConnect
         ProductStoreOperations::new(self.connection).get_product(product_id, service_id)
     }
 
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_product_history(product_id, service_id)
+    }
+
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_products(
+            service_id,
+            include_recalled,
+            offset,
+            limit,
+        )
+    }
+
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_products_after(service_id, after, limit)
+    }
+
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
-        ProductStoreOperations::new(self.connection).list_products(service_id, offset, limit)
+        ProductStoreOperations::new(self.connection).list_products_missing_required(
+            schema_name,
+            service_id,
+            offset,
+            limit,
+        )
+    }
+
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).get_bundle_components(product_id, service_id)
+    }
+
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_bundles_containing(product_id, service_id)
+    }
+
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).count_products(service_id, include_recalled)
+    }
+
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_dangling_references(service_id)
     }
 
     fn update_product(
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         ProductStoreOperations::new(self.connection).update_product(
             product_id,
             service_id,
+            expected_commit_num,
             current_commit_num,
         )
     }
 
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(self.connection).set_product_recall_status(
+            product_id,
+            service_id,
+            recall_status,
+            permission_aliases,
+        )
+    }
+
     fn delete_product(
         &self,
         address: &str,
@@ -241,6 +600,10 @@ impl<'a> ProductStore for DieselConnectionProductStore<'a, diesel::sqlite::Sqlit
         ProductStoreOperations::new(self.connection).add_product(product)
     }
 
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(self.connection).upsert_products(products)
+    }
+
     fn get_product(
         &self,
         product_id: &str,
@@ -249,28 +612,114 @@ impl<'a> ProductStore for DieselConnectionProductStore<'a, diesel::sqlite::Sqlit
         ProductStoreOperations::new(self.connection).get_product(product_id, service_id)
     }
 
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_product_history(product_id, service_id)
+    }
+
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
-        ProductStoreOperations::new(self.connection).list_products(service_id, offset, limit)
+        ProductStoreOperations::new(self.connection).list_products(
+            service_id,
+            include_recalled,
+            offset,
+            limit,
+        )
+    }
+
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_products_after(service_id, after, limit)
+    }
+
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_products_missing_required(
+            schema_name,
+            service_id,
+            offset,
+            limit,
+        )
+    }
+
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).get_bundle_components(product_id, service_id)
+    }
+
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_bundles_containing(product_id, service_id)
+    }
+
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).count_products(service_id, include_recalled)
+    }
+
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        ProductStoreOperations::new(self.connection).list_dangling_references(service_id)
     }
 
     fn update_product(
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         ProductStoreOperations::new(self.connection).update_product(
             product_id,
             service_id,
+            expected_commit_num,
             current_commit_num,
         )
     }
 
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        ProductStoreOperations::new(self.connection).set_product_recall_status(
+            product_id,
+            service_id,
+            recall_status,
+            permission_aliases,
+        )
+    }
+
     fn delete_product(
         &self,
         address: &str,
@@ -19,7 +19,7 @@ use crate::product::{
     MAX_COMMIT_NUM,
 };
 
-use super::schema::{product, product_property_value};
+use super::schema::{product, product_component, product_property_value};
 
 #[derive(Clone, Insertable, Debug)]
 #[table_name = "product"]
@@ -31,6 +31,7 @@ pub struct NewProduct {
     pub start_commit_num: i64,
     pub end_commit_num: i64,
     pub service_id: Option<String>,
+    pub recall_status: String,
 }
 
 #[derive(Queryable, Identifiable, Debug)]
@@ -45,6 +46,7 @@ pub struct Product {
     pub end_commit_num: i64,
     pub service_id: Option<String>,
     pub last_updated: Option<NaiveDateTime>,
+    pub recall_status: String,
 }
 
 #[derive(AsChangeset, Clone, Insertable, Debug)]
@@ -88,6 +90,29 @@ pub struct ProductPropertyValue {
     pub service_id: Option<String>,
 }
 
+#[derive(Clone, Insertable, Debug)]
+#[table_name = "product_component"]
+pub struct NewProductComponent {
+    pub product_id: String,
+    pub product_address: String,
+    pub component_id: String,
+    pub start_commit_num: i64,
+    pub end_commit_num: i64,
+    pub service_id: Option<String>,
+}
+
+#[derive(Queryable, Identifiable, Debug)]
+#[table_name = "product_component"]
+pub struct ProductComponent {
+    pub id: i64,
+    pub product_id: String,
+    pub product_address: String,
+    pub component_id: String,
+    pub start_commit_num: i64,
+    pub end_commit_num: i64,
+    pub service_id: Option<String>,
+}
+
 impl From<GridProduct> for (NewProduct, Vec<NewProductPropertyValue>) {
     fn from(product: GridProduct) -> Self {
         let new_product = NewProduct {
@@ -98,12 +123,28 @@ impl From<GridProduct> for (NewProduct, Vec<NewProductPropertyValue>) {
             start_commit_num: product.start_commit_num,
             end_commit_num: product.end_commit_num,
             service_id: product.service_id.clone(),
+            recall_status: product.recall_status.to_string(),
         };
 
         (new_product, make_property_values(None, &product.properties))
     }
 }
 
+pub fn make_components(product: &GridProduct) -> Vec<NewProductComponent> {
+    product
+        .components
+        .iter()
+        .map(|component_id| NewProductComponent {
+            product_id: product.product_id.clone(),
+            product_address: product.product_address.clone(),
+            component_id: component_id.clone(),
+            start_commit_num: product.start_commit_num,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: product.service_id.clone(),
+        })
+        .collect()
+}
+
 impl From<(Product, Vec<PropertyValue>)> for GridProduct {
     fn from((model, properties): (Product, Vec<PropertyValue>)) -> Self {
         Self {
@@ -116,6 +157,8 @@ impl From<(Product, Vec<PropertyValue>)> for GridProduct {
             service_id: model.service_id,
             last_updated: model.last_updated.map(|d| d.timestamp()),
             properties,
+            components: vec![],
+            recall_status: model.recall_status.parse().unwrap_or_default(),
         }
     }
 }
@@ -12,13 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::get_product;
+use super::list_dangling_references::{dangling_references, warn_dangling};
 use super::ProductStoreOperations;
 
 use crate::product::{
     store::{
         diesel::{
-            models::{NewProduct, NewProductPropertyValue},
-            schema::{product, product_property_value},
+            models::{make_components, NewProduct, NewProductComponent, NewProductPropertyValue},
+            schema::{product, product_component, product_property_value},
         },
         error::ProductStoreError,
         Product,
@@ -38,11 +40,29 @@ pub(in crate::product) trait AddProductOperation {
 #[cfg(feature = "postgres")]
 impl<'a> AddProductOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
     fn add_product(&self, product: Product) -> Result<(), ProductStoreError> {
+        let component_models = make_components(&product);
         let (product_model, property_models) = product.into();
 
         self.conn.transaction::<_, ProductStoreError, _>(|| {
             pg::insert_product(self.conn, &product_model)?;
             pg::insert_product_property_values(self.conn, &property_models)?;
+            pg::insert_product_components(self.conn, &product_model, &component_models)?;
+
+            let service_id = product_model.service_id.as_deref();
+            warn_dangling(&dangling_references(
+                &product_model.product_id,
+                &product_model.owner,
+                &product_model.product_namespace,
+                service_id,
+                |org_id| {
+                    super::list_dangling_references::pg::organization_exists(
+                        self.conn, org_id, service_id,
+                    )
+                },
+                |name| {
+                    super::list_dangling_references::pg::schema_exists(self.conn, name, service_id)
+                },
+            )?);
 
             Ok(())
         })
@@ -52,11 +72,31 @@ impl<'a> AddProductOperation for ProductStoreOperations<'a, diesel::pg::PgConnec
 #[cfg(feature = "sqlite")]
 impl<'a> AddProductOperation for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection> {
     fn add_product(&self, product: Product) -> Result<(), ProductStoreError> {
+        let component_models = make_components(&product);
         let (product_model, property_models) = product.into();
 
         self.conn.transaction::<_, ProductStoreError, _>(|| {
             sqlite::insert_product(self.conn, &product_model)?;
             sqlite::insert_product_property_values(self.conn, &property_models)?;
+            sqlite::insert_product_components(self.conn, &product_model, &component_models)?;
+
+            let service_id = product_model.service_id.as_deref();
+            warn_dangling(&dangling_references(
+                &product_model.product_id,
+                &product_model.owner,
+                &product_model.product_namespace,
+                service_id,
+                |org_id| {
+                    super::list_dangling_references::sqlite::organization_exists(
+                        self.conn, org_id, service_id,
+                    )
+                },
+                |name| {
+                    super::list_dangling_references::sqlite::schema_exists(
+                        self.conn, name, service_id,
+                    )
+                },
+            )?);
 
             Ok(())
         })
@@ -64,10 +104,16 @@ impl<'a> AddProductOperation for ProductStoreOperations<'a, diesel::sqlite::Sqli
 }
 
 #[cfg(feature = "postgres")]
-mod pg {
+pub(super) mod pg {
     use super::*;
 
     pub fn insert_product(conn: &PgConnection, product: &NewProduct) -> QueryResult<()> {
+        // The recall status is not part of the product's state, so a new version keeps the
+        // status of the version it replaces
+        let recall_status =
+            get_product::pg::get_product(conn, &product.product_id, product.service_id.as_deref())?
+                .map(|current| current.recall_status);
+
         update_prod_end_commit_num(
             conn,
             &product.product_id,
@@ -76,7 +122,10 @@ mod pg {
         )?;
 
         insert_into(product::table)
-            .values(product)
+            .values(&NewProduct {
+                recall_status: recall_status.unwrap_or_else(|| product.recall_status.clone()),
+                ..product.clone()
+            })
             .execute(conn)
             .map(|_| ())
     }
@@ -99,6 +148,24 @@ mod pg {
             .execute(conn)
             .map(|_| ())
     }
+    pub fn insert_product_components(
+        conn: &PgConnection,
+        product: &NewProduct,
+        components: &[NewProductComponent],
+    ) -> QueryResult<()> {
+        end_product_components(
+            conn,
+            &product.product_id,
+            product.service_id.as_deref(),
+            product.start_commit_num,
+        )?;
+
+        insert_into(product_component::table)
+            .values(components)
+            .execute(conn)
+            .map(|_| ())
+    }
+
     fn update_prod_end_commit_num(
         conn: &PgConnection,
         product_id: &str,
@@ -162,13 +229,54 @@ mod pg {
                 .map(|_| ())
         }
     }
+
+    pub fn end_product_components(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        let update = update(product_component::table);
+
+        if let Some(service_id) = service_id {
+            update
+                .filter(
+                    product_component::product_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_component::service_id.eq(service_id)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)
+                .map(|_| ())
+        } else {
+            update
+                .filter(
+                    product_component::product_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)
+                .map(|_| ())
+        }
+    }
 }
 
 #[cfg(feature = "sqlite")]
-mod sqlite {
+pub(super) mod sqlite {
     use super::*;
 
     pub fn insert_product(conn: &SqliteConnection, product: &NewProduct) -> QueryResult<()> {
+        // The recall status is not part of the product's state, so a new version keeps the
+        // status of the version it replaces
+        let recall_status = get_product::sqlite::get_product(
+            conn,
+            &product.product_id,
+            product.service_id.as_deref(),
+        )?
+        .map(|current| current.recall_status);
+
         update_prod_end_commit_num(
             conn,
             &product.product_id,
@@ -177,7 +285,10 @@ mod sqlite {
         )?;
 
         insert_into(product::table)
-            .values(product)
+            .values(&NewProduct {
+                recall_status: recall_status.unwrap_or_else(|| product.recall_status.clone()),
+                ..product.clone()
+            })
             .execute(conn)
             .map(|_| ())
     }
@@ -201,6 +312,24 @@ mod sqlite {
             .map(|_| ())
     }
 
+    pub fn insert_product_components(
+        conn: &SqliteConnection,
+        product: &NewProduct,
+        components: &[NewProductComponent],
+    ) -> QueryResult<()> {
+        end_product_components(
+            conn,
+            &product.product_id,
+            product.service_id.as_deref(),
+            product.start_commit_num,
+        )?;
+
+        insert_into(product_component::table)
+            .values(components)
+            .execute(conn)
+            .map(|_| ())
+    }
+
     fn update_prod_end_commit_num(
         conn: &SqliteConnection,
         product_id: &str,
@@ -264,4 +393,36 @@ mod sqlite {
                 .map(|_| ())
         }
     }
+
+    pub fn end_product_components(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        let update = update(product_component::table);
+
+        if let Some(service_id) = service_id {
+            update
+                .filter(
+                    product_component::product_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_component::service_id.eq(service_id)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)
+                .map(|_| ())
+        } else {
+            update
+                .filter(
+                    product_component::product_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)
+                .map(|_| ())
+        }
+    }
 }
@@ -0,0 +1,210 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::ProductStoreOperations;
+
+use crate::{
+    error::InternalError,
+    product::{
+        recall::RecallStatus,
+        store::{diesel::schema::product, error::ProductStoreError},
+        MAX_COMMIT_NUM,
+    },
+};
+
+use diesel::prelude::*;
+
+pub(in crate::product) trait CountProductsOperation {
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CountProductsOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        pg::listed_products(service_id, include_recalled)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                ProductStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CountProductsOperation for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn count_products(
+        &self,
+        service_id: Option<&str>,
+        include_recalled: bool,
+    ) -> Result<i64, ProductStoreError> {
+        sqlite::listed_products(service_id, include_recalled)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                ProductStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current products.
+    pub fn products<'a>(service_id: Option<&'a str>) -> product::BoxedQuery<'a, Pg> {
+        let mut query = product::table
+            .into_boxed()
+            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query
+    }
+
+    /// Selects the current products that are listed, leaving out withdrawn and recalled
+    /// products unless `include_recalled` is set.
+    pub fn listed_products<'a>(
+        service_id: Option<&'a str>,
+        include_recalled: bool,
+    ) -> product::BoxedQuery<'a, Pg> {
+        let query = products(service_id);
+
+        if include_recalled {
+            query
+        } else {
+            query.filter(product::recall_status.eq(RecallStatus::Active.as_str()))
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current products.
+    pub fn products<'a>(service_id: Option<&'a str>) -> product::BoxedQuery<'a, Sqlite> {
+        let mut query = product::table
+            .into_boxed()
+            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query
+    }
+
+    /// Selects the current products that are listed, leaving out withdrawn and recalled
+    /// products unless `include_recalled` is set.
+    pub fn listed_products<'a>(
+        service_id: Option<&'a str>,
+        include_recalled: bool,
+    ) -> product::BoxedQuery<'a, Sqlite> {
+        let query = products(service_id);
+
+        if include_recalled {
+            query
+        } else {
+            query.filter(product::recall_status.eq(RecallStatus::Active.as_str()))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::{
+        recall::{RecallStatus, RECALL_MANAGER_ALIAS},
+        store::{DieselConnectionProductStore, Product, ProductBuilder, ProductStore},
+        MAX_COMMIT_NUM,
+    };
+
+    fn product(
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Product, Box<dyn std::error::Error>> {
+        Ok(ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address(format!("address-{}", product_id))
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(1)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .with_service_id(service_id.map(String::from))
+            .build()?)
+    }
+
+    /// Verifies that products are counted with the same service and recall filters they are
+    /// listed with, and that the total of a page of products is the unpaged count.
+    #[test]
+    fn test_count_products() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionProductStore::new(&conn);
+
+        store.add_product(product("00012345678905", None)?)?;
+        store.add_product(product("00012345678912", None)?)?;
+        store.add_product(product("00012345678929", None)?)?;
+        store.add_product(product("00012345678936", Some("service"))?)?;
+        store.set_product_recall_status(
+            "00012345678912",
+            None,
+            RecallStatus::Recalled,
+            &[RECALL_MANAGER_ALIAS.to_string()],
+        )?;
+
+        for (service_id, include_recalled, expected) in [
+            (None, false, 2),
+            (None, true, 3),
+            (Some("service"), false, 1),
+            (Some("other"), true, 0),
+        ] {
+            assert_eq!(
+                store.count_products(service_id, include_recalled)?,
+                expected
+            );
+            assert_eq!(
+                store
+                    .list_products(service_id, include_recalled, 0, 100)?
+                    .data()
+                    .len() as i64,
+                expected
+            );
+
+            let page = store.list_products(service_id, include_recalled, 0, 1)?;
+            assert_eq!(page.data().len() as i64, expected.min(1));
+            assert_eq!(page.paging().total, expected);
+        }
+
+        Ok(())
+    }
+}
@@ -16,7 +16,7 @@ use super::ProductStoreOperations;
 
 use crate::product::{
     store::{
-        diesel::schema::{product, product_property_value},
+        diesel::schema::{product, product_component, product_property_value},
         error::ProductStoreError,
     },
     MAX_COMMIT_NUM,
@@ -41,6 +41,7 @@ impl<'a> DeleteProductOperation for ProductStoreOperations<'a, diesel::pg::PgCon
         self.conn.transaction::<_, ProductStoreError, _>(|| {
             pg::delete_product(self.conn, address, current_commit_num)?;
             pg::delete_product_property_values(self.conn, address, current_commit_num)?;
+            pg::delete_product_components(self.conn, address, current_commit_num)?;
 
             Ok(())
         })
@@ -57,6 +58,7 @@ impl<'a> DeleteProductOperation for ProductStoreOperations<'a, diesel::sqlite::S
         self.conn.transaction::<_, ProductStoreError, _>(|| {
             sqlite::delete_product(self.conn, address, current_commit_num)?;
             sqlite::delete_product_property_values(self.conn, address, current_commit_num)?;
+            sqlite::delete_product_components(self.conn, address, current_commit_num)?;
 
             Ok(())
         })
@@ -98,6 +100,22 @@ mod pg {
             .execute(conn)
             .map(|_| ())
     }
+
+    pub fn delete_product_components(
+        conn: &PgConnection,
+        address: &str,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        update(product_component::table)
+            .filter(
+                product_component::product_address
+                    .eq(address)
+                    .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+            )
+            .set(product_component::end_commit_num.eq(current_commit_num))
+            .execute(conn)
+            .map(|_| ())
+    }
 }
 
 #[cfg(feature = "sqlite")]
@@ -135,4 +153,20 @@ mod sqlite {
             .execute(conn)
             .map(|_| ())
     }
+
+    pub fn delete_product_components(
+        conn: &SqliteConnection,
+        address: &str,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        update(product_component::table)
+            .filter(
+                product_component::product_address
+                    .eq(address)
+                    .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+            )
+            .set(product_component::end_commit_num.eq(current_commit_num))
+            .execute(conn)
+            .map(|_| ())
+    }
 }
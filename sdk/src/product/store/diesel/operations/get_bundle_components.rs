@@ -0,0 +1,166 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_product::GetProductOperation;
+use super::ProductStoreOperations;
+
+use crate::error::InvalidStateError;
+use crate::product::store::{error::ProductStoreError, BundleComponent};
+
+pub(in crate::product) trait GetBundleComponentsOperation {
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError>;
+}
+
+impl<'a, C> GetBundleComponentsOperation for ProductStoreOperations<'a, C>
+where
+    ProductStoreOperations<'a, C>: GetProductOperation,
+{
+    fn get_bundle_components(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<BundleComponent>, ProductStoreError> {
+        let bundle = self.get_product(product_id, service_id)?.ok_or_else(|| {
+            ProductStoreError::NotFoundError(format!("Could not find product: {}", product_id))
+        })?;
+
+        let mut path = vec![product_id.to_string()];
+        resolve_components(self, bundle.components(), service_id, &mut path)
+    }
+}
+
+/// Resolves each component to its product, recursing into components that are themselves
+/// bundles. `path` holds the chain of bundles currently being resolved, so that a component
+/// that refers back to one of them is reported as a cycle rather than followed.
+fn resolve_components<O: GetProductOperation>(
+    ops: &O,
+    component_ids: &[String],
+    service_id: Option<&str>,
+    path: &mut Vec<String>,
+) -> Result<Vec<BundleComponent>, ProductStoreError> {
+    let mut resolved = Vec::with_capacity(component_ids.len());
+
+    for component_id in component_ids {
+        if path.contains(component_id) {
+            return Err(ProductStoreError::InvalidStateError(
+                InvalidStateError::with_message(format!(
+                    "Bundle components form a cycle: {} -> {}",
+                    path.join(" -> "),
+                    component_id
+                )),
+            ));
+        }
+
+        let product = ops.get_product(component_id, service_id)?;
+
+        let components = match &product {
+            Some(product) if !product.components().is_empty() => {
+                path.push(component_id.to_string());
+                let components = resolve_components(ops, product.components(), service_id, path)?;
+                path.pop();
+                components
+            }
+            _ => vec![],
+        };
+
+        resolved.push(BundleComponent {
+            component_id: component_id.to_string(),
+            product,
+            components,
+        });
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, Product, ProductBuilder,
+    };
+    use crate::product::MAX_COMMIT_NUM;
+
+    fn product(product_id: &str, components: &[&str]) -> Product {
+        ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address(format!("address-{}", product_id))
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(1)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .with_components(components.iter().map(|id| id.to_string()).collect())
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that nested bundles are resolved, that a component referencing a product that
+    /// does not exist is flagged, and that bundles containing a product can be listed.
+    #[test]
+    fn test_get_bundle_components() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = ProductStoreOperations::new(&conn);
+        ops.add_product(product("kit", &["box", "missing"]))?;
+        ops.add_product(product("box", &["widget"]))?;
+        ops.add_product(product("widget", &[]))?;
+
+        let components = ops.get_bundle_components("kit", None)?;
+
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0].component_id(), "box");
+        assert!(components[0].product().is_some());
+        assert_eq!(components[0].components().len(), 1);
+        assert_eq!(components[0].components()[0].component_id(), "widget");
+        assert!(components[0].components()[0].components().is_empty());
+        assert_eq!(components[1].component_id(), "missing");
+        assert!(components[1].product().is_none());
+
+        Ok(())
+    }
+
+    /// Verifies that a bundle whose components refer back to it is rejected and that an
+    /// unknown bundle is reported as not found.
+    #[test]
+    fn test_get_bundle_components_cycle() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = ProductStoreOperations::new(&conn);
+        ops.add_product(product("a", &["b"]))?;
+        ops.add_product(product("b", &["a"]))?;
+
+        assert!(matches!(
+            ops.get_bundle_components("a", None),
+            Err(ProductStoreError::InvalidStateError(_))
+        ));
+        assert!(matches!(
+            ops.get_bundle_components("unknown", None),
+            Err(ProductStoreError::NotFoundError(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -15,10 +15,11 @@
 use super::ProductStoreOperations;
 
 use crate::product::{
+    gtin,
     store::{
         diesel::{
             models::{Product as ModelProduct, ProductPropertyValue},
-            schema::{product, product_property_value},
+            schema::{product, product_component, product_property_value},
         },
         error::ProductStoreError,
         Product, PropertyValue,
@@ -43,18 +44,26 @@ impl<'a> GetProductOperation for ProductStoreOperations<'a, diesel::pg::PgConnec
         service_id: Option<&str>,
     ) -> Result<Option<Product>, ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
-            let product = if let Some(product) = pg::get_product(self.conn, product_id, service_id)?
+            // A product may be stored under another representation of the requested GTIN
+            let product = match gtin::product_ids(product_id)
+                .iter()
+                .map(|product_id| pg::get_product(self.conn, product_id, service_id))
+                .find_map(Result::transpose)
+                .transpose()?
             {
-                product
-            } else {
-                return Ok(None);
+                Some(product) => product,
+                None => return Ok(None),
             };
+            let product_id = product.product_id.clone();
 
-            let root_values = pg::get_root_values(self.conn, product_id)?;
+            let root_values = pg::get_root_values(self.conn, &product_id)?;
 
             let values = pg::get_property_values(self.conn, root_values)?;
 
-            Ok(Some(Product::from((product, values))))
+            let mut product = Product::from((product, values));
+            product.components = pg::get_components(self.conn, &product_id, service_id)?;
+
+            Ok(Some(product))
         })
     }
 }
@@ -67,24 +76,32 @@ impl<'a> GetProductOperation for ProductStoreOperations<'a, diesel::sqlite::Sqli
         service_id: Option<&str>,
     ) -> Result<Option<Product>, ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
-            let product =
-                if let Some(product) = sqlite::get_product(self.conn, product_id, service_id)? {
-                    product
-                } else {
-                    return Ok(None);
-                };
+            // A product may be stored under another representation of the requested GTIN
+            let product = match gtin::product_ids(product_id)
+                .iter()
+                .map(|product_id| sqlite::get_product(self.conn, product_id, service_id))
+                .find_map(Result::transpose)
+                .transpose()?
+            {
+                Some(product) => product,
+                None => return Ok(None),
+            };
+            let product_id = product.product_id.clone();
 
-            let root_values = sqlite::get_root_values(self.conn, product_id)?;
+            let root_values = sqlite::get_root_values(self.conn, &product_id)?;
 
             let values = sqlite::get_property_values(self.conn, root_values)?;
 
-            Ok(Some(Product::from((product, values))))
+            let mut product = Product::from((product, values));
+            product.components = sqlite::get_components(self.conn, &product_id, service_id)?;
+
+            Ok(Some(product))
         })
     }
 }
 
 #[cfg(feature = "postgres")]
-mod pg {
+pub(super) mod pg {
     use super::*;
 
     pub fn get_product(
@@ -152,10 +169,33 @@ mod pg {
 
         Ok(definitions)
     }
+
+    pub fn get_components(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = product_component::table
+            .into_boxed()
+            .select(product_component::component_id)
+            .filter(
+                product_component::product_id
+                    .eq(product_id)
+                    .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_component::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_component::service_id.is_null());
+        }
+
+        query.order(product_component::id).load(conn)
+    }
 }
 
 #[cfg(feature = "sqlite")]
-mod sqlite {
+pub(super) mod sqlite {
     use super::*;
 
     pub fn get_product(
@@ -223,4 +263,72 @@ mod sqlite {
 
         Ok(definitions)
     }
+
+    pub fn get_components(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = product_component::table
+            .into_boxed()
+            .select(product_component::component_id)
+            .filter(
+                product_component::product_id
+                    .eq(product_id)
+                    .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_component::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_component::service_id.is_null());
+        }
+
+        query.order(product_component::id).load(conn)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, ProductBuilder,
+    };
+
+    /// Verifies that a product stored under its GTIN-14 is found by its GTIN-12, GTIN-13 and
+    /// GTIN-14, and that its components are returned with it.
+    #[test]
+    fn test_get_product_by_any_gtin_representation() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(
+            ProductBuilder::default()
+                .with_product_id("00012345678905".to_string())
+                .with_product_address("address".to_string())
+                .with_product_namespace("GS1".to_string())
+                .with_owner("owner".to_string())
+                .with_start_commit_number(1)
+                .with_end_commit_number(MAX_COMMIT_NUM)
+                .with_components(vec!["component".to_string()])
+                .build()
+                .expect("Unable to build product"),
+        )?;
+
+        for gtin in &["012345678905", "0012345678905", "00012345678905"] {
+            let product = ops
+                .get_product(gtin, None)?
+                .unwrap_or_else(|| panic!("Product not found by GTIN {}", gtin));
+            assert_eq!(product.product_id(), "00012345678905");
+            assert_eq!(product.components(), &["component".to_string()]);
+        }
+        assert!(ops.get_product("112345678902", None)?.is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,112 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_product::GetProductOperation;
+use super::ProductStoreOperations;
+
+use crate::product::{
+    store::{diesel::schema::product_component, error::ProductStoreError, Product},
+    MAX_COMMIT_NUM,
+};
+use diesel::prelude::*;
+
+pub(in crate::product) trait ListBundlesContainingOperation {
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListBundlesContainingOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut query = product_component::table
+                .into_boxed()
+                .select(product_component::product_id)
+                .distinct()
+                .filter(
+                    product_component::component_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                query = query.filter(product_component::service_id.eq(service_id));
+            } else {
+                query = query.filter(product_component::service_id.is_null());
+            }
+
+            let bundle_ids = query
+                .order(product_component::product_id)
+                .load::<String>(self.conn)?;
+
+            let mut bundles = Vec::with_capacity(bundle_ids.len());
+            for bundle_id in bundle_ids {
+                if let Some(bundle) = self.get_product(&bundle_id, service_id)? {
+                    bundles.push(bundle);
+                }
+            }
+
+            Ok(bundles)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListBundlesContainingOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_bundles_containing(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut query = product_component::table
+                .into_boxed()
+                .select(product_component::product_id)
+                .distinct()
+                .filter(
+                    product_component::component_id
+                        .eq(product_id)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                query = query.filter(product_component::service_id.eq(service_id));
+            } else {
+                query = query.filter(product_component::service_id.is_null());
+            }
+
+            let bundle_ids = query
+                .order(product_component::product_id)
+                .load::<String>(self.conn)?;
+
+            let mut bundles = Vec::with_capacity(bundle_ids.len());
+            for bundle_id in bundle_ids {
+                if let Some(bundle) = self.get_product(&bundle_id, service_id)? {
+                    bundles.push(bundle);
+                }
+            }
+
+            Ok(bundles)
+        })
+    }
+}
@@ -0,0 +1,368 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use super::ProductStoreOperations;
+
+use crate::{
+    pike::store::diesel::schema::pike_organization,
+    product::{
+        store::{
+            diesel::schema::product, error::ProductStoreError, DanglingReference, ReferenceKind,
+        },
+        MAX_COMMIT_NUM,
+    },
+    schema::store::diesel::schema::grid_schema,
+};
+
+use diesel::{dsl::exists, prelude::*, select};
+
+pub(in crate::product) trait ListDanglingReferencesOperation {
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError>;
+}
+
+/// Returns the name of the schema the product smart contract validates products in a namespace
+/// against, as recorded in the `product_namespace` column, if it validates them against one.
+fn namespace_schema_name(product_namespace: &str) -> Option<&'static str> {
+    match product_namespace.to_lowercase().as_str() {
+        "gs1" => Some("gs1_product"),
+        _ => None,
+    }
+}
+
+/// Returns the references of a product to an owner organization or schema that does not exist,
+/// checking each with the given queries.
+pub(super) fn dangling_references(
+    product_id: &str,
+    owner: &str,
+    product_namespace: &str,
+    service_id: Option<&str>,
+    mut organization_exists: impl FnMut(&str) -> QueryResult<bool>,
+    mut schema_exists: impl FnMut(&str) -> QueryResult<bool>,
+) -> QueryResult<Vec<DanglingReference>> {
+    let dangling = |kind, reference: &str| DanglingReference {
+        product_id: product_id.to_string(),
+        kind,
+        reference: reference.to_string(),
+        service_id: service_id.map(String::from),
+    };
+
+    let mut references = vec![];
+    if !organization_exists(owner)? {
+        references.push(dangling(ReferenceKind::Organization, owner));
+    }
+    if let Some(schema_name) = namespace_schema_name(product_namespace) {
+        if !schema_exists(schema_name)? {
+            references.push(dangling(ReferenceKind::Schema, schema_name));
+        }
+    }
+
+    Ok(references)
+}
+
+/// Logs a warning for each dangling reference of a product being written.
+///
+/// Products are written as they are committed on chain, so a dangling reference is flagged
+/// rather than rejected: the referenced entity may simply not have reached the store yet.
+pub(super) fn warn_dangling(references: &[DanglingReference]) {
+    for reference in references {
+        warn!(
+            "Product {} references {} {}, which is not in the store",
+            reference.product_id, reference.kind, reference.reference
+        );
+    }
+}
+
+/// Runs `exists` once for each distinct argument, reusing the answer for repeated arguments.
+fn memoize<'a>(
+    mut exists: impl FnMut(&str) -> QueryResult<bool> + 'a,
+) -> impl FnMut(&str) -> QueryResult<bool> + 'a {
+    let mut answers = HashMap::new();
+    move |arg: &str| match answers.get(arg) {
+        Some(answer) => Ok(*answer),
+        None => {
+            let answer = exists(arg)?;
+            answers.insert(arg.to_string(), answer);
+            Ok(answer)
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListDanglingReferencesOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut organization_exists =
+                memoize(|org_id| pg::organization_exists(self.conn, org_id, service_id));
+            let mut schema_exists = memoize(|name| pg::schema_exists(self.conn, name, service_id));
+
+            let mut references = vec![];
+            for (product_id, owner, product_namespace) in pg::products(self.conn, service_id)? {
+                references.extend(dangling_references(
+                    &product_id,
+                    &owner,
+                    &product_namespace,
+                    service_id,
+                    &mut organization_exists,
+                    &mut schema_exists,
+                )?);
+            }
+
+            Ok(references)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListDanglingReferencesOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_dangling_references(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<DanglingReference>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut organization_exists =
+                memoize(|org_id| sqlite::organization_exists(self.conn, org_id, service_id));
+            let mut schema_exists =
+                memoize(|name| sqlite::schema_exists(self.conn, name, service_id));
+
+            let mut references = vec![];
+            for (product_id, owner, product_namespace) in sqlite::products(self.conn, service_id)? {
+                references.extend(dangling_references(
+                    &product_id,
+                    &owner,
+                    &product_namespace,
+                    service_id,
+                    &mut organization_exists,
+                    &mut schema_exists,
+                )?);
+            }
+
+            Ok(references)
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+
+    /// Returns the ID, owner and namespace of each current product, in product ID order.
+    pub fn products(
+        conn: &PgConnection,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<(String, String, String)>> {
+        let mut query = product::table
+            .into_boxed()
+            .select((
+                product::product_id,
+                product::owner,
+                product::product_namespace,
+            ))
+            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM))
+            .order(product::product_id.asc());
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query.load(conn)
+    }
+
+    pub fn organization_exists(
+        conn: &PgConnection,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<bool> {
+        let mut query = pike_organization::table
+            .into_boxed()
+            .filter(pike_organization::org_id.eq(org_id.to_string()))
+            .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_organization::service_id.eq(service_id.to_string()));
+        } else {
+            query = query.filter(pike_organization::service_id.is_null());
+        }
+
+        select(exists(query)).get_result(conn)
+    }
+
+    pub fn schema_exists(
+        conn: &PgConnection,
+        name: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<bool> {
+        let mut query = grid_schema::table
+            .into_boxed()
+            .filter(grid_schema::name.eq(name.to_string()))
+            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_schema::service_id.eq(service_id.to_string()));
+        } else {
+            query = query.filter(grid_schema::service_id.is_null());
+        }
+
+        select(exists(query)).get_result(conn)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+
+    /// Returns the ID, owner and namespace of each current product, in product ID order.
+    pub fn products(
+        conn: &SqliteConnection,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<(String, String, String)>> {
+        let mut query = product::table
+            .into_boxed()
+            .select((
+                product::product_id,
+                product::owner,
+                product::product_namespace,
+            ))
+            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM))
+            .order(product::product_id.asc());
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query.load(conn)
+    }
+
+    pub fn organization_exists(
+        conn: &SqliteConnection,
+        org_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<bool> {
+        let mut query = pike_organization::table
+            .into_boxed()
+            .filter(pike_organization::org_id.eq(org_id.to_string()))
+            .filter(pike_organization::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(pike_organization::service_id.eq(service_id.to_string()));
+        } else {
+            query = query.filter(pike_organization::service_id.is_null());
+        }
+
+        select(exists(query)).get_result(conn)
+    }
+
+    pub fn schema_exists(
+        conn: &SqliteConnection,
+        name: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<bool> {
+        let mut query = grid_schema::table
+            .into_boxed()
+            .filter(grid_schema::name.eq(name.to_string()))
+            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_schema::service_id.eq(service_id.to_string()));
+        } else {
+            query = query.filter(grid_schema::service_id.is_null());
+        }
+
+        select(exists(query)).get_result(conn)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{DieselConnectionPikeStore, OrganizationBuilder, PikeStore};
+    use crate::product::store::{
+        DanglingReference, DieselConnectionProductStore, ProductBuilder, ProductStore,
+        ReferenceKind,
+    };
+    use crate::product::MAX_COMMIT_NUM;
+
+    fn add_product(store: &dyn ProductStore, product_id: &str, owner: &str) {
+        store
+            .add_product(
+                ProductBuilder::default()
+                    .with_product_id(product_id.to_string())
+                    .with_product_address(format!("address-{}", product_id))
+                    .with_product_namespace("GS1".to_string())
+                    .with_owner(owner.to_string())
+                    .with_start_commit_number(1)
+                    .with_end_commit_number(MAX_COMMIT_NUM)
+                    .build()
+                    .expect("Unable to build product"),
+            )
+            .expect("Unable to add product");
+    }
+
+    /// Verifies that products whose owner organization or namespace schema is not in the store
+    /// are reported, and that products whose references exist are not.
+    #[test]
+    fn test_list_dangling_references() {
+        let conn = SqliteConnection::establish(":memory:").expect("Unable to connect");
+        run_sqlite_migrations(&conn).expect("Unable to run migrations");
+        let store = DieselConnectionProductStore::new(&conn);
+
+        DieselConnectionPikeStore::new(&conn)
+            .add_organization(
+                OrganizationBuilder::default()
+                    .with_org_id("known".to_string())
+                    .with_name("Known".to_string())
+                    .with_start_commit_num(1)
+                    .with_end_commit_num(MAX_COMMIT_NUM)
+                    .build()
+                    .expect("Unable to build organization"),
+            )
+            .expect("Unable to add organization");
+
+        add_product(&store, "00012345678905", "known");
+        add_product(&store, "00012345678912", "unknown");
+
+        let dangling = |product_id: &str, kind, reference: &str| DanglingReference {
+            product_id: product_id.to_string(),
+            kind,
+            reference: reference.to_string(),
+            service_id: None,
+        };
+
+        assert_eq!(
+            store
+                .list_dangling_references(None)
+                .expect("Unable to list dangling references"),
+            vec![
+                dangling("00012345678905", ReferenceKind::Schema, "gs1_product"),
+                dangling("00012345678912", ReferenceKind::Organization, "unknown"),
+                dangling("00012345678912", ReferenceKind::Schema, "gs1_product"),
+            ]
+        );
+    }
+}
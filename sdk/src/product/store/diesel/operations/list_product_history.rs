@@ -0,0 +1,314 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_product;
+use super::ProductStoreOperations;
+
+use crate::product::store::{
+    diesel::{
+        models::{Product as ModelProduct, ProductPropertyValue},
+        schema::{product, product_component, product_property_value},
+    },
+    error::ProductStoreError,
+    Product,
+};
+use diesel::prelude::*;
+
+pub(in crate::product) trait ListProductHistoryOperation {
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListProductHistoryOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            pg::get_versions(self.conn, product_id, service_id)?
+                .into_iter()
+                .map(|version| {
+                    let commit_num = version.start_commit_num;
+                    let root_values =
+                        pg::get_root_values(self.conn, product_id, service_id, commit_num)?;
+                    let values = get_product::pg::get_property_values(self.conn, root_values)?;
+
+                    let mut product = Product::from((version, values));
+                    product.components =
+                        pg::get_components(self.conn, product_id, service_id, commit_num)?;
+
+                    Ok(product)
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListProductHistoryOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_product_history(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            sqlite::get_versions(self.conn, product_id, service_id)?
+                .into_iter()
+                .map(|version| {
+                    let commit_num = version.start_commit_num;
+                    let root_values =
+                        sqlite::get_root_values(self.conn, product_id, service_id, commit_num)?;
+                    let values = get_product::sqlite::get_property_values(self.conn, root_values)?;
+
+                    let mut product = Product::from((version, values));
+                    product.components =
+                        sqlite::get_components(self.conn, product_id, service_id, commit_num)?;
+
+                    Ok(product)
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    use super::*;
+
+    pub fn get_versions(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<ModelProduct>> {
+        let mut query = product::table
+            .into_boxed()
+            .select(product::all_columns)
+            .filter(product::product_id.eq(product_id));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query.order(product::start_commit_num).load(conn)
+    }
+
+    /// Loads the top-level property values of the version written at `commit_num`.
+    pub fn get_root_values(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        commit_num: i64,
+    ) -> QueryResult<Vec<ProductPropertyValue>> {
+        let mut query = product_property_value::table
+            .into_boxed()
+            .select(product_property_value::all_columns)
+            .filter(
+                product_property_value::product_id
+                    .eq(product_id)
+                    .and(product_property_value::parent_property.is_null())
+                    .and(product_property_value::start_commit_num.le(commit_num))
+                    .and(product_property_value::end_commit_num.gt(commit_num)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_property_value::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_property_value::service_id.is_null());
+        }
+
+        query.order(product_property_value::id).load(conn)
+    }
+
+    /// Loads the components of the version written at `commit_num`.
+    pub fn get_components(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        commit_num: i64,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = product_component::table
+            .into_boxed()
+            .select(product_component::component_id)
+            .filter(
+                product_component::product_id
+                    .eq(product_id)
+                    .and(product_component::start_commit_num.le(commit_num))
+                    .and(product_component::end_commit_num.gt(commit_num)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_component::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_component::service_id.is_null());
+        }
+
+        query.order(product_component::id).load(conn)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+
+    pub fn get_versions(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<ModelProduct>> {
+        let mut query = product::table
+            .into_boxed()
+            .select(product::all_columns)
+            .filter(product::product_id.eq(product_id));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        query.order(product::start_commit_num).load(conn)
+    }
+
+    /// Loads the top-level property values of the version written at `commit_num`.
+    pub fn get_root_values(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        commit_num: i64,
+    ) -> QueryResult<Vec<ProductPropertyValue>> {
+        let mut query = product_property_value::table
+            .into_boxed()
+            .select(product_property_value::all_columns)
+            .filter(
+                product_property_value::product_id
+                    .eq(product_id)
+                    .and(product_property_value::parent_property.is_null())
+                    .and(product_property_value::start_commit_num.le(commit_num))
+                    .and(product_property_value::end_commit_num.gt(commit_num)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_property_value::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_property_value::service_id.is_null());
+        }
+
+        query.order(product_property_value::id).load(conn)
+    }
+
+    /// Loads the components of the version written at `commit_num`.
+    pub fn get_components(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        commit_num: i64,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = product_component::table
+            .into_boxed()
+            .select(product_component::component_id)
+            .filter(
+                product_component::product_id
+                    .eq(product_id)
+                    .and(product_component::start_commit_num.le(commit_num))
+                    .and(product_component::end_commit_num.gt(commit_num)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(product_component::service_id.eq(service_id));
+        } else {
+            query = query.filter(product_component::service_id.is_null());
+        }
+
+        query.order(product_component::id).load(conn)
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::{
+        store::{
+            diesel::operations::{
+                add_product::AddProductOperation, delete_product::DeleteProductOperation,
+            },
+            ProductBuilder,
+        },
+        MAX_COMMIT_NUM,
+    };
+
+    fn product(start_commit_num: i64, owner: &str, components: &[&str]) -> Product {
+        ProductBuilder::default()
+            .with_product_id("product".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("GS1".to_string())
+            .with_owner(owner.to_string())
+            .with_start_commit_number(start_commit_num)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .with_components(components.iter().map(|id| id.to_string()).collect())
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that every version of a product is listed in commit order, each with the
+    /// components it had at that commit, including versions replaced by a deletion.
+    #[test]
+    fn test_list_product_history() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(product(1, "owner-1", &["a"]))?;
+        ops.add_product(product(3, "owner-2", &["a", "b"]))?;
+        ops.delete_product("address", 5)?;
+
+        let history = ops.list_product_history("product", None)?;
+
+        assert_eq!(
+            history
+                .iter()
+                .map(|product| (
+                    *product.start_commit_num(),
+                    *product.end_commit_num(),
+                    product.owner().to_string(),
+                    product.components().to_vec()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 3, "owner-1".to_string(), vec!["a".to_string()]),
+                (
+                    3,
+                    5,
+                    "owner-2".to_string(),
+                    vec!["a".to_string(), "b".to_string()]
+                ),
+            ]
+        );
+        assert!(ops.list_product_history("unknown", None)?.is_empty());
+
+        Ok(())
+    }
+}
@@ -12,10 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::count_products::{self, CountProductsOperation};
+use super::get_product;
 use super::ProductStoreOperations;
 
 use crate::{
-    error::InternalError,
     paging::Paging,
     product::{
         store::{
@@ -31,12 +32,12 @@ use crate::{
 };
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::product) trait ListProductsOperation {
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError>;
@@ -47,15 +48,15 @@ impl<'a> ListProductsOperation for ProductStoreOperations<'a, diesel::pg::PgConn
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
-            let db_products = pg::list_products(self.conn, service_id, offset, limit)?;
+            let db_products =
+                pg::list_products(self.conn, service_id, include_recalled, offset, limit)?;
 
-            let total = db_products.len().try_into().map_err(|err| {
-                ProductStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_products(service_id, include_recalled)?;
 
             let mut products = Vec::new();
 
@@ -64,7 +65,12 @@ impl<'a> ListProductsOperation for ProductStoreOperations<'a, diesel::pg::PgConn
 
                 let values = pg::get_property_values(self.conn, root_values)?;
 
-                products.push(Product::from((product, values)));
+                let components =
+                    get_product::pg::get_components(self.conn, &product.product_id, service_id)?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
             }
 
             Ok(ProductList::new(
@@ -80,15 +86,15 @@ impl<'a> ListProductsOperation for ProductStoreOperations<'a, diesel::sqlite::Sq
     fn list_products(
         &self,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> Result<ProductList, ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
-            let db_products = sqlite::list_products(self.conn, service_id, offset, limit)?;
+            let db_products =
+                sqlite::list_products(self.conn, service_id, include_recalled, offset, limit)?;
 
-            let total = db_products.len().try_into().map_err(|err| {
-                ProductStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
+            let total = self.count_products(service_id, include_recalled)?;
 
             let mut products = Vec::new();
 
@@ -97,7 +103,15 @@ impl<'a> ListProductsOperation for ProductStoreOperations<'a, diesel::sqlite::Sq
 
                 let values = sqlite::get_property_values(self.conn, root_values)?;
 
-                products.push(Product::from((product, values)));
+                let components = get_product::sqlite::get_components(
+                    self.conn,
+                    &product.product_id,
+                    service_id,
+                )?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
             }
 
             Ok(ProductList::new(
@@ -109,28 +123,21 @@ impl<'a> ListProductsOperation for ProductStoreOperations<'a, diesel::sqlite::Sq
 }
 
 #[cfg(feature = "postgres")]
-mod pg {
+pub(super) mod pg {
     use super::*;
 
     pub fn list_products(
         conn: &PgConnection,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> QueryResult<Vec<ModelProduct>> {
-        let mut query = product::table
-            .into_boxed()
+        count_products::pg::listed_products(service_id, include_recalled)
             .select(product::all_columns)
             .limit(limit)
             .offset(offset)
-            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(product::service_id.eq(service_id));
-        } else {
-            query = query.filter(product::service_id.is_null());
-        }
-        query.load::<ModelProduct>(conn)
+            .load::<ModelProduct>(conn)
     }
 
     pub fn get_root_values(
@@ -175,28 +182,21 @@ mod pg {
 }
 
 #[cfg(feature = "sqlite")]
-mod sqlite {
+pub(super) mod sqlite {
     use super::*;
 
     pub fn list_products(
         conn: &SqliteConnection,
         service_id: Option<&str>,
+        include_recalled: bool,
         offset: i64,
         limit: i64,
     ) -> QueryResult<Vec<ModelProduct>> {
-        let mut query = product::table
-            .into_boxed()
+        count_products::sqlite::listed_products(service_id, include_recalled)
             .select(product::all_columns)
             .limit(limit)
             .offset(offset)
-            .filter(product::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(product::service_id.eq(service_id));
-        } else {
-            query = query.filter(product::service_id.is_null());
-        }
-        query.load::<ModelProduct>(conn)
+            .load::<ModelProduct>(conn)
     }
 
     pub fn get_root_values(
@@ -0,0 +1,124 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::count_products;
+use super::get_product;
+use super::list_products;
+use super::ProductStoreOperations;
+
+use crate::product::store::{
+    diesel::{models::Product as ModelProduct, schema::product},
+    error::ProductStoreError,
+    Product,
+};
+
+use diesel::prelude::*;
+
+pub(in crate::product) trait ListProductsAfterOperation {
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListProductsAfterOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut query = count_products::pg::products(service_id);
+
+            if let Some(after) = after {
+                query = query.filter(product::product_id.gt(after));
+            }
+
+            let db_products = query
+                .select(product::all_columns)
+                .order(product::product_id.asc())
+                .limit(limit)
+                .load::<ModelProduct>(self.conn)?;
+
+            let mut products = Vec::with_capacity(db_products.len());
+
+            for product in db_products {
+                let root_values =
+                    list_products::pg::get_root_values(self.conn, &product.product_id)?;
+
+                let values = list_products::pg::get_property_values(self.conn, root_values)?;
+
+                let components =
+                    get_product::pg::get_components(self.conn, &product.product_id, service_id)?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
+            }
+
+            Ok(products)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListProductsAfterOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_products_after(
+        &self,
+        service_id: Option<&str>,
+        after: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Product>, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let mut query = count_products::sqlite::products(service_id);
+
+            if let Some(after) = after {
+                query = query.filter(product::product_id.gt(after));
+            }
+
+            let db_products = query
+                .select(product::all_columns)
+                .order(product::product_id.asc())
+                .limit(limit)
+                .load::<ModelProduct>(self.conn)?;
+
+            let mut products = Vec::with_capacity(db_products.len());
+
+            for product in db_products {
+                let root_values =
+                    list_products::sqlite::get_root_values(self.conn, &product.product_id)?;
+
+                let values = list_products::sqlite::get_property_values(self.conn, root_values)?;
+
+                let components = get_product::sqlite::get_components(
+                    self.conn,
+                    &product.product_id,
+                    service_id,
+                )?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
+            }
+
+            Ok(products)
+        })
+    }
+}
@@ -0,0 +1,431 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::count_products;
+use super::get_product;
+use super::list_products;
+use super::ProductStoreOperations;
+
+use crate::{
+    paging::Paging,
+    product::{
+        store::{
+            diesel::{
+                models::Product as ModelProduct,
+                schema::{product, product_property_value},
+            },
+            error::ProductStoreError,
+            Product, ProductList,
+        },
+        MAX_COMMIT_NUM,
+    },
+    schema::store::diesel::schema::grid_property_definition,
+};
+
+use diesel::{dsl::not, prelude::*, sql_types::Bool};
+
+pub(in crate::product) trait ListProductsMissingRequiredOperation {
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError>;
+}
+
+/// Returns the namespace of the products the product smart contract validates against the
+/// schema, as recorded in the `product_namespace` column, if it validates any.
+fn schema_product_namespace(schema_name: &str) -> Option<&'static str> {
+    match schema_name {
+        "gs1_product" => Some("Gs1"),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> ListProductsMissingRequiredOperation
+    for ProductStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let required = pg::required_properties(self.conn, schema_name, service_id)?;
+
+            let namespace = match schema_product_namespace(schema_name) {
+                Some(namespace) if !required.is_empty() => namespace,
+                _ => return Ok(ProductList::new(vec![], Paging::new(offset, limit, 0))),
+            };
+
+            let total = pg::missing_required(service_id, namespace, &required)
+                .count()
+                .get_result(self.conn)?;
+
+            let db_products = pg::missing_required(service_id, namespace, &required)
+                .select(product::all_columns)
+                .order(product::product_id.asc())
+                .limit(limit)
+                .offset(offset)
+                .load::<ModelProduct>(self.conn)?;
+
+            let mut products = Vec::with_capacity(db_products.len());
+
+            for product in db_products {
+                let root_values =
+                    list_products::pg::get_root_values(self.conn, &product.product_id)?;
+
+                let values = list_products::pg::get_property_values(self.conn, root_values)?;
+
+                let components =
+                    get_product::pg::get_components(self.conn, &product.product_id, service_id)?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
+            }
+
+            Ok(ProductList::new(
+                products,
+                Paging::new(offset, limit, total),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListProductsMissingRequiredOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_products_missing_required(
+        &self,
+        schema_name: &str,
+        service_id: Option<&str>,
+        offset: i64,
+        limit: i64,
+    ) -> Result<ProductList, ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let required = sqlite::required_properties(self.conn, schema_name, service_id)?;
+
+            let namespace = match schema_product_namespace(schema_name) {
+                Some(namespace) if !required.is_empty() => namespace,
+                _ => return Ok(ProductList::new(vec![], Paging::new(offset, limit, 0))),
+            };
+
+            let total = sqlite::missing_required(service_id, namespace, &required)
+                .count()
+                .get_result(self.conn)?;
+
+            let db_products = sqlite::missing_required(service_id, namespace, &required)
+                .select(product::all_columns)
+                .order(product::product_id.asc())
+                .limit(limit)
+                .offset(offset)
+                .load::<ModelProduct>(self.conn)?;
+
+            let mut products = Vec::with_capacity(db_products.len());
+
+            for product in db_products {
+                let root_values =
+                    list_products::sqlite::get_root_values(self.conn, &product.product_id)?;
+
+                let values = list_products::sqlite::get_property_values(self.conn, root_values)?;
+
+                let components = get_product::sqlite::get_components(
+                    self.conn,
+                    &product.product_id,
+                    service_id,
+                )?;
+
+                let mut product = Product::from((product, values));
+                product.components = components;
+                products.push(product);
+            }
+
+            Ok(ProductList::new(
+                products,
+                Paging::new(offset, limit, total),
+            ))
+        })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Returns the names of the top-level properties the schema currently requires.
+    pub fn required_properties(
+        conn: &PgConnection,
+        schema_name: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = grid_property_definition::table
+            .into_boxed()
+            .select(grid_property_definition::name)
+            .filter(
+                grid_property_definition::schema_name
+                    .eq(schema_name)
+                    .and(grid_property_definition::required.eq(true))
+                    .and(grid_property_definition::parent_name.is_null())
+                    .and(grid_property_definition::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_property_definition::service_id.eq(service_id));
+        } else {
+            query = query.filter(grid_property_definition::service_id.is_null());
+        }
+
+        query.load(conn)
+    }
+
+    /// Selects the current products in the namespace that lack a current value for any of the
+    /// `required` properties.
+    pub fn missing_required<'a>(
+        service_id: Option<&'a str>,
+        product_namespace: &'a str,
+        required: &'a [String],
+    ) -> product::BoxedQuery<'a, Pg> {
+        let mut missing: Option<Box<dyn BoxableExpression<product::table, Pg, SqlType = Bool>>> =
+            None;
+
+        for property_name in required {
+            let mut with_property = product_property_value::table
+                .into_boxed()
+                .select(product_property_value::product_id)
+                .filter(
+                    product_property_value::property_name
+                        .eq(property_name)
+                        .and(product_property_value::parent_property.is_null())
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                with_property =
+                    with_property.filter(product_property_value::service_id.eq(service_id));
+            } else {
+                with_property = with_property.filter(product_property_value::service_id.is_null());
+            }
+
+            let lacks_property = Box::new(not(product::product_id.eq_any(with_property)));
+
+            missing = Some(match missing {
+                Some(missing) => Box::new(missing.or(lacks_property)),
+                None => lacks_property,
+            });
+        }
+
+        let query = count_products::pg::products(service_id)
+            .filter(product::product_namespace.eq(product_namespace));
+
+        match missing {
+            Some(missing) => query.filter(missing),
+            None => query,
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Returns the names of the top-level properties the schema currently requires.
+    pub fn required_properties(
+        conn: &SqliteConnection,
+        schema_name: &str,
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<String>> {
+        let mut query = grid_property_definition::table
+            .into_boxed()
+            .select(grid_property_definition::name)
+            .filter(
+                grid_property_definition::schema_name
+                    .eq(schema_name)
+                    .and(grid_property_definition::required.eq(true))
+                    .and(grid_property_definition::parent_name.is_null())
+                    .and(grid_property_definition::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_property_definition::service_id.eq(service_id));
+        } else {
+            query = query.filter(grid_property_definition::service_id.is_null());
+        }
+
+        query.load(conn)
+    }
+
+    /// Selects the current products in the namespace that lack a current value for any of the
+    /// `required` properties.
+    pub fn missing_required<'a>(
+        service_id: Option<&'a str>,
+        product_namespace: &'a str,
+        required: &'a [String],
+    ) -> product::BoxedQuery<'a, Sqlite> {
+        let mut missing: Option<
+            Box<dyn BoxableExpression<product::table, Sqlite, SqlType = Bool>>,
+        > = None;
+
+        for property_name in required {
+            let mut with_property = product_property_value::table
+                .into_boxed()
+                .select(product_property_value::product_id)
+                .filter(
+                    product_property_value::property_name
+                        .eq(property_name)
+                        .and(product_property_value::parent_property.is_null())
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                with_property =
+                    with_property.filter(product_property_value::service_id.eq(service_id));
+            } else {
+                with_property = with_property.filter(product_property_value::service_id.is_null());
+            }
+
+            let lacks_property = Box::new(not(product::product_id.eq_any(with_property)));
+
+            missing = Some(match missing {
+                Some(missing) => Box::new(missing.or(lacks_property)),
+                None => lacks_property,
+            });
+        }
+
+        let query = count_products::sqlite::products(service_id)
+            .filter(product::product_namespace.eq(product_namespace));
+
+        match missing {
+            Some(missing) => query.filter(missing),
+            None => query,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, ProductBuilder, PropertyValueBuilder,
+    };
+    use crate::schema::store::{
+        DieselConnectionSchemaStore, PropertyDefinition, Schema, SchemaStore,
+    };
+
+    fn property_definition(name: &str, required: bool) -> PropertyDefinition {
+        PropertyDefinition {
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            name: name.to_string(),
+            schema_name: "gs1_product".to_string(),
+            data_type: "STRING".to_string(),
+            required,
+            description: String::new(),
+            access_level: 0,
+            number_exponent: 0,
+            enum_options: vec![],
+            struct_properties: vec![],
+            service_id: None,
+        }
+    }
+
+    fn product(product_id: &str, property_names: &[&str]) -> Product {
+        let properties = property_names
+            .iter()
+            .map(|name| {
+                PropertyValueBuilder::default()
+                    .with_product_id(product_id.to_string())
+                    .with_product_address(format!("address-{}", product_id))
+                    .with_property_name(name.to_string())
+                    .with_data_type("STRING".to_string())
+                    .with_string_value(Some("value".to_string()))
+                    .with_start_commit_number(1)
+                    .with_end_commit_number(MAX_COMMIT_NUM)
+                    .build()
+                    .expect("Unable to build property value")
+            })
+            .collect();
+
+        ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address(format!("address-{}", product_id))
+            .with_product_namespace("Gs1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(1)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .with_properties(properties)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that only the products lacking a property the schema requires are listed, in
+    /// product ID order and a page at a time, and that for other schemas none are.
+    #[test]
+    fn test_list_products_missing_required() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        DieselConnectionSchemaStore::new(&conn).add_schema(Schema {
+            name: "gs1_product".to_string(),
+            description: String::new(),
+            owner: "owner".to_string(),
+            properties: vec![
+                property_definition("brand", true),
+                property_definition("color", true),
+                property_definition("size", false),
+            ],
+            service_id: None,
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            last_updated: None,
+        })?;
+
+        let ops = ProductStoreOperations::new(&conn);
+        ops.add_product(product("complete", &["brand", "color"]))?;
+        ops.add_product(product("no-color", &["brand", "size"]))?;
+        ops.add_product(product("empty", &[]))?;
+        ops.add_product(product("no-brand", &["color"]))?;
+
+        let ids = |list: &ProductList| {
+            list.data()
+                .iter()
+                .map(|product| product.product_id().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let page = ops.list_products_missing_required("gs1_product", None, 0, 2)?;
+        assert_eq!(ids(&page), vec!["empty", "no-brand"]);
+        assert_eq!(page.paging().total, 3);
+
+        let page = ops.list_products_missing_required("gs1_product", None, 2, 2)?;
+        assert_eq!(ids(&page), vec!["no-color"]);
+
+        let page = ops.list_products_missing_required("gs1_location", None, 0, 10)?;
+        assert!(page.data().is_empty());
+
+        Ok(())
+    }
+}
@@ -13,10 +13,19 @@
 // limitations under the License.
 
 pub(super) mod add_product;
+pub(super) mod count_products;
 pub(super) mod delete_product;
+pub(super) mod get_bundle_components;
 pub(super) mod get_product;
+pub(super) mod list_bundles_containing;
+pub(super) mod list_dangling_references;
+pub(super) mod list_product_history;
 pub(super) mod list_products;
+pub(super) mod list_products_after;
+pub(super) mod list_products_missing_required;
+pub(super) mod set_product_recall_status;
 pub(super) mod update_product;
+pub(super) mod upsert_products;
 
 pub(super) struct ProductStoreOperations<'a, C> {
     conn: &'a C,
@@ -0,0 +1,233 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_product;
+use super::ProductStoreOperations;
+
+use crate::error::InvalidStateError;
+use crate::product::{
+    recall::{check_recall_transition, RecallStatus},
+    store::{
+        diesel::{models::Product as ModelProduct, schema::product},
+        error::ProductStoreError,
+    },
+};
+use diesel::{dsl::update, prelude::*};
+
+pub(in crate::product) trait SetProductRecallStatusOperation {
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError>;
+}
+
+/// Checks that the holder of the permission aliases may move the current version of the product
+/// to the recall status, returning the ID of its row.
+fn check_transition(
+    product_id: &str,
+    product: Option<ModelProduct>,
+    recall_status: RecallStatus,
+    permission_aliases: &[String],
+) -> Result<i64, ProductStoreError> {
+    let product = product.ok_or_else(|| {
+        ProductStoreError::NotFoundError(format!("Could not find product {}", product_id))
+    })?;
+
+    let current_status = product.recall_status.parse().map_err(|_| {
+        ProductStoreError::InvalidStateError(InvalidStateError::with_message(format!(
+            "Product {} has an unrecognized recall status '{}'",
+            product_id, product.recall_status
+        )))
+    })?;
+
+    check_recall_transition(current_status, recall_status, permission_aliases)
+        .map_err(ProductStoreError::InvalidStateError)?;
+
+    Ok(product.id)
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> SetProductRecallStatusOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let id = check_transition(
+                product_id,
+                get_product::pg::get_product(self.conn, product_id, service_id)?,
+                recall_status,
+                permission_aliases,
+            )?;
+
+            update(product::table.find(id))
+                .set(product::recall_status.eq(recall_status.as_str()))
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> SetProductRecallStatusOperation
+    for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn set_product_recall_status(
+        &self,
+        product_id: &str,
+        service_id: Option<&str>,
+        recall_status: RecallStatus,
+        permission_aliases: &[String],
+    ) -> Result<(), ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            let id = check_transition(
+                product_id,
+                get_product::sqlite::get_product(self.conn, product_id, service_id)?,
+                recall_status,
+                permission_aliases,
+            )?;
+
+            update(product::table.find(id))
+                .set(product::recall_status.eq(recall_status.as_str()))
+                .execute(self.conn)?;
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::{
+        recall::RECALL_MANAGER_ALIAS,
+        store::{
+            diesel::operations::{
+                add_product::AddProductOperation, get_product::GetProductOperation,
+            },
+            Product, ProductBuilder,
+        },
+        MAX_COMMIT_NUM,
+    };
+
+    fn product(start_commit_num: i64) -> Product {
+        ProductBuilder::default()
+            .with_product_id("product".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(start_commit_num)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that a product's recall status follows the recall workflow for the holder of the
+    /// recall manager alias, and that it is kept by later versions of the product.
+    #[test]
+    fn test_set_product_recall_status() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+        let manager = vec![RECALL_MANAGER_ALIAS.to_string()];
+
+        ops.add_product(product(1))?;
+        ops.set_product_recall_status("product", None, RecallStatus::Recalled, &manager)?;
+        ops.add_product(product(2))?;
+
+        let recall_status = |ops: &ProductStoreOperations<SqliteConnection>| {
+            ops.get_product("product", None)
+                .map(|product| product.map(|product| product.recall_status()))
+        };
+        assert_eq!(recall_status(&ops)?, Some(RecallStatus::Recalled));
+
+        assert!(matches!(
+            ops.set_product_recall_status("product", None, RecallStatus::Withdrawn, &manager),
+            Err(ProductStoreError::InvalidStateError(_))
+        ));
+        assert!(matches!(
+            ops.set_product_recall_status("unknown", None, RecallStatus::Recalled, &manager),
+            Err(ProductStoreError::NotFoundError(_))
+        ));
+
+        ops.set_product_recall_status("product", None, RecallStatus::Active, &manager)?;
+        assert_eq!(recall_status(&ops)?, Some(RecallStatus::Active));
+
+        Ok(())
+    }
+
+    /// Verifies that the recall status cannot be changed without the recall manager alias.
+    #[test]
+    fn test_set_product_recall_status_requires_alias() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(product(1))?;
+
+        assert!(matches!(
+            ops.set_product_recall_status(
+                "product",
+                None,
+                RecallStatus::Recalled,
+                &["product::other-alias".to_string()]
+            ),
+            Err(ProductStoreError::InvalidStateError(_))
+        ));
+        assert_eq!(
+            ops.get_product("product", None)?
+                .map(|product| product.recall_status()),
+            Some(RecallStatus::Active)
+        );
+
+        Ok(())
+    }
+
+    /// Verifies that a stored recall status that is not recognized is reported as an error,
+    /// rather than treated as active.
+    #[test]
+    fn test_set_product_recall_status_unrecognized() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(product(1))?;
+        update(product::table)
+            .set(product::recall_status.eq("lost"))
+            .execute(&conn)?;
+
+        assert!(matches!(
+            ops.set_product_recall_status(
+                "product",
+                None,
+                RecallStatus::Recalled,
+                &[RECALL_MANAGER_ALIAS.to_string()]
+            ),
+            Err(ProductStoreError::InvalidStateError(_))
+        ));
+
+        Ok(())
+    }
+}
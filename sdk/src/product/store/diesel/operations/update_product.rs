@@ -12,10 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::add_product;
+use super::get_product;
 use super::ProductStoreOperations;
 
 use crate::product::{
-    store::{diesel::schema::product_property_value, error::ProductStoreError},
+    store::{
+        diesel::{
+            models::Product as ModelProduct,
+            schema::{product, product_property_value},
+        },
+        error::ProductStoreError,
+    },
     MAX_COMMIT_NUM,
 };
 use diesel::{dsl::update, prelude::*};
@@ -25,25 +33,65 @@ pub(in crate::product) trait UpdateProductOperation {
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError>;
 }
 
+/// Returns the error for an update whose expected version of the product is not current.
+fn expected_commit_num_error(
+    product_id: &str,
+    product: Option<ModelProduct>,
+    expected_commit_num: i64,
+) -> ProductStoreError {
+    match product {
+        Some(product) => ProductStoreError::ConflictError(format!(
+            "Product {} was updated at commit {}, after the expected commit {}",
+            product_id, product.start_commit_num, expected_commit_num
+        )),
+        None => ProductStoreError::NotFoundError(format!("Could not find product {}", product_id)),
+    }
+}
+
 #[cfg(feature = "postgres")]
 impl<'a> UpdateProductOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
     fn update_product(
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
+            if let Some(expected_commit_num) = expected_commit_num {
+                let claimed = pg::claim_current_product(
+                    self.conn,
+                    product_id,
+                    service_id,
+                    expected_commit_num,
+                )?;
+
+                if claimed == 0 {
+                    return Err(expected_commit_num_error(
+                        product_id,
+                        get_product::pg::get_product(self.conn, product_id, service_id)?,
+                        expected_commit_num,
+                    ));
+                }
+            }
+
             pg::update_product_property_values(
                 self.conn,
                 product_id,
                 service_id,
                 current_commit_num,
             )?;
+            add_product::pg::end_product_components(
+                self.conn,
+                product_id,
+                service_id,
+                current_commit_num,
+            )?;
 
             Ok(())
         })
@@ -56,15 +104,39 @@ impl<'a> UpdateProductOperation for ProductStoreOperations<'a, diesel::sqlite::S
         &self,
         product_id: &str,
         service_id: Option<&str>,
+        expected_commit_num: Option<i64>,
         current_commit_num: i64,
     ) -> Result<(), ProductStoreError> {
         self.conn.transaction::<_, ProductStoreError, _>(|| {
+            if let Some(expected_commit_num) = expected_commit_num {
+                let claimed = sqlite::claim_current_product(
+                    self.conn,
+                    product_id,
+                    service_id,
+                    expected_commit_num,
+                )?;
+
+                if claimed == 0 {
+                    return Err(expected_commit_num_error(
+                        product_id,
+                        get_product::sqlite::get_product(self.conn, product_id, service_id)?,
+                        expected_commit_num,
+                    ));
+                }
+            }
+
             sqlite::update_product_property_values(
                 self.conn,
                 product_id,
                 service_id,
                 current_commit_num,
             )?;
+            add_product::sqlite::end_product_components(
+                self.conn,
+                product_id,
+                service_id,
+                current_commit_num,
+            )?;
 
             Ok(())
         })
@@ -75,6 +147,36 @@ impl<'a> UpdateProductOperation for ProductStoreOperations<'a, diesel::sqlite::S
 mod pg {
     use super::*;
 
+    /// Rewrites the current version of the product in place if it was written at or before
+    /// `expected_commit_num`, returning the number of rows written. The written row stays
+    /// locked until the transaction ends, so a concurrent update of the same version waits for
+    /// it and then finds the version superseded.
+    pub fn claim_current_product(
+        conn: &PgConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        expected_commit_num: i64,
+    ) -> QueryResult<usize> {
+        let update = update(product::table).filter(
+            product::product_id
+                .eq(product_id)
+                .and(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .and(product::start_commit_num.le(expected_commit_num)),
+        );
+
+        if let Some(service_id) = service_id {
+            update
+                .filter(product::service_id.eq(service_id))
+                .set(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .execute(conn)
+        } else {
+            update
+                .filter(product::service_id.is_null())
+                .set(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .execute(conn)
+        }
+    }
+
     pub fn update_product_property_values(
         conn: &PgConnection,
         product_id: &str,
@@ -112,6 +214,35 @@ mod pg {
 mod sqlite {
     use super::*;
 
+    /// Rewrites the current version of the product in place if it was written at or before
+    /// `expected_commit_num`, returning the number of rows written. The write holds the
+    /// database's write lock until the transaction ends.
+    pub fn claim_current_product(
+        conn: &SqliteConnection,
+        product_id: &str,
+        service_id: Option<&str>,
+        expected_commit_num: i64,
+    ) -> QueryResult<usize> {
+        let update = update(product::table).filter(
+            product::product_id
+                .eq(product_id)
+                .and(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .and(product::start_commit_num.le(expected_commit_num)),
+        );
+
+        if let Some(service_id) = service_id {
+            update
+                .filter(product::service_id.eq(service_id))
+                .set(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .execute(conn)
+        } else {
+            update
+                .filter(product::service_id.is_null())
+                .set(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                .execute(conn)
+        }
+    }
+
     pub fn update_product_property_values(
         conn: &SqliteConnection,
         product_id: &str,
@@ -144,3 +275,152 @@ mod sqlite {
         }
     }
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, Product, ProductBuilder,
+    };
+
+    fn product(start_commit_num: i64) -> Product {
+        ProductBuilder::default()
+            .with_product_id("product".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(start_commit_num)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that of two writers updating the same version of a product, the second is
+    /// rejected with a conflict, and may retry against the current version.
+    #[test]
+    fn test_update_product_conflict() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        let ops = ProductStoreOperations::new(&conn);
+        ops.add_product(product(1))?;
+
+        // Both writers read the version written at commit 1; the first updates it at commit 2
+        ops.update_product("product", None, Some(1), 2)?;
+        ops.add_product(product(2))?;
+
+        assert!(matches!(
+            ops.update_product("product", None, Some(1), 3),
+            Err(ProductStoreError::ConflictError(_))
+        ));
+
+        ops.update_product("product", None, Some(2), 3)?;
+
+        assert!(matches!(
+            ops.update_product("unknown", None, Some(1), 3),
+            Err(ProductStoreError::NotFoundError(_))
+        ));
+
+        Ok(())
+    }
+}
+
+/// Runs against the database at `GRID_TEST_POSTGRES_URL`, so it is ignored by default; run it
+/// with `cargo test --features postgres -- --ignored test_update_product_conflict_postgres`.
+#[cfg(all(test, feature = "postgres"))]
+mod postgres_tests {
+    use super::*;
+
+    use std::error::Error;
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use diesel::{dsl::sql, sql_types::Bool, Connection, PgConnection};
+
+    use crate::migrations::run_postgres_migrations;
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, Product, ProductBuilder,
+    };
+
+    fn product(product_id: &str, start_commit_num: i64) -> Product {
+        ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(start_commit_num)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    fn connect() -> Result<PgConnection, Box<dyn Error>> {
+        Ok(PgConnection::establish(&std::env::var(
+            "GRID_TEST_POSTGRES_URL",
+        )?)?)
+    }
+
+    /// Verifies that of two transactions updating the same version of a product at once, the
+    /// one that writes second waits for the first to commit and is then rejected with a
+    /// conflict.
+    #[test]
+    #[ignore]
+    fn test_update_product_conflict_postgres() -> Result<(), Box<dyn Error>> {
+        let conn = connect()?;
+        run_postgres_migrations(&conn)?;
+        let monitor = connect()?;
+
+        let product_id = format!(
+            "product-{}",
+            SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos()
+        );
+        let ops = ProductStoreOperations::new(&conn);
+        ops.add_product(product(&product_id, 1))?;
+
+        let second = conn.transaction::<_, Box<dyn Error>, _>(|| {
+            // The first writer updates the version written at commit 1 and holds it
+            ops.update_product(&product_id, None, Some(1), 2)?;
+            ops.add_product(product(&product_id, 2))?;
+
+            // The second writer read the same version, and updates it before the first commits
+            let second = {
+                let product_id = product_id.clone();
+                thread::spawn(move || -> Result<(), String> {
+                    let conn = connect().map_err(|err| err.to_string())?;
+                    let ops = ProductStoreOperations::new(&conn);
+                    conn.transaction(|| ops.update_product(&product_id, None, Some(1), 3))
+                        .map_err(|err| match err {
+                            ProductStoreError::ConflictError(_) => "conflict".to_string(),
+                            err => err.to_string(),
+                        })
+                })
+            };
+
+            let mut waits = 0;
+            while !diesel::select(sql::<Bool>(
+                "EXISTS (SELECT 1 FROM pg_stat_activity \
+                 WHERE datname = current_database() AND wait_event_type = 'Lock')",
+            ))
+            .get_result::<bool>(&monitor)?
+            {
+                waits += 1;
+                assert!(waits < 100, "The second writer never waited for the first");
+                thread::sleep(Duration::from_millis(50));
+            }
+
+            Ok(second)
+        })?;
+
+        assert_eq!(
+            second.join().expect("The second writer panicked"),
+            Err("conflict".to_string())
+        );
+
+        Ok(())
+    }
+}
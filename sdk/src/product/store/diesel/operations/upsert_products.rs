@@ -0,0 +1,661 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use super::ProductStoreOperations;
+
+use crate::product::{
+    store::{
+        diesel::{
+            models::{make_components, NewProduct, NewProductComponent, NewProductPropertyValue},
+            schema::{product, product_component, product_property_value},
+        },
+        error::ProductStoreError,
+        Product,
+    },
+    MAX_COMMIT_NUM,
+};
+
+use diesel::{
+    dsl::{insert_into, update},
+    prelude::*,
+};
+
+/// The most rows written, or product IDs matched, by one statement. This keeps the bind
+/// parameters of a statement well within the limits of both PostgreSQL and SQLite.
+const CHUNK_SIZE: usize = 500;
+
+pub(in crate::product) trait UpsertProductsOperation {
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> UpsertProductsOperation for ProductStoreOperations<'a, diesel::pg::PgConnection> {
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            for run in runs(products) {
+                pg::upsert_run(self.conn, run)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> UpsertProductsOperation for ProductStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn upsert_products(&self, products: &[Product]) -> Result<(), ProductStoreError> {
+        self.conn.transaction::<_, ProductStoreError, _>(|| {
+            for run in runs(products) {
+                sqlite::upsert_run(self.conn, run)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Splits the products into runs in which no product appears twice, so that each run can be
+/// written with one set of statements while a later version of a product still supersedes an
+/// earlier one.
+fn runs(products: &[Product]) -> Vec<&[Product]> {
+    let mut runs = vec![];
+    let mut start = 0;
+    let mut seen = HashSet::new();
+
+    for (i, product) in products.iter().enumerate() {
+        if !seen.insert((&product.product_id, &product.service_id)) {
+            runs.push(&products[start..i]);
+            start = i;
+            seen.clear();
+            seen.insert((&product.product_id, &product.service_id));
+        }
+    }
+    if start < products.len() {
+        runs.push(&products[start..]);
+    }
+
+    runs
+}
+
+/// Groups the IDs of a run's products by the service ID and start commit number their current
+/// versions are ended with.
+fn by_version(run: &[Product]) -> BTreeMap<(Option<&str>, i64), Vec<&str>> {
+    let mut versions: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for product in run {
+        versions
+            .entry((product.service_id.as_deref(), product.start_commit_num))
+            .or_default()
+            .push(product.product_id.as_str());
+    }
+
+    versions
+}
+
+/// Makes the rows of a run's products. As in `add_product`, a new version keeps the recall
+/// status of the version it replaces, which `recall_statuses` holds by product and service ID.
+fn models(
+    run: &[Product],
+    mut recall_statuses: HashMap<(String, Option<String>), String>,
+) -> (
+    Vec<NewProduct>,
+    Vec<NewProductPropertyValue>,
+    Vec<NewProductComponent>,
+) {
+    let mut products = Vec::with_capacity(run.len());
+    let mut property_values = vec![];
+    let mut components = vec![];
+
+    for product in run {
+        components.extend(make_components(product));
+        let (mut product_model, property_models): (NewProduct, _) = product.clone().into();
+        if let Some(recall_status) = recall_statuses.remove(&(
+            product_model.product_id.clone(),
+            product_model.service_id.clone(),
+        )) {
+            product_model.recall_status = recall_status;
+        }
+        products.push(product_model);
+        property_values.extend(property_models);
+    }
+
+    (products, property_values, components)
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+
+    pub fn upsert_run(conn: &PgConnection, run: &[Product]) -> QueryResult<()> {
+        let mut recall_statuses = HashMap::new();
+        for ((service_id, start_commit_num), product_ids) in by_version(run) {
+            for product_ids in product_ids.chunks(CHUNK_SIZE) {
+                recall_statuses.extend(current_recall_statuses(conn, product_ids, service_id)?);
+                end_current_versions(conn, product_ids, service_id, start_commit_num)?;
+            }
+        }
+
+        let (products, property_values, components) = models(run, recall_statuses);
+        for chunk in products.chunks(CHUNK_SIZE) {
+            insert_into(product::table).values(chunk).execute(conn)?;
+        }
+        for chunk in property_values.chunks(CHUNK_SIZE) {
+            insert_into(product_property_value::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+        for chunk in components.chunks(CHUNK_SIZE) {
+            insert_into(product_component::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn current_recall_statuses(
+        conn: &PgConnection,
+        product_ids: &[&str],
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<((String, Option<String>), String)>> {
+        let mut query = product::table
+            .select((product::product_id, product::recall_status))
+            .filter(
+                product::product_id
+                    .eq_any(product_ids)
+                    .and(product::end_commit_num.eq(MAX_COMMIT_NUM)),
+            )
+            .into_boxed();
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        Ok(query
+            .load::<(String, String)>(conn)?
+            .into_iter()
+            .map(|(product_id, recall_status)| {
+                ((product_id, service_id.map(String::from)), recall_status)
+            })
+            .collect())
+    }
+
+    fn end_current_versions(
+        conn: &PgConnection,
+        product_ids: &[&str],
+        service_id: Option<&str>,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        if let Some(service_id) = service_id {
+            update(product::table)
+                .filter(
+                    product::product_id
+                        .eq_any(product_ids)
+                        .and(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product::service_id.eq(service_id)),
+                )
+                .set(product::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_property_value::table)
+                .filter(
+                    product_property_value::product_id
+                        .eq_any(product_ids)
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_property_value::service_id.eq(service_id)),
+                )
+                .set(product_property_value::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_component::table)
+                .filter(
+                    product_component::product_id
+                        .eq_any(product_ids)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_component::service_id.eq(service_id)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+        } else {
+            update(product::table)
+                .filter(
+                    product::product_id
+                        .eq_any(product_ids)
+                        .and(product::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_property_value::table)
+                .filter(
+                    product_property_value::product_id
+                        .eq_any(product_ids)
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_property_value::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_component::table)
+                .filter(
+                    product_component::product_id
+                        .eq_any(product_ids)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+
+    pub fn upsert_run(conn: &SqliteConnection, run: &[Product]) -> QueryResult<()> {
+        let mut recall_statuses = HashMap::new();
+        for ((service_id, start_commit_num), product_ids) in by_version(run) {
+            for product_ids in product_ids.chunks(CHUNK_SIZE) {
+                recall_statuses.extend(current_recall_statuses(conn, product_ids, service_id)?);
+                end_current_versions(conn, product_ids, service_id, start_commit_num)?;
+            }
+        }
+
+        let (products, property_values, components) = models(run, recall_statuses);
+        for chunk in products.chunks(CHUNK_SIZE) {
+            insert_into(product::table).values(chunk).execute(conn)?;
+        }
+        for chunk in property_values.chunks(CHUNK_SIZE) {
+            insert_into(product_property_value::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+        for chunk in components.chunks(CHUNK_SIZE) {
+            insert_into(product_component::table)
+                .values(chunk)
+                .execute(conn)?;
+        }
+
+        Ok(())
+    }
+
+    fn current_recall_statuses(
+        conn: &SqliteConnection,
+        product_ids: &[&str],
+        service_id: Option<&str>,
+    ) -> QueryResult<Vec<((String, Option<String>), String)>> {
+        let mut query = product::table
+            .select((product::product_id, product::recall_status))
+            .filter(
+                product::product_id
+                    .eq_any(product_ids)
+                    .and(product::end_commit_num.eq(MAX_COMMIT_NUM)),
+            )
+            .into_boxed();
+        if let Some(service_id) = service_id {
+            query = query.filter(product::service_id.eq(service_id));
+        } else {
+            query = query.filter(product::service_id.is_null());
+        }
+
+        Ok(query
+            .load::<(String, String)>(conn)?
+            .into_iter()
+            .map(|(product_id, recall_status)| {
+                ((product_id, service_id.map(String::from)), recall_status)
+            })
+            .collect())
+    }
+
+    fn end_current_versions(
+        conn: &SqliteConnection,
+        product_ids: &[&str],
+        service_id: Option<&str>,
+        current_commit_num: i64,
+    ) -> QueryResult<()> {
+        if let Some(service_id) = service_id {
+            update(product::table)
+                .filter(
+                    product::product_id
+                        .eq_any(product_ids)
+                        .and(product::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product::service_id.eq(service_id)),
+                )
+                .set(product::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_property_value::table)
+                .filter(
+                    product_property_value::product_id
+                        .eq_any(product_ids)
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_property_value::service_id.eq(service_id)),
+                )
+                .set(product_property_value::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_component::table)
+                .filter(
+                    product_component::product_id
+                        .eq_any(product_ids)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM))
+                        .and(product_component::service_id.eq(service_id)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+        } else {
+            update(product::table)
+                .filter(
+                    product::product_id
+                        .eq_any(product_ids)
+                        .and(product::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_property_value::table)
+                .filter(
+                    product_property_value::product_id
+                        .eq_any(product_ids)
+                        .and(product_property_value::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_property_value::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+            update(product_component::table)
+                .filter(
+                    product_component::product_id
+                        .eq_any(product_ids)
+                        .and(product_component::end_commit_num.eq(MAX_COMMIT_NUM)),
+                )
+                .set(product_component::end_commit_num.eq(current_commit_num))
+                .execute(conn)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::{
+        recall::{RecallStatus, RECALL_MANAGER_ALIAS},
+        store::{
+            diesel::operations::{
+                add_product::AddProductOperation, get_product::GetProductOperation,
+                list_product_history::ListProductHistoryOperation,
+                set_product_recall_status::SetProductRecallStatusOperation,
+            },
+            ProductBuilder,
+        },
+    };
+
+    fn product(product_id: &str, owner: &str, start_commit_num: i64) -> Product {
+        ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address(format!("address-{}", product_id))
+            .with_product_namespace("GS1".to_string())
+            .with_owner(owner.to_string())
+            .with_start_commit_number(start_commit_num)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that upserted products supersede their current versions, keeping their recall
+    /// status, and that a product given twice ends with the later version current.
+    #[test]
+    fn test_upsert_products() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(product("a", "org-1", 1))?;
+        ops.set_product_recall_status(
+            "a",
+            None,
+            RecallStatus::Withdrawn,
+            &[RECALL_MANAGER_ALIAS.to_string()],
+        )?;
+
+        ops.upsert_products(&[
+            product("a", "org-2", 2),
+            product("b", "org-1", 2),
+            product("b", "org-2", 3),
+        ])?;
+
+        let a = ops.get_product("a", None)?.expect("Product a not found");
+        assert_eq!(a.owner(), "org-2");
+        assert_eq!(a.recall_status(), RecallStatus::Withdrawn);
+
+        let b = ops.get_product("b", None)?.expect("Product b not found");
+        assert_eq!(b.owner(), "org-2");
+        assert_eq!(*b.start_commit_num(), 3);
+
+        let history = ops.list_product_history("b", None)?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history
+                .iter()
+                .filter(|version| *version.end_commit_num() == MAX_COMMIT_NUM)
+                .count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    /// Verifies that an upsert without a service ID does not take the recall status of a
+    /// product with the same ID in a service.
+    #[test]
+    fn test_upsert_products_without_service_id() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        ops.add_product(
+            ProductBuilder::default()
+                .with_product_id("a".to_string())
+                .with_product_address("address-a".to_string())
+                .with_product_namespace("GS1".to_string())
+                .with_owner("org-1".to_string())
+                .with_start_commit_number(1)
+                .with_end_commit_number(MAX_COMMIT_NUM)
+                .with_service_id(Some("service".to_string()))
+                .build()?,
+        )?;
+        ops.set_product_recall_status(
+            "a",
+            Some("service"),
+            RecallStatus::Recalled,
+            &[RECALL_MANAGER_ALIAS.to_string()],
+        )?;
+
+        ops.upsert_products(&[product("a", "org-1", 2)])?;
+
+        let a = ops.get_product("a", None)?.expect("Product a not found");
+        assert_eq!(a.recall_status(), RecallStatus::Active);
+
+        Ok(())
+    }
+
+    /// Verifies that a large number of products is written in chunks without exceeding the
+    /// database's limit on bind parameters.
+    #[test]
+    fn test_upsert_products_in_chunks() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        let products = (0..CHUNK_SIZE * 2 + 1)
+            .map(|i| product(&format!("product-{}", i), "org", 1))
+            .collect::<Vec<_>>();
+        ops.upsert_products(&products)?;
+
+        assert!(ops.get_product("product-0", None)?.is_some());
+        assert!(ops
+            .get_product(&format!("product-{}", CHUNK_SIZE * 2), None)?
+            .is_some());
+
+        Ok(())
+    }
+}
+
+/// Compares writing products one at a time with `add_product` against writing them with
+/// `upsert_products`, each inside one transaction as the commit handler writes them. The
+/// comparisons take seconds, so they are ignored by default; run them with
+/// `cargo test --release -- --ignored --nocapture test_upsert_throughput`. The postgres
+/// comparison migrates and writes to the database at `GRID_TEST_POSTGRES_URL`.
+#[cfg(all(test, any(feature = "sqlite", feature = "postgres")))]
+mod throughput {
+    use super::*;
+
+    use std::error::Error;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    use diesel::Connection;
+
+    use crate::product::store::{
+        diesel::operations::add_product::AddProductOperation, ProductBuilder, PropertyValueBuilder,
+    };
+
+    const PRODUCT_COUNT: usize = 1000;
+
+    fn products(service_id: &str, start_commit_num: i64) -> Result<Vec<Product>, Box<dyn Error>> {
+        (0..PRODUCT_COUNT)
+            .map(|i| {
+                let product_id = format!("{:014}", i);
+                let properties = ["name", "brand", "size"]
+                    .iter()
+                    .map(|name| {
+                        PropertyValueBuilder::default()
+                            .with_product_id(product_id.clone())
+                            .with_product_address(format!("address-{}", product_id))
+                            .with_property_name(name.to_string())
+                            .with_data_type("STRING".to_string())
+                            .with_string_value(Some(format!("{} {}", name, start_commit_num)))
+                            .with_start_commit_number(start_commit_num)
+                            .with_end_commit_number(MAX_COMMIT_NUM)
+                            .with_service_id(Some(service_id.to_string()))
+                            .build()
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(ProductBuilder::default()
+                    .with_product_id(product_id.clone())
+                    .with_product_address(format!("address-{}", product_id))
+                    .with_product_namespace("GS1".to_string())
+                    .with_owner("owner".to_string())
+                    .with_start_commit_number(start_commit_num)
+                    .with_end_commit_number(MAX_COMMIT_NUM)
+                    .with_service_id(Some(service_id.to_string()))
+                    .with_properties(properties)
+                    .build()?)
+            })
+            .collect()
+    }
+
+    /// Returns the time taken to write a load of new products, and then to supersede each of
+    /// them with a new version.
+    fn time_writes<F>(service_id: &str, mut write: F) -> Result<[Duration; 2], Box<dyn Error>>
+    where
+        F: FnMut(&[Product]) -> Result<(), ProductStoreError>,
+    {
+        let mut durations = [Duration::default(); 2];
+        for (duration, start_commit_num) in durations.iter_mut().zip(1..) {
+            let products = products(service_id, start_commit_num)?;
+            let start = Instant::now();
+            write(&products)?;
+            *duration = start.elapsed();
+        }
+
+        Ok(durations)
+    }
+
+    /// Writes the same products with each operation, under separate service IDs so that each
+    /// starts from an empty set, and checks that the upsert is the faster.
+    fn compare<A, U>(database: &str, add: A, upsert: U) -> Result<(), Box<dyn Error>>
+    where
+        A: FnMut(&[Product]) -> Result<(), ProductStoreError>,
+        U: FnMut(&[Product]) -> Result<(), ProductStoreError>,
+    {
+        let run = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+        let added = time_writes(&format!("add-{}", run), add)?;
+        let upserted = time_writes(&format!("upsert-{}", run), upsert)?;
+
+        for ((stage, added), upserted) in ["loading", "superseding"]
+            .iter()
+            .zip(added.iter())
+            .zip(upserted.iter())
+        {
+            println!(
+                "{}: {} {} products took {:?} with add_product and {:?} with upsert_products",
+                database, stage, PRODUCT_COUNT, added, upserted
+            );
+            assert!(upserted < added);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    #[ignore]
+    fn test_upsert_throughput_sqlite() -> Result<(), Box<dyn Error>> {
+        use diesel::SqliteConnection;
+
+        use crate::migrations::run_sqlite_migrations;
+
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        compare(
+            "sqlite",
+            |products| {
+                conn.transaction(|| {
+                    products
+                        .iter()
+                        .try_for_each(|product| ops.add_product(product.clone()))
+                })
+            },
+            |products| conn.transaction(|| ops.upsert_products(products)),
+        )
+    }
+
+    #[cfg(feature = "postgres")]
+    #[test]
+    #[ignore]
+    fn test_upsert_throughput_postgres() -> Result<(), Box<dyn Error>> {
+        use diesel::PgConnection;
+
+        use crate::migrations::run_postgres_migrations;
+
+        let conn = PgConnection::establish(&std::env::var("GRID_TEST_POSTGRES_URL")?)?;
+        run_postgres_migrations(&conn)?;
+        let ops = ProductStoreOperations::new(&conn);
+
+        compare(
+            "postgres",
+            |products| {
+                conn.transaction(|| {
+                    products
+                        .iter()
+                        .try_for_each(|product| ops.add_product(product.clone()))
+                })
+            },
+            |products| conn.transaction(|| ops.upsert_products(products)),
+        )
+    }
+}
@@ -44,5 +44,18 @@ table! {
         end_commit_num -> Int8,
         service_id -> Nullable<Text>,
         last_updated -> Nullable<Timestamp>,
+        recall_status -> Text,
+    }
+}
+
+table! {
+    product_component (id) {
+        id -> Int8,
+        product_id -> Varchar,
+        product_address -> Varchar,
+        component_id -> Varchar,
+        start_commit_num -> Int8,
+        end_commit_num -> Int8,
+        service_id -> Nullable<Text>,
     }
 }
@@ -17,7 +17,9 @@ use std::fmt;
 
 #[cfg(feature = "diesel")]
 use crate::error::ConstraintViolationType;
-use crate::error::{ConstraintViolationError, InternalError, ResourceTemporarilyUnavailableError};
+use crate::error::{
+    ConstraintViolationError, InternalError, InvalidStateError, ResourceTemporarilyUnavailableError,
+};
 
 /// Represents Store errors
 #[derive(Debug)]
@@ -26,6 +28,9 @@ pub enum ProductStoreError {
     ConstraintViolationError(ConstraintViolationError),
     ResourceTemporarilyUnavailableError(ResourceTemporarilyUnavailableError),
     NotFoundError(String),
+    InvalidStateError(InvalidStateError),
+    /// The record was modified more recently than the caller expected
+    ConflictError(String),
 }
 
 impl Error for ProductStoreError {
@@ -35,6 +40,8 @@ impl Error for ProductStoreError {
             ProductStoreError::ConstraintViolationError(err) => Some(err),
             ProductStoreError::ResourceTemporarilyUnavailableError(err) => Some(err),
             ProductStoreError::NotFoundError(_) => None,
+            ProductStoreError::InvalidStateError(err) => Some(err),
+            ProductStoreError::ConflictError(_) => None,
         }
     }
 }
@@ -46,6 +53,8 @@ impl fmt::Display for ProductStoreError {
             ProductStoreError::ConstraintViolationError(err) => err.fmt(f),
             ProductStoreError::ResourceTemporarilyUnavailableError(err) => err.fmt(f),
             ProductStoreError::NotFoundError(ref s) => write!(f, "Element not found: {}", s),
+            ProductStoreError::InvalidStateError(err) => err.fmt(f),
+            ProductStoreError::ConflictError(ref s) => write!(f, "Conflict: {}", s),
         }
     }
 }
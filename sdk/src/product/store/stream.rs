@@ -0,0 +1,128 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Iteration over every product in a store without loading them all at once.
+
+use crate::paging::PageStream;
+
+use super::{Product, ProductStore, ProductStoreError};
+
+type ReadPage<'a> = Box<dyn FnMut(i64) -> Result<Vec<Product>, ProductStoreError> + 'a>;
+
+/// An iterator over the current products of a store, in product ID order.
+///
+/// This is a `PageStream` over `ProductStore::list_products_after`: each page is read by its
+/// own query, starting after the last product ID read, so the iteration is unaffected by
+/// products added or removed in the meantime.
+///
+/// A failed read is returned as the next item, after which the iterator ends.
+pub struct ProductStream<'a> {
+    pages: PageStream<Product, ReadPage<'a>>,
+}
+
+impl<'a> ProductStream<'a> {
+    /// Creates an iterator over the products of the given service, starting after the product
+    /// with the ID `after`, or from the first product if `after` is `None`.
+    pub fn new(store: &'a dyn ProductStore, service_id: Option<&str>, after: Option<&str>) -> Self {
+        let service_id = service_id.map(String::from);
+        let mut after = after.map(String::from);
+        let read_page: ReadPage<'a> = Box::new(move |page_size| {
+            let page =
+                store.list_products_after(service_id.as_deref(), after.as_deref(), page_size)?;
+            if let Some(product) = page.last() {
+                after = Some(product.product_id().to_string());
+            }
+            Ok(page)
+        });
+
+        ProductStream {
+            pages: PageStream::new(read_page),
+        }
+    }
+
+    /// Sets the number of products read from the store at a time.
+    pub fn with_page_size(self, page_size: u16) -> Self {
+        ProductStream {
+            pages: self.pages.with_page_size(i64::from(page_size)),
+        }
+    }
+}
+
+impl<'a> Iterator for ProductStream<'a> {
+    type Item = Result<Product, ProductStoreError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pages.next()
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::{
+        store::{DieselConnectionProductStore, ProductBuilder},
+        MAX_COMMIT_NUM,
+    };
+
+    fn product(product_id: &str) -> Product {
+        ProductBuilder::default()
+            .with_product_id(product_id.to_string())
+            .with_product_address(format!("address-{}", product_id))
+            .with_product_namespace("GS1".to_string())
+            .with_owner("owner".to_string())
+            .with_start_commit_number(1)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .build()
+            .expect("Unable to build product")
+    }
+
+    /// Verifies that every product is returned once in product ID order across several pages,
+    /// including when the last page is full, and that iteration can start after a product.
+    #[test]
+    fn test_product_stream() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionProductStore::new(&conn);
+
+        for id in ["e", "b", "d", "a", "c", "f"] {
+            store.add_product(product(id))?;
+        }
+
+        let ids = |stream: ProductStream| {
+            stream
+                .map(|product| product.map(|product| product.product_id().to_string()))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        assert_eq!(
+            ids(ProductStream::new(&store, None, None).with_page_size(4))?,
+            vec!["a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            ids(ProductStream::new(&store, None, None).with_page_size(3))?,
+            vec!["a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            ids(ProductStream::new(&store, None, Some("d")).with_page_size(1))?,
+            vec!["e", "f"]
+        );
+        assert!(ids(ProductStream::new(&store, Some("other"), None))?.is_empty());
+
+        Ok(())
+    }
+}
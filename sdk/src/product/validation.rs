@@ -0,0 +1,185 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of product create and update actions before they are submitted, applying the same
+//! rules as the product smart contract.
+
+use crate::error::{ValidationError, ValidationErrors};
+use crate::gs1;
+use crate::protocol::product::payload::{ProductCreateAction, ProductUpdateAction};
+use crate::protocol::product::state::ProductNamespace;
+use crate::protocol::schema::state::PropertyDefinition;
+use crate::schema::validation::{validate_properties, validate_required_properties};
+
+/// Checks that a GTIN is a GTIN-12, GTIN-13 or GTIN-14 with a valid check digit.
+///
+/// See https://www.gs1.org/services/how-calculate-check-digit-manually
+pub fn validate_gtin(gtin: &str) -> Result<(), ValidationError> {
+    let invalid = |code: &str, message: String| {
+        Err(ValidationError::new(
+            "product_id".into(),
+            code.into(),
+            message,
+        ))
+    };
+
+    let digits = match gs1::digits(gtin) {
+        Some(digits) => digits,
+        None => {
+            return invalid(
+                "invalid_format",
+                format!("GTIN {} may only contain digits", gtin),
+            )
+        }
+    };
+
+    match digits.len() {
+        12..=14 => (),
+        8 => {
+            return invalid(
+                "unsupported_length",
+                format!("GTIN-8 is not supported at this time: {}", gtin),
+            )
+        }
+        _ => {
+            return invalid(
+                "invalid_length",
+                format!("GTIN {} must be 12, 13 or 14 digits", gtin),
+            )
+        }
+    }
+
+    if !gs1::has_valid_check_digit(&digits) {
+        return invalid(
+            "invalid_check_digit",
+            format!("GTIN {} has an invalid check digit", gtin),
+        );
+    }
+
+    Ok(())
+}
+
+/// Validates a product create action against the property definitions of the product's schema,
+/// returning every problem found.
+pub fn validate_product_create(
+    action: &ProductCreateAction,
+    definitions: &[PropertyDefinition],
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    match action.product_namespace() {
+        ProductNamespace::Gs1 => errors.check(validate_gtin(action.product_id())),
+    }
+
+    if action.owner().is_empty() {
+        errors.push(ValidationError::new(
+            "owner".into(),
+            "required".into(),
+            "owner is not set".into(),
+        ));
+    }
+
+    validate_properties(definitions, action.properties(), &mut errors);
+    validate_required_properties(definitions, action.properties(), &mut errors);
+
+    errors.into_result()
+}
+
+/// Validates a product update action against the property definitions of the product's schema,
+/// returning every problem found.
+///
+/// Required properties are not checked, as an update only carries the properties it changes.
+pub fn validate_product_update(
+    action: &ProductUpdateAction,
+    definitions: &[PropertyDefinition],
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    match action.product_namespace() {
+        ProductNamespace::Gs1 => errors.check(validate_gtin(action.product_id())),
+    }
+
+    validate_properties(definitions, action.properties(), &mut errors);
+
+    errors.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::product::payload::ProductCreateActionBuilder;
+    use crate::protocol::schema::state::{
+        DataType, PropertyDefinitionBuilder, PropertyValueBuilder,
+    };
+
+    /// Verifies that GTIN-12, GTIN-13 and GTIN-14 with valid check digits are accepted.
+    #[test]
+    fn test_validate_gtin_valid() {
+        assert!(validate_gtin("012345678905").is_ok());
+        assert!(validate_gtin("9781981855728").is_ok());
+        assert!(validate_gtin("10012345678902").is_ok());
+    }
+
+    /// Verifies that each invalid GTIN is rejected with the code of the rule it fails.
+    #[test]
+    fn test_validate_gtin_invalid() {
+        let code = |gtin| validate_gtin(gtin).unwrap_err().code().to_string();
+
+        assert_eq!(code("01234567890a"), "invalid_format");
+        assert_eq!(code("12345670"), "unsupported_length");
+        assert_eq!(code("123"), "invalid_length");
+        assert_eq!(code("012345678906"), "invalid_check_digit");
+    }
+
+    /// Verifies that an invalid GTIN, missing owner and invalid properties are all reported
+    /// together, each against its own field.
+    #[test]
+    fn test_validate_product_create_reports_all_errors() {
+        let definitions = vec![PropertyDefinitionBuilder::new()
+            .with_name("brand".to_string())
+            .with_data_type(DataType::String)
+            .with_required(true)
+            .with_description("".to_string())
+            .build()
+            .expect("Unable to build property definition")];
+        let action = ProductCreateActionBuilder::new()
+            .with_product_namespace(ProductNamespace::Gs1)
+            .with_product_id("012345678906".to_string())
+            .with_owner("".to_string())
+            .with_properties(vec![PropertyValueBuilder::new()
+                .with_name("brand".to_string())
+                .with_data_type(DataType::Number)
+                .with_number_value(1)
+                .build()
+                .expect("Unable to build property value")])
+            .build()
+            .expect("Unable to build action");
+
+        let errors = validate_product_create(&action, &definitions).unwrap_err();
+
+        assert_eq!(
+            errors
+                .errors()
+                .iter()
+                .map(|err| (err.field(), err.code()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("product_id", "invalid_check_digit"),
+                ("owner", "required"),
+                ("properties.brand", "invalid_type"),
+            ]
+        );
+    }
+}
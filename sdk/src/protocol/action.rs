@@ -0,0 +1,277 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Classification of Grid transactions by the action their payload performs.
+
+#[cfg(feature = "sabre-sdk")]
+use sabre_sdk::protocol::payload::{Action as SabreAction, SabrePayload};
+#[cfg(feature = "sabre-sdk")]
+use sabre_sdk::protos::FromBytes as SabreFromBytes;
+
+use crate::protos::{FromBytes, ProtoConversionError};
+
+use super::location::payload::{Action as LocationAction, LocationPayload};
+use super::pike::payload::{Action as PikeAction, PikePayload};
+use super::product::payload::{Action as ProductAction, ProductPayload};
+#[cfg(feature = "purchase-order")]
+use super::purchase_order::payload::{Action as PurchaseOrderAction, PurchaseOrderPayload};
+use super::schema::payload::{Action as SchemaAction, SchemaPayload};
+use super::track_and_trace::payload::{Action as TrackAndTraceAction, TrackAndTracePayload};
+
+pub const SABRE_FAMILY_NAME: &str = "sabre";
+pub const GRID_LOCATION_FAMILY_NAME: &str = "grid_location";
+pub const GRID_PIKE_FAMILY_NAME: &str = "grid_pike";
+pub const GRID_PRODUCT_FAMILY_NAME: &str = "grid_product";
+pub const GRID_PURCHASE_ORDER_FAMILY_NAME: &str = "grid_purchase_order";
+pub const GRID_SCHEMA_FAMILY_NAME: &str = "grid_schema";
+pub const GRID_TRACK_AND_TRACE_FAMILY_NAME: &str = "grid_track_and_trace";
+
+/// The action performed by a Grid transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridAction {
+    CreateAgent,
+    UpdateAgent,
+    DeleteAgent,
+    CreateOrganization,
+    UpdateOrganization,
+    DeleteOrganization,
+    CreateRole,
+    UpdateRole,
+    DeleteRole,
+    CreateProduct,
+    UpdateProduct,
+    DeleteProduct,
+    CreateSchema,
+    UpdateSchema,
+    CreateLocation,
+    UpdateLocation,
+    DeleteLocation,
+    CreatePurchaseOrder,
+    UpdatePurchaseOrder,
+    CreatePurchaseOrderVersion,
+    UpdatePurchaseOrderVersion,
+    CreateRecord,
+    FinalizeRecord,
+    UpdateProperties,
+    CreateProposal,
+    AnswerProposal,
+    RevokeReporter,
+    /// A transaction of a family that is not a Grid smart contract
+    Other {
+        family_name: String,
+        family_version: String,
+    },
+}
+
+impl GridAction {
+    /// Classifies a transaction by the family and version in its header and by its payload.
+    ///
+    /// A transaction wrapped in a sabre `ExecuteContract` action is classified by the contract
+    /// it executes. Transactions of any other family, including sabre transactions that manage
+    /// contracts, are classified as `Other`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ProtoConversionError` if the family is a Grid smart contract but the payload
+    /// cannot be decoded as one of its payloads.
+    pub fn parse(
+        family_name: &str,
+        family_version: &str,
+        payload: &[u8],
+    ) -> Result<Self, ProtoConversionError> {
+        let action = match family_name {
+            #[cfg(feature = "sabre-sdk")]
+            SABRE_FAMILY_NAME => {
+                let sabre_payload = SabrePayload::from_bytes(payload)
+                    .map_err(|err| ProtoConversionError::SerializationError(err.to_string()))?;
+                match sabre_payload.action() {
+                    SabreAction::ExecuteContract(execute) => {
+                        return GridAction::parse(
+                            execute.name(),
+                            execute.version(),
+                            execute.payload(),
+                        )
+                    }
+                    _ => GridAction::other(family_name, family_version),
+                }
+            }
+            GRID_LOCATION_FAMILY_NAME => match LocationPayload::from_bytes(payload)?.action() {
+                LocationAction::LocationCreate(_) => GridAction::CreateLocation,
+                LocationAction::LocationUpdate(_) => GridAction::UpdateLocation,
+                LocationAction::LocationDelete(_) => GridAction::DeleteLocation,
+            },
+            GRID_PIKE_FAMILY_NAME => match PikePayload::from_bytes(payload)?.action() {
+                PikeAction::CreateAgent(_) => GridAction::CreateAgent,
+                PikeAction::UpdateAgent(_) => GridAction::UpdateAgent,
+                PikeAction::DeleteAgent(_) => GridAction::DeleteAgent,
+                PikeAction::CreateOrganization(_) => GridAction::CreateOrganization,
+                PikeAction::UpdateOrganization(_) => GridAction::UpdateOrganization,
+                PikeAction::DeleteOrganization(_) => GridAction::DeleteOrganization,
+                PikeAction::CreateRole(_) => GridAction::CreateRole,
+                PikeAction::UpdateRole(_) => GridAction::UpdateRole,
+                PikeAction::DeleteRole(_) => GridAction::DeleteRole,
+            },
+            GRID_PRODUCT_FAMILY_NAME => match ProductPayload::from_bytes(payload)?.action() {
+                ProductAction::ProductCreate(_) => GridAction::CreateProduct,
+                ProductAction::ProductUpdate(_) => GridAction::UpdateProduct,
+                ProductAction::ProductDelete(_) => GridAction::DeleteProduct,
+            },
+            #[cfg(feature = "purchase-order")]
+            GRID_PURCHASE_ORDER_FAMILY_NAME => {
+                match PurchaseOrderPayload::from_bytes(payload)?.action() {
+                    PurchaseOrderAction::CreatePo(_) => GridAction::CreatePurchaseOrder,
+                    PurchaseOrderAction::UpdatePo(_) => GridAction::UpdatePurchaseOrder,
+                    PurchaseOrderAction::CreateVersion(_) => GridAction::CreatePurchaseOrderVersion,
+                    PurchaseOrderAction::UpdateVersion(_) => GridAction::UpdatePurchaseOrderVersion,
+                }
+            }
+            GRID_SCHEMA_FAMILY_NAME => match SchemaPayload::from_bytes(payload)?.action() {
+                SchemaAction::SchemaCreate(_) => GridAction::CreateSchema,
+                SchemaAction::SchemaUpdate(_) => GridAction::UpdateSchema,
+            },
+            GRID_TRACK_AND_TRACE_FAMILY_NAME => {
+                match TrackAndTracePayload::from_bytes(payload)?.action() {
+                    TrackAndTraceAction::CreateRecord(_) => GridAction::CreateRecord,
+                    TrackAndTraceAction::FinalizeRecord(_) => GridAction::FinalizeRecord,
+                    TrackAndTraceAction::UpdateProperties(_) => GridAction::UpdateProperties,
+                    TrackAndTraceAction::CreateProposal(_) => GridAction::CreateProposal,
+                    TrackAndTraceAction::AnswerProposal(_) => GridAction::AnswerProposal,
+                    TrackAndTraceAction::RevokeReporter(_) => GridAction::RevokeReporter,
+                }
+            }
+            _ => GridAction::other(family_name, family_version),
+        };
+
+        Ok(action)
+    }
+
+    /// Returns the name of the smart contract family that performs the action.
+    pub fn family_name(&self) -> &str {
+        match self {
+            GridAction::CreateAgent
+            | GridAction::UpdateAgent
+            | GridAction::DeleteAgent
+            | GridAction::CreateOrganization
+            | GridAction::UpdateOrganization
+            | GridAction::DeleteOrganization
+            | GridAction::CreateRole
+            | GridAction::UpdateRole
+            | GridAction::DeleteRole => GRID_PIKE_FAMILY_NAME,
+            GridAction::CreateProduct | GridAction::UpdateProduct | GridAction::DeleteProduct => {
+                GRID_PRODUCT_FAMILY_NAME
+            }
+            GridAction::CreateSchema | GridAction::UpdateSchema => GRID_SCHEMA_FAMILY_NAME,
+            GridAction::CreateLocation
+            | GridAction::UpdateLocation
+            | GridAction::DeleteLocation => GRID_LOCATION_FAMILY_NAME,
+            GridAction::CreatePurchaseOrder
+            | GridAction::UpdatePurchaseOrder
+            | GridAction::CreatePurchaseOrderVersion
+            | GridAction::UpdatePurchaseOrderVersion => GRID_PURCHASE_ORDER_FAMILY_NAME,
+            GridAction::CreateRecord
+            | GridAction::FinalizeRecord
+            | GridAction::UpdateProperties
+            | GridAction::CreateProposal
+            | GridAction::AnswerProposal
+            | GridAction::RevokeReporter => GRID_TRACK_AND_TRACE_FAMILY_NAME,
+            GridAction::Other { family_name, .. } => family_name,
+        }
+    }
+
+    fn other(family_name: &str, family_version: &str) -> Self {
+        GridAction::Other {
+            family_name: family_name.to_string(),
+            family_version: family_version.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::product::{
+        payload::{ProductDeleteActionBuilder, ProductPayloadBuilder},
+        state::ProductNamespace,
+    };
+    use crate::protos::IntoBytes;
+
+    fn delete_product_payload() -> Vec<u8> {
+        ProductPayloadBuilder::new()
+            .with_action(ProductAction::ProductDelete(
+                ProductDeleteActionBuilder::new()
+                    .with_product_namespace(ProductNamespace::Gs1)
+                    .with_product_id("product".to_string())
+                    .build()
+                    .expect("Unable to build action"),
+            ))
+            .with_timestamp(1)
+            .build()
+            .expect("Unable to build payload")
+            .into_bytes()
+            .expect("Unable to serialize payload")
+    }
+
+    /// Verifies that a Grid payload is classified by its action, that an unknown family is
+    /// classified as `Other` with its name and version preserved, and that a payload that cannot
+    /// be decoded for its family is an error.
+    #[test]
+    fn test_parse() {
+        let action = GridAction::parse(GRID_PRODUCT_FAMILY_NAME, "2", &delete_product_payload())
+            .expect("Unable to parse action");
+        assert_eq!(action, GridAction::DeleteProduct);
+        assert_eq!(action.family_name(), GRID_PRODUCT_FAMILY_NAME);
+
+        let action =
+            GridAction::parse("intkey", "1.0", b"payload").expect("Unable to parse action");
+        assert_eq!(
+            action,
+            GridAction::Other {
+                family_name: "intkey".to_string(),
+                family_version: "1.0".to_string(),
+            }
+        );
+        assert_eq!(action.family_name(), "intkey");
+
+        assert!(GridAction::parse(GRID_PRODUCT_FAMILY_NAME, "2", b"not a payload").is_err());
+    }
+
+    /// Verifies that a transaction wrapped in a sabre `ExecuteContract` action is classified by
+    /// the contract it executes.
+    #[cfg(feature = "sabre-sdk")]
+    #[test]
+    fn test_parse_sabre_execute_contract() {
+        use sabre_sdk::protocol::payload::ExecuteContractActionBuilder;
+        use sabre_sdk::protos::IntoBytes as SabreIntoBytes;
+
+        let sabre_payload = ExecuteContractActionBuilder::new()
+            .with_name(GRID_PRODUCT_FAMILY_NAME.to_string())
+            .with_version("2".to_string())
+            .with_inputs(vec![])
+            .with_outputs(vec![])
+            .with_payload(delete_product_payload())
+            .into_payload_builder()
+            .expect("Unable to build sabre payload")
+            .build()
+            .expect("Unable to build sabre payload")
+            .into_bytes()
+            .expect("Unable to serialize sabre payload");
+
+        assert_eq!(
+            GridAction::parse(SABRE_FAMILY_NAME, "0.5", &sabre_payload)
+                .expect("Unable to parse action"),
+            GridAction::DeleteProduct
+        );
+    }
+}
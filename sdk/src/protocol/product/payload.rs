@@ -417,12 +417,29 @@ impl IntoBytes for ProductUpdateAction {
 impl IntoProto<protos::product_payload::ProductUpdateAction> for ProductUpdateAction {}
 impl IntoNative<ProductUpdateAction> for protos::product_payload::ProductUpdateAction {}
 
+/// How the properties of an "update product" action are combined with the product's current
+/// properties.
+///
+/// The product smart contract replaces all of a product's properties with those of the update,
+/// so a replacing update must include every property the product should keep.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// The update's properties replace all of the product's current properties
+    #[default]
+    Replace,
+    /// The update's properties are merged into the product's current properties; properties
+    /// that are not updated keep their current values
+    Merge,
+}
+
 /// Builder used to create an "update product" action
 #[derive(Default, Clone)]
 pub struct ProductUpdateActionBuilder {
     product_namespace: Option<ProductNamespace>,
     product_id: Option<String>,
     properties: Vec<PropertyValue>,
+    mode: UpdateMode,
+    current_properties: Vec<PropertyValue>,
 }
 
 impl ProductUpdateActionBuilder {
@@ -445,6 +462,20 @@ impl ProductUpdateActionBuilder {
         self
     }
 
+    /// Sets how the properties are combined with the product's current properties. Updates
+    /// replace the current properties by default.
+    pub fn with_mode(mut self, mode: UpdateMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Sets the product's current properties, which the update's properties are merged into
+    /// in `UpdateMode::Merge`. These are ignored when replacing.
+    pub fn with_current_properties(mut self, current_properties: Vec<PropertyValue>) -> Self {
+        self.current_properties = current_properties;
+        self
+    }
+
     pub fn build(self) -> Result<ProductUpdateAction, BuilderError> {
         let product_namespace = self.product_namespace.ok_or_else(|| {
             BuilderError::MissingField("'product_namespace' field is required".to_string())
@@ -454,9 +485,14 @@ impl ProductUpdateActionBuilder {
             BuilderError::MissingField("'product_id' field is required".to_string())
         })?;
 
+        let properties = match self.mode {
+            UpdateMode::Replace => self.properties,
+            UpdateMode::Merge => merge_properties(self.current_properties, self.properties),
+        };
+
         let properties = {
-            if !self.properties.is_empty() {
-                self.properties
+            if !properties.is_empty() {
+                properties
             } else {
                 return Err(BuilderError::MissingField(
                     "'properties' field is required".to_string(),
@@ -472,6 +508,27 @@ impl ProductUpdateActionBuilder {
     }
 }
 
+/// Merges updated properties into a product's current properties.
+///
+/// Current properties keep their order, with updated values in place of their current ones;
+/// properties the product does not have yet are added after them.
+fn merge_properties(
+    mut current: Vec<PropertyValue>,
+    updates: Vec<PropertyValue>,
+) -> Vec<PropertyValue> {
+    for update in updates {
+        match current
+            .iter_mut()
+            .find(|property| property.name() == update.name())
+        {
+            Some(property) => *property = update,
+            None => current.push(update),
+        }
+    }
+
+    current
+}
+
 /// Native representation of the "delete product" action payload
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct ProductDeleteAction {
@@ -643,6 +700,37 @@ mod tests {
         assert_eq!(*action.properties()[1].number_value(), 3);
     }
 
+    #[test]
+    /// Validate that a merging `ProductUpdateAction` keeps the current properties it does not
+    /// update, and that a replacing one drops them
+    fn test_product_update_builder_merge() {
+        let current = make_properties();
+        let updated_price = PropertyValueBuilder::new()
+            .with_name("price".into())
+            .with_data_type(DataType::Number)
+            .with_number_value(5)
+            .build()
+            .unwrap();
+        let builder = ProductUpdateActionBuilder::new()
+            .with_product_id("688955434684".into()) // GTIN-12
+            .with_product_namespace(ProductNamespace::Gs1)
+            .with_properties(vec![updated_price.clone()])
+            .with_current_properties(current.clone());
+
+        let merged = builder
+            .clone()
+            .with_mode(UpdateMode::Merge)
+            .build()
+            .unwrap();
+        assert_eq!(
+            merged.properties(),
+            &[current[0].clone(), updated_price.clone()]
+        );
+
+        let replaced = builder.build().unwrap();
+        assert_eq!(replaced.properties(), &[updated_price]);
+    }
+
     #[test]
     /// Validate that an `ProductUpdateAction` may be correctly converted into bytes and back
     /// to its native representation
@@ -14,6 +14,8 @@
 
 //! Protocol structs for Schema state
 
+use crypto::digest::Digest;
+use crypto::sha2::Sha256;
 use protobuf::Message;
 use protobuf::RepeatedField;
 
@@ -36,6 +38,19 @@ pub enum DataType {
     Enum,
     Struct,
     LatLong,
+    /// A list of values, each of the given type
+    List(Box<DataType>),
+}
+
+impl DataType {
+    /// Returns the type of the innermost elements of a list, or the type itself if it is not a
+    /// list
+    pub fn base_type(&self) -> &DataType {
+        match self {
+            DataType::List(element_type) => element_type.base_type(),
+            data_type => data_type,
+        }
+    }
 }
 
 impl FromProto<protos::schema_state::PropertyDefinition_DataType> for DataType {
@@ -50,6 +65,12 @@ impl FromProto<protos::schema_state::PropertyDefinition_DataType> for DataType {
             protos::schema_state::PropertyDefinition_DataType::ENUM => Ok(DataType::Enum),
             protos::schema_state::PropertyDefinition_DataType::STRUCT => Ok(DataType::Struct),
             protos::schema_state::PropertyDefinition_DataType::LAT_LONG => Ok(DataType::LatLong),
+            protos::schema_state::PropertyDefinition_DataType::LIST => {
+                Err(ProtoConversionError::InvalidTypeError(
+                    "Cannot convert PropertyDefinition_DataType LIST without element type."
+                        .to_string(),
+                ))
+            }
             protos::schema_state::PropertyDefinition_DataType::UNSET_DATA_TYPE => {
                 Err(ProtoConversionError::InvalidTypeError(
                     "Cannot convert PropertyDefinition_DataType with type unset.".to_string(),
@@ -69,6 +90,7 @@ impl FromNative<DataType> for protos::schema_state::PropertyDefinition_DataType
             DataType::Enum => Ok(protos::schema_state::PropertyDefinition_DataType::ENUM),
             DataType::Struct => Ok(protos::schema_state::PropertyDefinition_DataType::STRUCT),
             DataType::LatLong => Ok(protos::schema_state::PropertyDefinition_DataType::LAT_LONG),
+            DataType::List(_) => Ok(protos::schema_state::PropertyDefinition_DataType::LIST),
         }
     }
 }
@@ -76,6 +98,44 @@ impl FromNative<DataType> for protos::schema_state::PropertyDefinition_DataType
 impl IntoProto<protos::schema_state::PropertyDefinition_DataType> for DataType {}
 impl IntoNative<DataType> for protos::schema_state::PropertyDefinition_DataType {}
 
+/// Converts a proto data type, along with the element types of a `LIST`, each of which is the
+/// element type of the one before it
+fn data_type_from_proto(
+    data_type: protos::schema_state::PropertyDefinition_DataType,
+    list_element_types: &[protos::schema_state::PropertyDefinition_DataType],
+) -> Result<DataType, ProtoConversionError> {
+    match data_type {
+        protos::schema_state::PropertyDefinition_DataType::LIST => {
+            let (element_type, nested_types) =
+                list_element_types.split_first().ok_or_else(|| {
+                    ProtoConversionError::InvalidTypeError(
+                        "Cannot convert PropertyDefinition_DataType LIST without element type."
+                            .to_string(),
+                    )
+                })?;
+            Ok(DataType::List(Box::new(data_type_from_proto(
+                *element_type,
+                nested_types,
+            )?)))
+        }
+        data_type => DataType::from_proto(data_type),
+    }
+}
+
+/// Returns the proto element types of a list data type, as read by `data_type_from_proto`; the
+/// result is empty for any other type
+fn list_element_types_into_proto(
+    data_type: &DataType,
+) -> Result<Vec<protos::schema_state::PropertyDefinition_DataType>, ProtoConversionError> {
+    let mut list_element_types = vec![];
+    let mut data_type = data_type;
+    while let DataType::List(element_type) = data_type {
+        list_element_types.push(element_type.as_ref().clone().into_proto()?);
+        data_type = element_type;
+    }
+    Ok(list_element_types)
+}
+
 /// Native representation of the `LatLong` schema data type
 ///
 /// A `LatLong` object is comprised of a latitude, longitude pair represented as signed integers
@@ -189,6 +249,7 @@ pub struct PropertyDefinition {
     data_type: DataType,
     required: bool,
     description: String,
+    access_level: u32,
     number_exponent: i32,
     enum_options: Vec<String>,
     struct_properties: Vec<PropertyDefinition>,
@@ -211,6 +272,12 @@ impl PropertyDefinition {
         &self.description
     }
 
+    /// Returns the clearance a caller needs to read values of the property; 0 means the
+    /// property is public
+    pub fn access_level(&self) -> u32 {
+        self.access_level
+    }
+
     pub fn number_exponent(&self) -> &i32 {
         &self.number_exponent
     }
@@ -222,6 +289,20 @@ impl PropertyDefinition {
     pub fn struct_properties(&self) -> &[PropertyDefinition] {
         &self.struct_properties
     }
+
+    /// Sorts the definitions of the property's struct properties, and their own, by name.
+    ///
+    /// Enum options are left in place, as values refer to them by index.
+    fn canonicalize(&mut self) {
+        sort_by_name(&mut self.struct_properties);
+    }
+}
+
+fn sort_by_name(properties: &mut [PropertyDefinition]) {
+    properties.sort_by(|a, b| a.name.cmp(&b.name));
+    for property in properties.iter_mut() {
+        property.canonicalize();
+    }
 }
 
 impl FromProto<protos::schema_state::PropertyDefinition> for PropertyDefinition {
@@ -230,9 +311,13 @@ impl FromProto<protos::schema_state::PropertyDefinition> for PropertyDefinition
     ) -> Result<Self, ProtoConversionError> {
         Ok(PropertyDefinition {
             name: property_definition.get_name().to_string(),
-            data_type: DataType::from_proto(property_definition.get_data_type())?,
+            data_type: data_type_from_proto(
+                property_definition.get_data_type(),
+                property_definition.get_list_element_types(),
+            )?,
             required: property_definition.get_required(),
             description: property_definition.get_description().to_string(),
+            access_level: property_definition.get_access_level(),
             number_exponent: property_definition.get_number_exponent(),
             enum_options: property_definition.get_enum_options().to_vec(),
             struct_properties: property_definition
@@ -251,8 +336,12 @@ impl FromNative<PropertyDefinition> for protos::schema_state::PropertyDefinition
         proto_property_definition.set_name(property_definition.name().to_string());
         proto_property_definition
             .set_data_type(property_definition.data_type().clone().into_proto()?);
+        proto_property_definition.set_list_element_types(list_element_types_into_proto(
+            property_definition.data_type(),
+        )?);
         proto_property_definition.set_required(*property_definition.required());
         proto_property_definition.set_description(property_definition.description().to_string());
+        proto_property_definition.set_access_level(property_definition.access_level());
         proto_property_definition.set_number_exponent(*property_definition.number_exponent());
         proto_property_definition.set_enum_options(RepeatedField::from_vec(
             property_definition.enum_options().to_vec(),
@@ -333,6 +422,7 @@ pub struct PropertyDefinitionBuilder {
     pub data_type: Option<DataType>,
     pub required: Option<bool>,
     pub description: Option<String>,
+    pub access_level: Option<u32>,
     pub number_exponent: Option<i32>,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<PropertyDefinition>,
@@ -363,6 +453,13 @@ impl PropertyDefinitionBuilder {
         self
     }
 
+    /// Sets the clearance a caller needs to read values of the property; properties are public
+    /// by default
+    pub fn with_access_level(mut self, access_level: u32) -> PropertyDefinitionBuilder {
+        self.access_level = Some(access_level);
+        self
+    }
+
     pub fn with_number_exponent(mut self, number_exponent: i32) -> PropertyDefinitionBuilder {
         self.number_exponent = Some(number_exponent);
         self
@@ -392,9 +489,13 @@ impl PropertyDefinitionBuilder {
 
         let required = self.required.unwrap_or(false);
         let description = self.description.unwrap_or_default();
+        let access_level = self.access_level.unwrap_or(0);
+
+        // The exponent, options or properties of a list apply to its elements
+        let base_type = data_type.base_type();
 
         let number_exponent = {
-            if data_type == DataType::Number {
+            if base_type == &DataType::Number {
                 self.number_exponent.ok_or_else(|| {
                     PropertyDefinitionBuildError::MissingField(
                         "'number_exponent' field is required".to_string(),
@@ -406,7 +507,7 @@ impl PropertyDefinitionBuilder {
         };
 
         let enum_options = {
-            if data_type == DataType::Enum {
+            if base_type == &DataType::Enum {
                 if !self.enum_options.is_empty() {
                     self.enum_options
                 } else {
@@ -420,7 +521,7 @@ impl PropertyDefinitionBuilder {
         };
 
         let struct_properties = {
-            if data_type == DataType::Struct {
+            if base_type == &DataType::Struct {
                 if !self.struct_properties.is_empty() {
                     self.struct_properties
                 } else {
@@ -438,6 +539,7 @@ impl PropertyDefinitionBuilder {
             data_type,
             required,
             description,
+            access_level,
             number_exponent,
             enum_options,
             struct_properties,
@@ -472,6 +574,36 @@ impl Schema {
     pub fn properties(&self) -> &[PropertyDefinition] {
         &self.properties
     }
+
+    /// Returns the schema with its property definitions, including the definitions of struct
+    /// properties, sorted by name.
+    ///
+    /// The order properties are defined in has no meaning, so schemas that differ only in that
+    /// order have the same canonical form.
+    pub fn canonicalize(mut self) -> Self {
+        sort_by_name(&mut self.properties);
+        self
+    }
+
+    /// Returns the protobuf serialization of the schema's canonical form, for storing or
+    /// comparing schemas regardless of the order their properties are defined in.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>, ProtoConversionError> {
+        self.clone().canonicalize().into_bytes()
+    }
+
+    /// Returns the lower-case hex encoding of the SHA-256 hash of the schema's canonical
+    /// serialization.
+    pub fn canonical_hash(&self) -> Result<String, ProtoConversionError> {
+        let mut sha = Sha256::new();
+        sha.input(&self.canonical_bytes()?);
+        Ok(sha.result_str())
+    }
+
+    /// Returns whether two schemas are the same apart from the order their properties are
+    /// defined in.
+    pub fn is_equivalent(&self, other: &Schema) -> bool {
+        self.clone().canonicalize() == other.clone().canonicalize()
+    }
 }
 
 impl FromProto<protos::schema_state::Schema> for Schema {
@@ -770,6 +902,9 @@ pub struct PropertyValue {
     enum_value: u32,
     struct_values: Vec<PropertyValue>,
     lat_long_value: LatLong,
+    list_values: Vec<PropertyValue>,
+    effective_from: Option<i64>,
+    effective_to: Option<i64>,
 }
 
 impl PropertyValue {
@@ -808,6 +943,21 @@ impl PropertyValue {
     pub fn lat_long_value(&self) -> &LatLong {
         &self.lat_long_value
     }
+
+    /// Returns the elements of a list value, named after their index
+    pub fn list_values(&self) -> &[PropertyValue] {
+        &self.list_values
+    }
+
+    /// Returns the time, in seconds since the epoch, from which the value is in effect
+    pub fn effective_from(&self) -> Option<i64> {
+        self.effective_from
+    }
+
+    /// Returns the time, in seconds since the epoch, until which the value is in effect
+    pub fn effective_to(&self) -> Option<i64> {
+        self.effective_to
+    }
 }
 
 impl FromProto<protos::schema_state::PropertyValue> for PropertyValue {
@@ -816,7 +966,10 @@ impl FromProto<protos::schema_state::PropertyValue> for PropertyValue {
     ) -> Result<Self, ProtoConversionError> {
         Ok(PropertyValue {
             name: property_value.get_name().to_string(),
-            data_type: DataType::from_proto(property_value.get_data_type())?,
+            data_type: data_type_from_proto(
+                property_value.get_data_type(),
+                property_value.get_list_element_types(),
+            )?,
             bytes_value: property_value.get_bytes_value().to_vec(),
             boolean_value: property_value.get_boolean_value(),
             number_value: property_value.get_number_value(),
@@ -829,15 +982,34 @@ impl FromProto<protos::schema_state::PropertyValue> for PropertyValue {
                 .map(PropertyValue::from_proto)
                 .collect::<Result<Vec<PropertyValue>, ProtoConversionError>>()?,
             lat_long_value: property_value.get_lat_long_value().clone().into_native()?,
+            list_values: property_value
+                .get_list_values()
+                .iter()
+                .cloned()
+                .map(PropertyValue::from_proto)
+                .collect::<Result<Vec<PropertyValue>, ProtoConversionError>>()?,
+            effective_from: effective_bound_from_proto(property_value.get_effective_from()),
+            effective_to: effective_bound_from_proto(property_value.get_effective_to()),
         })
     }
 }
 
+/// An unset bound of an effective range is stored as 0 in the protobuf
+fn effective_bound_from_proto(bound: i64) -> Option<i64> {
+    if bound == 0 {
+        None
+    } else {
+        Some(bound)
+    }
+}
+
 impl FromNative<PropertyValue> for protos::schema_state::PropertyValue {
     fn from_native(property_value: PropertyValue) -> Result<Self, ProtoConversionError> {
         let mut proto_property_value = protos::schema_state::PropertyValue::new();
         proto_property_value.set_name(property_value.name().to_string());
         proto_property_value.set_data_type(property_value.data_type().clone().into_proto()?);
+        proto_property_value
+            .set_list_element_types(list_element_types_into_proto(property_value.data_type())?);
         proto_property_value.set_bytes_value(property_value.bytes_value().to_vec());
         proto_property_value.set_boolean_value(*property_value.boolean_value());
         proto_property_value.set_number_value(*property_value.number_value());
@@ -854,6 +1026,17 @@ impl FromNative<PropertyValue> for protos::schema_state::PropertyValue {
         ));
         proto_property_value
             .set_lat_long_value(property_value.lat_long_value().clone().into_proto()?);
+        proto_property_value.set_list_values(RepeatedField::from_vec(
+            property_value
+                .list_values()
+                .iter()
+                .cloned()
+                .map(PropertyValue::into_proto)
+                .collect::<Result<Vec<protos::schema_state::PropertyValue>, ProtoConversionError>>(
+                )?,
+        ));
+        proto_property_value.set_effective_from(property_value.effective_from().unwrap_or(0));
+        proto_property_value.set_effective_to(property_value.effective_to().unwrap_or(0));
         Ok(proto_property_value)
     }
 }
@@ -890,18 +1073,24 @@ impl IntoNative<PropertyValue> for protos::schema_state::PropertyValue {}
 #[derive(Debug)]
 pub enum PropertyValueBuildError {
     MissingField(String),
+    InvalidListElement(String),
+    InvalidEffectiveRange(String),
 }
 
 impl StdError for PropertyValueBuildError {
     fn description(&self) -> &str {
         match *self {
             PropertyValueBuildError::MissingField(ref msg) => msg,
+            PropertyValueBuildError::InvalidListElement(ref msg) => msg,
+            PropertyValueBuildError::InvalidEffectiveRange(ref msg) => msg,
         }
     }
 
     fn cause(&self) -> Option<&dyn StdError> {
         match *self {
             PropertyValueBuildError::MissingField(_) => None,
+            PropertyValueBuildError::InvalidListElement(_) => None,
+            PropertyValueBuildError::InvalidEffectiveRange(_) => None,
         }
     }
 }
@@ -910,6 +1099,12 @@ impl std::fmt::Display for PropertyValueBuildError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match *self {
             PropertyValueBuildError::MissingField(ref s) => write!(f, "MissingField: {}", s),
+            PropertyValueBuildError::InvalidListElement(ref s) => {
+                write!(f, "InvalidListElement: {}", s)
+            }
+            PropertyValueBuildError::InvalidEffectiveRange(ref s) => {
+                write!(f, "InvalidEffectiveRange: {}", s)
+            }
         }
     }
 }
@@ -926,6 +1121,9 @@ pub struct PropertyValueBuilder {
     pub enum_value: Option<u32>,
     pub struct_values: Vec<PropertyValue>,
     pub lat_long_value: Option<LatLong>,
+    pub list_values: Vec<PropertyValue>,
+    pub effective_from: Option<i64>,
+    pub effective_to: Option<i64>,
 }
 
 impl PropertyValueBuilder {
@@ -978,6 +1176,26 @@ impl PropertyValueBuilder {
         self
     }
 
+    /// Sets the elements of a list value; each must be of the list's element type, and is
+    /// renamed after its index
+    pub fn with_list_values(mut self, list_values: Vec<PropertyValue>) -> PropertyValueBuilder {
+        self.list_values = list_values;
+        self
+    }
+
+    /// Sets the time, in seconds since the epoch, from which the value is in effect
+    pub fn with_effective_from(mut self, effective_from: i64) -> PropertyValueBuilder {
+        self.effective_from = Some(effective_from);
+        self
+    }
+
+    /// Sets the time, in seconds since the epoch, until which the value is in effect; the value
+    /// is no longer in effect at `effective_to`
+    pub fn with_effective_to(mut self, effective_to: i64) -> PropertyValueBuilder {
+        self.effective_to = Some(effective_to);
+        self
+    }
+
     pub fn build(self) -> Result<PropertyValue, PropertyValueBuildError> {
         let name = self.name.ok_or_else(|| {
             PropertyValueBuildError::MissingField("'name' field is required".to_string())
@@ -1076,6 +1294,47 @@ impl PropertyValueBuilder {
             }
         };
 
+        let list_values = {
+            if let DataType::List(element_type) = &data_type {
+                if let Some(element) = self
+                    .list_values
+                    .iter()
+                    .find(|element| element.data_type() != element_type.as_ref())
+                {
+                    return Err(PropertyValueBuildError::InvalidListElement(format!(
+                        "'list_values' elements must be of type {:?}, not {:?}",
+                        element_type,
+                        element.data_type()
+                    )));
+                }
+                self.list_values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, element)| PropertyValue {
+                        name: index.to_string(),
+                        ..element
+                    })
+                    .collect()
+            } else {
+                self.list_values
+            }
+        };
+
+        if self.effective_from == Some(0) || self.effective_to == Some(0) {
+            return Err(PropertyValueBuildError::InvalidEffectiveRange(
+                "'effective_from' and 'effective_to' cannot be 0".to_string(),
+            ));
+        }
+        if let (Some(effective_from), Some(effective_to)) = (self.effective_from, self.effective_to)
+        {
+            if effective_from >= effective_to {
+                return Err(PropertyValueBuildError::InvalidEffectiveRange(format!(
+                    "'effective_from' ({}) must be before 'effective_to' ({})",
+                    effective_from, effective_to
+                )));
+            }
+        }
+
         Ok(PropertyValue {
             name,
             data_type,
@@ -1086,6 +1345,9 @@ impl PropertyValueBuilder {
             enum_value,
             struct_values,
             lat_long_value,
+            list_values,
+            effective_from: self.effective_from,
+            effective_to: self.effective_to,
         })
     }
 }
@@ -1108,6 +1370,7 @@ mod tests {
         assert_eq!(property_definition.name, "TEST");
         assert_eq!(property_definition.data_type, DataType::String);
         assert_eq!(property_definition.description, "Optional");
+        assert_eq!(property_definition.access_level, 0);
     }
 
     #[test]
@@ -1175,6 +1438,7 @@ mod tests {
             .with_name("TEST".to_string())
             .with_data_type(DataType::String)
             .with_description("Optional".to_string())
+            .with_access_level(2)
             .build()
             .unwrap();
 
@@ -1288,6 +1552,142 @@ mod tests {
         assert_eq!(property_value.struct_values, vec![string_value]);
     }
 
+    #[test]
+    /// Validate that a `PropertyValue` with a `List` data type may be empty
+    fn check_property_value_builder_empty_list() {
+        let property_value = PropertyValueBuilder::new()
+            .with_name("TEST_LIST".to_string())
+            .with_data_type(DataType::List(Box::new(DataType::String)))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            property_value.data_type,
+            DataType::List(Box::new(DataType::String))
+        );
+        assert!(property_value.list_values.is_empty());
+    }
+
+    #[test]
+    /// Validate that a `PropertyValue` with a `List` data type is not built with an element of
+    /// another type
+    fn check_property_value_builder_list_element_type() {
+        let string_value = PropertyValueBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("String value".to_string())
+            .build()
+            .unwrap();
+        let number_value = PropertyValueBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::Number)
+            .with_number_value(1)
+            .build()
+            .unwrap();
+
+        let result = PropertyValueBuilder::new()
+            .with_name("TEST_LIST".to_string())
+            .with_data_type(DataType::List(Box::new(DataType::String)))
+            .with_list_values(vec![string_value, number_value])
+            .build();
+
+        assert!(matches!(
+            result,
+            Err(PropertyValueBuildError::InvalidListElement(_))
+        ));
+    }
+
+    #[test]
+    /// Validate that a `PropertyValue` with a nested `List` data type may be converted into bytes
+    /// and back to its native representation successfully, with its elements named after their
+    /// index
+    fn check_property_value_list_bytes() {
+        let number_list = |numbers: &[i64]| {
+            PropertyValueBuilder::new()
+                .with_name("NUMBERS".to_string())
+                .with_data_type(DataType::List(Box::new(DataType::Number)))
+                .with_list_values(
+                    numbers
+                        .iter()
+                        .map(|number| {
+                            PropertyValueBuilder::new()
+                                .with_name("NUMBER".to_string())
+                                .with_data_type(DataType::Number)
+                                .with_number_value(*number)
+                                .build()
+                                .unwrap()
+                        })
+                        .collect(),
+                )
+                .build()
+                .unwrap()
+        };
+        let original = PropertyValueBuilder::new()
+            .with_name("TEST_LIST".to_string())
+            .with_data_type(DataType::List(Box::new(DataType::List(Box::new(
+                DataType::Number,
+            )))))
+            .with_list_values(vec![number_list(&[1, 2]), number_list(&[])])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            original
+                .list_values()
+                .iter()
+                .map(|element| element.name())
+                .collect::<Vec<_>>(),
+            vec!["0", "1"]
+        );
+        assert_eq!(original.list_values()[0].list_values()[1].name(), "1");
+
+        let bytes = original.clone().into_bytes().unwrap();
+
+        let property_value = PropertyValue::from_bytes(&bytes).unwrap();
+        assert_eq!(property_value, original);
+    }
+
+    #[test]
+    /// Validate that a `PropertyValue`'s effective range survives conversion into bytes and back,
+    /// and that a range which ends before it starts is not built
+    fn check_property_value_effective_range() {
+        let original = PropertyValueBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("String value".to_string())
+            .with_effective_from(1_000)
+            .with_effective_to(2_000)
+            .build()
+            .unwrap();
+
+        let property_value = PropertyValue::from_bytes(&original.into_bytes().unwrap()).unwrap();
+        assert_eq!(property_value.effective_from(), Some(1_000));
+        assert_eq!(property_value.effective_to(), Some(2_000));
+
+        let open_ended = PropertyValueBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("String value".to_string())
+            .with_effective_from(1_000)
+            .build()
+            .unwrap();
+        let property_value = PropertyValue::from_bytes(&open_ended.into_bytes().unwrap()).unwrap();
+        assert_eq!(property_value.effective_from(), Some(1_000));
+        assert_eq!(property_value.effective_to(), None);
+
+        let result = PropertyValueBuilder::new()
+            .with_name("TEST".to_string())
+            .with_data_type(DataType::String)
+            .with_string_value("String value".to_string())
+            .with_effective_from(2_000)
+            .with_effective_to(1_000)
+            .build();
+        assert!(matches!(
+            result,
+            Err(PropertyValueBuildError::InvalidEffectiveRange(_))
+        ));
+    }
+
     #[test]
     /// Validate that a `PropertyValue` with a `LatLong` data type is built correctly
     fn check_property_value_builder_lat_long() {
@@ -1325,4 +1725,66 @@ mod tests {
         let property_value = PropertyValue::from_bytes(&bytes).unwrap();
         assert_eq!(property_value, original);
     }
+
+    fn property(name: &str, struct_properties: Vec<PropertyDefinition>) -> PropertyDefinition {
+        let data_type = if struct_properties.is_empty() {
+            DataType::String
+        } else {
+            DataType::Struct
+        };
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(data_type)
+            .with_description(format!("{} description", name))
+            .with_struct_properties(struct_properties)
+            .build()
+            .unwrap()
+    }
+
+    fn schema(properties: Vec<PropertyDefinition>) -> Schema {
+        SchemaBuilder::new()
+            .with_name("TestSchema".to_string())
+            .with_description("Test Schema".to_string())
+            .with_owner("owner".to_string())
+            .with_properties(properties)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    /// Validate that schemas which differ only in the order of their property definitions,
+    /// including nested struct properties, have the same canonical form and hash, and that a
+    /// schema with a different property does not
+    fn check_schema_canonical_hash() {
+        let original = schema(vec![
+            property("b", vec![]),
+            property("a", vec![property("y", vec![]), property("x", vec![])]),
+        ]);
+        let reordered = schema(vec![
+            property("a", vec![property("x", vec![]), property("y", vec![])]),
+            property("b", vec![]),
+        ]);
+        let different = schema(vec![
+            property("a", vec![property("x", vec![]), property("z", vec![])]),
+            property("b", vec![]),
+        ]);
+
+        assert_ne!(original, reordered);
+        assert!(original.is_equivalent(&reordered));
+        assert_eq!(
+            original.canonical_bytes().unwrap(),
+            reordered.canonical_bytes().unwrap()
+        );
+        assert_eq!(
+            original.canonical_hash().unwrap(),
+            reordered.canonical_hash().unwrap()
+        );
+        assert_eq!(reordered.clone().canonicalize(), reordered);
+
+        assert!(!original.is_equivalent(&different));
+        assert_ne!(
+            original.canonical_hash().unwrap(),
+            different.canonical_hash().unwrap()
+        );
+    }
 }
@@ -34,8 +34,10 @@ use models::{
 use crate::error::ResourceTemporarilyUnavailableError;
 
 use operations::add_purchase_order::PurchaseOrderStoreAddPurchaseOrderOperation as _;
+use operations::count_purchase_orders::PurchaseOrderStoreCountPurchaseOrdersOperation as _;
 use operations::get_latest_revision_id::PurchaseOrderStoreGetLatestRevisionIdOperation as _;
 use operations::get_purchase_order::PurchaseOrderStoreGetPurchaseOrderOperation as _;
+use operations::get_purchase_order_by_alternate_id::PurchaseOrderStoreGetPurchaseOrderByAlternateIdOperation as _;
 use operations::get_purchase_order_version::PurchaseOrderStoreGetPurchaseOrderVersionOperation as _;
 use operations::get_purchase_order_version_revision::PurchaseOrderStoreGetPurchaseOrderRevisionOperation as _;
 use operations::list_alternate_ids_for_purchase_order::PurchaseOrderStoreListAlternateIdsForPurchaseOrderOperation as _;
@@ -96,6 +98,19 @@ impl PurchaseOrderStore for DieselPurchaseOrderStore<diesel::pg::PgConnection> {
         .list_purchase_orders(filters, service_id, offset, limit)
     }
 
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PurchaseOrderStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_purchase_orders(&filters, service_id)
+    }
+
     fn list_purchase_order_versions(
         &self,
         po_uid: &str,
@@ -127,6 +142,20 @@ impl PurchaseOrderStore for DieselPurchaseOrderStore<diesel::pg::PgConnection> {
         .get_purchase_order(purchase_order_uid, version_id, revision_number, service_id)
     }
 
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PurchaseOrderStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_purchase_order_by_alternate_id(alternate_id_type, alternate_id, service_id)
+    }
+
     fn get_purchase_order_version(
         &self,
         po_uid: &str,
@@ -242,6 +271,19 @@ impl PurchaseOrderStore for DieselPurchaseOrderStore<diesel::sqlite::SqliteConne
         .list_purchase_orders(filters, service_id, offset, limit)
     }
 
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PurchaseOrderStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_purchase_orders(&filters, service_id)
+    }
+
     fn list_purchase_order_versions(
         &self,
         po_uid: &str,
@@ -273,6 +315,20 @@ impl PurchaseOrderStore for DieselPurchaseOrderStore<diesel::sqlite::SqliteConne
         .get_purchase_order(purchase_order_uid, version_id, revision_number, service_id)
     }
 
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            PurchaseOrderStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .get_purchase_order_by_alternate_id(alternate_id_type, alternate_id, service_id)
+    }
+
     fn get_purchase_order_version(
         &self,
         po_uid: &str,
@@ -397,6 +453,15 @@ impl<'a> PurchaseOrderStore for DieselConnectionPurchaseOrderStore<'a, diesel::p
             .list_purchase_orders(filters, service_id, offset, limit)
     }
 
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(self.connection)
+            .count_purchase_orders(&filters, service_id)
+    }
+
     fn list_purchase_order_versions(
         &self,
         po_uid: &str,
@@ -424,6 +489,19 @@ impl<'a> PurchaseOrderStore for DieselConnectionPurchaseOrderStore<'a, diesel::p
         )
     }
 
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(self.connection).get_purchase_order_by_alternate_id(
+            alternate_id_type,
+            alternate_id,
+            service_id,
+        )
+    }
+
     fn get_purchase_order_version(
         &self,
         po_uid: &str,
@@ -518,6 +596,15 @@ impl<'a> PurchaseOrderStore
             .list_purchase_orders(filters, service_id, offset, limit)
     }
 
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(self.connection)
+            .count_purchase_orders(&filters, service_id)
+    }
+
     fn list_purchase_order_versions(
         &self,
         po_uid: &str,
@@ -545,6 +632,19 @@ impl<'a> PurchaseOrderStore
         )
     }
 
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        PurchaseOrderStoreOperations::new(self.connection).get_purchase_order_by_alternate_id(
+            alternate_id_type,
+            alternate_id,
+            service_id,
+        )
+    }
+
     fn get_purchase_order_version(
         &self,
         po_uid: &str,
@@ -0,0 +1,296 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{get_uid_from_alternate_id, PurchaseOrderStoreOperations};
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::purchase_order::store::diesel::{schema::purchase_order, ListPOFilters};
+
+use crate::purchase_order::store::PurchaseOrderStoreError;
+use diesel::prelude::*;
+
+pub(in crate::purchase_order::store::diesel) trait PurchaseOrderStoreCountPurchaseOrdersOperation {
+    fn count_purchase_orders(
+        &self,
+        filters: &ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PurchaseOrderStoreCountPurchaseOrdersOperation
+    for PurchaseOrderStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn count_purchase_orders(
+        &self,
+        filters: &ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        match pg::purchase_orders(self.conn, filters, service_id)? {
+            Some(query) => query.count().get_result(self.conn).map_err(|err| {
+                PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            }),
+            None => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PurchaseOrderStoreCountPurchaseOrdersOperation
+    for PurchaseOrderStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_purchase_orders(
+        &self,
+        filters: &ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        match sqlite::purchase_orders(self.conn, filters, service_id)? {
+            Some(query) => query.count().get_result(self.conn).map_err(|err| {
+                PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            }),
+            None => Ok(0),
+        }
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::dsl::any;
+    use diesel::pg::Pg;
+
+    /// Selects the current purchase orders matching the filters, or `None` if none can match.
+    pub fn purchase_orders<'a>(
+        conn: &diesel::pg::PgConnection,
+        filters: &'a ListPOFilters,
+        service_id: Option<&'a str>,
+    ) -> Result<Option<purchase_order::BoxedQuery<'a, Pg>>, PurchaseOrderStoreError> {
+        let mut query = purchase_order::table
+            .into_boxed()
+            .filter(purchase_order::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(ref alternate_ids) = filters.alternate_ids {
+            let uids = alternate_ids
+                .split(',')
+                .filter_map(|id| {
+                    match get_uid_from_alternate_id::pg::get_uid_from_alternate_id(
+                        conn, id, service_id,
+                    ) {
+                        Err(PurchaseOrderStoreError::NotFoundError(_)) => None,
+                        other => Some(other),
+                    }
+                })
+                .collect::<Result<Vec<String>, _>>()?;
+
+            if uids.is_empty() {
+                return Ok(None);
+            }
+
+            query = query.filter(purchase_order::purchase_order_uid.eq(any(uids)));
+        }
+
+        if let Some(has_accepted_version) = filters.has_accepted_version {
+            if has_accepted_version {
+                query = query.filter(purchase_order::accepted_version_id.is_not_null())
+            } else {
+                query = query.filter(purchase_order::accepted_version_id.is_null())
+            }
+        }
+
+        if let Some(is_open) = filters.is_open {
+            query = query.filter(purchase_order::is_closed.eq(!is_open))
+        }
+
+        if let Some(ref buyer_org_id) = filters.buyer_org_id {
+            query = query.filter(purchase_order::buyer_org_id.eq(buyer_org_id))
+        }
+
+        if let Some(ref seller_org_id) = filters.seller_org_id {
+            query = query.filter(purchase_order::seller_org_id.eq(seller_org_id))
+        }
+
+        if let Some(service_id) = service_id {
+            query = query.filter(purchase_order::service_id.eq(service_id));
+        } else {
+            query = query.filter(purchase_order::service_id.is_null());
+        }
+
+        Ok(Some(query))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current purchase orders matching the filters, or `None` if none can match.
+    pub fn purchase_orders<'a>(
+        conn: &diesel::sqlite::SqliteConnection,
+        filters: &'a ListPOFilters,
+        service_id: Option<&'a str>,
+    ) -> Result<Option<purchase_order::BoxedQuery<'a, Sqlite>>, PurchaseOrderStoreError> {
+        let mut query = purchase_order::table
+            .into_boxed()
+            .filter(purchase_order::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(ref alternate_ids) = filters.alternate_ids {
+            let uids = alternate_ids
+                .split(',')
+                .filter_map(
+                    |id| match get_uid_from_alternate_id::sqlite::get_uid_from_alternate_id(
+                        conn, id, service_id,
+                    ) {
+                        Err(PurchaseOrderStoreError::NotFoundError(_)) => None,
+                        other => Some(other),
+                    },
+                )
+                .collect::<Result<Vec<String>, _>>()?;
+
+            if uids.is_empty() {
+                return Ok(None);
+            }
+
+            query = query.filter(purchase_order::purchase_order_uid.eq_any(uids));
+        }
+
+        if let Some(has_accepted_version) = filters.has_accepted_version {
+            if has_accepted_version {
+                query = query.filter(purchase_order::accepted_version_id.is_not_null())
+            } else {
+                query = query.filter(purchase_order::accepted_version_id.is_null())
+            }
+        }
+
+        if let Some(is_open) = filters.is_open {
+            query = query.filter(purchase_order::is_closed.eq(!is_open))
+        }
+
+        if let Some(ref buyer_org_id) = filters.buyer_org_id {
+            query = query.filter(purchase_order::buyer_org_id.eq(buyer_org_id))
+        }
+
+        if let Some(ref seller_org_id) = filters.seller_org_id {
+            query = query.filter(purchase_order::seller_org_id.eq(seller_org_id))
+        }
+
+        if let Some(service_id) = service_id {
+            query = query.filter(purchase_order::service_id.eq(service_id));
+        } else {
+            query = query.filter(purchase_order::service_id.is_null());
+        }
+
+        Ok(Some(query))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::purchase_order::store::{
+        DieselConnectionPurchaseOrderStore, ListPOFilters, PurchaseOrder, PurchaseOrderBuilder,
+        PurchaseOrderStore,
+    };
+
+    fn purchase_order(
+        uid: &str,
+        buyer_org_id: &str,
+        is_closed: bool,
+        service_id: Option<&str>,
+    ) -> Result<PurchaseOrder, Box<dyn std::error::Error>> {
+        Ok(PurchaseOrderBuilder::default()
+            .with_purchase_order_uid(uid.to_string())
+            .with_workflow_state("issued".to_string())
+            .with_buyer_org_id(buyer_org_id.to_string())
+            .with_seller_org_id("seller".to_string())
+            .with_is_closed(is_closed)
+            .with_workflow_id("built-in::collaborative::v1".to_string())
+            .with_start_commit_number(1)
+            .with_end_commit_number(MAX_COMMIT_NUM)
+            .with_service_id(service_id.map(String::from))
+            .build()?)
+    }
+
+    fn filters(
+        buyer_org_id: Option<&str>,
+        is_open: Option<bool>,
+        alternate_ids: Option<&str>,
+    ) -> ListPOFilters {
+        ListPOFilters {
+            buyer_org_id: buyer_org_id.map(String::from),
+            seller_org_id: None,
+            has_accepted_version: None,
+            is_open,
+            alternate_ids: alternate_ids.map(String::from),
+        }
+    }
+
+    /// Verifies that purchase orders are counted with the same filters they are listed with,
+    /// including alternate IDs that match no purchase order, and that the total of a page of
+    /// purchase orders is the unpaged count.
+    #[test]
+    fn test_count_purchase_orders() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPurchaseOrderStore::new(&conn);
+
+        store.add_purchase_order(purchase_order("po-1", "buyer-1", false, None)?)?;
+        store.add_purchase_order(purchase_order("po-2", "buyer-1", true, None)?)?;
+        store.add_purchase_order(purchase_order("po-3", "buyer-2", false, None)?)?;
+        store.add_purchase_order(purchase_order("po-4", "buyer-1", false, Some("service"))?)?;
+
+        for ((buyer_org_id, is_open, alternate_ids), service_id, expected) in [
+            ((None, None, None), None, 3),
+            ((Some("buyer-1"), None, None), None, 2),
+            ((None, Some(true), None), None, 2),
+            ((Some("buyer-1"), Some(true), None), None, 1),
+            ((None, None, Some("po_number:missing")), None, 0),
+            ((None, None, None), Some("service"), 1),
+        ] {
+            assert_eq!(
+                store.count_purchase_orders(
+                    filters(buyer_org_id, is_open, alternate_ids),
+                    service_id
+                )?,
+                expected
+            );
+            assert_eq!(
+                store
+                    .list_purchase_orders(
+                        filters(buyer_org_id, is_open, alternate_ids),
+                        service_id,
+                        0,
+                        100
+                    )?
+                    .data
+                    .len() as i64,
+                expected
+            );
+
+            let page = store.list_purchase_orders(
+                filters(buyer_org_id, is_open, alternate_ids),
+                service_id,
+                0,
+                1,
+            )?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
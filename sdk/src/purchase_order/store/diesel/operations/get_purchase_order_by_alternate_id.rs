@@ -0,0 +1,205 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::get_purchase_order::PurchaseOrderStoreGetPurchaseOrderOperation;
+use super::PurchaseOrderStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::purchase_order::store::diesel::{schema::purchase_order_alternate_id, PurchaseOrder};
+
+use crate::purchase_order::store::PurchaseOrderStoreError;
+use diesel::prelude::*;
+
+pub(in crate::purchase_order::store::diesel) trait PurchaseOrderStoreGetPurchaseOrderByAlternateIdOperation
+{
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> PurchaseOrderStoreGetPurchaseOrderByAlternateIdOperation
+    for PurchaseOrderStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        self.conn.transaction::<_, PurchaseOrderStoreError, _>(|| {
+            let mut query = purchase_order_alternate_id::table
+                .into_boxed()
+                .select(purchase_order_alternate_id::purchase_order_uid)
+                .filter(
+                    purchase_order_alternate_id::alternate_id_type
+                        .eq(alternate_id_type)
+                        .and(purchase_order_alternate_id::alternate_id.eq(alternate_id))
+                        .and(purchase_order_alternate_id::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                query = query.filter(purchase_order_alternate_id::service_id.eq(service_id));
+            } else {
+                query = query.filter(purchase_order_alternate_id::service_id.is_null());
+            }
+
+            let uids = query
+                .distinct()
+                .order(purchase_order_alternate_id::purchase_order_uid)
+                .load::<String>(self.conn)
+                .map_err(|err| {
+                    PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(
+                        err,
+                    )))
+                })?;
+
+            let mut orders = Vec::new();
+            for uid in uids {
+                if let Some(order) = self.get_purchase_order(&uid, None, None, service_id)? {
+                    orders.push(order);
+                }
+            }
+
+            Ok(orders)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> PurchaseOrderStoreGetPurchaseOrderByAlternateIdOperation
+    for PurchaseOrderStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        self.conn.transaction::<_, PurchaseOrderStoreError, _>(|| {
+            let mut query = purchase_order_alternate_id::table
+                .into_boxed()
+                .select(purchase_order_alternate_id::purchase_order_uid)
+                .filter(
+                    purchase_order_alternate_id::alternate_id_type
+                        .eq(alternate_id_type)
+                        .and(purchase_order_alternate_id::alternate_id.eq(alternate_id))
+                        .and(purchase_order_alternate_id::end_commit_num.eq(MAX_COMMIT_NUM)),
+                );
+
+            if let Some(service_id) = service_id {
+                query = query.filter(purchase_order_alternate_id::service_id.eq(service_id));
+            } else {
+                query = query.filter(purchase_order_alternate_id::service_id.is_null());
+            }
+
+            let uids = query
+                .distinct()
+                .order(purchase_order_alternate_id::purchase_order_uid)
+                .load::<String>(self.conn)
+                .map_err(|err| {
+                    PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(
+                        err,
+                    )))
+                })?;
+
+            let mut orders = Vec::new();
+            for uid in uids {
+                if let Some(order) = self.get_purchase_order(&uid, None, None, service_id)? {
+                    orders.push(order);
+                }
+            }
+
+            Ok(orders)
+        })
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::insert_into;
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::purchase_order::store::diesel::{
+        models::{NewPurchaseOrderAlternateIdModel, NewPurchaseOrderModel},
+        schema::purchase_order,
+    };
+
+    fn order(uid: &str) -> NewPurchaseOrderModel {
+        NewPurchaseOrderModel {
+            purchase_order_uid: uid.into(),
+            workflow_state: "issued".into(),
+            buyer_org_id: "buyer".into(),
+            seller_org_id: "seller".into(),
+            is_closed: false,
+            accepted_version_id: None,
+            created_at: 1,
+            workflow_id: "workflow".into(),
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: None,
+        }
+    }
+
+    fn alternate_id(uid: &str, id: &str) -> NewPurchaseOrderAlternateIdModel {
+        NewPurchaseOrderAlternateIdModel {
+            purchase_order_uid: uid.into(),
+            alternate_id_type: "buyer_po".into(),
+            alternate_id: id.into(),
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: None,
+        }
+    }
+
+    /// Verifies that every purchase order sharing an alternate ID is returned, and that an
+    /// unknown alternate ID returns no purchase orders.
+    #[test]
+    fn test_get_purchase_order_by_alternate_id() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+
+        run_sqlite_migrations(&conn)?;
+
+        insert_into(purchase_order::table)
+            .values(vec![order("po-1"), order("po-2"), order("po-3")])
+            .execute(&conn)?;
+        insert_into(purchase_order_alternate_id::table)
+            .values(vec![
+                alternate_id("po-1", "1001"),
+                alternate_id("po-2", "1001"),
+                alternate_id("po-3", "1002"),
+            ])
+            .execute(&conn)?;
+
+        let ops = PurchaseOrderStoreOperations::new(&conn);
+
+        let uids = ops
+            .get_purchase_order_by_alternate_id("buyer_po", "1001", None)?
+            .iter()
+            .map(|order| order.purchase_order_uid().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(uids, vec!["po-1".to_string(), "po-2".to_string()]);
+
+        assert!(ops
+            .get_purchase_order_by_alternate_id("buyer_po", "9999", None)?
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{get_uid_from_alternate_id, PurchaseOrderStoreOperations};
+use super::count_purchase_orders::{self, PurchaseOrderStoreCountPurchaseOrdersOperation};
+use super::PurchaseOrderStoreOperations;
 use crate::commits::MAX_COMMIT_NUM;
 use crate::error::InternalError;
 use crate::paging::Paging;
@@ -30,9 +31,7 @@ use crate::purchase_order::store::diesel::{
 };
 
 use crate::purchase_order::store::PurchaseOrderStoreError;
-use diesel::dsl::*;
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::purchase_order::store::diesel) trait PurchaseOrderStoreListPurchaseOrdersOperation {
     fn list_purchase_orders(
@@ -56,75 +55,22 @@ impl<'a> PurchaseOrderStoreListPurchaseOrdersOperation
         limit: i64,
     ) -> Result<PurchaseOrderList, PurchaseOrderStoreError> {
         self.conn.transaction::<_, PurchaseOrderStoreError, _>(|| {
-            let ListPOFilters {
-                buyer_org_id,
-                seller_org_id,
-                has_accepted_version,
-                is_open,
-                alternate_ids,
-            } = filters;
-
-            let mut uids: Vec<String> = Vec::new();
-
-            if let Some(ref alternate_ids) = alternate_ids {
-                let id_vec = alternate_ids
-                    .split(',')
-                    .map(String::from)
-                    .collect::<Vec<String>>();
-                uids = id_vec
-                    .iter()
-                    .filter_map(
-                        |id| match get_uid_from_alternate_id::pg::get_uid_from_alternate_id(
-                            self.conn, id, service_id,
-                        ) {
-                            Err(PurchaseOrderStoreError::NotFoundError(_)) => None,
-                            other => Some(other),
-                        },
-                    )
-                    .collect::<Result<_, _>>()?;
-            }
-
-            let mut query = purchase_order::table
-                .into_boxed()
-                .select(purchase_order::all_columns)
-                .offset(offset)
-                .limit(limit)
-                .filter(purchase_order::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if !uids.is_empty() {
-                query = query.filter(purchase_order::purchase_order_uid.eq(any(uids)));
-            } else if uids.is_empty() && alternate_ids.is_some() {
-                return Ok(PurchaseOrderList::new(
-                    Vec::new(),
-                    Paging::new(offset, limit, 0),
-                ));
-            }
-
-            if let Some(has_accepted_version) = has_accepted_version {
-                if has_accepted_version {
-                    query = query.filter(purchase_order::accepted_version_id.is_not_null())
-                } else {
-                    query = query.filter(purchase_order::accepted_version_id.is_null())
+            let total = self.count_purchase_orders(&filters, service_id)?;
+
+            let query = match count_purchase_orders::pg::purchase_orders(
+                self.conn, &filters, service_id,
+            )? {
+                Some(query) => query
+                    .select(purchase_order::all_columns)
+                    .offset(offset)
+                    .limit(limit),
+                None => {
+                    return Ok(PurchaseOrderList::new(
+                        Vec::new(),
+                        Paging::new(offset, limit, 0),
+                    ))
                 }
-            }
-
-            if let Some(is_open) = is_open {
-                query = query.filter(purchase_order::is_closed.eq(!is_open))
-            }
-
-            if let Some(buyer_org_id) = buyer_org_id {
-                query = query.filter(purchase_order::buyer_org_id.eq(buyer_org_id))
-            }
-
-            if let Some(seller_org_id) = seller_org_id {
-                query = query.filter(purchase_order::seller_org_id.eq(seller_org_id))
-            }
-
-            if let Some(service_id) = service_id {
-                query = query.filter(purchase_order::service_id.eq(service_id));
-            } else {
-                query = query.filter(purchase_order::service_id.is_null());
-            }
+            };
 
             let purchase_order_models =
                 query.load::<PurchaseOrderModel>(self.conn).map_err(|err| {
@@ -133,10 +79,6 @@ impl<'a> PurchaseOrderStoreListPurchaseOrdersOperation
                     )))
                 })?;
 
-            let total = purchase_order_models.len().try_into().map_err(|err| {
-                PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
-
             let mut orders = Vec::new();
 
             for o in purchase_order_models {
@@ -249,75 +191,22 @@ impl<'a> PurchaseOrderStoreListPurchaseOrdersOperation
         limit: i64,
     ) -> Result<PurchaseOrderList, PurchaseOrderStoreError> {
         self.conn.transaction::<_, PurchaseOrderStoreError, _>(|| {
-            let ListPOFilters {
-                buyer_org_id,
-                seller_org_id,
-                has_accepted_version,
-                is_open,
-                alternate_ids,
-            } = filters;
-
-            let mut uids: Vec<String> = Vec::new();
-
-            if let Some(ref alternate_ids) = alternate_ids {
-                let id_vec = alternate_ids
-                    .split(',')
-                    .map(String::from)
-                    .collect::<Vec<String>>();
-                uids = id_vec
-                    .iter()
-                    .filter_map(|id| {
-                        match get_uid_from_alternate_id::sqlite::get_uid_from_alternate_id(
-                            self.conn, id, service_id,
-                        ) {
-                            Err(PurchaseOrderStoreError::NotFoundError(_)) => None,
-                            other => Some(other),
-                        }
-                    })
-                    .collect::<Result<_, _>>()?;
-            }
-
-            let mut query = purchase_order::table
-                .into_boxed()
-                .select(purchase_order::all_columns)
-                .offset(offset)
-                .limit(limit)
-                .filter(purchase_order::end_commit_num.eq(MAX_COMMIT_NUM));
-
-            if !uids.is_empty() {
-                query = query.filter(purchase_order::purchase_order_uid.eq_any(uids));
-            } else if uids.is_empty() && alternate_ids.is_some() {
-                return Ok(PurchaseOrderList::new(
-                    Vec::new(),
-                    Paging::new(offset, limit, 0),
-                ));
-            }
-
-            if let Some(has_accepted_version) = has_accepted_version {
-                if has_accepted_version {
-                    query = query.filter(purchase_order::accepted_version_id.is_not_null())
-                } else {
-                    query = query.filter(purchase_order::accepted_version_id.is_null())
+            let total = self.count_purchase_orders(&filters, service_id)?;
+
+            let query = match count_purchase_orders::sqlite::purchase_orders(
+                self.conn, &filters, service_id,
+            )? {
+                Some(query) => query
+                    .select(purchase_order::all_columns)
+                    .offset(offset)
+                    .limit(limit),
+                None => {
+                    return Ok(PurchaseOrderList::new(
+                        Vec::new(),
+                        Paging::new(offset, limit, 0),
+                    ))
                 }
-            }
-
-            if let Some(is_open) = is_open {
-                query = query.filter(purchase_order::is_closed.eq(!is_open))
-            }
-
-            if let Some(buyer_org_id) = buyer_org_id {
-                query = query.filter(purchase_order::buyer_org_id.eq(buyer_org_id))
-            }
-
-            if let Some(seller_org_id) = seller_org_id {
-                query = query.filter(purchase_order::seller_org_id.eq(seller_org_id))
-            }
-
-            if let Some(service_id) = service_id {
-                query = query.filter(purchase_order::service_id.eq(service_id));
-            } else {
-                query = query.filter(purchase_order::service_id.is_null());
-            }
+            };
 
             let purchase_order_models =
                 query.load::<PurchaseOrderModel>(self.conn).map_err(|err| {
@@ -326,10 +215,6 @@ impl<'a> PurchaseOrderStoreListPurchaseOrdersOperation
                     )))
                 })?;
 
-            let total = purchase_order_models.len().try_into().map_err(|err| {
-                PurchaseOrderStoreError::InternalError(InternalError::from_source(Box::new(err)))
-            })?;
-
             let mut orders = Vec::new();
 
             for o in purchase_order_models {
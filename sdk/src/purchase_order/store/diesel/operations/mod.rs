@@ -16,8 +16,10 @@ pub(super) mod add_alternate_id;
 pub(super) mod add_purchase_order;
 mod add_purchase_order_version;
 mod add_purchase_order_version_revision;
+pub(super) mod count_purchase_orders;
 pub(super) mod get_latest_revision_id;
 pub(super) mod get_purchase_order;
+pub(super) mod get_purchase_order_by_alternate_id;
 pub(super) mod get_purchase_order_version;
 pub(super) mod get_purchase_order_version_revision;
 mod get_uid_from_alternate_id;
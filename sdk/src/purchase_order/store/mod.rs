@@ -848,6 +848,21 @@ pub trait PurchaseOrderStore {
         limit: i64,
     ) -> Result<PurchaseOrderList, PurchaseOrderStoreError>;
 
+    /// Counts the purchase orders in the underlying storage, applying the same filters as
+    /// `list_purchase_orders`
+    ///
+    /// # Arguments
+    ///
+    ///  * `filters` - Optional filters for the POs: `buyer_org_id`,
+    ///    `seller_org_id`, `has_accepted_version`, `is_open`, and
+    ///    `alternate_ids`
+    ///  * `service_id` - The service ID
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError>;
+
     /// Lists purchase order versions from the underlying storage
     ///
     /// # Arguments
@@ -883,6 +898,24 @@ pub trait PurchaseOrderStore {
         service_id: Option<&str>,
     ) -> Result<Option<PurchaseOrder>, PurchaseOrderStoreError>;
 
+    /// Fetches the purchase orders that carry the given alternate ID, such as a buyer's own
+    /// order number, from the underlying storage
+    ///
+    /// An alternate ID is not required to be unique, so every matching purchase order is
+    /// returned; the list is empty if none match.
+    ///
+    /// # Arguments
+    ///
+    ///  * `alternate_id_type` - The type of the alternate ID
+    ///  * `alternate_id` - The alternate ID
+    ///  * `service_id` - The service id
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError>;
+
     /// Fetches a purchase order version from the underlying storage
     ///
     /// # Arguments
@@ -981,6 +1014,14 @@ where
         (**self).list_purchase_orders(filters, service_id, offset, limit)
     }
 
+    fn count_purchase_orders(
+        &self,
+        filters: ListPOFilters,
+        service_id: Option<&str>,
+    ) -> Result<i64, PurchaseOrderStoreError> {
+        (**self).count_purchase_orders(filters, service_id)
+    }
+
     fn list_purchase_order_versions(
         &self,
         po_uid: &str,
@@ -1002,6 +1043,15 @@ where
         (**self).get_purchase_order(purchase_order_uid, version_id, revision_number, service_id)
     }
 
+    fn get_purchase_order_by_alternate_id(
+        &self,
+        alternate_id_type: &str,
+        alternate_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<PurchaseOrder>, PurchaseOrderStoreError> {
+        (**self).get_purchase_order_by_alternate_id(alternate_id_type, alternate_id, service_id)
+    }
+
     fn get_purchase_order_version(
         &self,
         po_uid: &str,
@@ -0,0 +1,192 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of purchase order create and update payloads against the purchase order's
+//! workflow before they are submitted.
+
+use regex::Regex;
+
+use crate::error::{ValidationError, ValidationErrors};
+use crate::protocol::purchase_order::payload::{
+    CreatePurchaseOrderPayload, UpdatePurchaseOrderPayload,
+};
+use crate::protocol::purchase_order::state::PurchaseOrderAlternateId;
+use crate::workflow::Workflow;
+
+/// The format of an alternate ID, written as `<alternate_id_type>:<alternate_id>`
+pub const ALT_ID_FORMAT: &str =
+    "^[\\w\\-\\+=/~!@#\\$%\\^&\\*{}|\\[\\]<>\\?]+:[\\w\\-\\+=/~!@#\\$%\\^&\\*{}|\\[\\]<>\\?]+$";
+
+/// Validates a purchase order create payload against the purchase order's workflow, returning
+/// every problem found.
+pub fn validate_purchase_order_create(
+    payload: &CreatePurchaseOrderPayload,
+    workflow: &Workflow,
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    for (field, value) in [
+        ("buyer_org_id", payload.buyer_org_id()),
+        ("seller_org_id", payload.seller_org_id()),
+    ] {
+        if value.is_empty() {
+            errors.push(ValidationError::new(
+                field.into(),
+                "required".into(),
+                format!("{} is not set", field),
+            ));
+        }
+    }
+
+    errors.check(validate_workflow_state(
+        workflow,
+        "po",
+        "workflow_state",
+        payload.workflow_state(),
+    ));
+    validate_alternate_ids(payload.alternate_ids(), &mut errors);
+
+    if let Some(version) = payload.create_version_payload() {
+        errors.check(validate_workflow_state(
+            workflow,
+            "version",
+            "create_version_payload.workflow_state",
+            version.workflow_state(),
+        ));
+    }
+
+    errors.into_result()
+}
+
+/// Validates a purchase order update payload against the purchase order's workflow, returning
+/// every problem found.
+pub fn validate_purchase_order_update(
+    payload: &UpdatePurchaseOrderPayload,
+    workflow: &Workflow,
+) -> Result<(), ValidationErrors> {
+    let mut errors = ValidationErrors::new();
+
+    errors.check(validate_workflow_state(
+        workflow,
+        "po",
+        "workflow_state",
+        payload.workflow_state(),
+    ));
+    validate_alternate_ids(payload.alternate_ids(), &mut errors);
+
+    for (i, version) in payload.version_updates().iter().enumerate() {
+        errors.check(validate_workflow_state(
+            workflow,
+            "version",
+            &format!("version_updates[{}].workflow_state", i),
+            version.workflow_state(),
+        ));
+    }
+
+    errors.into_result()
+}
+
+/// Checks that `state` is a state of the named subworkflow of `workflow`.
+fn validate_workflow_state(
+    workflow: &Workflow,
+    subworkflow: &str,
+    field: &str,
+    state: &str,
+) -> Result<(), ValidationError> {
+    let known = workflow
+        .subworkflow(subworkflow)
+        .and_then(|subworkflow| subworkflow.state(state))
+        .is_some();
+
+    if known {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            field.into(),
+            "unknown_workflow_state".into(),
+            format!("'{}' is not a state of the {} workflow", state, subworkflow),
+        ))
+    }
+}
+
+fn validate_alternate_ids(
+    alternate_ids: &[PurchaseOrderAlternateId],
+    errors: &mut ValidationErrors,
+) {
+    let alt_id_format = Regex::new(ALT_ID_FORMAT).unwrap();
+
+    for (i, alternate_id) in alternate_ids.iter().enumerate() {
+        let id = format!("{}:{}", alternate_id.id_type(), alternate_id.id());
+        if !alt_id_format.is_match(&id) {
+            errors.push(ValidationError::new(
+                format!("alternate_ids[{}]", i),
+                "invalid_format".into(),
+                format!(
+                    "Invalid alternate ID format: '{}'; must match \
+                     <alternate_id_type>:<alternate_id>",
+                    id
+                ),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::purchase_order::payload::CreatePurchaseOrderPayloadBuilder;
+    use crate::workflow::{SubWorkflowBuilder, WorkflowStateBuilder};
+
+    fn workflow() -> Workflow {
+        Workflow::new(vec![SubWorkflowBuilder::new("po")
+            .add_state(WorkflowStateBuilder::new("issued").build())
+            .build()])
+    }
+
+    /// Verifies that missing organizations, an unknown workflow state and a malformed alternate
+    /// ID are all reported together, each against its own field.
+    #[test]
+    fn test_validate_purchase_order_create_reports_all_errors() {
+        let payload = CreatePurchaseOrderPayloadBuilder::new()
+            .with_uid("PO-00000-0000".to_string())
+            .with_created_at(1)
+            .with_buyer_org_id("".to_string())
+            .with_seller_org_id("seller".to_string())
+            .with_workflow_state("shipped".to_string())
+            .with_alternate_ids(vec![PurchaseOrderAlternateId::new(
+                "PO-00000-0000",
+                "po number",
+                "1",
+            )])
+            .with_workflow_id("built-in::system_of_record::v1".to_string())
+            .build()
+            .expect("Unable to build payload");
+
+        let errors = validate_purchase_order_create(&payload, &workflow()).unwrap_err();
+
+        assert_eq!(
+            errors
+                .errors()
+                .iter()
+                .map(|err| (err.field(), err.code()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("buyer_org_id", "required"),
+                ("workflow_state", "unknown_workflow_state"),
+                ("alternate_ids[0]", "invalid_format"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use actix_web_4::{
+    dev, error,
+    http::{header::AUTHORIZATION, StatusCode},
+    Error, FromRequest, HttpRequest, Result,
+};
+use cylinder::{jwt::JsonWebTokenParser, secp256k1::Secp256k1Context, Context};
+use futures::future;
+use futures_util::future::{FutureExt, LocalBoxFuture};
+
+use crate::error::InvalidArgumentError;
+
+/// The scheme of an `Authorization` header carrying a Cylinder JSON web token
+const CYLINDER_BEARER_PREFIX: &str = "Bearer Cylinder:";
+
+/// The public key of the caller of a request that changes data held by the REST API.
+///
+/// The caller proves that it holds the matching private key by sending a Cylinder JSON web
+/// token, signed with that key, in an `Authorization: Bearer Cylinder:<token>` header; the same
+/// scheme the daemon uses to authenticate to Splinter. Requests without a valid token are
+/// rejected with a 401.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Caller(pub String);
+
+impl FromRequest for Caller {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let unauthorized = |message: &str| {
+            future::err(Error::from(error::InternalError::new(
+                InvalidArgumentError::new(AUTHORIZATION.to_string(), message.to_string()),
+                StatusCode::UNAUTHORIZED,
+            )))
+            .boxed_local()
+        };
+
+        let token = match req
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(value) => match value.strip_prefix(CYLINDER_BEARER_PREFIX) {
+                Some(token) => token.to_string(),
+                None => return unauthorized("must be a Cylinder bearer token"),
+            },
+            None => return unauthorized("is required"),
+        };
+
+        let verifier = Secp256k1Context::new().new_verifier();
+        match JsonWebTokenParser::new(&*verifier).parse(&token) {
+            Ok(jwt) => future::ok(Caller(jwt.issuer().as_hex())).boxed_local(),
+            Err(err) => unauthorized(&format!("is not a valid token: {}", err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web_4::test::TestRequest;
+    use cylinder::jwt::JsonWebTokenBuilder;
+
+    fn caller(req: TestRequest) -> Result<Caller, Error> {
+        let (req, mut payload) = req.to_http_parts();
+        futures::executor::block_on(Caller::from_request(&req, &mut payload))
+    }
+
+    /// Verifies that the caller is the issuer of a valid token, and that requests without one
+    /// are rejected.
+    #[test]
+    fn test_caller() {
+        let context = Secp256k1Context::new();
+        let private_key = context.new_random_private_key();
+        let public_key = context
+            .get_public_key(&private_key)
+            .expect("Unable to get public key");
+        let token = JsonWebTokenBuilder::new()
+            .build(&*context.new_signer(private_key))
+            .expect("Unable to build token");
+
+        assert_eq!(
+            Caller(public_key.as_hex()),
+            caller(TestRequest::default().insert_header((
+                AUTHORIZATION,
+                format!("{}{}", CYLINDER_BEARER_PREFIX, token)
+            )))
+            .unwrap()
+        );
+
+        assert!(caller(TestRequest::default()).is_err());
+        assert!(caller(
+            TestRequest::default().insert_header((AUTHORIZATION, format!("Bearer {}", token)))
+        )
+        .is_err());
+        assert!(caller(TestRequest::default().insert_header((
+            AUTHORIZATION,
+            format!("{}{}x", CYLINDER_BEARER_PREFIX, token)
+        )))
+        .is_err());
+    }
+}
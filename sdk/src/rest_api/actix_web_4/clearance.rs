@@ -0,0 +1,113 @@
+// Copyright 2018-2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use actix_web_4::{dev, error, http::StatusCode, Error, FromRequest, HttpRequest, Result};
+use futures::future;
+use futures_util::future::{FutureExt, LocalBoxFuture};
+
+use crate::error::InvalidArgumentError;
+
+/// The header giving the clearance of the caller
+pub const CLEARANCE_HEADER: &str = "GridClearance";
+
+/// Whether the `GridClearance` header of a request is trusted, registered as app data.
+///
+/// The header is not authenticated, so it should only be trusted when the REST API is deployed
+/// behind a proxy that authenticates callers and sets, or strips, the header. If no policy is
+/// registered, the header is not trusted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClearancePolicy {
+    /// The header is ignored, and every caller has a clearance of 0
+    #[default]
+    Untrusted,
+    /// The clearance is read from the header
+    TrustHeader,
+}
+
+/// The clearance of the caller, compared against the access levels of schema properties to
+/// decide which property values are returned.
+///
+/// The clearance is read from the `GridClearance` header only if the app's [`ClearancePolicy`]
+/// trusts it. Otherwise, or if the request has no such header, the clearance is 0 and only
+/// public properties are shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Clearance(pub u32);
+
+impl FromRequest for Clearance {
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut dev::Payload) -> Self::Future {
+        let policy = req
+            .app_data::<ClearancePolicy>()
+            .copied()
+            .unwrap_or_default();
+        if policy == ClearancePolicy::Untrusted {
+            return future::ok(Clearance::default()).boxed_local();
+        }
+
+        let clearance = match req.headers().get(CLEARANCE_HEADER) {
+            Some(value) => match value.to_str().ok().and_then(|value| value.parse().ok()) {
+                Some(clearance) => clearance,
+                None => {
+                    return future::err(Error::from(error::InternalError::new(
+                        InvalidArgumentError::new(
+                            CLEARANCE_HEADER.to_string(),
+                            "must be a non-negative integer".to_string(),
+                        ),
+                        StatusCode::BAD_REQUEST,
+                    )))
+                    .boxed_local()
+                }
+            },
+            None => 0,
+        };
+
+        future::ok(Clearance(clearance)).boxed_local()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web_4::test::TestRequest;
+
+    fn clearance(req: TestRequest) -> Result<Clearance, Error> {
+        let (req, mut payload) = req.to_http_parts();
+        futures::executor::block_on(Clearance::from_request(&req, &mut payload))
+    }
+
+    /// Verifies that the header is ignored unless the policy trusts it.
+    #[test]
+    fn test_clearance_policy() {
+        let with_header = || TestRequest::default().insert_header((CLEARANCE_HEADER, "5"));
+
+        assert_eq!(Clearance(0), clearance(with_header()).unwrap());
+        assert_eq!(
+            Clearance(0),
+            clearance(with_header().app_data(ClearancePolicy::Untrusted)).unwrap()
+        );
+        assert_eq!(
+            Clearance(5),
+            clearance(with_header().app_data(ClearancePolicy::TrustHeader)).unwrap()
+        );
+        assert!(clearance(
+            TestRequest::default()
+                .insert_header((CLEARANCE_HEADER, "-1"))
+                .app_data(ClearancePolicy::TrustHeader)
+        )
+        .is_err());
+    }
+}
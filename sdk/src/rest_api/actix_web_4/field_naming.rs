@@ -0,0 +1,256 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The naming convention of the fields in JSON response bodies.
+//!
+//! Response types are serialized with the documented snake_case field names. When another
+//! convention is configured, the field names of JSON responses are rewritten as they are sent,
+//! so every response type follows it without per-type changes.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+use actix_web_4::{
+    body::{to_bytes, BoxBody, MessageBody},
+    dev::ServiceResponse,
+    error::ErrorInternalServerError,
+    http::header::CONTENT_TYPE,
+    Error, HttpResponse,
+};
+use serde_json::{Map, Value};
+
+use crate::error::InvalidArgumentError;
+
+/// Fields whose values are free-form objects supplied by users; their keys are left as-is.
+const OPAQUE_FIELDS: &[&str] = &["metadata"];
+
+/// The naming convention of the fields in JSON response bodies.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FieldNaming {
+    /// Fields are named as documented, e.g. `transaction_id`
+    #[default]
+    SnakeCase,
+    /// Fields are named for JavaScript clients, e.g. `transactionId`
+    CamelCase,
+}
+
+impl FieldNaming {
+    /// Renames a single field name to this convention.
+    ///
+    /// Field names are snake_case as serialized, so only the camelCase convention changes them.
+    /// Leading underscores are kept.
+    pub fn rename(&self, field: &str) -> String {
+        match self {
+            FieldNaming::SnakeCase => field.to_string(),
+            FieldNaming::CamelCase => {
+                let trimmed = field.trim_start_matches('_');
+                let mut renamed = field[..field.len() - trimmed.len()].to_string();
+                let mut upper_next = false;
+                for c in trimmed.chars() {
+                    if c == '_' {
+                        upper_next = true;
+                    } else if upper_next {
+                        renamed.extend(c.to_uppercase());
+                        upper_next = false;
+                    } else {
+                        renamed.push(c);
+                    }
+                }
+                renamed
+            }
+        }
+    }
+
+    /// Renames the fields of every object in a JSON value to this convention.
+    pub fn rename_fields(&self, value: Value) -> Value {
+        match value {
+            Value::Object(object) => Value::Object(
+                object
+                    .into_iter()
+                    .map(|(field, value)| {
+                        let value = if OPAQUE_FIELDS.contains(&field.as_str()) {
+                            value
+                        } else {
+                            self.rename_fields(value)
+                        };
+                        (self.rename(&field), value)
+                    })
+                    .collect::<Map<_, _>>(),
+            ),
+            Value::Array(values) => Value::Array(
+                values
+                    .into_iter()
+                    .map(|value| self.rename_fields(value))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+}
+
+impl FromStr for FieldNaming {
+    type Err = InvalidArgumentError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "snake_case" => Ok(FieldNaming::SnakeCase),
+            "camelCase" => Ok(FieldNaming::CamelCase),
+            _ => Err(InvalidArgumentError::new(
+                "field_naming".to_string(),
+                format!("expected snake_case or camelCase, found {}", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FieldNaming {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FieldNaming::SnakeCase => f.write_str("snake_case"),
+            FieldNaming::CamelCase => f.write_str("camelCase"),
+        }
+    }
+}
+
+/// Rewrites the field names of a JSON response to the given convention.
+///
+/// This is intended to be applied to every response of the REST API, e.g. with `wrap_fn`.
+/// Responses that are not JSON, or that are already in the documented convention, are passed
+/// through unchanged.
+pub async fn apply_field_naming<B>(
+    naming: FieldNaming,
+    res: ServiceResponse<B>,
+) -> Result<ServiceResponse<BoxBody>, Error>
+where
+    B: MessageBody + 'static,
+{
+    if naming == FieldNaming::SnakeCase || !is_json(res.response()) {
+        return Ok(res.map_into_boxed_body());
+    }
+
+    let (req, res) = res.into_parts();
+    let (res, body) = res.into_parts();
+    let bytes = to_bytes(body).await.map_err(|err| {
+        let err: Box<dyn StdError> = err.into();
+        ErrorInternalServerError(err.to_string())
+    })?;
+
+    let body = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(value) => serde_json::to_vec(&naming.rename_fields(value))?,
+        Err(_) => bytes.to_vec(),
+    };
+
+    Ok(ServiceResponse::new(req, res.set_body(BoxBody::new(body))))
+}
+
+fn is_json<B>(res: &HttpResponse<B>) -> bool {
+    res.headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("application/json"))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web_4::test::TestRequest;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct BatchStatus {
+        batch_id: String,
+        invalid_transactions: Vec<InvalidTransaction>,
+        metadata: Value,
+    }
+
+    #[derive(Serialize)]
+    struct InvalidTransaction {
+        transaction_id: String,
+        error_message: String,
+    }
+
+    fn response_body() -> BatchStatus {
+        BatchStatus {
+            batch_id: "batch".to_string(),
+            invalid_transactions: vec![InvalidTransaction {
+                transaction_id: "txn".to_string(),
+                error_message: "invalid".to_string(),
+            }],
+            metadata: json!({ "user_key": "value" }),
+        }
+    }
+
+    async fn serialized_with(naming: FieldNaming) -> Value {
+        let res = ServiceResponse::new(
+            TestRequest::default().to_http_request(),
+            HttpResponse::Ok().json(response_body()),
+        );
+        let res = apply_field_naming(naming, res)
+            .await
+            .expect("Unable to apply field naming");
+        let bytes = to_bytes(res.into_body())
+            .await
+            .expect("Unable to read response body");
+
+        serde_json::from_slice(&bytes).expect("Response is not JSON")
+    }
+
+    /// Verifies that responses keep the documented snake_case field names by default.
+    #[actix_rt::test]
+    async fn test_snake_case_fields_by_default() {
+        assert_eq!(
+            serialized_with(FieldNaming::default()).await,
+            json!({
+                "batch_id": "batch",
+                "invalid_transactions": [
+                    { "transaction_id": "txn", "error_message": "invalid" }
+                ],
+                "metadata": { "user_key": "value" },
+            })
+        );
+    }
+
+    /// Verifies that every field, including those of nested objects, is renamed to camelCase,
+    /// while user-supplied metadata keys are left as-is.
+    #[actix_rt::test]
+    async fn test_camel_case_fields() {
+        assert_eq!(
+            serialized_with(FieldNaming::CamelCase).await,
+            json!({
+                "batchId": "batch",
+                "invalidTransactions": [
+                    { "transactionId": "txn", "errorMessage": "invalid" }
+                ],
+                "metadata": { "user_key": "value" },
+            })
+        );
+    }
+
+    /// Verifies that conventions are parsed from their names.
+    #[test]
+    fn test_field_naming_from_str() {
+        assert_eq!(
+            "snake_case".parse::<FieldNaming>().ok(),
+            Some(FieldNaming::SnakeCase)
+        );
+        assert_eq!(
+            "camelCase".parse::<FieldNaming>().ok(),
+            Some(FieldNaming::CamelCase)
+        );
+        assert!("kebab-case".parse::<FieldNaming>().is_err());
+    }
+}
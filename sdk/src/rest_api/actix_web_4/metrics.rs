@@ -0,0 +1,140 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics of the requests served by the REST API.
+//!
+//! Requests are counted and timed per method, route pattern and status code. The route pattern,
+//! e.g. `/product/{id}`, is used rather than the request's path so that IDs do not end up in
+//! labels.
+
+use std::time::{Duration, Instant};
+
+use actix_web_4::{
+    dev::{ServiceRequest, ServiceResponse},
+    http::StatusCode,
+    Error,
+};
+
+use crate::metrics;
+
+/// The route label of requests that did not match any route.
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Times a request and records its metrics once it has been served.
+///
+/// This is intended to be started and finished around every request of the REST API, e.g. with
+/// `wrap_fn`:
+///
+/// ```ignore
+/// .wrap_fn(|req, srv| {
+///     let timer = RequestTimer::start(&req);
+///     let res = srv.call(req);
+///     async move {
+///         let res = res.await;
+///         timer.finish(&res);
+///         res
+///     }
+/// })
+/// ```
+pub struct RequestTimer {
+    method: String,
+    route: String,
+    start: Instant,
+}
+
+impl RequestTimer {
+    /// Starts timing a request.
+    pub fn start(req: &ServiceRequest) -> Self {
+        RequestTimer {
+            method: req.method().to_string(),
+            route: req
+                .match_pattern()
+                .unwrap_or_else(|| UNMATCHED_ROUTE.to_string()),
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the request's metrics with the status of its response.
+    pub fn finish<B>(self, res: &Result<ServiceResponse<B>, Error>) {
+        let status = match res {
+            Ok(res) => res.status(),
+            Err(err) => err.as_response_error().status_code(),
+        };
+        self.record(status, self.start.elapsed());
+    }
+
+    fn record(&self, status: StatusCode, elapsed: Duration) {
+        metrics::increment_counter(
+            "grid_http_requests_total",
+            &[
+                ("method", &self.method),
+                ("route", &self.route),
+                ("status", status.as_str()),
+            ],
+        );
+        metrics::observe_duration(
+            "grid_http_request_duration_seconds",
+            &[("method", &self.method), ("route", &self.route)],
+            elapsed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use actix_web_4::{test, web, App, HttpResponse};
+
+    /// Verifies that requests are labeled with the pattern of the route they matched rather
+    /// than their path.
+    #[actix_rt::test]
+    async fn test_requests_labeled_by_route_pattern() {
+        let app = test::init_service(
+            App::new()
+                .wrap_fn(|req, srv| {
+                    use actix_web_4::dev::Service;
+
+                    let timer = RequestTimer::start(&req);
+                    let res = srv.call(req);
+                    async move {
+                        let res = res.await;
+                        timer.finish(&res);
+                        res
+                    }
+                })
+                .route(
+                    "/timed/{id}",
+                    web::get().to(|| async { HttpResponse::Ok().finish() }),
+                ),
+        )
+        .await;
+
+        for id in &["a", "b"] {
+            let req = test::TestRequest::get()
+                .uri(&format!("/timed/{}", id))
+                .to_request();
+            test::call_service(&app, req).await;
+        }
+
+        let output = metrics::render();
+        assert!(output.contains(
+            "grid_http_requests_total{method=\"GET\",route=\"/timed/{id}\",status=\"200\"} 2\n"
+        ));
+        assert!(output.contains(
+            "grid_http_request_duration_seconds_count{method=\"GET\",route=\"/timed/{id}\"} 2\n"
+        ));
+        assert!(!output.contains("/timed/a"));
+    }
+}
@@ -13,8 +13,13 @@
 // limitations under the License.
 
 mod backend_state;
+mod caller;
+mod clearance;
 mod endpoint;
+mod field_naming;
 mod key_state;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod paging;
 pub mod routes;
 #[cfg(feature = "rest-api-actix-web-4-run")]
@@ -24,8 +29,13 @@ mod service;
 mod store_state;
 
 pub use backend_state::BackendState;
+pub use caller::Caller;
+pub use clearance::{Clearance, ClearancePolicy, CLEARANCE_HEADER};
 pub use endpoint::{Backend, Endpoint};
+pub use field_naming::{apply_field_naming, FieldNaming};
 pub use key_state::KeyState;
+#[cfg(feature = "metrics")]
+pub use metrics::RequestTimer;
 pub use paging::QueryPaging;
 #[cfg(feature = "rest-api-actix-web-4-run")]
 pub use run::run;
@@ -94,3 +104,145 @@ pub(crate) mod request {
         }
     }
 }
+
+#[cfg(any(
+    feature = "rest-api-endpoint-agent",
+    feature = "rest-api-endpoint-location",
+    feature = "rest-api-endpoint-organization",
+    feature = "rest-api-endpoint-product",
+    feature = "rest-api-endpoint-purchase-order",
+    feature = "rest-api-endpoint-record",
+    feature = "rest-api-endpoint-role",
+    feature = "rest-api-endpoint-schema",
+))]
+pub(crate) mod response {
+    use actix_web_4::{
+        http::header::{ETAG, IF_NONE_MATCH, VARY},
+        HttpRequest, HttpResponse,
+    };
+    use crypto::digest::Digest;
+    use crypto::sha2::Sha256;
+    use serde::Serialize;
+
+    use crate::rest_api::resources::error::ErrorResponse;
+
+    use super::CLEARANCE_HEADER;
+
+    /// Builds a JSON response for a single record, tagged with an `ETag` derived from the
+    /// record's serialized form.
+    ///
+    /// The records include the commit information they were last updated at, so the tag changes
+    /// whenever the record does. If the request's `If-None-Match` header matches the tag, a
+    /// `304 Not Modified` response is returned without a body.
+    ///
+    /// Which properties a record includes may depend on the caller's clearance, so the response
+    /// varies on the `GridClearance` header, keeping shared caches from serving one caller's
+    /// record to another.
+    pub fn json_with_etag<T: Serialize>(req: &HttpRequest, body: &T) -> HttpResponse {
+        let bytes = match serde_json::to_vec(body) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                return HttpResponse::InternalServerError()
+                    .json(ErrorResponse::internal_error(Box::new(err)))
+            }
+        };
+
+        let etag = etag(&bytes);
+
+        if if_none_match(req, &etag) {
+            return HttpResponse::NotModified()
+                .insert_header((ETAG, etag))
+                .insert_header((VARY, CLEARANCE_HEADER))
+                .finish();
+        }
+
+        HttpResponse::Ok()
+            .insert_header((ETAG, etag))
+            .insert_header((VARY, CLEARANCE_HEADER))
+            .content_type("application/json")
+            .body(bytes)
+    }
+
+    fn etag(bytes: &[u8]) -> String {
+        let mut sha = Sha256::new();
+        sha.input(bytes);
+        format!("\"{}\"", sha.result_str())
+    }
+
+    fn if_none_match(req: &HttpRequest, etag: &str) -> bool {
+        req.headers()
+            .get_all(IF_NONE_MATCH)
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim())
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use actix_web_4::{http::StatusCode, test::TestRequest};
+
+        #[derive(Serialize)]
+        struct Record {
+            id: String,
+            last_updated: i64,
+        }
+
+        fn record(last_updated: i64) -> Record {
+            Record {
+                id: "test".to_string(),
+                last_updated,
+            }
+        }
+
+        fn etag_of(res: &HttpResponse) -> String {
+            res.headers()
+                .get(ETAG)
+                .expect("no etag")
+                .to_str()
+                .expect("invalid etag")
+                .to_string()
+        }
+
+        /// Verifies that the same record produces the same tag, and an updated record a
+        /// different one.
+        #[test]
+        fn test_etag_changes_with_record() {
+            let req = TestRequest::default().to_http_request();
+
+            let first = json_with_etag(&req, &record(1));
+            let again = json_with_etag(&req, &record(1));
+            let updated = json_with_etag(&req, &record(2));
+
+            assert_eq!(first.status(), StatusCode::OK);
+            assert_eq!(
+                first
+                    .headers()
+                    .get(VARY)
+                    .and_then(|vary| vary.to_str().ok()),
+                Some(CLEARANCE_HEADER)
+            );
+            assert_eq!(etag_of(&first), etag_of(&again));
+            assert_ne!(etag_of(&first), etag_of(&updated));
+        }
+
+        /// Verifies that a matching If-None-Match returns 304 and a stale one returns the record.
+        #[test]
+        fn test_if_none_match() {
+            let etag = etag_of(&json_with_etag(
+                &TestRequest::default().to_http_request(),
+                &record(1),
+            ));
+
+            let req = TestRequest::default()
+                .insert_header((IF_NONE_MATCH, format!("\"stale\", {}", etag)))
+                .to_http_request();
+            assert_eq!(
+                json_with_etag(&req, &record(1)).status(),
+                StatusCode::NOT_MODIFIED
+            );
+            assert_eq!(json_with_etag(&req, &record(2)).status(), StatusCode::OK);
+        }
+    }
+}
@@ -12,6 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
+
+use crate::paging::MAX_LIMIT;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct QueryPaging {
     pub offset: Option<u64>,
@@ -25,7 +29,7 @@ impl QueryPaging {
 
     pub fn limit(&self) -> u16 {
         self.limit
-            .map(|l| if l > 1024 { 1024 } else { l })
+            .map(|l| cmp::min(l, MAX_LIMIT as u16))
             .unwrap_or(10)
     }
 }
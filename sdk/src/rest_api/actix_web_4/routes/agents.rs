@@ -17,13 +17,16 @@ use futures::future;
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState,
+    },
     resources::agents::v1,
 };
 
 use super::DEFAULT_GRID_PROTOCOL_VERSION;
 
 pub async fn get_agent(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     public_key: web::Path<String>,
     query: web::Query<QueryServiceId>,
@@ -38,7 +41,7 @@ pub async fn get_agent(
                 public_key.into_inner(),
                 query.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
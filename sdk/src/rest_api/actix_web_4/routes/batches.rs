@@ -19,18 +19,33 @@ use futures_util::{
     StreamExt,
 };
 
+use crate::backend::CORRELATION_ID_HEADER;
 use crate::rest_api::{
     actix_web_4::{AcceptServiceIdParam, BackendState, QueryServiceId},
     resources::{batches::v1, error::ErrorResponse},
 };
 
+use crate::rest_api::actix_web_4::StoreState;
+
 use super::DEFAULT_GRID_PROTOCOL_VERSION;
 
+#[derive(Deserialize, Debug)]
+pub struct QuerySubmitWait {
+    pub wait: Option<String>,
+}
+
+/// Submits a batch list, returning the link to poll for the statuses of its batches.
+///
+/// If a `wait` time in seconds is given, the statuses are also returned, once the batches are
+/// committed or the time is up; `wait=0` returns them as pending without waiting.
+#[allow(clippy::too_many_arguments)]
 pub async fn submit_batches(
     req: HttpRequest,
     mut body: web::Payload,
     state: web::Data<BackendState>,
+    store_state: web::Data<StoreState>,
     query_service_id: web::Query<QueryServiceId>,
+    query_wait: web::Query<QuerySubmitWait>,
     version: ProtocolVersion,
     _: AcceptServiceIdParam,
 ) -> HttpResponse {
@@ -58,7 +73,44 @@ pub async fn submit_batches(
                 bytes.extend_from_slice(&item);
             }
 
-            match v1::submit_batches(response_url, state.client.clone(), &*bytes, service_id).await
+            let batch_store = store_state.store_factory.get_batch_store();
+            #[cfg(feature = "pike")]
+            let pike_store = store_state.store_factory.get_grid_pike_store();
+
+            if let Some(wait) = query_wait.into_inner().wait.filter(|wait| wait != "false") {
+                return match v1::submit_batches_and_wait(
+                    response_url,
+                    state.client.clone(),
+                    &bytes,
+                    service_id,
+                    correlation_id(&req),
+                    &wait,
+                    &*batch_store,
+                    #[cfg(feature = "pike")]
+                    &*pike_store,
+                )
+                .await
+                {
+                    Ok(res) => HttpResponse::Ok().json(res),
+                    Err(err) => HttpResponse::build(
+                        StatusCode::from_u16(err.status_code())
+                            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                    )
+                    .json(err),
+                };
+            }
+
+            match v1::submit_batches(
+                response_url,
+                state.client.clone(),
+                &*bytes,
+                service_id,
+                correlation_id(&req),
+                &*batch_store,
+                #[cfg(feature = "pike")]
+                &*pike_store,
+            )
+            .await
             {
                 Ok(res) => HttpResponse::Ok().json(res),
                 Err(err) => HttpResponse::build(
@@ -81,6 +133,7 @@ pub struct QueryParams {
 pub async fn get_batch_statuses(
     req: HttpRequest,
     state: web::Data<BackendState>,
+    store_state: web::Data<StoreState>,
     query: web::Query<QueryParams>,
     version: ProtocolVersion,
     _: AcceptServiceIdParam,
@@ -101,8 +154,16 @@ pub async fn get_batch_statuses(
                 }
             };
 
-            match v1::get_batch_statuses(response_url, state.client.clone(), id, wait, service_id)
-                .await
+            match v1::get_batch_statuses(
+                response_url,
+                state.client.clone(),
+                id,
+                wait,
+                service_id,
+                correlation_id(&req),
+                &*store_state.store_factory.get_batch_store(),
+            )
+            .await
             {
                 Ok(res) => HttpResponse::Ok().json(res),
                 Err(err) => HttpResponse::build(
@@ -115,6 +176,21 @@ pub async fn get_batch_statuses(
     }
 }
 
+/// Reports whether the backend can reach its node, along with the node's version and the
+/// round-trip latency of the check.
+pub async fn get_backend_health(state: web::Data<BackendState>) -> HttpResponse {
+    HttpResponse::Ok().json(v1::get_backend_health(state.client.clone()).await)
+}
+
+/// Returns the correlation id provided by the caller, if any, so it is carried through to the
+/// backend.
+fn correlation_id(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(CORRELATION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+}
+
 pub enum ProtocolVersion {
     V1,
 }
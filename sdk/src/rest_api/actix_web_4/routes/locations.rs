@@ -17,13 +17,16 @@ use futures::future;
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState,
+    },
     resources::locations::v1,
 };
 
 use super::DEFAULT_GRID_PROTOCOL_VERSION;
 
 pub async fn get_location(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     location_id: web::Path<String>,
     query: web::Query<QueryServiceId>,
@@ -37,6 +40,39 @@ pub async fn get_location(
                 store,
                 location_id.into_inner(),
                 query.into_inner().service_id.as_deref(),
+            ) {
+                Ok(res) => response::json_with_etag(&req, &res),
+                Err(err) => HttpResponse::build(
+                    StatusCode::from_u16(err.status_code())
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                )
+                .json(err),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryAsOf {
+    pub as_of: i64,
+}
+
+pub async fn get_location_attributes_as_of(
+    store_state: web::Data<StoreState>,
+    location_id: web::Path<String>,
+    query: web::Query<QueryServiceId>,
+    query_as_of: web::Query<QueryAsOf>,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    let store = store_state.store_factory.get_grid_location_store();
+    match version {
+        ProtocolVersion::V1 => {
+            match v1::get_location_attributes_as_of(
+                store,
+                location_id.into_inner(),
+                query_as_of.into_inner().as_of,
+                query.into_inner().service_id.as_deref(),
             ) {
                 Ok(res) => HttpResponse::Ok().json(res),
                 Err(err) => HttpResponse::build(
@@ -18,6 +18,8 @@ pub(crate) mod agents;
 pub(crate) mod batches;
 #[cfg(feature = "rest-api-endpoint-location")]
 pub(crate) mod locations;
+#[cfg(feature = "metrics")]
+mod metrics;
 #[cfg(feature = "rest-api-endpoint-organization")]
 pub(crate) mod organizations;
 #[cfg(feature = "rest-api-endpoint-product")]
@@ -41,6 +43,8 @@ pub use agents::*;
 pub use batches::*;
 #[cfg(feature = "rest-api-endpoint-location")]
 pub use locations::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
 #[cfg(feature = "rest-api-endpoint-organization")]
 pub use organizations::*;
 #[cfg(feature = "rest-api-endpoint-product")]
@@ -17,7 +17,9 @@ use futures::future;
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState,
+    },
     resources::organizations::v1,
 };
 
@@ -28,6 +30,7 @@ pub enum ProtocolVersion {
 }
 
 pub async fn get_organization(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     id: web::Path<String>,
     query: web::Query<QueryServiceId>,
@@ -42,7 +45,7 @@ pub async fn get_organization(
                 id.into_inner(),
                 query.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -12,52 +12,159 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use actix_web_4::{dev, http::StatusCode, web, Error, FromRequest, HttpRequest, HttpResponse};
-use futures::future;
+use actix_web_4::{
+    dev,
+    http::{header::VARY, StatusCode},
+    web, Error, FromRequest, HttpRequest, HttpResponse,
+};
+use futures::{future, stream, StreamExt};
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
+use crate::product::recall::RecallStatus;
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
-    resources::products::v1,
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, Caller, Clearance, QueryPaging, QueryServiceId,
+        StoreState, CLEARANCE_HEADER,
+    },
+    resources::{error::ErrorResponse, products::v1},
 };
 
 use super::DEFAULT_GRID_PROTOCOL_VERSION;
 
+/// The number of products read from the store at a time during an export
+const EXPORT_PAGE_SIZE: u16 = 100;
+
+/// A page of exported products as NDJSON lines, and the cursor of the page after it
+type ExportPage = (Vec<web::Bytes>, Option<String>);
+
+#[derive(Debug, Deserialize)]
+pub struct QueryExportCursor {
+    pub cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryIncludeRecalled {
+    #[serde(default)]
+    pub include_recalled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecallStatusRequest {
+    pub recall_status: RecallStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryExpand {
+    pub expand: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueryNumberFormat {
+    pub number_format: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn get_product(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     product_id: web::Path<String>,
     query: web::Query<QueryServiceId>,
+    query_expand: web::Query<QueryExpand>,
+    query_number_format: web::Query<QueryNumberFormat>,
+    clearance: Clearance,
     version: ProtocolVersion,
     _: AcceptServiceIdParam,
 ) -> HttpResponse {
     let store = store_state.store_factory.get_grid_product_store();
+    let schema_store = store_state.store_factory.get_grid_schema_store();
+    let service_id = query.into_inner().service_id;
     match version {
-        ProtocolVersion::V1 => {
-            match v1::get_product(
+        ProtocolVersion::V1 => match query_expand.into_inner().expand.as_deref() {
+            None => match v1::get_product(
                 store,
+                schema_store,
                 product_id.into_inner(),
-                query.into_inner().service_id.as_deref(),
+                service_id.as_deref(),
+                clearance.0,
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
-                Err(err) => HttpResponse::build(
-                    StatusCode::from_u16(err.status_code())
-                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
-                )
-                .json(err),
-            }
-        }
+                Ok(res) => response::json_with_etag(&req, &res),
+                Err(err) => error_response(err),
+            },
+            Some("schema") => match query_number_format
+                .into_inner()
+                .number_format
+                .map(|number_format| number_format.parse())
+                .transpose()
+                .map_err(|err: String| ErrorResponse::new(400, &err))
+                .and_then(|number_format| {
+                    v1::get_product_with_schema(
+                        store,
+                        schema_store,
+                        product_id.into_inner(),
+                        service_id.as_deref(),
+                        clearance.0,
+                        number_format.unwrap_or_default(),
+                    )
+                }) {
+                Ok(res) => response::json_with_etag(&req, &res),
+                Err(err) => error_response(err),
+            },
+            Some(expand) => error_response(ErrorResponse::new(
+                400,
+                &format!("Unsupported expand value {}; expected schema", expand),
+            )),
+        },
     }
 }
 
+/// Moves a product to the requested recall status on behalf of the authenticated caller, which
+/// must hold a permission alias allowed to make the transition.
+pub async fn set_product_recall_status(
+    store_state: web::Data<StoreState>,
+    product_id: web::Path<String>,
+    query: web::Query<QueryServiceId>,
+    caller: Caller,
+    body: web::Json<RecallStatusRequest>,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    let store = store_state.store_factory.get_grid_product_store();
+    let pike_store = store_state.store_factory.get_grid_pike_store();
+    let service_id = query.into_inner().service_id;
+    match version {
+        ProtocolVersion::V1 => match v1::set_product_recall_status(
+            store,
+            pike_store,
+            &product_id.into_inner(),
+            service_id.as_deref(),
+            &caller.0,
+            body.into_inner().recall_status,
+        ) {
+            Ok(()) => HttpResponse::NoContent().finish(),
+            Err(err) => error_response(err),
+        },
+    }
+}
+
+fn error_response(err: ErrorResponse) -> HttpResponse {
+    HttpResponse::build(
+        StatusCode::from_u16(err.status_code()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+    )
+    .json(err)
+}
+
 pub async fn list_products(
     req: HttpRequest,
     store_state: web::Data<StoreState>,
     query_service_id: web::Query<QueryServiceId>,
     query_paging: web::Query<QueryPaging>,
+    query_include_recalled: web::Query<QueryIncludeRecalled>,
+    clearance: Clearance,
     version: ProtocolVersion,
     _: AcceptServiceIdParam,
 ) -> HttpResponse {
     let store = store_state.store_factory.get_grid_product_store();
+    let schema_store = store_state.store_factory.get_grid_schema_store();
     match version {
         ProtocolVersion::V1 => {
             let paging = query_paging.into_inner();
@@ -66,12 +173,189 @@ pub async fn list_products(
                 v1::list_products(
                     url,
                     store,
+                    schema_store,
                     service_id.as_deref(),
+                    query_include_recalled.include_recalled,
                     paging.offset(),
                     paging.limit(),
+                    clearance.0,
                 )
             }) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => HttpResponse::Ok()
+                    .insert_header((VARY, CLEARANCE_HEADER))
+                    .json(res),
+                Err(err) => HttpResponse::build(
+                    StatusCode::from_u16(err.status_code())
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                )
+                .json(err),
+            }
+        }
+    }
+}
+
+/// Streams every current product as newline-delimited JSON, one product per line, in product
+/// ID order.
+///
+/// An interrupted export can be resumed by passing the product ID of the last line received as
+/// the `cursor` query parameter.
+pub async fn export_products(
+    store_state: web::Data<StoreState>,
+    query_service_id: web::Query<QueryServiceId>,
+    query_cursor: web::Query<QueryExportCursor>,
+    clearance: Clearance,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    match version {
+        ProtocolVersion::V1 => {
+            let service_id = query_service_id.into_inner().service_id;
+            let cursor = query_cursor.into_inner().cursor;
+            let clearance = clearance.0;
+
+            // Each page is read when the client is ready for it, so no more than a page of
+            // products is read ahead of the client, and reading stops once it disconnects. The
+            // store's reads block, so pages are read on actix's blocking thread pool.
+            let mut lines = stream::unfold(Some(cursor), move |cursor| {
+                let store_state = store_state.clone();
+                let service_id = service_id.clone();
+                async move {
+                    let cursor = cursor?;
+                    let page = web::block(move || {
+                        read_export_page(&store_state, service_id.as_deref(), cursor, clearance)
+                    })
+                    .await
+                    .map_err(|err| ErrorResponse::internal_error(Box::new(err)))
+                    .and_then(|page| {
+                        page.map_err(|(status_code, message)| {
+                            ErrorResponse::new(status_code, &message)
+                        })
+                    });
+                    match page {
+                        Ok((lines, next)) => Some((
+                            stream::iter(lines.into_iter().map(Ok).collect::<Vec<_>>()),
+                            next.map(Some),
+                        )),
+                        Err(err) => Some((stream::iter(vec![Err(err)]), None)),
+                    }
+                }
+            })
+            .flatten()
+            .boxed_local();
+
+            // The first line is read before responding so that a store failure is reported
+            // with its status code rather than as a truncated stream.
+            match lines.next().await {
+                Some(Err(err)) => error_response(err),
+                first => HttpResponse::Ok()
+                    .insert_header((VARY, CLEARANCE_HEADER))
+                    .content_type("application/x-ndjson")
+                    .streaming(stream::iter(first).chain(lines)),
+            }
+        }
+    }
+}
+
+/// Reads the page of products that follows the product ID `cursor` as NDJSON lines, along with
+/// the cursor of the next page, or `None` if this is the last page.
+///
+/// The error's source cannot be sent back from the blocking thread pool, so it is logged here
+/// and only its status code and message are returned.
+fn read_export_page(
+    store_state: &StoreState,
+    service_id: Option<&str>,
+    cursor: Option<String>,
+    clearance: u32,
+) -> Result<ExportPage, (u16, String)> {
+    let store = store_state.store_factory.get_grid_product_store();
+    let schema_store = store_state.store_factory.get_grid_schema_store();
+    let mut last = cursor.clone();
+    let lines = v1::export_products(
+        &*store,
+        &*schema_store,
+        service_id,
+        cursor.as_deref(),
+        EXPORT_PAGE_SIZE,
+        clearance,
+    )
+    .take(usize::from(EXPORT_PAGE_SIZE))
+    .map(|product| {
+        let product = product?;
+        last = Some(product.product_id.clone());
+        let mut line = serde_json::to_vec(&product)
+            .map_err(|err| ErrorResponse::internal_error(Box::new(err)))?;
+        line.push(b'\n');
+        Ok(web::Bytes::from(line))
+    })
+    .collect::<Result<Vec<_>, ErrorResponse>>()
+    .map_err(|err| {
+        error!("Product export failed: {}", err);
+        (err.status_code(), err.message().to_string())
+    })?;
+
+    let next = if lines.len() < usize::from(EXPORT_PAGE_SIZE) {
+        None
+    } else {
+        last
+    };
+
+    Ok((lines, next))
+}
+
+pub async fn get_bundle_components(
+    store_state: web::Data<StoreState>,
+    product_id: web::Path<String>,
+    query: web::Query<QueryServiceId>,
+    clearance: Clearance,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    let store = store_state.store_factory.get_grid_product_store();
+    let schema_store = store_state.store_factory.get_grid_schema_store();
+    match version {
+        ProtocolVersion::V1 => {
+            match v1::get_bundle_components(
+                store,
+                schema_store,
+                product_id.into_inner(),
+                query.into_inner().service_id.as_deref(),
+                clearance.0,
+            ) {
+                Ok(res) => HttpResponse::Ok()
+                    .insert_header((VARY, CLEARANCE_HEADER))
+                    .json(res),
+                Err(err) => HttpResponse::build(
+                    StatusCode::from_u16(err.status_code())
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                )
+                .json(err),
+            }
+        }
+    }
+}
+
+pub async fn list_bundles_containing(
+    store_state: web::Data<StoreState>,
+    product_id: web::Path<String>,
+    query: web::Query<QueryServiceId>,
+    clearance: Clearance,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    let store = store_state.store_factory.get_grid_product_store();
+    let schema_store = store_state.store_factory.get_grid_schema_store();
+    match version {
+        ProtocolVersion::V1 => {
+            match v1::list_bundles_containing(
+                store,
+                schema_store,
+                product_id.into_inner(),
+                query.into_inner().service_id.as_deref(),
+                clearance.0,
+            ) {
+                Ok(res) => HttpResponse::Ok()
+                    .insert_header((VARY, CLEARANCE_HEADER))
+                    .json(res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
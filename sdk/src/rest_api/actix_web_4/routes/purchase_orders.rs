@@ -19,7 +19,9 @@ use futures::future;
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState,
+    },
     resources::purchase_order::v1,
 };
 
@@ -91,13 +93,16 @@ pub async fn list_purchase_orders(
 ///
 /// # Arguments
 ///
+/// `req` - Request submitted to the endpoint
 /// `store_state` - Provides a `store_factory` to access Grid's stores
 /// `uid` - The unique identifier of the purchase order to list versions from
 /// `version_id` - Optional version ID, specifies the version to return
 /// `revision_number` - Optional revision number, specifies the revision to return
 /// `query_service_id` - Optional service ID provided in the query string
 /// `version` - Determines the type of response, corresponding to the versions of the rest API
+#[allow(clippy::too_many_arguments)]
 pub async fn get_purchase_order(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     uid: web::Path<String>,
     version_id: web::Query<QueryVersionId>,
@@ -116,7 +121,7 @@ pub async fn get_purchase_order(
                 revision_number.into_inner().revision_number,
                 query_service_id.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -179,11 +184,13 @@ pub async fn list_purchase_order_versions(
 ///
 /// # Arguments
 ///
+/// `req` - Request submitted to the endpoint
 /// `store_state` - Provides a `store_factory` to access Grid's stores
 /// `path` - Used to retrieve the purchase order UID and version ID from the request's path
 /// `query_service_id` - Optional service ID provided in the query string
 /// `version` - Determines the type of response, corresponding to the versions of the rest API
 pub async fn get_purchase_order_version(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     path: web::Path<(String, String)>,
     query_service_id: web::Query<QueryServiceId>,
@@ -200,7 +207,7 @@ pub async fn get_purchase_order_version(
                 &version_id,
                 query_service_id.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -261,12 +268,14 @@ pub async fn list_purchase_order_version_revisions(
 ///
 /// # Arguments
 ///
+/// `req` - Request submitted to the endpoint
 /// `store_state` - Provides a `store_factory` to access Grid's stores
 /// `path` - Used to retrieve the purchase order UID and version ID from the
 ///  request's path
 /// `query_service_id` - Optional service ID provided in the query string
 /// `version` - Determines the type of response, corresponding to the versions of the rest API
 pub async fn get_latest_revision_id(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     path: web::Path<(String, String)>,
     query_service_id: web::Query<QueryServiceId>,
@@ -283,7 +292,7 @@ pub async fn get_latest_revision_id(
                 version_id,
                 query_service_id.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -298,12 +307,14 @@ pub async fn get_latest_revision_id(
 ///
 /// # Arguments
 ///
+/// `req` - Request submitted to the endpoint
 /// `store_state` - Provides a `store_factory` to access Grid's stores
 /// `path` - Used to retrieve the purchase order UID, version ID, revision number from the
 ///  request's path
 /// `query_service_id` - Optional service ID provided in the query string
 /// `version` - Determines the type of response, corresponding to the versions of the rest API
 pub async fn get_purchase_order_version_revision(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     path: web::Path<(String, String, i64)>,
     query_service_id: web::Query<QueryServiceId>,
@@ -321,7 +332,7 @@ pub async fn get_purchase_order_version_revision(
                 revision_number,
                 query_service_id.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
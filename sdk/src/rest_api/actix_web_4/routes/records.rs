@@ -17,13 +17,16 @@ use futures::future;
 use futures_util::future::{FutureExt, LocalBoxFuture};
 
 use crate::rest_api::{
-    actix_web_4::{request, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState},
+    actix_web_4::{
+        request, response, AcceptServiceIdParam, QueryPaging, QueryServiceId, StoreState,
+    },
     resources::track_and_trace::v1,
 };
 
 use super::DEFAULT_GRID_PROTOCOL_VERSION;
 
 pub async fn get_record(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     record_id: web::Path<String>,
     query: web::Query<QueryServiceId>,
@@ -38,7 +41,7 @@ pub async fn get_record(
                 record_id.into_inner(),
                 query.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -82,7 +85,34 @@ pub async fn list_records(
     }
 }
 
+pub async fn get_record_custody_chain(
+    store_state: web::Data<StoreState>,
+    record_id: web::Path<String>,
+    query: web::Query<QueryServiceId>,
+    version: ProtocolVersion,
+    _: AcceptServiceIdParam,
+) -> HttpResponse {
+    let store = store_state.store_factory.get_grid_track_and_trace_store();
+    match version {
+        ProtocolVersion::V1 => {
+            match v1::get_record_custody_chain(
+                store,
+                record_id.into_inner(),
+                query.into_inner().service_id.as_deref(),
+            ) {
+                Ok(res) => HttpResponse::Ok().json(res),
+                Err(err) => HttpResponse::build(
+                    StatusCode::from_u16(err.status_code())
+                        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+                )
+                .json(err),
+            }
+        }
+    }
+}
+
 pub async fn get_record_property_name(
+    req: HttpRequest,
     store_state: web::Data<StoreState>,
     path_variables: web::Path<(String, String)>,
     query: web::Query<QueryServiceId>,
@@ -99,7 +129,7 @@ pub async fn get_record_property_name(
                 property_name,
                 query.into_inner().service_id.as_deref(),
             ) {
-                Ok(res) => HttpResponse::Ok().json(res),
+                Ok(res) => response::json_with_etag(&req, &res),
                 Err(err) => HttpResponse::build(
                     StatusCode::from_u16(err.status_code())
                         .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
@@ -87,13 +87,21 @@ pub async fn run(
                         .name("get_batch_statuses")
                         .route(web::post().to(batches::submit_batches)),
                 )
+                .route(
+                    "/healthz/backend",
+                    web::get().to(batches::get_backend_health),
+                )
         }
 
         #[cfg(feature = "rest-api-endpoint-location")]
         {
             app = app
                 .route("/location", web::get().to(locations::list_locations))
-                .route("/location/{id}", web::get().to(locations::get_location));
+                .route("/location/{id}", web::get().to(locations::get_location))
+                .route(
+                    "/location/{id}/attributes",
+                    web::get().to(locations::get_location_attributes_as_of),
+                );
         }
 
         #[cfg(feature = "rest-api-endpoint-organization")]
@@ -113,7 +121,20 @@ pub async fn run(
         {
             app = app
                 .route("/product", web::get().to(products::list_products))
-                .route("/product/{id}", web::get().to(products::get_product));
+                .route("/products/export", web::get().to(products::export_products))
+                .route("/product/{id}", web::get().to(products::get_product))
+                .route(
+                    "/product/{id}/recall_status",
+                    web::put().to(products::set_product_recall_status),
+                )
+                .route(
+                    "/product/{id}/components",
+                    web::get().to(products::get_bundle_components),
+                )
+                .route(
+                    "/product/{id}/bundles",
+                    web::get().to(products::list_bundles_containing),
+                );
         }
 
         #[cfg(feature = "rest-api-endpoint-purchase-order")]
@@ -154,6 +175,10 @@ pub async fn run(
             app = app
                 .route("/record", web::get().to(records::list_records))
                 .route("/record/{record_id}", web::get().to(records::get_record))
+                .route(
+                    "/record/{record_id}/custody",
+                    web::get().to(records::get_record_custody_chain),
+                )
                 .route(
                     "record/{record_id}/property/{property_name}",
                     web::get().to(records::get_record_property_name),
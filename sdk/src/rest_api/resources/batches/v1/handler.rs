@@ -13,23 +13,158 @@
 // limitations under the License.
 
 use std::sync::Arc;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
 
-use sawtooth_sdk::messages::batch::BatchList;
+use sawtooth_sdk::messages::batch::{BatchHeader, BatchList};
 use url::Url;
 
 use crate::backend::{
-    BackendClient, BackendClientError, BatchStatuses, SubmitBatches, DEFAULT_TIME_OUT,
+    BackendClient, BackendClientError, BatchStatus, BatchStatuses, SubmissionSummary,
+    SubmitBatchesBuilder, WaitTime, DEFAULT_TIME_OUT,
 };
+use crate::batches::store::{BatchStore, SubmissionOutcome, SubmissionRecord};
+#[cfg(feature = "metrics")]
+use crate::metrics;
+#[cfg(feature = "pike")]
+use crate::pike::store::PikeStore;
 use crate::rest_api::resources::error::ErrorResponse;
 
-use super::payloads::{BatchStatus, BatchStatusLink, BatchStatusResponse};
+use super::payloads::{BackendHealthReport, BatchStatusLink, BatchStatusResponse};
+
+/// The status reported for batches that have been submitted but not yet checked.
+const PENDING_STATUS: &str = "PENDING";
 
 pub async fn submit_batches(
     response_url: Url,
     backend_client: Arc<dyn BackendClient>,
     bytes: &[u8],
     service_id: Option<String>,
+    correlation_id: Option<String>,
+    batch_store: &dyn BatchStore,
+    #[cfg(feature = "pike")] pike_store: &dyn PikeStore,
 ) -> Result<BatchStatusLink, ErrorResponse> {
+    count_submission(
+        submit_batch_list(
+            response_url,
+            backend_client,
+            bytes,
+            service_id,
+            correlation_id,
+            batch_store,
+            #[cfg(feature = "pike")]
+            pike_store,
+        )
+        .await,
+    )
+    .map(|(link, _)| link)
+}
+
+/// Submits a batch list and then waits up to `wait` seconds for its batches to be committed,
+/// returning the status link along with the statuses of the batches. The submissions of the
+/// batches found to be committed or invalid are updated with that outcome.
+///
+/// A `wait` of "0" returns as soon as the batches are submitted, with each reported as
+/// pending. As the batches have been submitted either way, a failure to fetch their statuses
+/// is also reported as pending rather than as an error, so that the caller keeps the link to
+/// poll.
+pub async fn submit_batches_and_wait(
+    response_url: Url,
+    backend_client: Arc<dyn BackendClient>,
+    bytes: &[u8],
+    service_id: Option<String>,
+    correlation_id: Option<String>,
+    wait: &str,
+    batch_store: &dyn BatchStore,
+    #[cfg(feature = "pike")] pike_store: &dyn PikeStore,
+) -> Result<BatchStatusResponse, ErrorResponse> {
+    let wait = parse_wait(wait)?;
+
+    let (link, batch_ids) = count_submission(
+        submit_batch_list(
+            response_url,
+            backend_client.clone(),
+            bytes,
+            service_id.clone(),
+            correlation_id.clone(),
+            batch_store,
+            #[cfg(feature = "pike")]
+            pike_store,
+        )
+        .await,
+    )?;
+
+    let pending = |batch_ids: Vec<String>| {
+        batch_ids
+            .into_iter()
+            .map(|id| BatchStatus {
+                id,
+                invalid_transactions: vec![],
+                status: PENDING_STATUS.into(),
+            })
+            .collect()
+    };
+
+    if wait == 0 {
+        return Ok(BatchStatusResponse::from_batch_statuses(
+            pending(batch_ids),
+            link.link,
+        ));
+    }
+
+    let statuses = match query_batch_statuses(
+        backend_client,
+        BatchStatuses {
+            batch_ids: batch_ids.clone(),
+            wait: Some(WaitTime::from_secs(wait.into())),
+            service_id,
+            correlation_id: correlation_id.clone(),
+        },
+    )
+    .await
+    {
+        Ok(statuses) => {
+            SubmissionSummary::from_batch_statuses(&statuses).log(correlation_id.as_deref());
+            record_final_outcomes(batch_store, &statuses);
+            statuses
+        }
+        Err(err) => {
+            warn!(
+                "Unable to fetch the statuses of submitted batches, reporting them as pending: {}",
+                err
+            );
+            pending(batch_ids)
+        }
+    };
+
+    Ok(BatchStatusResponse::from_batch_statuses(
+        statuses, link.link,
+    ))
+}
+
+/// Counts a batch submission by its outcome.
+fn count_submission<T>(result: Result<T, ErrorResponse>) -> Result<T, ErrorResponse> {
+    #[cfg(feature = "metrics")]
+    {
+        let status = match &result {
+            Ok(_) => "200".to_string(),
+            Err(err) => err.status_code().to_string(),
+        };
+        metrics::increment_counter("grid_batch_submissions_total", &[("status", &status)]);
+    }
+
+    result
+}
+
+async fn submit_batch_list(
+    response_url: Url,
+    backend_client: Arc<dyn BackendClient>,
+    bytes: &[u8],
+    service_id: Option<String>,
+    correlation_id: Option<String>,
+    batch_store: &dyn BatchStore,
+    #[cfg(feature = "pike")] pike_store: &dyn PikeStore,
+) -> Result<(BatchStatusLink, Vec<String>), ErrorResponse> {
     let batch_list: BatchList = match protobuf::Message::parse_from_bytes(bytes) {
         Ok(batch_list) => batch_list,
         Err(err) => {
@@ -40,22 +175,93 @@ pub async fn submit_batches(
         }
     };
 
-    backend_client
-        .submit_batches(SubmitBatches {
-            batch_list,
-            response_url,
-            service_id,
+    super::validation::validate_batch_list(&batch_list)?;
+
+    #[cfg(feature = "pike")]
+    super::permissions::check_permissions(&batch_list, pike_store, service_id.as_deref())?;
+
+    #[cfg(feature = "metrics")]
+    let (batch_count, start) = (batch_list.get_batches().len() as u64, Instant::now());
+
+    let submitters = batch_list
+        .get_batches()
+        .iter()
+        .map(|batch| {
+            protobuf::Message::parse_from_bytes(batch.get_header())
+                .map(|header: BatchHeader| header.get_signer_public_key().to_string())
+                .unwrap_or_default()
         })
-        .await
+        .collect::<Vec<_>>();
+    let batch_ids = batch_list
+        .get_batches()
+        .iter()
+        .map(|batch| batch.get_header_signature().to_string())
+        .collect::<Vec<_>>();
+    let record_service_id = service_id.clone();
+
+    let mut builder = SubmitBatchesBuilder::for_client(backend_client.as_ref())
+        .with_batch_list(batch_list)
+        .with_response_url(response_url);
+    if let Some(service_id) = service_id {
+        builder = builder.with_service_id(service_id);
+    }
+    if let Some(correlation_id) = correlation_id {
+        builder = builder.with_correlation_id(correlation_id);
+    }
+    let submit_batches = builder
+        .build()
+        .map_err(|err| ErrorResponse::new(400, &err.to_string()))?;
+
+    let result = backend_client.submit_batches(submit_batches).await;
+
+    #[cfg(feature = "metrics")]
+    {
+        metrics::observe_duration(
+            "grid_backend_request_duration_seconds",
+            &[("operation", "submit_batches")],
+            start.elapsed(),
+        );
+        if result.is_ok() {
+            metrics::add_to_counter("grid_batches_submitted_total", &[], batch_count);
+        }
+    }
+
+    let (outcome, error_message) = match &result {
+        Ok(_) => (SubmissionOutcome::Submitted, None),
+        Err(err @ BackendClientError::BadRequestError(_)) => {
+            (SubmissionOutcome::Rejected, Some(err.to_string()))
+        }
+        Err(err) => (SubmissionOutcome::Failed, Some(err.to_string())),
+    };
+    let submitted_at = chrono::Utc::now().naive_utc();
+    for (batch_id, submitter) in batch_ids.iter().zip(submitters) {
+        record_submission(
+            batch_store,
+            SubmissionRecord {
+                batch_id: batch_id.clone(),
+                submitter,
+                service_id: record_service_id.clone(),
+                submitted_at,
+                outcome,
+                error_message: error_message.clone(),
+            },
+        );
+    }
+
+    result
         .map_err(|err| match err {
             BackendClientError::BadRequestError(ref msg) => ErrorResponse::new(400, msg),
-            BackendClientError::ConnectionError(ref msg) => ErrorResponse::new(503, msg),
+            BackendClientError::ConnectionError(ref msg)
+            | BackendClientError::DnsError(ref msg)
+            | BackendClientError::ConnectionRefusedError(ref msg)
+            | BackendClientError::TlsError(ref msg) => ErrorResponse::new(503, msg),
             BackendClientError::InternalError(ref msg) => ErrorResponse::new(500, msg),
             BackendClientError::ResourceTemporarilyUnavailableError(ref msg) => {
                 ErrorResponse::new(503, msg)
             }
+            BackendClientError::Cancelled(ref msg) => ErrorResponse::new(503, msg),
         })
-        .map(BatchStatusLink::from)
+        .map(|submission| (BatchStatusLink::from(submission), batch_ids))
 }
 
 pub async fn get_batch_statuses(
@@ -64,59 +270,133 @@ pub async fn get_batch_statuses(
     ids: String,
     wait: Option<String>,
     service_id: Option<String>,
+    correlation_id: Option<String>,
+    batch_store: &dyn BatchStore,
 ) -> Result<BatchStatusResponse, ErrorResponse> {
     let batch_ids = ids.split(',').map(ToString::to_string).collect();
 
-    // Max wait time allowed is 95% of network's configured timeout
-    let max_wait_time = (DEFAULT_TIME_OUT * 95) / 100;
-
     let wait = match wait {
-        Some(wait_time) => {
-            if wait_time == "false" {
-                None
-            } else {
-                match wait_time.parse::<u32>() {
-                    Ok(wait_time) => {
-                        if wait_time > max_wait_time {
-                            Some(max_wait_time)
-                        } else {
-                            Some(wait_time)
-                        }
-                    }
-                    Err(_) => {
-                        return Err(ErrorResponse::new(
-                            400,
-                            &format!(
-                                "Query wait has invalid value {}. \
-                             It should set to false or a time in seconds to wait for the commit",
-                                wait_time
-                            ),
-                        ));
-                    }
-                }
-            }
-        }
-
-        None => Some(max_wait_time),
+        Some(wait_time) if wait_time == "false" => None,
+        Some(wait_time) => Some(parse_wait(&wait_time)?),
+        None => Some(max_wait_time()),
     };
 
-    backend_client
-        .batch_status(BatchStatuses {
+    query_batch_statuses(
+        backend_client,
+        BatchStatuses {
             batch_ids,
-            wait,
+            wait: wait.map(|secs| WaitTime::from_secs(secs.into())),
             service_id,
+            correlation_id: correlation_id.clone(),
+        },
+    )
+    .await
+    .map(|batches| {
+        SubmissionSummary::from_batch_statuses(&batches).log(correlation_id.as_deref());
+        record_final_outcomes(batch_store, &batches);
+
+        BatchStatusResponse::from_batch_statuses(batches, response_url)
+    })
+}
+
+/// Adds a batch's submission to the submission history. A failure to record it is logged
+/// rather than returned, as the submission itself has already been handled.
+fn record_submission(batch_store: &dyn BatchStore, record: SubmissionRecord) {
+    if let Err(err) = batch_store.add_submission_record(record) {
+        error!("Failed to record batch submission: {}", err);
+    }
+}
+
+/// Updates the submissions of the batches that have been committed or found to be invalid with
+/// that outcome, giving the messages of an invalid batch's transactions as its error. Failures
+/// are logged rather than returned, as the statuses have been fetched either way.
+fn record_final_outcomes(batch_store: &dyn BatchStore, statuses: &[BatchStatus]) {
+    for status in statuses {
+        let (outcome, error_message) = match status.status.as_str() {
+            "COMMITTED" => (SubmissionOutcome::Committed, None),
+            "INVALID" => (
+                SubmissionOutcome::Invalid,
+                Some(
+                    status
+                        .invalid_transactions
+                        .iter()
+                        .map(|transaction| transaction.message.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                ),
+            ),
+            _ => continue,
+        };
+
+        if let Err(err) =
+            batch_store.update_submission_outcome(&status.id, outcome, error_message.as_deref())
+        {
+            error!(
+                "Failed to record the outcome of batch {}: {}",
+                status.id, err
+            );
+        }
+    }
+}
+
+/// Checks whether the backend can reach its node, for diagnosing connectivity.
+///
+/// The check always produces a report; an unreachable node is described by the report rather
+/// than returned as an error.
+pub async fn get_backend_health(backend_client: Arc<dyn BackendClient>) -> BackendHealthReport {
+    BackendHealthReport::from(backend_client.health().await)
+}
+
+/// Returns the longest a request may wait for batches to be committed, which is 95% of the
+/// network's configured timeout.
+fn max_wait_time() -> u32 {
+    (DEFAULT_TIME_OUT * 95) / 100
+}
+
+/// Parses a wait time in seconds, limiting it to `max_wait_time`.
+fn parse_wait(wait_time: &str) -> Result<u32, ErrorResponse> {
+    wait_time
+        .parse::<u32>()
+        .map(|wait_time| wait_time.min(max_wait_time()))
+        .map_err(|_| {
+            ErrorResponse::new(
+                400,
+                &format!(
+                    "Query wait has invalid value {}. \
+                 It should set to false or a time in seconds to wait for the commit",
+                    wait_time
+                ),
+            )
         })
-        .await
-        .map_err(|err| match err {
-            BackendClientError::BadRequestError(ref msg) => ErrorResponse::new(400, msg),
-            BackendClientError::ConnectionError(ref msg) => ErrorResponse::new(503, msg),
-            BackendClientError::InternalError(ref msg) => ErrorResponse::new(500, msg),
-            BackendClientError::ResourceTemporarilyUnavailableError(ref msg) => {
-                ErrorResponse::new(500, msg)
-            }
-        })
-        .map(|batches| BatchStatusResponse {
-            data: batches.into_iter().map(BatchStatus::from).collect(),
-            link: response_url,
-        })
+}
+
+/// Requests the statuses of batches from the backend.
+async fn query_batch_statuses(
+    backend_client: Arc<dyn BackendClient>,
+    batch_statuses: BatchStatuses,
+) -> Result<Vec<BatchStatus>, ErrorResponse> {
+    #[cfg(feature = "metrics")]
+    let start = Instant::now();
+
+    let result = backend_client.batch_status(batch_statuses).await;
+
+    #[cfg(feature = "metrics")]
+    metrics::observe_duration(
+        "grid_backend_request_duration_seconds",
+        &[("operation", "batch_status")],
+        start.elapsed(),
+    );
+
+    result.map_err(|err| match err {
+        BackendClientError::BadRequestError(ref msg) => ErrorResponse::new(400, msg),
+        BackendClientError::ConnectionError(ref msg)
+        | BackendClientError::DnsError(ref msg)
+        | BackendClientError::ConnectionRefusedError(ref msg)
+        | BackendClientError::TlsError(ref msg) => ErrorResponse::new(503, msg),
+        BackendClientError::InternalError(ref msg) => ErrorResponse::new(500, msg),
+        BackendClientError::ResourceTemporarilyUnavailableError(ref msg) => {
+            ErrorResponse::new(500, msg)
+        }
+        BackendClientError::Cancelled(ref msg) => ErrorResponse::new(503, msg),
+    })
 }
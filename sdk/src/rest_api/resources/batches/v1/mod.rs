@@ -14,6 +14,13 @@
 
 pub mod handler;
 pub mod payloads;
+#[cfg(feature = "pike")]
+mod permissions;
+mod validation;
 
-pub use handler::{get_batch_statuses, submit_batches};
-pub use payloads::{BatchStatus, BatchStatusLink, BatchStatusResponse, InvalidTransaction};
+pub use handler::{
+    get_backend_health, get_batch_statuses, submit_batches, submit_batches_and_wait,
+};
+pub use payloads::{
+    BackendHealthReport, BatchStatus, BatchStatusLink, BatchStatusResponse, InvalidTransaction,
+};
@@ -12,8 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+//! The JSON representations of batch statuses returned by the REST API.
+//!
+//! Services embedding the SDK that return batch statuses from their own APIs should convert
+//! `backend::BatchStatus` values into these types, so that the JSON shape is the same as the
+//! Grid REST API's. The serialized field names are part of the REST API and will not change.
+
 use crate::backend;
 
+/// The status of a submitted batch, serialized as:
+///
+/// ```json
+/// {
+///     "id": "<batch header signature>",
+///     "invalid_transactions": [ ... ],
+///     "status": "COMMITTED"
+/// }
+/// ```
+///
+/// `status` is one of `COMMITTED`, `INVALID`, `PENDING` or `UNKNOWN`, and
+/// `invalid_transactions` is empty unless the status is `INVALID`.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BatchStatus {
     pub id: String,
@@ -35,11 +53,25 @@ impl From<backend::BatchStatus> for BatchStatus {
     }
 }
 
+/// A transaction that caused its batch to be invalid, serialized as:
+///
+/// ```json
+/// {
+///     "id": "<transaction header signature>",
+///     "message": "<reason the transaction is invalid>",
+///     "extended_data": "<base64-encoded data>",
+///     "error_text": "<extended data as text, if human-readable>"
+/// }
+/// ```
+///
+/// `error_text` is omitted unless the backend client decoded it.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct InvalidTransaction {
     pub id: String,
     pub message: String,
     pub extended_data: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_text: Option<String>,
 }
 
 impl From<backend::InvalidTransaction> for InvalidTransaction {
@@ -48,16 +80,40 @@ impl From<backend::InvalidTransaction> for InvalidTransaction {
             id: invalid_transaction.id,
             message: invalid_transaction.message,
             extended_data: invalid_transaction.extended_data,
+            error_text: invalid_transaction.error_text,
         }
     }
 }
 
+/// The response to a batch status request, serialized as:
+///
+/// ```json
+/// {
+///     "data": [ ... ],
+///     "link": "<url of the request>"
+/// }
+/// ```
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BatchStatusResponse {
     pub data: Vec<BatchStatus>,
     pub link: String,
 }
 
+impl BatchStatusResponse {
+    /// Constructs a response for the given batch statuses, as returned by a backend client.
+    ///
+    /// # Arguments
+    ///
+    ///  * `batch_statuses` - The statuses of the requested batches
+    ///  * `link` - The url of the status request
+    pub fn from_batch_statuses(batch_statuses: Vec<backend::BatchStatus>, link: String) -> Self {
+        Self {
+            data: batch_statuses.into_iter().map(BatchStatus::from).collect(),
+            link,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct BatchStatusLink {
     pub link: String,
@@ -70,3 +126,89 @@ impl From<backend::BatchStatusLink> for BatchStatusLink {
         }
     }
 }
+
+impl From<backend::BatchSubmission> for BatchStatusLink {
+    fn from(submission: backend::BatchSubmission) -> Self {
+        Self::from(backend::BatchStatusLink::from(submission))
+    }
+}
+
+/// The report of whether the backend's node can be reached, serialized as:
+///
+/// ```json
+/// {
+///     "reachable": true,
+///     "node_url": "<url of the node>",
+///     "protocol_version": "<protocol version requested of the node>",
+///     "node_version": "<version reported by the node>",
+///     "latency_ms": 12,
+///     "last_error": null
+/// }
+/// ```
+///
+/// Fields the backend could not determine are `null`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BackendHealthReport {
+    pub reachable: bool,
+    pub node_url: Option<String>,
+    pub protocol_version: Option<String>,
+    pub node_version: Option<String>,
+    pub latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+}
+
+impl From<backend::BackendHealth> for BackendHealthReport {
+    fn from(health: backend::BackendHealth) -> Self {
+        Self {
+            reachable: health.reachable,
+            node_url: health.node_url,
+            protocol_version: health.protocol_version,
+            node_version: health.node_version,
+            latency_ms: health.latency.map(|latency| latency.as_millis() as u64),
+            last_error: health.last_error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    /// Verifies that a batch status response serializes to the shape documented for the REST
+    /// API.
+    #[test]
+    fn batch_status_response_serializes_documented_shape() {
+        let response = BatchStatusResponse::from_batch_statuses(
+            vec![backend::BatchStatus {
+                id: "batch-1".into(),
+                invalid_transactions: vec![backend::InvalidTransaction {
+                    id: "txn-1".into(),
+                    message: "invalid".into(),
+                    extended_data: "ZXJyb3I=".into(),
+                    error_text: None,
+                    scabbard_error: None,
+                }],
+                status: "INVALID".into(),
+            }],
+            "/batch_statuses?id=batch-1".into(),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&response).expect("Unable to serialize response"),
+            json!({
+                "data": [{
+                    "id": "batch-1",
+                    "invalid_transactions": [{
+                        "id": "txn-1",
+                        "message": "invalid",
+                        "extended_data": "ZXJyb3I=",
+                    }],
+                    "status": "INVALID",
+                }],
+                "link": "/batch_statuses?id=batch-1",
+            })
+        );
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks that the signers of submitted transactions hold the permissions their actions require,
+//! so that batches the smart contracts would reject are not sent to the node.
+
+use std::collections::HashMap;
+
+use sawtooth_sdk::messages::batch::BatchList;
+use sawtooth_sdk::messages::transaction::TransactionHeader;
+
+use crate::pike::store::{resolve_agent_permissions, AgentPermissions, PikeStore};
+use crate::protocol::action::GridAction;
+use crate::rest_api::resources::error::ErrorResponse;
+
+/// Rejects the batch list with a 403 if any transaction's signer lacks the permission its action
+/// requires.
+///
+/// Transactions whose family or action is not recognized, or whose payload cannot be decoded,
+/// are passed through and left to the node to validate, as are transactions whose signer is not
+/// an agent in the Pike store: the store may not have caught up with the agent's creation yet.
+pub fn check_permissions(
+    batch_list: &BatchList,
+    store: &dyn PikeStore,
+    service_id: Option<&str>,
+) -> Result<(), ErrorResponse> {
+    let mut resolved: HashMap<String, Option<AgentPermissions>> = HashMap::new();
+
+    for batch in batch_list.get_batches() {
+        for transaction in batch.get_transactions() {
+            let header: TransactionHeader =
+                match protobuf::Message::parse_from_bytes(transaction.get_header()) {
+                    Ok(header) => header,
+                    Err(_) => continue,
+                };
+
+            let permission = match GridAction::parse(
+                header.get_family_name(),
+                header.get_family_version(),
+                transaction.get_payload(),
+            )
+            .ok()
+            .as_ref()
+            .and_then(required_permission)
+            {
+                Some(permission) => permission,
+                None => continue,
+            };
+
+            let signer = header.get_signer_public_key();
+
+            if !resolved.contains_key(signer) {
+                let agent_permissions = resolve_agent_permissions(store, signer, service_id)
+                    .map_err(|err| ErrorResponse::internal_error(Box::new(err)))?;
+                resolved.insert(signer.to_string(), agent_permissions);
+            }
+
+            match &resolved[signer] {
+                Some(agent_permissions) if agent_permissions.has_permission(permission) => (),
+                Some(_) => {
+                    return Err(ErrorResponse::new(
+                        403,
+                        &format!(
+                            "Transaction {} requires the {} permission, which the signer {} \
+                            does not have",
+                            transaction.get_header_signature(),
+                            permission,
+                            signer
+                        ),
+                    ))
+                }
+                None => (),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the permission required to perform the given action, or `None` if it is not checked
+/// here.
+fn required_permission(action: &GridAction) -> Option<&'static str> {
+    match action {
+        GridAction::CreateLocation => Some("location::can-create-location"),
+        GridAction::UpdateLocation => Some("location::can-update-location"),
+        GridAction::DeleteLocation => Some("location::can-delete-location"),
+        GridAction::CreateProduct => Some("product::can-create-product"),
+        GridAction::UpdateProduct => Some("product::can-update-product"),
+        GridAction::DeleteProduct => Some("product::can-delete-product"),
+        GridAction::CreateSchema => Some("schema::can-create-schema"),
+        GridAction::UpdateSchema => Some("schema::can-update-schema"),
+        _ => None,
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+    use protobuf::{Message, RepeatedField};
+    use sabre_sdk::protocol::payload::ExecuteContractActionBuilder;
+    use sabre_sdk::protos::IntoBytes as SabreIntoBytes;
+    use sawtooth_sdk::messages::batch::Batch;
+    use sawtooth_sdk::messages::transaction::Transaction;
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{AgentBuilder, DieselConnectionPikeStore};
+    use crate::protocol::action::{GRID_PRODUCT_FAMILY_NAME, SABRE_FAMILY_NAME};
+    use crate::protocol::product::{
+        payload::{Action as ProductAction, ProductDeleteActionBuilder, ProductPayloadBuilder},
+        state::ProductNamespace,
+    };
+    use crate::protos::IntoBytes;
+
+    fn batch_list(family_name: &str, payload: Vec<u8>, signer: &str) -> BatchList {
+        let mut header = TransactionHeader::new();
+        header.set_family_name(family_name.to_string());
+        header.set_signer_public_key(signer.to_string());
+
+        let mut transaction = Transaction::new();
+        transaction.set_header(header.write_to_bytes().unwrap());
+        transaction.set_header_signature("txn".to_string());
+        transaction.set_payload(payload);
+
+        let mut batch = Batch::new();
+        batch.set_transactions(RepeatedField::from_vec(vec![transaction]));
+
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(vec![batch]));
+        batch_list
+    }
+
+    fn delete_product_batch_list(signer: &str) -> BatchList {
+        let product_payload = ProductPayloadBuilder::new()
+            .with_action(ProductAction::ProductDelete(
+                ProductDeleteActionBuilder::new()
+                    .with_product_namespace(ProductNamespace::Gs1)
+                    .with_product_id("product".to_string())
+                    .build()
+                    .unwrap(),
+            ))
+            .with_timestamp(1)
+            .build()
+            .unwrap();
+
+        let sabre_payload = ExecuteContractActionBuilder::new()
+            .with_name(GRID_PRODUCT_FAMILY_NAME.to_string())
+            .with_version("2".to_string())
+            .with_inputs(vec![])
+            .with_outputs(vec![])
+            .with_payload(product_payload.into_bytes().unwrap())
+            .into_payload_builder()
+            .unwrap()
+            .build()
+            .unwrap();
+
+        batch_list(
+            SABRE_FAMILY_NAME,
+            sabre_payload.into_bytes().unwrap(),
+            signer,
+        )
+    }
+
+    /// Verifies that product actions require the matching product permission, and that actions
+    /// of other families require none.
+    #[test]
+    fn test_required_permission() {
+        assert_eq!(
+            required_permission(&GridAction::DeleteProduct),
+            Some("product::can-delete-product")
+        );
+        assert_eq!(required_permission(&GridAction::CreateAgent), None);
+        assert_eq!(
+            required_permission(&GridAction::Other {
+                family_name: "intkey".to_string(),
+                family_version: "1.0".to_string(),
+            }),
+            None
+        );
+    }
+
+    /// Verifies that a transaction requiring a permission is rejected with a 403 when its signer
+    /// is an agent without that permission.
+    #[test]
+    fn test_check_permissions_rejects_agents_without_permission(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+        store.add_agent(
+            AgentBuilder::new()
+                .with_public_key("agent-key".to_string())
+                .with_org_id("org".to_string())
+                .with_active(true)
+                .with_roles(vec![])
+                .with_start_commit_num(1)
+                .with_end_commit_num(i64::MAX)
+                .build()?,
+        )?;
+
+        let err = check_permissions(&delete_product_batch_list("agent-key"), &store, None)
+            .expect_err("Batch should have been rejected");
+        assert_eq!(err.status_code(), 403);
+
+        Ok(())
+    }
+
+    /// Verifies that a transaction requiring a permission is passed through when its signer is
+    /// not an agent in the store, as the store may not have caught up with the agent's creation.
+    #[test]
+    fn test_check_permissions_passes_unknown_signers() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+
+        assert!(check_permissions(&delete_product_batch_list("unknown-key"), &store, None).is_ok());
+
+        Ok(())
+    }
+
+    /// Verifies that transactions whose action is not recognized are passed through, even when
+    /// the signer is not an agent.
+    #[test]
+    fn test_check_permissions_passes_unrecognized_actions() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionPikeStore::new(&conn);
+
+        assert!(check_permissions(
+            &batch_list("grid_pike", b"payload".to_vec(), "unknown-key"),
+            &store,
+            None
+        )
+        .is_ok());
+        assert!(check_permissions(
+            &batch_list(
+                GRID_PRODUCT_FAMILY_NAME,
+                b"not a payload".to_vec(),
+                "unknown-key"
+            ),
+            &store,
+            None
+        )
+        .is_ok());
+
+        Ok(())
+    }
+}
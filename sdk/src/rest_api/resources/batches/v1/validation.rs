@@ -0,0 +1,151 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Structural validation of submitted batch lists, so that malformed submissions are rejected
+//! without being sent to the node.
+
+use sawtooth_sdk::messages::batch::BatchList;
+
+use crate::rest_api::resources::error::ErrorResponse;
+
+/// Rejects the batch list with a 400 if it contains no batches, if a batch contains no
+/// transactions, or if a batch or transaction is missing its header or header signature.
+///
+/// Signatures are only checked for presence; verifying them is left to the node.
+pub fn validate_batch_list(batch_list: &BatchList) -> Result<(), ErrorResponse> {
+    if batch_list.get_batches().is_empty() {
+        return Err(ErrorResponse::new(400, "Batch list contains no batches"));
+    }
+
+    for (i, batch) in batch_list.get_batches().iter().enumerate() {
+        if batch.get_header_signature().is_empty() {
+            return Err(ErrorResponse::new(
+                400,
+                &format!("Batch {} has no header signature", i),
+            ));
+        }
+
+        let batch_id = batch.get_header_signature();
+
+        if batch.get_header().is_empty() {
+            return Err(ErrorResponse::new(
+                400,
+                &format!("Batch {} has no header", batch_id),
+            ));
+        }
+
+        if batch.get_transactions().is_empty() {
+            return Err(ErrorResponse::new(
+                400,
+                &format!("Batch {} contains no transactions", batch_id),
+            ));
+        }
+
+        for (j, transaction) in batch.get_transactions().iter().enumerate() {
+            if transaction.get_header_signature().is_empty() {
+                return Err(ErrorResponse::new(
+                    400,
+                    &format!(
+                        "Transaction {} of batch {} has no header signature",
+                        j, batch_id
+                    ),
+                ));
+            }
+
+            if transaction.get_header().is_empty() {
+                return Err(ErrorResponse::new(
+                    400,
+                    &format!(
+                        "Transaction {} of batch {} has no header",
+                        transaction.get_header_signature(),
+                        batch_id
+                    ),
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use protobuf::RepeatedField;
+    use sawtooth_sdk::messages::batch::Batch;
+    use sawtooth_sdk::messages::transaction::Transaction;
+
+    fn transaction(header: &[u8], header_signature: &str) -> Transaction {
+        let mut transaction = Transaction::new();
+        transaction.set_header(header.to_vec());
+        transaction.set_header_signature(header_signature.to_string());
+        transaction
+    }
+
+    fn batch_list(header: &[u8], header_signature: &str, txns: Vec<Transaction>) -> BatchList {
+        let mut batch = Batch::new();
+        batch.set_header(header.to_vec());
+        batch.set_header_signature(header_signature.to_string());
+        batch.set_transactions(RepeatedField::from_vec(txns));
+
+        let mut batch_list = BatchList::new();
+        batch_list.set_batches(RepeatedField::from_vec(vec![batch]));
+        batch_list
+    }
+
+    /// Verifies that a batch list whose batches and transactions all have headers and
+    /// signatures is accepted.
+    #[test]
+    fn test_validate_batch_list_accepts_complete_batches() {
+        let batch_list = batch_list(b"header", "batch", vec![transaction(b"header", "txn")]);
+
+        assert!(validate_batch_list(&batch_list).is_ok());
+    }
+
+    /// Verifies that empty batch lists and batches, and missing headers and signatures, are
+    /// rejected with a 400 naming the problem.
+    #[test]
+    fn test_validate_batch_list_rejects_incomplete_batches() {
+        let cases = vec![
+            (BatchList::new(), "Batch list contains no batches"),
+            (
+                batch_list(b"header", "", vec![transaction(b"header", "txn")]),
+                "Batch 0 has no header signature",
+            ),
+            (
+                batch_list(b"", "batch", vec![transaction(b"header", "txn")]),
+                "Batch batch has no header",
+            ),
+            (
+                batch_list(b"header", "batch", vec![]),
+                "Batch batch contains no transactions",
+            ),
+            (
+                batch_list(b"header", "batch", vec![transaction(b"header", "")]),
+                "Transaction 0 of batch batch has no header signature",
+            ),
+            (
+                batch_list(b"header", "batch", vec![transaction(b"", "txn")]),
+                "Transaction txn of batch batch has no header",
+            ),
+        ];
+
+        for (batch_list, message) in cases {
+            let err = validate_batch_list(&batch_list).expect_err("Batch list should be invalid");
+            assert_eq!(err.status_code(), 400);
+            assert_eq!(err.message(), message);
+        }
+    }
+}
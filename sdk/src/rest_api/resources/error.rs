@@ -15,6 +15,8 @@
 use std::error;
 use std::fmt;
 
+use crate::error::ValidationErrors;
+
 /// Generic error designed with the expectation that it may be converted into an HTTP response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ErrorResponse {
@@ -24,6 +26,10 @@ pub struct ErrorResponse {
     /// The message that would be returned in an HTTP response
     message: String,
 
+    /// The fields that failed validation, if the request was rejected for failing validation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    errors: Option<ValidationErrors>,
+
     /// Wrapped error that is not exposed in the HTTP response
     #[serde(skip_serializing)]
     #[serde(skip_deserializing)]
@@ -51,6 +57,39 @@ impl ErrorResponse {
         Self {
             status_code,
             message: message.to_string(),
+            errors: None,
+            source: None,
+        }
+    }
+
+    /// Create a new 400 ErrorResponse listing the fields that failed validation
+    ///
+    /// # Arguments
+    ///
+    /// * `errors` - The validation errors of every invalid field
+    ///
+    /// # Examples
+    /// ```
+    /// use crate::grid_sdk::error::{ValidationError, ValidationErrors};
+    /// use crate::grid_sdk::rest_api::resources::error::ErrorResponse;
+    ///
+    /// let errors = ValidationErrors::from(ValidationError::new(
+    ///     "owner".to_string(),
+    ///     "required".to_string(),
+    ///     "owner is not set".to_string(),
+    /// ));
+    ///
+    /// let response = ErrorResponse::validation_failed(errors);
+    ///
+    /// assert_eq!(400, response.status_code());
+    /// assert_eq!("owner is not set (owner)", response.message());
+    /// assert_eq!("required", response.validation_errors().unwrap().errors()[0].code());
+    /// ```
+    pub fn validation_failed(errors: ValidationErrors) -> Self {
+        Self {
+            status_code: 400,
+            message: errors.to_string(),
+            errors: Some(errors),
             source: None,
         }
     }
@@ -77,6 +116,7 @@ impl ErrorResponse {
         Self {
             status_code: 500,
             message: "An internal error occurred".to_string(),
+            errors: None,
             source: Some(source),
         }
     }
@@ -88,6 +128,10 @@ impl ErrorResponse {
     pub fn message(&self) -> &str {
         &self.message
     }
+
+    pub fn validation_errors(&self) -> Option<&ValidationErrors> {
+        self.errors.as_ref()
+    }
 }
 
 impl error::Error for ErrorResponse {
@@ -117,6 +161,8 @@ mod tests {
     use serde::Deserialize;
     use serde_json::Result;
 
+    use crate::error::ValidationError;
+
     // Deny any unknown fields so we can test for data leaks
     #[derive(Deserialize)]
     #[serde(deny_unknown_fields)]
@@ -165,4 +211,42 @@ mod tests {
 
         Ok(())
     }
+
+    /// Verifies that a validation failure serializes every invalid field with its code, so that
+    /// clients can handle each failure without parsing the message.
+    #[test]
+    fn test_error_response_validation_failed_json_serialization() -> Result<()> {
+        let mut errors = ValidationErrors::new();
+        errors.push(ValidationError::new(
+            "product_id".into(),
+            "invalid_check_digit".into(),
+            "GTIN 012345678906 has an invalid check digit".into(),
+        ));
+        errors.push(ValidationError::new(
+            "owner".into(),
+            "required".into(),
+            "owner is not set".into(),
+        ));
+
+        let json = serde_json::to_value(ErrorResponse::validation_failed(errors))?;
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "status_code": 400,
+                "message": "GTIN 012345678906 has an invalid check digit (product_id); \
+                    owner is not set (owner)",
+                "errors": [
+                    {
+                        "field": "product_id",
+                        "code": "invalid_check_digit",
+                        "message": "GTIN 012345678906 has an invalid check digit"
+                    },
+                    {"field": "owner", "code": "required", "message": "owner is not set"}
+                ]
+            })
+        );
+
+        Ok(())
+    }
 }
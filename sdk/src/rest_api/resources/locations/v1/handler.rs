@@ -20,7 +20,7 @@ use crate::{
     rest_api::resources::{error::ErrorResponse, paging::v1::Paging},
 };
 
-use super::payloads::{LocationListSlice, LocationSlice};
+use super::payloads::{LocationListSlice, LocationPropertyValueSlice, LocationSlice};
 
 pub fn list_locations<'a>(
     url: Url,
@@ -43,6 +43,9 @@ pub fn list_locations<'a>(
                 LocationStoreError::ConstraintViolationError(err) => {
                     ErrorResponse::new(400, &format!("{}", err))
                 }
+                LocationStoreError::InvalidStateError(err) => {
+                    ErrorResponse::new(409, &format!("{}", err))
+                }
                 LocationStoreError::ResourceTemporarilyUnavailableError(_) => {
                     ErrorResponse::new(503, "Service Unavailable")
                 }
@@ -74,6 +77,9 @@ pub fn get_location<'a>(
             LocationStoreError::ConstraintViolationError(err) => {
                 ErrorResponse::new(400, &format!("{}", err))
             }
+            LocationStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(409, &format!("{}", err))
+            }
             LocationStoreError::ResourceTemporarilyUnavailableError(_) => {
                 ErrorResponse::new(503, "Service Unavailable")
             }
@@ -86,3 +92,47 @@ pub fn get_location<'a>(
         ErrorResponse::new(404, &format!("Location {} not found", location_id))
     })?))
 }
+
+/// Returns the properties of a location that are in effect at `as_of`, in seconds since the
+/// epoch.
+///
+/// A property with more than one value in effect at `as_of` has overlapping effective ranges,
+/// which is reported as a conflict.
+pub fn get_location_attributes_as_of<'a>(
+    store: Box<dyn LocationStore + 'a>,
+    location_id: String,
+    as_of: i64,
+    service_id: Option<&str>,
+) -> Result<Vec<LocationPropertyValueSlice>, ErrorResponse> {
+    let map_err = |err| match err {
+        LocationStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+        LocationStoreError::ConstraintViolationError(err) => {
+            ErrorResponse::new(400, &format!("{}", err))
+        }
+        LocationStoreError::InvalidStateError(err) => ErrorResponse::new(409, &format!("{}", err)),
+        LocationStoreError::ResourceTemporarilyUnavailableError(_) => {
+            ErrorResponse::new(503, "Service Unavailable")
+        }
+        LocationStoreError::NotFoundError(_) => {
+            ErrorResponse::new(404, &format!("Location {} not found", location_id))
+        }
+    };
+
+    if store
+        .get_location(&location_id, service_id)
+        .map_err(map_err)?
+        .is_none()
+    {
+        return Err(ErrorResponse::new(
+            404,
+            &format!("Location {} not found", location_id),
+        ));
+    }
+
+    Ok(store
+        .location_attributes_as_of(&location_id, as_of, service_id)
+        .map_err(map_err)?
+        .into_iter()
+        .map(LocationPropertyValueSlice::from)
+        .collect())
+}
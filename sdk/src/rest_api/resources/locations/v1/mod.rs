@@ -15,5 +15,5 @@
 pub mod handler;
 pub mod payloads;
 
-pub use handler::{get_location, list_locations};
+pub use handler::{get_location, get_location_attributes_as_of, list_locations};
 pub use payloads::{LatLongSlice, LocationListSlice, LocationPropertyValueSlice, LocationSlice};
@@ -67,6 +67,12 @@ pub struct LocationPropertyValueSlice {
     pub enum_value: Option<i32>,
     pub struct_values: Option<Vec<LocationPropertyValueSlice>>,
     pub lat_long_value: Option<LatLongSlice>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_from: Option<i64>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_to: Option<i64>,
 }
 
 impl From<LocationAttribute> for LocationPropertyValueSlice {
@@ -87,6 +93,8 @@ impl From<LocationAttribute> for LocationPropertyValueSlice {
                     .collect()
             }),
             lat_long_value: attribute.lat_long_value.map(LatLongSlice::from),
+            effective_from: attribute.effective_from,
+            effective_to: attribute.effective_to,
         }
     }
 }
@@ -12,55 +12,109 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::convert::TryFrom;
 use url::Url;
 
 use crate::{
-    product::store::{ProductStore, ProductStoreError},
+    pike::store::{resolve_agent_permissions, PikeStore},
+    product::{
+        recall::RecallStatus,
+        store::{ProductStore, ProductStoreError, ProductStream},
+    },
     rest_api::resources::{error::ErrorResponse, paging::v1::Paging},
+    schema::store::{Schema, SchemaStore, SchemaStoreError},
 };
 
-use super::payloads::{ProductListSlice, ProductSlice};
+use super::payloads::{
+    BundleComponentSlice, NumberFormat, ProductListSlice, ProductSchemaViewSlice, ProductSlice,
+};
 
 pub fn list_products<'a>(
     url: Url,
     store: Box<dyn ProductStore + 'a>,
+    schema_store: Box<dyn SchemaStore + 'a>,
     service_id: Option<&str>,
+    include_recalled: bool,
     offset: u64,
     limit: u16,
+    clearance: u32,
 ) -> Result<ProductListSlice, ErrorResponse> {
     let offset = i64::try_from(offset).unwrap_or(i64::MAX);
 
     let limit = i64::try_from(limit).unwrap_or(10);
 
     let product_list = store
-        .list_products(service_id, offset, limit)
+        .list_products(service_id, include_recalled, offset, limit)
         .map_err(|err| match err {
             ProductStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
             ProductStoreError::ConstraintViolationError(err) => {
                 ErrorResponse::new(400, &format!("{}", err))
             }
+            ProductStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
             ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
                 ErrorResponse::new(503, "Service Unavailable")
             }
             ProductStoreError::NotFoundError(_) => ErrorResponse::new(404, "Resource not found"),
         })?;
 
+    let mut access = PropertyAccess::new(&*schema_store, service_id, clearance);
     let data = product_list
         .data()
         .into_iter()
-        .map(ProductSlice::from)
-        .collect();
+        .map(|product| access.filter(ProductSlice::from(product)))
+        .collect::<Result<_, _>>()?;
 
     let paging = Paging::new(url, product_list.paging().clone(), service_id);
 
     Ok(ProductListSlice { data, paging })
 }
 
+/// Returns an iterator over the current products in product ID order, starting after the
+/// product ID `cursor`, reading them from the store a page at a time.
+pub fn export_products<'a>(
+    store: &'a dyn ProductStore,
+    schema_store: &'a dyn SchemaStore,
+    service_id: Option<&str>,
+    cursor: Option<&str>,
+    page_size: u16,
+    clearance: u32,
+) -> impl Iterator<Item = Result<ProductSlice, ErrorResponse>> + 'a {
+    let mut access = PropertyAccess::new(schema_store, service_id, clearance);
+    ProductStream::new(store, service_id, cursor)
+        .with_page_size(page_size)
+        .map(move |product| {
+            let product = product.map(ProductSlice::from).map_err(|err| match err {
+                ProductStoreError::InternalError(err) => {
+                    ErrorResponse::internal_error(Box::new(err))
+                }
+                ProductStoreError::ConstraintViolationError(err) => {
+                    ErrorResponse::new(400, &format!("{}", err))
+                }
+                ProductStoreError::InvalidStateError(err) => {
+                    ErrorResponse::new(400, &format!("{}", err))
+                }
+                ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
+                ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
+                    ErrorResponse::new(503, "Service Unavailable")
+                }
+                ProductStoreError::NotFoundError(_) => {
+                    ErrorResponse::new(404, "Resource not found")
+                }
+            })?;
+            access.filter(product)
+        })
+}
+
 pub fn get_product<'a>(
     store: Box<dyn ProductStore + 'a>,
+    schema_store: Box<dyn SchemaStore + 'a>,
     product_id: String,
     service_id: Option<&str>,
+    clearance: u32,
 ) -> Result<ProductSlice, ErrorResponse> {
     let product = store
         .get_product(&product_id, service_id)
@@ -69,6 +123,264 @@ pub fn get_product<'a>(
             ProductStoreError::ConstraintViolationError(err) => {
                 ErrorResponse::new(400, &format!("{}", err))
             }
+            ProductStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
+            ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
+                ErrorResponse::new(503, "Service Unavailable")
+            }
+            ProductStoreError::NotFoundError(_) => {
+                ErrorResponse::new(404, &format!("Product {} not found", product_id))
+            }
+        })?;
+
+    let product =
+        ProductSlice::from(product.ok_or_else(|| {
+            ErrorResponse::new(404, &format!("Product {} not found", product_id))
+        })?);
+
+    PropertyAccess::new(&*schema_store, service_id, clearance).filter(product)
+}
+
+/// Moves a product to a recall status on behalf of the agent with the given public key.
+///
+/// The agent must be an agent of the organization that owns the product, and the permissions its
+/// roles grant it are the permission aliases checked against the `recall` subworkflow.
+pub fn set_product_recall_status<'a>(
+    store: Box<dyn ProductStore + 'a>,
+    pike_store: Box<dyn PikeStore + 'a>,
+    product_id: &str,
+    service_id: Option<&str>,
+    public_key: &str,
+    recall_status: RecallStatus,
+) -> Result<(), ErrorResponse> {
+    let map_err = |err: ProductStoreError| match err {
+        ProductStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+        ProductStoreError::ConstraintViolationError(err) => {
+            ErrorResponse::new(400, &format!("{}", err))
+        }
+        ProductStoreError::InvalidStateError(err) => ErrorResponse::new(403, &format!("{}", err)),
+        ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
+        ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
+            ErrorResponse::new(503, "Service Unavailable")
+        }
+        ProductStoreError::NotFoundError(_) => {
+            ErrorResponse::new(404, &format!("Product {} not found", product_id))
+        }
+    };
+
+    let product = store
+        .get_product(product_id, service_id)
+        .map_err(map_err)?
+        .ok_or_else(|| ErrorResponse::new(404, &format!("Product {} not found", product_id)))?;
+
+    let agent_permissions = resolve_agent_permissions(&*pike_store, public_key, service_id)
+        .map_err(|err| ErrorResponse::internal_error(Box::new(err)))?
+        .filter(|agent_permissions| agent_permissions.agent().org_id() == product.owner())
+        .ok_or_else(|| {
+            ErrorResponse::new(
+                403,
+                &format!(
+                    "{} is not an agent of {}, which owns product {}",
+                    public_key,
+                    product.owner(),
+                    product_id
+                ),
+            )
+        })?;
+
+    let permission_aliases = agent_permissions
+        .permissions()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    store
+        .set_product_recall_status(product_id, service_id, recall_status, &permission_aliases)
+        .map_err(map_err)
+}
+
+/// Fetches a product along with the schema of its namespace, and interprets its property
+/// values with the schema's definitions.
+///
+/// If the product's namespace has no schema, or the schema has not been stored, the values are
+/// returned uninterpreted. Properties above the caller's `clearance` are omitted, and numbers
+/// are encoded in the given format.
+pub fn get_product_with_schema<'a>(
+    product_store: Box<dyn ProductStore + 'a>,
+    schema_store: Box<dyn SchemaStore + 'a>,
+    product_id: String,
+    service_id: Option<&str>,
+    clearance: u32,
+    number_format: NumberFormat,
+) -> Result<ProductSchemaViewSlice, ErrorResponse> {
+    let product = product_store
+        .get_product(&product_id, service_id)
+        .map_err(|err| match err {
+            ProductStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+            ProductStoreError::ConstraintViolationError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
+            ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
+                ErrorResponse::new(503, "Service Unavailable")
+            }
+            ProductStoreError::NotFoundError(_) => {
+                ErrorResponse::new(404, &format!("Product {} not found", product_id))
+            }
+        })?
+        .ok_or_else(|| ErrorResponse::new(404, &format!("Product {} not found", product_id)))?;
+
+    let mut access = PropertyAccess::new(&*schema_store, service_id, clearance);
+    let schema = access.schema(product.product_namespace())?;
+
+    Ok(ProductSchemaViewSlice::new(
+        &product,
+        schema,
+        clearance,
+        number_format,
+    ))
+}
+
+/// Returns the name of the schema that products in a namespace are validated against.
+fn schema_name(product_namespace: &str) -> Option<&'static str> {
+    match product_namespace.to_lowercase().as_str() {
+        "gs1" => Some("gs1_product"),
+        _ => None,
+    }
+}
+
+/// Omits the property values of products that the caller is not cleared to read.
+///
+/// The access levels of a product's properties are given by the schema of its namespace, which
+/// is fetched once and reused for the other products of a request. Products whose namespace has
+/// no schema, or whose schema has not been stored, have only public properties.
+struct PropertyAccess<'a> {
+    schema_store: &'a dyn SchemaStore,
+    service_id: Option<String>,
+    clearance: u32,
+    schemas: HashMap<&'static str, Option<Schema>>,
+}
+
+impl<'a> PropertyAccess<'a> {
+    fn new(schema_store: &'a dyn SchemaStore, service_id: Option<&str>, clearance: u32) -> Self {
+        Self {
+            schema_store,
+            service_id: service_id.map(String::from),
+            clearance,
+            schemas: HashMap::new(),
+        }
+    }
+
+    /// Returns the schema of a product namespace, if it has one that has been stored.
+    fn schema(&mut self, product_namespace: &str) -> Result<Option<&Schema>, ErrorResponse> {
+        let name = match schema_name(product_namespace) {
+            Some(name) => name,
+            None => return Ok(None),
+        };
+
+        if !self.schemas.contains_key(name) {
+            let schema = self
+                .schema_store
+                .get_schema(name, self.service_id.as_deref())
+                .map_err(|err| match err {
+                    SchemaStoreError::InternalError(err) => {
+                        ErrorResponse::internal_error(Box::new(err))
+                    }
+                    SchemaStoreError::ConstraintViolationError(err) => {
+                        ErrorResponse::new(400, &format!("{}", err))
+                    }
+                    SchemaStoreError::ResourceTemporarilyUnavailableError(_) => {
+                        ErrorResponse::new(503, "Service Unavailable")
+                    }
+                    SchemaStoreError::NotFoundError(_) => {
+                        ErrorResponse::new(404, &format!("Schema {} not found", name))
+                    }
+                })?;
+            self.schemas.insert(name, schema);
+        }
+
+        Ok(self.schemas.get(name).and_then(Option::as_ref))
+    }
+
+    fn filter(&mut self, mut product: ProductSlice) -> Result<ProductSlice, ErrorResponse> {
+        let clearance = self.clearance;
+        let schema = self.schema(&product.product_namespace)?;
+        product.retain_accessible(schema, clearance);
+        Ok(product)
+    }
+
+    fn filter_component(
+        &mut self,
+        mut component: BundleComponentSlice,
+    ) -> Result<BundleComponentSlice, ErrorResponse> {
+        component.product = component
+            .product
+            .map(|product| self.filter(product))
+            .transpose()?;
+        component.components = component
+            .components
+            .into_iter()
+            .map(|child| self.filter_component(child))
+            .collect::<Result<_, _>>()?;
+        Ok(component)
+    }
+}
+
+pub fn get_bundle_components<'a>(
+    store: Box<dyn ProductStore + 'a>,
+    schema_store: Box<dyn SchemaStore + 'a>,
+    product_id: String,
+    service_id: Option<&str>,
+    clearance: u32,
+) -> Result<Vec<BundleComponentSlice>, ErrorResponse> {
+    let components = store
+        .get_bundle_components(&product_id, service_id)
+        .map_err(|err| match err {
+            ProductStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+            ProductStoreError::ConstraintViolationError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
+            ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
+                ErrorResponse::new(503, "Service Unavailable")
+            }
+            ProductStoreError::NotFoundError(_) => {
+                ErrorResponse::new(404, &format!("Product {} not found", product_id))
+            }
+        })?;
+
+    let mut access = PropertyAccess::new(&*schema_store, service_id, clearance);
+    components
+        .iter()
+        .map(|component| access.filter_component(BundleComponentSlice::from(component)))
+        .collect()
+}
+
+pub fn list_bundles_containing<'a>(
+    store: Box<dyn ProductStore + 'a>,
+    schema_store: Box<dyn SchemaStore + 'a>,
+    product_id: String,
+    service_id: Option<&str>,
+    clearance: u32,
+) -> Result<Vec<ProductSlice>, ErrorResponse> {
+    let bundles = store
+        .list_bundles_containing(&product_id, service_id)
+        .map_err(|err| match err {
+            ProductStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+            ProductStoreError::ConstraintViolationError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::InvalidStateError(err) => {
+                ErrorResponse::new(400, &format!("{}", err))
+            }
+            ProductStoreError::ConflictError(ref msg) => ErrorResponse::new(409, msg),
             ProductStoreError::ResourceTemporarilyUnavailableError(_) => {
                 ErrorResponse::new(503, "Service Unavailable")
             }
@@ -77,7 +389,116 @@ pub fn get_product<'a>(
             }
         })?;
 
-    Ok(ProductSlice::from(product.ok_or_else(|| {
-        ErrorResponse::new(404, &format!("Product {} not found", product_id))
-    })?))
+    let mut access = PropertyAccess::new(&*schema_store, service_id, clearance);
+    bundles
+        .into_iter()
+        .map(|bundle| access.filter(ProductSlice::from(bundle)))
+        .collect()
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::pike::store::{AgentBuilder, DieselConnectionPikeStore, RoleBuilder};
+    use crate::product::{
+        recall::RECALL_MANAGER_ALIAS,
+        store::{DieselConnectionProductStore, ProductBuilder},
+        MAX_COMMIT_NUM,
+    };
+
+    const OWNER: &str = "owner-org";
+
+    fn populate(conn: &SqliteConnection) {
+        let product_store = DieselConnectionProductStore::new(conn);
+        product_store
+            .add_product(
+                ProductBuilder::default()
+                    .with_product_id("product".to_string())
+                    .with_product_address("address".to_string())
+                    .with_product_namespace("GS1".to_string())
+                    .with_owner(OWNER.to_string())
+                    .with_start_commit_number(1)
+                    .with_end_commit_number(MAX_COMMIT_NUM)
+                    .build()
+                    .expect("Unable to build product"),
+            )
+            .expect("Unable to add product");
+
+        let pike_store = DieselConnectionPikeStore::new(conn);
+        for org_id in &[OWNER, "other-org"] {
+            pike_store
+                .add_role(
+                    RoleBuilder::new()
+                        .with_name("recaller".to_string())
+                        .with_org_id(org_id.to_string())
+                        .with_description("recalls products".to_string())
+                        .with_active(true)
+                        .with_permissions(vec![RECALL_MANAGER_ALIAS.to_string()])
+                        .with_start_commit_num(1)
+                        .with_end_commit_num(i64::MAX)
+                        .build()
+                        .expect("Unable to build role"),
+                )
+                .expect("Unable to add role");
+        }
+
+        for (public_key, org_id, roles) in &[
+            ("manager-key", OWNER, vec!["recaller".to_string()]),
+            ("member-key", OWNER, vec![]),
+            ("outsider-key", "other-org", vec!["recaller".to_string()]),
+        ] {
+            pike_store
+                .add_agent(
+                    AgentBuilder::new()
+                        .with_public_key(public_key.to_string())
+                        .with_org_id(org_id.to_string())
+                        .with_active(true)
+                        .with_roles(roles.clone())
+                        .with_start_commit_num(1)
+                        .with_end_commit_num(i64::MAX)
+                        .build()
+                        .expect("Unable to build agent"),
+                )
+                .expect("Unable to add agent");
+        }
+    }
+
+    /// Verifies that only an agent of the owning organization whose roles grant the recall
+    /// manager alias may recall a product.
+    #[test]
+    fn test_set_product_recall_status() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        populate(&conn);
+
+        let set_status = |public_key: &str| {
+            set_product_recall_status(
+                Box::new(DieselConnectionProductStore::new(&conn)),
+                Box::new(DieselConnectionPikeStore::new(&conn)),
+                "product",
+                None,
+                public_key,
+                RecallStatus::Recalled,
+            )
+        };
+
+        for public_key in &["unknown-key", "outsider-key", "member-key"] {
+            let err = set_status(public_key).expect_err("Recall should have been rejected");
+            assert_eq!(err.status_code(), 403);
+        }
+
+        set_status("manager-key")?;
+        assert_eq!(
+            DieselConnectionProductStore::new(&conn)
+                .get_product("product", None)?
+                .map(|product| product.recall_status()),
+            Some(RecallStatus::Recalled)
+        );
+
+        Ok(())
+    }
 }
@@ -15,5 +15,11 @@
 mod handler;
 mod payloads;
 
-pub use handler::{get_product, list_products};
-pub use payloads::{LatLongSlice, ProductListSlice, ProductPropertyValueSlice, ProductSlice};
+pub use handler::{
+    export_products, get_bundle_components, get_product, get_product_with_schema,
+    list_bundles_containing, list_products, set_product_recall_status,
+};
+pub use payloads::{
+    BundleComponentSlice, LatLongSlice, NumberFormat, ProductListSlice, ProductPropertyValueSlice,
+    ProductSchemaViewSlice, ProductSlice, PropertyViewSlice,
+};
@@ -12,9 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+use serde_json::{json, Value as JsonValue};
+
 use crate::{
-    product::store::{LatLongValue, Product, PropertyValue},
+    product::{
+        recall::RecallStatus,
+        store::{BundleComponent, LatLongValue, Product, PropertyValue},
+    },
     rest_api::resources::paging::v1::Paging,
+    schema::{
+        decimal::format_decimal,
+        store::{PropertyDefinition, Schema},
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +40,11 @@ pub struct ProductSlice {
     pub service_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_updated: Option<i64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<String>,
+    /// Whether the product is `active`, or has been `withdrawn` or `recalled`
+    #[serde(default)]
+    pub recall_status: RecallStatus,
 }
 
 impl From<Product> for ProductSlice {
@@ -44,6 +61,99 @@ impl From<Product> for ProductSlice {
                 .collect(),
             service_id: product.service_id().map(String::from),
             last_updated: product.last_updated().cloned(),
+            components: product.components().to_vec(),
+            recall_status: product.recall_status(),
+        }
+    }
+}
+
+impl ProductSlice {
+    /// Removes the property values the caller is not cleared to read, according to the access
+    /// levels of the schema's property definitions.
+    ///
+    /// Values without a definition are public.
+    pub fn retain_accessible(&mut self, schema: Option<&Schema>, clearance: u32) {
+        let definitions = schema.map(|schema| &schema.properties[..]).unwrap_or(&[]);
+        retain_accessible(&mut self.properties, definitions, clearance);
+    }
+}
+
+fn retain_accessible(
+    values: &mut Vec<ProductPropertyValueSlice>,
+    definitions: &[PropertyDefinition],
+    clearance: u32,
+) {
+    values.retain(|value| is_accessible(find_definition(definitions, &value.name), clearance));
+    for value in values.iter_mut() {
+        if let Some(definition) = find_definition(definitions, &value.name) {
+            retain_accessible_children(value, definition, clearance);
+        }
+    }
+}
+
+/// Removes the struct values of a value the caller is not cleared to read; the elements of a
+/// list are stored as its struct values, and are each checked against the list's definition.
+fn retain_accessible_children(
+    value: &mut ProductPropertyValueSlice,
+    definition: &PropertyDefinition,
+    clearance: u32,
+) {
+    if is_list(&value.data_type) {
+        for element in value.struct_values.iter_mut() {
+            retain_accessible_children(element, definition, clearance);
+        }
+    } else {
+        retain_accessible(
+            &mut value.struct_values,
+            &definition.struct_properties,
+            clearance,
+        );
+    }
+}
+
+/// Returns whether a stored data type, such as `List(Number)`, is a list.
+fn is_list(data_type: &str) -> bool {
+    data_type.to_lowercase().starts_with("list(")
+}
+
+fn find_definition<'a>(
+    definitions: &'a [PropertyDefinition],
+    name: &str,
+) -> Option<&'a PropertyDefinition> {
+    definitions
+        .iter()
+        .find(|definition| definition.name == name)
+}
+
+/// Returns whether a caller with the given clearance may read values of a property; a property
+/// without a definition is public.
+fn is_accessible(definition: Option<&PropertyDefinition>, clearance: u32) -> bool {
+    definition
+        .map(|definition| definition.access_level <= i64::from(clearance))
+        .unwrap_or(true)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleComponentSlice {
+    pub component_id: String,
+    pub missing: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<ProductSlice>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<BundleComponentSlice>,
+}
+
+impl From<&BundleComponent> for BundleComponentSlice {
+    fn from(component: &BundleComponent) -> Self {
+        Self {
+            component_id: component.component_id().to_string(),
+            missing: component.product().is_none(),
+            product: component.product().cloned().map(ProductSlice::from),
+            components: component
+                .components()
+                .iter()
+                .map(BundleComponentSlice::from)
+                .collect(),
         }
     }
 }
@@ -126,3 +236,512 @@ impl From<LatLongValue> for LatLongSlice {
         }
     }
 }
+
+/// A product with its property values interpreted according to the definitions of its schema.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductSchemaViewSlice {
+    pub product_id: String,
+    pub product_address: String,
+    pub product_namespace: String,
+    pub owner: String,
+    /// The schema the values were interpreted with, if the product's namespace has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_name: Option<String>,
+    pub properties: Vec<PropertyViewSlice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<i64>,
+    /// Whether the product is `active`, or has been `withdrawn` or `recalled`
+    #[serde(default)]
+    pub recall_status: RecallStatus,
+}
+
+impl ProductSchemaViewSlice {
+    /// Interprets a product's property values with the property definitions of a schema.
+    ///
+    /// Properties without a definition are still included, with their values unscaled and
+    /// enums as their raw index. Properties above the caller's `clearance` are omitted, and
+    /// numbers are encoded in the given format.
+    pub fn new(
+        product: &Product,
+        schema: Option<&Schema>,
+        clearance: u32,
+        number_format: NumberFormat,
+    ) -> Self {
+        let definitions = schema.map(|schema| &schema.properties[..]).unwrap_or(&[]);
+
+        Self {
+            product_id: product.product_id().to_string(),
+            product_address: product.product_address().to_string(),
+            product_namespace: product.product_namespace().to_string(),
+            owner: product.owner().to_string(),
+            schema_name: schema.map(|schema| schema.name.clone()),
+            properties: product
+                .properties()
+                .iter()
+                .filter(|value| {
+                    is_accessible(
+                        find_definition(definitions, value.property_name()),
+                        clearance,
+                    )
+                })
+                .map(|value| PropertyViewSlice::new(value, definitions, clearance, number_format))
+                .collect(),
+            service_id: product.service_id().map(String::from),
+            last_updated: product.last_updated().cloned(),
+            recall_status: product.recall_status(),
+        }
+    }
+}
+
+/// How numbers, including latitudes and longitudes, are encoded in a `ProductSchemaViewSlice`.
+///
+/// Neither format passes through a float, so fixed-point values such as 0.1 are exact.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumberFormat {
+    /// A decimal string, such as `"19.99"`
+    #[default]
+    Decimal,
+    /// The stored integer with the number of decimal places it is scaled by, such as
+    /// `{"value": 1999, "scale": 2}`
+    Scaled,
+}
+
+impl FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "decimal" => Ok(NumberFormat::Decimal),
+            "scaled" => Ok(NumberFormat::Scaled),
+            _ => Err(format!(
+                "Unsupported number format {}; expected decimal or scaled",
+                s
+            )),
+        }
+    }
+}
+
+/// A property value typed and labeled according to its definition.
+///
+/// Numbers are scaled by the definition's exponent and encoded in the requested
+/// `NumberFormat`, so that no precision is lost, enums are rendered as the label of their
+/// option, latitudes and longitudes are rendered in degrees, bytes are rendered as base64 and
+/// lists are rendered as arrays.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PropertyViewSlice {
+    pub name: String,
+    pub data_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub value: JsonValue,
+}
+
+impl PropertyViewSlice {
+    fn new(
+        property_value: &PropertyValue,
+        definitions: &[PropertyDefinition],
+        clearance: u32,
+        number_format: NumberFormat,
+    ) -> Self {
+        let definition = find_definition(definitions, property_value.property_name());
+
+        Self {
+            name: property_value.property_name().to_string(),
+            data_type: property_value.data_type().to_string(),
+            description: definition
+                .map(|definition| definition.description.clone())
+                .filter(|description| !description.is_empty()),
+            value: interpret_value(property_value, definition, clearance, number_format),
+        }
+    }
+}
+
+/// The exponent of the microdegrees latitudes and longitudes are stored in.
+const LAT_LONG_EXPONENT: i64 = -6;
+
+fn interpret_value(
+    property_value: &PropertyValue,
+    definition: Option<&PropertyDefinition>,
+    clearance: u32,
+    number_format: NumberFormat,
+) -> JsonValue {
+    match property_value.data_type().to_lowercase().as_str() {
+        "bytes" => property_value
+            .bytes_value()
+            .map(|bytes| JsonValue::from(base64::encode(bytes)))
+            .unwrap_or(JsonValue::Null),
+        "boolean" => property_value
+            .boolean_value()
+            .map(JsonValue::from)
+            .unwrap_or(JsonValue::Null),
+        "number" => property_value
+            .number_value()
+            .map(|number| {
+                let exponent = definition.map(|def| def.number_exponent).unwrap_or(0);
+                encode_number(number, exponent, number_format)
+            })
+            .unwrap_or(JsonValue::Null),
+        "string" => property_value
+            .string_value()
+            .map(JsonValue::from)
+            .unwrap_or(JsonValue::Null),
+        "enum" => property_value
+            .enum_value()
+            .map(|index| {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|index| definition.and_then(|def| def.enum_options.get(index)))
+                    .map(|label| JsonValue::from(label.as_str()))
+                    .unwrap_or_else(|| JsonValue::from(index))
+            })
+            .unwrap_or(JsonValue::Null),
+        "struct" => {
+            let definitions = definition
+                .map(|def| &def.struct_properties[..])
+                .unwrap_or(&[]);
+            JsonValue::Object(
+                property_value
+                    .struct_values()
+                    .iter()
+                    .filter_map(|value| {
+                        let definition = find_definition(definitions, value.property_name());
+                        is_accessible(definition, clearance).then(|| {
+                            (
+                                value.property_name().to_string(),
+                                interpret_value(value, definition, clearance, number_format),
+                            )
+                        })
+                    })
+                    .collect(),
+            )
+        }
+        data_type if is_list(data_type) => {
+            // The elements of a list are stored as its struct values, named after their index
+            let mut elements = property_value.struct_values().iter().collect::<Vec<_>>();
+            elements.sort_by_key(|element| element.property_name().parse::<usize>().ok());
+            JsonValue::Array(
+                elements
+                    .into_iter()
+                    .map(|element| interpret_value(element, definition, clearance, number_format))
+                    .collect(),
+            )
+        }
+        "latlong" | "lat_long" => property_value
+            .lat_long_value()
+            .map(|lat_long| {
+                json!({
+                    "latitude": encode_number(lat_long.latitude, LAT_LONG_EXPONENT, number_format),
+                    "longitude": encode_number(lat_long.longitude, LAT_LONG_EXPONENT, number_format),
+                })
+            })
+            .unwrap_or(JsonValue::Null),
+        _ => JsonValue::Null,
+    }
+}
+
+/// Encodes `value * 10^exponent` in the given format.
+fn encode_number(value: i64, exponent: i64, number_format: NumberFormat) -> JsonValue {
+    match number_format {
+        NumberFormat::Decimal => JsonValue::from(format_decimal(value, exponent)),
+        NumberFormat::Scaled => json!({ "value": value, "scale": -exponent }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::product::store::{ProductBuilder, PropertyValueBuilder};
+
+    fn property_value(name: &str, data_type: &str) -> PropertyValueBuilder {
+        PropertyValueBuilder::default()
+            .with_product_id("00012345678905".to_string())
+            .with_product_address("address".to_string())
+            .with_property_name(name.to_string())
+            .with_data_type(data_type.to_string())
+            .with_start_commit_number(0)
+            .with_end_commit_number(i64::MAX)
+    }
+
+    fn definition(name: &str, data_type: &str) -> PropertyDefinition {
+        PropertyDefinition {
+            start_commit_num: 0,
+            end_commit_num: i64::MAX,
+            name: name.to_string(),
+            schema_name: "gs1_product".to_string(),
+            data_type: data_type.to_string(),
+            required: false,
+            description: format!("The product's {}", name),
+            access_level: 0,
+            number_exponent: 0,
+            enum_options: vec![],
+            struct_properties: vec![],
+            service_id: None,
+        }
+    }
+
+    /// Verifies that numbers are scaled by their exponent, enums are labeled with their option,
+    /// and values without a definition are left uninterpreted.
+    #[test]
+    fn test_product_schema_view() {
+        let product = ProductBuilder::default()
+            .with_product_id("00012345678905".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("Gs1".to_string())
+            .with_owner("org".to_string())
+            .with_start_commit_number(0)
+            .with_end_commit_number(i64::MAX)
+            .with_properties(vec![
+                property_value("price", "Number")
+                    .with_number_value(Some(1999))
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("color", "Enum")
+                    .with_enum_value(Some(1))
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("weight", "Number")
+                    .with_number_value(Some(5))
+                    .build()
+                    .expect("Unable to build property value"),
+            ])
+            .build()
+            .expect("Unable to build product");
+        let schema = Schema {
+            name: "gs1_product".to_string(),
+            description: "".to_string(),
+            owner: "org".to_string(),
+            properties: vec![
+                PropertyDefinition {
+                    number_exponent: -2,
+                    ..definition("price", "Number")
+                },
+                PropertyDefinition {
+                    enum_options: vec!["red".to_string(), "blue".to_string()],
+                    ..definition("color", "Enum")
+                },
+            ],
+            service_id: None,
+            start_commit_num: 0,
+            end_commit_num: i64::MAX,
+            last_updated: None,
+        };
+
+        let view = ProductSchemaViewSlice::new(&product, Some(&schema), 0, NumberFormat::Decimal);
+
+        assert_eq!(view.schema_name.as_deref(), Some("gs1_product"));
+        assert_eq!(
+            view.properties
+                .iter()
+                .map(|property| (property.name.as_str(), &property.value))
+                .collect::<Vec<_>>(),
+            vec![
+                ("price", &json!("19.99")),
+                ("color", &json!("blue")),
+                ("weight", &json!("5")),
+            ]
+        );
+        assert_eq!(
+            view.properties[0].description.as_deref(),
+            Some("The product's price")
+        );
+        assert!(view.properties[2].description.is_none());
+    }
+
+    /// Verifies that lists are rendered as arrays in the order of their elements' indexes, with
+    /// each element interpreted by the list's definition.
+    #[test]
+    fn test_product_schema_view_lists() {
+        let price = |index: &str, number| {
+            property_value(index, "Number")
+                .with_number_value(Some(number))
+                .build()
+                .expect("Unable to build property value")
+        };
+        let product = ProductBuilder::default()
+            .with_product_id("00012345678905".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("Gs1".to_string())
+            .with_owner("org".to_string())
+            .with_start_commit_number(0)
+            .with_end_commit_number(i64::MAX)
+            .with_properties(vec![
+                property_value("prices", "List(Number)")
+                    .with_struct_values(vec![price("1", 250), price("0", 1999)])
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("discounts", "List(Number)")
+                    .build()
+                    .expect("Unable to build property value"),
+            ])
+            .build()
+            .expect("Unable to build product");
+        let schema = Schema {
+            name: "gs1_product".to_string(),
+            description: "".to_string(),
+            owner: "org".to_string(),
+            properties: vec![
+                PropertyDefinition {
+                    number_exponent: -2,
+                    ..definition("prices", "List(Number)")
+                },
+                definition("discounts", "List(Number)"),
+            ],
+            service_id: None,
+            start_commit_num: 0,
+            end_commit_num: i64::MAX,
+            last_updated: None,
+        };
+
+        let view = ProductSchemaViewSlice::new(&product, Some(&schema), 0, NumberFormat::Decimal);
+
+        assert_eq!(view.properties[0].value, json!(["19.99", "2.50"]));
+        assert_eq!(view.properties[1].value, json!([]));
+    }
+
+    /// Verifies that a number with more significant digits than a float holds is encoded
+    /// exactly in both number formats.
+    #[test]
+    fn test_product_schema_view_number_formats() {
+        let product = ProductBuilder::default()
+            .with_product_id("00012345678905".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("Gs1".to_string())
+            .with_owner("org".to_string())
+            .with_start_commit_number(0)
+            .with_end_commit_number(i64::MAX)
+            .with_properties(vec![property_value("quantity", "Number")
+                .with_number_value(Some(1_234_567_890_123_456_789))
+                .build()
+                .expect("Unable to build property value")])
+            .build()
+            .expect("Unable to build product");
+        let schema = Schema {
+            name: "gs1_product".to_string(),
+            description: "".to_string(),
+            owner: "org".to_string(),
+            properties: vec![PropertyDefinition {
+                number_exponent: -10,
+                ..definition("quantity", "Number")
+            }],
+            service_id: None,
+            start_commit_num: 0,
+            end_commit_num: i64::MAX,
+            last_updated: None,
+        };
+
+        let view = ProductSchemaViewSlice::new(&product, Some(&schema), 0, NumberFormat::Decimal);
+        assert_eq!(view.properties[0].value, json!("123456789.0123456789"));
+
+        let view = ProductSchemaViewSlice::new(&product, Some(&schema), 0, NumberFormat::Scaled);
+        assert_eq!(
+            view.properties[0].value,
+            json!({ "value": 1_234_567_890_123_456_789_i64, "scale": 10 })
+        );
+        let serialized = serde_json::to_string(&view).expect("Unable to serialize view");
+        assert!(
+            serialized.contains(r#""value":1234567890123456789"#),
+            "{}",
+            serialized
+        );
+    }
+
+    /// Verifies that values above the clearance are removed, including struct values, and that
+    /// values without a definition are kept.
+    #[test]
+    fn test_retain_accessible() {
+        let product = ProductBuilder::default()
+            .with_product_id("00012345678905".to_string())
+            .with_product_address("address".to_string())
+            .with_product_namespace("Gs1".to_string())
+            .with_owner("org".to_string())
+            .with_start_commit_number(0)
+            .with_end_commit_number(i64::MAX)
+            .with_properties(vec![
+                property_value("price", "String")
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("cost", "String")
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("supplier", "Struct")
+                    .with_struct_values(vec![
+                        property_value("name", "String")
+                            .build()
+                            .expect("Unable to build property value"),
+                        property_value("contract", "String")
+                            .build()
+                            .expect("Unable to build property value"),
+                    ])
+                    .build()
+                    .expect("Unable to build property value"),
+                property_value("color", "String")
+                    .build()
+                    .expect("Unable to build property value"),
+            ])
+            .build()
+            .expect("Unable to build product");
+        let schema = Schema {
+            name: "gs1_product".to_string(),
+            description: "".to_string(),
+            owner: "org".to_string(),
+            properties: vec![
+                definition("price", "String"),
+                PropertyDefinition {
+                    access_level: 2,
+                    ..definition("cost", "String")
+                },
+                PropertyDefinition {
+                    access_level: 1,
+                    struct_properties: vec![
+                        definition("name", "String"),
+                        PropertyDefinition {
+                            access_level: 2,
+                            ..definition("contract", "String")
+                        },
+                    ],
+                    ..definition("supplier", "Struct")
+                },
+            ],
+            service_id: None,
+            start_commit_num: 0,
+            end_commit_num: i64::MAX,
+            last_updated: None,
+        };
+
+        let names = |slice: &ProductSlice| {
+            slice
+                .properties
+                .iter()
+                .map(|value| {
+                    let children = value
+                        .struct_values
+                        .iter()
+                        .map(|child| child.name.clone())
+                        .collect::<Vec<_>>();
+                    (value.name.clone(), children)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut public = ProductSlice::from(product.clone());
+        public.retain_accessible(Some(&schema), 0);
+        assert_eq!(
+            names(&public),
+            vec![("price".into(), vec![]), ("color".into(), vec![])]
+        );
+
+        let mut cleared = ProductSlice::from(product);
+        cleared.retain_accessible(Some(&schema), 1);
+        assert_eq!(
+            names(&cleared),
+            vec![
+                ("price".into(), vec![]),
+                ("supplier".into(), vec!["name".to_string()]),
+                ("color".into(), vec![]),
+            ]
+        );
+    }
+}
@@ -60,6 +60,8 @@ pub struct PropertyDefinitionSlice {
     pub data_type: String,
     pub required: bool,
     pub description: String,
+    #[serde(default)]
+    pub access_level: i64,
     pub number_exponent: i64,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<PropertyDefinitionSlice>,
@@ -76,6 +78,7 @@ impl From<PropertyDefinition> for PropertyDefinitionSlice {
             data_type: definition.data_type.clone(),
             required: definition.required,
             description: definition.description.clone(),
+            access_level: definition.access_level,
             number_exponent: definition.number_exponent,
             enum_options: definition.enum_options.clone(),
             struct_properties: definition
@@ -604,6 +604,10 @@ pub struct PropertyValue {
     enum_value: u32,
     struct_values: Vec<PropertyValue>,
     lat_long_value: LatLong,
+    #[serde(default)]
+    effective_from: Option<i64>,
+    #[serde(default)]
+    effective_to: Option<i64>,
 }
 
 impl PropertyValue {
@@ -642,6 +646,14 @@ impl PropertyValue {
     pub fn lat_long_value(&self) -> &LatLong {
         &self.lat_long_value
     }
+
+    pub fn effective_from(&self) -> Option<i64> {
+        self.effective_from
+    }
+
+    pub fn effective_to(&self) -> Option<i64> {
+        self.effective_to
+    }
 }
 
 impl FromProto<protos::schema_state::PropertyValue> for PropertyValue {
@@ -663,6 +675,8 @@ impl FromProto<protos::schema_state::PropertyValue> for PropertyValue {
                 .map(PropertyValue::from_proto)
                 .collect::<Result<Vec<PropertyValue>, ProtoConversionError>>()?,
             lat_long_value: property_value.get_lat_long_value().clone().into_native()?,
+            effective_from: Some(property_value.get_effective_from()).filter(|bound| *bound != 0),
+            effective_to: Some(property_value.get_effective_to()).filter(|bound| *bound != 0),
         })
     }
 }
@@ -688,6 +702,8 @@ impl FromNative<PropertyValue> for protos::schema_state::PropertyValue {
         ));
         proto_property_value
             .set_lat_long_value(property_value.lat_long_value().clone().into_proto()?);
+        proto_property_value.set_effective_from(property_value.effective_from().unwrap_or(0));
+        proto_property_value.set_effective_to(property_value.effective_to().unwrap_or(0));
         Ok(proto_property_value)
     }
 }
@@ -727,6 +743,8 @@ pub struct PropertyValueBuilder {
     enum_value: Option<u32>,
     struct_values: Vec<PropertyValue>,
     lat_long_value: Option<LatLong>,
+    effective_from: Option<i64>,
+    effective_to: Option<i64>,
 }
 
 impl PropertyValueBuilder {
@@ -779,6 +797,16 @@ impl PropertyValueBuilder {
         self
     }
 
+    pub fn with_effective_from(mut self, effective_from: i64) -> PropertyValueBuilder {
+        self.effective_from = Some(effective_from);
+        self
+    }
+
+    pub fn with_effective_to(mut self, effective_to: i64) -> PropertyValueBuilder {
+        self.effective_to = Some(effective_to);
+        self
+    }
+
     pub fn build(self) -> Result<PropertyValue, BuilderError> {
         let name = self
             .name
@@ -875,6 +903,8 @@ impl PropertyValueBuilder {
             enum_value,
             struct_values,
             lat_long_value,
+            effective_from: self.effective_from,
+            effective_to: self.effective_to,
         })
     }
 }
@@ -905,6 +935,12 @@ impl FromProto<protos::schema_state::PropertyDefinition_DataType> for DataType {
             protos::schema_state::PropertyDefinition_DataType::ENUM => Ok(DataType::Enum),
             protos::schema_state::PropertyDefinition_DataType::STRUCT => Ok(DataType::Struct),
             protos::schema_state::PropertyDefinition_DataType::LAT_LONG => Ok(DataType::LatLong),
+            protos::schema_state::PropertyDefinition_DataType::LIST => {
+                Err(ProtoConversionError::InvalidTypeError(
+                    "Cannot convert PropertyDefinition_DataType LIST; lists are not supported."
+                        .to_string(),
+                ))
+            }
             protos::schema_state::PropertyDefinition_DataType::UNSET_DATA_TYPE => {
                 Err(ProtoConversionError::InvalidTypeError(
                     "Cannot convert PropertyDefinition_DataType with type unset.".to_string(),
@@ -24,7 +24,8 @@ use crate::{
 };
 
 use super::payloads::{
-    PropertySlice, PropertyValueSlice, RecordListSlice, RecordSlice, StructPropertyValue,
+    CustodyTransferSlice, PropertySlice, PropertyValueSlice, RecordListSlice, RecordSlice,
+    StructPropertyValue,
 };
 
 pub fn list_records<'a>(
@@ -231,6 +232,37 @@ pub fn get_record<'a>(
     ))
 }
 
+/// Returns the custody chain of a record, oldest transfer first.
+pub fn get_record_custody_chain<'a>(
+    store: Box<dyn TrackAndTraceStore + 'a>,
+    record_id: String,
+    service_id: Option<&str>,
+) -> Result<Vec<CustodyTransferSlice>, ErrorResponse> {
+    let map_err = |err: TrackAndTraceStoreError| match err {
+        TrackAndTraceStoreError::InternalError(err) => ErrorResponse::internal_error(Box::new(err)),
+        TrackAndTraceStoreError::ConstraintViolationError(err) => {
+            ErrorResponse::new(400, &format!("{}", err))
+        }
+        TrackAndTraceStoreError::ResourceTemporarilyUnavailableError(_) => {
+            ErrorResponse::new(503, "Service Unavailable")
+        }
+        TrackAndTraceStoreError::NotFoundError(_) => {
+            ErrorResponse::new(404, &format!("Record {} not found", record_id))
+        }
+    };
+
+    store
+        .get_record(&record_id, service_id)
+        .map_err(map_err)?
+        .ok_or_else(|| ErrorResponse::new(404, &format!("Record {} not found", record_id)))?;
+
+    let chain = store
+        .list_custody_chain(&record_id, service_id)
+        .map_err(map_err)?;
+
+    Ok(chain.into_iter().map(CustodyTransferSlice::from).collect())
+}
+
 pub fn get_record_property<'a>(
     store: Box<dyn TrackAndTraceStore + 'a>,
     record_id: String,
@@ -15,8 +15,8 @@
 pub mod handler;
 pub mod payloads;
 
-pub use handler::{get_record, get_record_property, list_records};
+pub use handler::{get_record, get_record_custody_chain, get_record_property, list_records};
 pub use payloads::{
-    AssociatedAgentSlice, LatLong, PropertySlice, PropertyValueSlice, ProposalSlice,
-    RecordListSlice, RecordSlice, ReporterSlice, StructPropertyValue, Value,
+    AssociatedAgentSlice, CustodyTransferSlice, LatLong, PropertySlice, PropertyValueSlice,
+    ProposalSlice, RecordListSlice, RecordSlice, ReporterSlice, StructPropertyValue, Value,
 };
@@ -15,7 +15,7 @@
 use crate::{
     rest_api::resources::{error::ErrorResponse, paging::v1::Paging},
     track_and_trace::store::{
-        AssociatedAgent, LatLongValue, Property, Proposal, Record,
+        AssociatedAgent, CustodyTransfer, LatLongValue, Property, Proposal, Record,
         ReportedValueReporterToAgentMetadata,
     },
 };
@@ -39,6 +39,25 @@ impl From<AssociatedAgent> for AssociatedAgentSlice {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustodyTransferSlice {
+    pub agent_id: String,
+    pub timestamp: u64,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_id: Option<String>,
+}
+
+impl From<CustodyTransfer> for CustodyTransferSlice {
+    fn from(transfer: CustodyTransfer) -> Self {
+        Self {
+            agent_id: transfer.agent_id,
+            timestamp: transfer.timestamp as u64,
+            service_id: transfer.service_id,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProposalSlice {
     pub receiving_agent: String,
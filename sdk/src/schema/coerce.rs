@@ -0,0 +1,280 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of string inputs, such as CLI arguments or CSV cells, into property values typed
+//! according to a schema's property definitions.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::hex::parse_hex;
+use crate::protocol::schema::state::{
+    DataType, LatLongBuilder, PropertyDefinition, PropertyValue, PropertyValueBuilder,
+};
+
+use super::decimal::parse_decimal;
+
+/// Errors that may occur while coercing a string into a `PropertyValue`.
+#[derive(Debug)]
+pub enum CoercionError {
+    /// The input could not be parsed as the property's declared type.
+    InvalidValue { property: String, message: String },
+    /// The property's type cannot be represented by a single string.
+    UnsupportedType { property: String },
+}
+
+impl Error for CoercionError {}
+
+impl fmt::Display for CoercionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CoercionError::InvalidValue { property, message } => {
+                write!(f, "Invalid value for property {}: {}", property, message)
+            }
+            CoercionError::UnsupportedType { property } => write!(
+                f,
+                "Property {} is a struct or list and cannot be set from a string",
+                property
+            ),
+        }
+    }
+}
+
+/// Parses `input` into a `PropertyValue` of the type declared by `definition`.
+///
+/// Input is interpreted by type as follows:
+///
+/// * `Bytes` - a hex string
+/// * `Boolean` - `true` or `false`, ignoring case
+/// * `Number` - a decimal number, stored scaled by the definition's number exponent; input with
+///   more precision than the exponent allows is rejected
+/// * `String` - the input, unchanged
+/// * `Enum` - one of the definition's enum options, or the index of one
+/// * `LatLong` - `<latitude>,<longitude>`, each in millionths of a degree
+///
+/// `Struct` and `List` properties cannot be coerced from a single string.
+pub fn coerce(
+    definition: &PropertyDefinition,
+    input: &str,
+) -> Result<PropertyValue, CoercionError> {
+    let property = definition.name();
+    let invalid = |message: String| CoercionError::InvalidValue {
+        property: property.to_string(),
+        message,
+    };
+
+    let builder = PropertyValueBuilder::new()
+        .with_name(property.to_string())
+        .with_data_type(definition.data_type().clone());
+
+    let builder = match definition.data_type() {
+        DataType::Bytes => {
+            builder.with_bytes_value(parse_hex(input).map_err(|err| invalid(err.to_string()))?)
+        }
+        DataType::Boolean => {
+            let value = match input.trim().to_lowercase().as_str() {
+                "true" => true,
+                "false" => false,
+                _ => return Err(invalid(format!("{} is not true or false", input))),
+            };
+            builder.with_boolean_value(value)
+        }
+        DataType::Number => builder.with_number_value(
+            parse_decimal(input, i64::from(*definition.number_exponent())).map_err(invalid)?,
+        ),
+        DataType::String => builder.with_string_value(input.to_string()),
+        DataType::Enum => {
+            let options = definition.enum_options();
+            let input = input.trim();
+            let index = match options.iter().position(|option| option == input) {
+                Some(index) => index,
+                None => match input.parse::<usize>() {
+                    Ok(index) if index < options.len() => index,
+                    _ => {
+                        return Err(invalid(format!(
+                            "{} is not one of the options: {}",
+                            input,
+                            options.join(", ")
+                        )))
+                    }
+                },
+            };
+            builder.with_enum_value(index as u32)
+        }
+        DataType::LatLong => {
+            let parts = input
+                .split(',')
+                .map(|part| part.trim().parse::<i64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|err| {
+                    invalid(format!(
+                        "{} is not a valid latitude longitude: {}",
+                        input, err
+                    ))
+                })?;
+
+            if parts.len() != 2 {
+                return Err(invalid(format!(
+                    "{} is not a valid latitude longitude: expected <latitude>,<longitude>",
+                    input
+                )));
+            }
+
+            builder.with_lat_long_value(
+                LatLongBuilder::new()
+                    .with_lat_long(parts[0], parts[1])
+                    .build()
+                    .map_err(|err| invalid(err.to_string()))?,
+            )
+        }
+        DataType::Struct | DataType::List(_) => {
+            return Err(CoercionError::UnsupportedType {
+                property: property.to_string(),
+            })
+        }
+    };
+
+    builder.build().map_err(|err| invalid(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::schema::state::PropertyDefinitionBuilder;
+
+    fn definition(data_type: DataType) -> PropertyDefinitionBuilder {
+        PropertyDefinitionBuilder::new()
+            .with_name("test".to_string())
+            .with_data_type(data_type)
+    }
+
+    /// Verifies that hex strings are coerced to bytes and invalid hex is rejected.
+    #[test]
+    fn test_coerce_bytes() {
+        let def = definition(DataType::Bytes).build().unwrap();
+
+        assert_eq!(coerce(&def, "0a0b").unwrap().bytes_value(), &[10, 11]);
+        assert!(matches!(
+            coerce(&def, "zz"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+    }
+
+    /// Verifies that booleans are coerced ignoring case and other input is rejected.
+    #[test]
+    fn test_coerce_boolean() {
+        let def = definition(DataType::Boolean).build().unwrap();
+
+        assert!(*coerce(&def, "TRUE").unwrap().boolean_value());
+        assert!(!*coerce(&def, "false").unwrap().boolean_value());
+        assert!(matches!(
+            coerce(&def, "yes"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+    }
+
+    /// Verifies that numbers are scaled by the definition's exponent, and that input with too
+    /// much precision or no digits is rejected.
+    #[test]
+    fn test_coerce_number() {
+        let def = definition(DataType::Number)
+            .with_number_exponent(-2)
+            .build()
+            .unwrap();
+
+        assert_eq!(*coerce(&def, "12.34").unwrap().number_value(), 1234);
+        assert_eq!(*coerce(&def, "-5").unwrap().number_value(), -500);
+        assert!(matches!(
+            coerce(&def, "1.234"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            coerce(&def, "abc"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+
+        let def = definition(DataType::Number)
+            .with_number_exponent(3)
+            .build()
+            .unwrap();
+
+        assert_eq!(*coerce(&def, "5000").unwrap().number_value(), 5);
+        assert!(matches!(
+            coerce(&def, "5001"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+    }
+
+    /// Verifies that strings are passed through unchanged.
+    #[test]
+    fn test_coerce_string() {
+        let def = definition(DataType::String).build().unwrap();
+
+        assert_eq!(coerce(&def, " a b ").unwrap().string_value(), " a b ");
+    }
+
+    /// Verifies that enum options are coerced by name or index, and values outside the options
+    /// are rejected.
+    #[test]
+    fn test_coerce_enum() {
+        let def = definition(DataType::Enum)
+            .with_enum_options(vec!["red".to_string(), "green".to_string()])
+            .build()
+            .unwrap();
+
+        assert_eq!(*coerce(&def, "green").unwrap().enum_value(), 1);
+        assert_eq!(*coerce(&def, "0").unwrap().enum_value(), 0);
+        assert!(matches!(
+            coerce(&def, "blue"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            coerce(&def, "2"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+    }
+
+    /// Verifies that latitude longitude pairs are coerced and out-of-range values are rejected.
+    #[test]
+    fn test_coerce_lat_long() {
+        let def = definition(DataType::LatLong).build().unwrap();
+
+        let value = coerce(&def, "44977753,-93265015").unwrap();
+        assert_eq!(*value.lat_long_value().latitude(), 44977753);
+        assert_eq!(*value.lat_long_value().longitude(), -93265015);
+        assert!(matches!(
+            coerce(&def, "100000000,0"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            coerce(&def, "1"),
+            Err(CoercionError::InvalidValue { .. })
+        ));
+    }
+
+    /// Verifies that struct properties cannot be coerced.
+    #[test]
+    fn test_coerce_struct() {
+        let def = definition(DataType::Struct)
+            .with_struct_properties(vec![definition(DataType::String).build().unwrap()])
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            coerce(&def, "anything"),
+            Err(CoercionError::UnsupportedType { .. })
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between decimal strings and the integers number properties are stored as.
+//!
+//! A number property is stored as the integer `value` for which the number is
+//! `value * 10^exponent`, where `exponent` is the number exponent of the property's definition,
+//! so that a value of `1234` with an exponent of `-2` is the number `12.34`. Both conversions
+//! work on the digits directly, without passing through a float, so they are exact.
+
+use std::convert::TryFrom;
+
+/// The largest number of places the digits of a nonzero number can be shifted left and still
+/// fit in an `i64`
+const MAX_SHIFT: i64 = 18;
+
+/// Parses a decimal string, such as `"-12.34"`, into the integer `value` for which the decimal
+/// is `value * 10^exponent`.
+///
+/// It is an error for the decimal to have more decimal places than the exponent allows, or for
+/// the value not to fit in an `i64`.
+pub fn parse_decimal(decimal: &str, exponent: i64) -> Result<i64, String> {
+    let trimmed = decimal.trim();
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("{} is not a decimal number", decimal));
+    }
+
+    let mut digits = format!("{}{}", whole, fraction);
+    if digits.chars().all(|c| c == '0') {
+        return Ok(0);
+    }
+
+    let out_of_range = || format!("{} is out of range", decimal);
+
+    // The number of places the digits are shifted left to give the stored integer
+    let shift = exponent
+        .saturating_neg()
+        .saturating_sub(fraction.len() as i64);
+
+    if shift > MAX_SHIFT {
+        return Err(out_of_range());
+    } else if shift >= 0 {
+        digits.push_str(&"0".repeat(shift as usize));
+    } else {
+        let excess = shift.unsigned_abs();
+        match usize::try_from(excess) {
+            Ok(excess)
+                if digits.len() > excess
+                    && digits[digits.len() - excess..].chars().all(|c| c == '0') =>
+            {
+                digits.truncate(digits.len() - excess)
+            }
+            _ => {
+                return Err(format!(
+                    "{} has more decimal places than an exponent of {} allows",
+                    decimal, exponent
+                ))
+            }
+        }
+    }
+
+    format!("{}{}", sign, digits)
+        .parse()
+        .map_err(|_| out_of_range())
+}
+
+/// Formats `value * 10^exponent` as a decimal string, such as `"-12.34"`.
+///
+/// Trailing zeros after the decimal point are kept, so that the number of decimal places shows
+/// the exponent.
+pub fn format_decimal(value: i64, exponent: i64) -> String {
+    if exponent >= 0 {
+        let mut formatted = value.to_string();
+        if value != 0 {
+            formatted.push_str(&"0".repeat(exponent as usize));
+        }
+        return formatted;
+    }
+
+    let places = exponent.unsigned_abs() as usize;
+    let digits = format!("{:0>width$}", value.unsigned_abs(), width = places + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - places);
+    let sign = if value < 0 { "-" } else { "" };
+
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that decimals are scaled by the exponent exactly, including values with more
+    /// significant digits than a float holds, and that invalid, too precise and out of range
+    /// decimals are rejected.
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(parse_decimal("0.1", -2), Ok(10));
+        assert_eq!(parse_decimal(" -0.005 ", -3), Ok(-5));
+        assert_eq!(parse_decimal("+12000", 3), Ok(12));
+        assert_eq!(parse_decimal("12.50", -1), Ok(125));
+        assert_eq!(parse_decimal("0.000", 40), Ok(0));
+        assert_eq!(
+            parse_decimal("123456789.0123456789", -10),
+            Ok(1_234_567_890_123_456_789)
+        );
+        assert_eq!(parse_decimal("1", -18), Ok(1_000_000_000_000_000_000));
+
+        assert!(parse_decimal("", 0).is_err());
+        assert!(parse_decimal("1.2.3", -2).is_err());
+        assert!(parse_decimal("1e3", 0).is_err());
+        assert!(parse_decimal("0.125", -2).is_err());
+        assert!(parse_decimal("12345", 3).is_err());
+        assert!(parse_decimal("1", i64::MAX).is_err());
+        assert!(parse_decimal("99999999999999999999", 0).is_err());
+        assert!(parse_decimal("1", -19).is_err());
+        assert!(parse_decimal("1", i64::MIN).is_err());
+        assert!(parse_decimal("0.5", i64::MAX).is_err());
+    }
+
+    /// Verifies that values are formatted with the decimal point placed by the exponent, and
+    /// that they round-trip through `parse_decimal`.
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!(format_decimal(1234, -2), "12.34");
+        assert_eq!(format_decimal(-5, -3), "-0.005");
+        assert_eq!(format_decimal(12, 3), "12000");
+        assert_eq!(format_decimal(0, 3), "0");
+        assert_eq!(format_decimal(1234, 0), "1234");
+
+        for (value, exponent) in [(1234, -2), (-5, -3), (12, 3), (i64::MIN, -10)] {
+            assert_eq!(
+                parse_decimal(&format_decimal(value, exponent), exponent),
+                Ok(value)
+            );
+        }
+    }
+}
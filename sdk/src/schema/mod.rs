@@ -13,6 +13,9 @@
 // limitations under the License.
 
 pub mod addressing;
+pub mod coerce;
+pub mod decimal;
 pub mod store;
+pub mod validation;
 
 pub const MAX_COMMIT_NUM: i64 = i64::MAX;
@@ -20,17 +20,18 @@ use crate::error::ResourceTemporarilyUnavailableError;
 
 use models::{GridPropertyDefinition, GridSchema, NewGridPropertyDefinition, NewGridSchema};
 use operations::{
-    add_schema::AddSchemaOperation,
+    add_schema::AddSchemaOperation, count_schemas::CountSchemasOperation,
     get_property_definition_by_name::GetPropertyDefinitionByNameOperation,
     get_schema::GetSchemaOperation, list_property_definitions::ListPropertyDefinitionsOperation,
     list_property_definitions_with_schema_name::ListPropertyDefinitionsWithSchemaNameOperation,
-    list_schemas::ListSchemasOperation, SchemaStoreOperations,
+    list_schemas::ListSchemasOperation, list_schemas_with_usage::ListSchemasWithUsageOperation,
+    SchemaStoreOperations,
 };
 
 use diesel::connection::AnsiTransactionManager;
 use diesel::r2d2::{ConnectionManager, Pool};
 
-use super::{PropertyDefinition, Schema, SchemaList, SchemaStore, SchemaStoreError};
+use super::{PropertyDefinition, Schema, SchemaList, SchemaStore, SchemaStoreError, SchemaUsage};
 
 /// Manages creating commits in the database
 #[derive(Clone)]
@@ -82,6 +83,27 @@ impl SchemaStore for DieselSchemaStore<diesel::pg::PgConnection> {
         .list_schemas(service_id, offset, limit)
     }
 
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        SchemaStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            SchemaStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_schemas(service_id)
+    }
+
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        SchemaStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            SchemaStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_with_usage(service_id)
+    }
+
     fn list_property_definitions(
         &self,
         service_id: Option<&str>,
@@ -160,6 +182,27 @@ impl SchemaStore for DieselSchemaStore<diesel::sqlite::SqliteConnection> {
         .list_schemas(service_id, offset, limit)
     }
 
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        SchemaStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            SchemaStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_schemas(service_id)
+    }
+
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        SchemaStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            SchemaStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_with_usage(service_id)
+    }
+
     fn list_property_definitions(
         &self,
         service_id: Option<&str>,
@@ -241,6 +284,17 @@ impl<'a> SchemaStore for DieselConnectionSchemaStore<'a, diesel::pg::PgConnectio
         SchemaStoreOperations::new(self.connection).list_schemas(service_id, offset, limit)
     }
 
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        SchemaStoreOperations::new(self.connection).count_schemas(service_id)
+    }
+
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        SchemaStoreOperations::new(self.connection).list_with_usage(service_id)
+    }
+
     fn list_property_definitions(
         &self,
         service_id: Option<&str>,
@@ -294,6 +348,17 @@ impl<'a> SchemaStore for DieselConnectionSchemaStore<'a, diesel::sqlite::SqliteC
         SchemaStoreOperations::new(self.connection).list_schemas(service_id, offset, limit)
     }
 
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        SchemaStoreOperations::new(self.connection).count_schemas(service_id)
+    }
+
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        SchemaStoreOperations::new(self.connection).list_with_usage(service_id)
+    }
+
     fn list_property_definitions(
         &self,
         service_id: Option<&str>,
@@ -375,6 +440,7 @@ fn make_property_definitions(
             start_commit_num: def.start_commit_num,
             end_commit_num: def.end_commit_num,
             service_id: def.service_id.clone(),
+            access_level: def.access_level,
         });
 
         if !def.struct_properties.is_empty() {
@@ -398,6 +464,7 @@ impl From<GridPropertyDefinition> for PropertyDefinition {
             data_type: model.data_type,
             required: model.required,
             description: model.description,
+            access_level: model.access_level,
             number_exponent: model.number_exponent,
             enum_options: model.enum_options.split(',').map(String::from).collect(),
             struct_properties: vec![],
@@ -416,6 +483,7 @@ impl From<(GridPropertyDefinition, Vec<PropertyDefinition>)> for PropertyDefinit
             data_type: model.data_type,
             required: model.required,
             description: model.description,
+            access_level: model.access_level,
             number_exponent: model.number_exponent,
             enum_options: model.enum_options.split(',').map(String::from).collect(),
             struct_properties: children,
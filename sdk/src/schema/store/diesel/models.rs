@@ -57,6 +57,7 @@ pub struct NewGridPropertyDefinition {
     pub enum_options: String,
     pub parent_name: Option<String>,
     pub service_id: Option<String>,
+    pub access_level: i64,
 }
 
 #[derive(Queryable, Debug)]
@@ -74,4 +75,5 @@ pub struct GridPropertyDefinition {
     pub enum_options: String,
     pub parent_name: Option<String>,
     pub service_id: Option<String>,
+    pub access_level: i64,
 }
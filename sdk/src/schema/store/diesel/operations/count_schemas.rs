@@ -0,0 +1,145 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::SchemaStoreOperations;
+
+use crate::{
+    error::InternalError,
+    schema::{
+        store::{diesel::schema::grid_schema, error::SchemaStoreError},
+        MAX_COMMIT_NUM,
+    },
+};
+
+use diesel::prelude::*;
+
+pub(in crate::schema) trait CountSchemasOperation {
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> CountSchemasOperation for SchemaStoreOperations<'a, diesel::pg::PgConnection> {
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        pg::schemas(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> CountSchemasOperation for SchemaStoreOperations<'a, diesel::sqlite::SqliteConnection> {
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        sqlite::schemas(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current schemas.
+    pub fn schemas<'a>(service_id: Option<&'a str>) -> grid_schema::BoxedQuery<'a, Pg> {
+        let mut query = grid_schema::table
+            .into_boxed()
+            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_schema::service_id.eq(service_id));
+        } else {
+            query = query.filter(grid_schema::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current schemas.
+    pub fn schemas<'a>(service_id: Option<&'a str>) -> grid_schema::BoxedQuery<'a, Sqlite> {
+        let mut query = grid_schema::table
+            .into_boxed()
+            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(grid_schema::service_id.eq(service_id));
+        } else {
+            query = query.filter(grid_schema::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::schema::store::{DieselConnectionSchemaStore, Schema, SchemaStore};
+    use crate::schema::MAX_COMMIT_NUM;
+
+    fn schema(name: &str, service_id: Option<&str>) -> Schema {
+        Schema {
+            name: name.to_string(),
+            description: String::new(),
+            owner: "owner".to_string(),
+            properties: vec![],
+            service_id: service_id.map(String::from),
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            last_updated: None,
+        }
+    }
+
+    /// Verifies that schemas are counted with the same filters they are listed with, and that
+    /// the total of a page of schemas is the unpaged count.
+    #[test]
+    fn test_count_schemas() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionSchemaStore::new(&conn);
+
+        store.add_schema(schema("gs1_product", None))?;
+        store.add_schema(schema("lightbulb", None))?;
+        store.add_schema(schema("pallet", None))?;
+        store.add_schema(schema("shipment", Some("service")))?;
+
+        for (service_id, expected) in [(None, 3), (Some("service"), 1), (Some("other"), 0)] {
+            assert_eq!(store.count_schemas(service_id)?, expected);
+            assert_eq!(
+                store.list_schemas(service_id, 0, 100)?.data.len() as i64,
+                expected
+            );
+
+            let page = store.list_schemas(service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
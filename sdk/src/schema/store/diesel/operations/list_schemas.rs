@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::count_schemas;
 use super::SchemaStoreOperations;
 
 use crate::{
@@ -31,7 +32,6 @@ use crate::{
 };
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::schema) trait ListSchemasOperation {
     fn list_schemas(
@@ -96,7 +96,7 @@ impl<'a> ListSchemasOperation for SchemaStoreOperations<'a, diesel::sqlite::Sqli
 }
 
 #[cfg(feature = "postgres")]
-mod pg {
+pub(super) mod pg {
     use super::*;
 
     pub fn fetch_grid_schemas(
@@ -105,26 +105,21 @@ mod pg {
         offset: i64,
         limit: i64,
     ) -> Result<(Vec<GridSchema>, i64), SchemaStoreError> {
-        let mut query = grid_schema::table
-            .into_boxed()
+        let models = count_schemas::pg::schemas(service_id)
             .select(grid_schema::all_columns)
             .offset(offset)
             .limit(limit)
-            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(grid_schema::service_id.eq(service_id));
-        } else {
-            query = query.filter(grid_schema::service_id.is_null());
-        }
-
-        let models = query.load(conn).map_err(|err| {
-            SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
-
-        let total = models.len().try_into().map_err(|err| {
-            SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
+            .load(conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        let total = count_schemas::pg::schemas(service_id)
+            .count()
+            .get_result(conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
 
         Ok((models, total))
     }
@@ -171,7 +166,7 @@ mod pg {
 }
 
 #[cfg(feature = "sqlite")]
-mod sqlite {
+pub(super) mod sqlite {
     use super::*;
 
     pub fn fetch_grid_schemas(
@@ -180,26 +175,21 @@ mod sqlite {
         offset: i64,
         limit: i64,
     ) -> Result<(Vec<GridSchema>, i64), SchemaStoreError> {
-        let mut query = grid_schema::table
-            .into_boxed()
+        let models = count_schemas::sqlite::schemas(service_id)
             .select(grid_schema::all_columns)
             .offset(offset)
             .limit(limit)
-            .filter(grid_schema::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(grid_schema::service_id.eq(service_id));
-        } else {
-            query = query.filter(grid_schema::service_id.is_null());
-        }
-
-        let models = query.load(conn).map_err(|err| {
-            SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
-
-        let total = models.len().try_into().map_err(|err| {
-            SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
+            .load(conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        let total = count_schemas::sqlite::schemas(service_id)
+            .count()
+            .get_result(conn)
+            .map_err(|err| {
+                SchemaStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
 
         Ok((models, total))
     }
@@ -0,0 +1,256 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use super::count_schemas;
+use super::list_schemas;
+use super::SchemaStoreOperations;
+
+use crate::{
+    error::InternalError,
+    schema::{
+        store::{
+            diesel::{models::GridSchema, schema::grid_schema},
+            error::SchemaStoreError,
+            Schema, SchemaUsage,
+        },
+        MAX_COMMIT_NUM,
+    },
+};
+
+use diesel::{
+    prelude::*,
+    sql_query,
+    sql_types::{BigInt, Nullable, Text},
+};
+
+pub(in crate::schema) trait ListSchemasWithUsageOperation {
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError>;
+}
+
+#[derive(QueryableByName)]
+struct ProductCount {
+    #[column_name = "name"]
+    #[sql_type = "Text"]
+    pub name: String,
+
+    #[column_name = "product_count"]
+    #[sql_type = "BigInt"]
+    pub product_count: i64,
+}
+
+// Products reference a schema through their namespace: the product smart contract validates the
+// products in a namespace against the schema named for it. This must be kept in line with the
+// namespaces the contract supports.
+const PRODUCT_COUNT_SELECT: &str = r#"
+    SELECT grid_schema.name, COUNT(product.id) AS product_count
+    FROM grid_schema
+    LEFT JOIN product
+    ON LOWER(product.product_namespace) = CASE grid_schema.name
+        WHEN 'gs1_product' THEN 'gs1'
+    END
+"#;
+
+#[cfg(feature = "postgres")]
+impl<'a> ListSchemasWithUsageOperation for SchemaStoreOperations<'a, diesel::pg::PgConnection> {
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        self.conn.transaction::<_, SchemaStoreError, _>(|| {
+            let counts = pg::product_counts(self.conn, service_id)?;
+
+            let db_schemas: Vec<GridSchema> = count_schemas::pg::schemas(service_id)
+                .select(grid_schema::all_columns)
+                .order(grid_schema::name.asc())
+                .load(self.conn)?;
+
+            let mut usage = Vec::with_capacity(db_schemas.len());
+            for schema in db_schemas {
+                let roots = list_schemas::pg::get_root_definitions(self.conn, &schema.name)?;
+                let properties =
+                    list_schemas::pg::get_property_definitions_for_schema(self.conn, roots)?;
+
+                usage.push(SchemaUsage {
+                    product_count: counts.get(&schema.name).copied().unwrap_or(0),
+                    schema: Schema::from((schema, properties)),
+                });
+            }
+
+            Ok(usage)
+        })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> ListSchemasWithUsageOperation
+    for SchemaStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        self.conn.transaction::<_, SchemaStoreError, _>(|| {
+            let counts = sqlite::product_counts(self.conn, service_id)?;
+
+            let db_schemas: Vec<GridSchema> = count_schemas::sqlite::schemas(service_id)
+                .select(grid_schema::all_columns)
+                .order(grid_schema::name.asc())
+                .load(self.conn)?;
+
+            let mut usage = Vec::with_capacity(db_schemas.len());
+            for schema in db_schemas {
+                let roots = list_schemas::sqlite::get_root_definitions(self.conn, &schema.name)?;
+                let properties =
+                    list_schemas::sqlite::get_property_definitions_for_schema(self.conn, roots)?;
+
+                usage.push(SchemaUsage {
+                    product_count: counts.get(&schema.name).copied().unwrap_or(0),
+                    schema: Schema::from((schema, properties)),
+                });
+            }
+
+            Ok(usage)
+        })
+    }
+}
+
+fn into_map(counts: Vec<ProductCount>) -> HashMap<String, i64> {
+    counts
+        .into_iter()
+        .map(|count| (count.name, count.product_count))
+        .collect()
+}
+
+#[cfg(feature = "postgres")]
+mod pg {
+    use super::*;
+
+    /// Returns the number of current products referencing each current schema, by schema name.
+    pub fn product_counts(
+        conn: &PgConnection,
+        service_id: Option<&str>,
+    ) -> Result<HashMap<String, i64>, SchemaStoreError> {
+        // A raw query is required, as diesel does not support joins between tables without a
+        // foreign key relationship as of its 1.4.x release branch.
+        sql_query(format!(
+            "{}
+            AND product.end_commit_num = $1
+            AND product.service_id IS NOT DISTINCT FROM grid_schema.service_id
+            WHERE grid_schema.end_commit_num = $1
+            AND grid_schema.service_id IS NOT DISTINCT FROM $2
+            GROUP BY grid_schema.name",
+            PRODUCT_COUNT_SELECT
+        ))
+        .bind::<BigInt, _>(MAX_COMMIT_NUM)
+        .bind::<Nullable<Text>, _>(service_id)
+        .load::<ProductCount>(conn)
+        .map(into_map)
+        .map_err(|err| SchemaStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use super::*;
+
+    /// Returns the number of current products referencing each current schema, by schema name.
+    pub fn product_counts(
+        conn: &SqliteConnection,
+        service_id: Option<&str>,
+    ) -> Result<HashMap<String, i64>, SchemaStoreError> {
+        // A raw query is required, as diesel does not support joins between tables without a
+        // foreign key relationship as of its 1.4.x release branch.
+        sql_query(format!(
+            "{}
+            AND product.end_commit_num = ?
+            AND product.service_id IS grid_schema.service_id
+            WHERE grid_schema.end_commit_num = ?
+            AND grid_schema.service_id IS ?
+            GROUP BY grid_schema.name",
+            PRODUCT_COUNT_SELECT
+        ))
+        .bind::<BigInt, _>(MAX_COMMIT_NUM)
+        .bind::<BigInt, _>(MAX_COMMIT_NUM)
+        .bind::<Nullable<Text>, _>(service_id)
+        .load::<ProductCount>(conn)
+        .map(into_map)
+        .map_err(|err| SchemaStoreError::InternalError(InternalError::from_source(Box::new(err))))
+    }
+}
+
+#[cfg(all(test, feature = "sqlite", feature = "product"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::migrations::run_sqlite_migrations;
+    use crate::product::store::{DieselConnectionProductStore, ProductBuilder, ProductStore};
+    use crate::schema::store::{DieselConnectionSchemaStore, Schema, SchemaStore};
+    use crate::schema::MAX_COMMIT_NUM;
+
+    fn schema(name: &str) -> Schema {
+        Schema {
+            name: name.to_string(),
+            description: String::new(),
+            owner: "owner".to_string(),
+            properties: vec![],
+            service_id: None,
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            last_updated: None,
+        }
+    }
+
+    /// Verifies that each schema is listed with the number of current products referencing it,
+    /// and that a schema with no products is listed with a count of zero.
+    #[test]
+    fn test_list_with_usage() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+
+        let schema_store = DieselConnectionSchemaStore::new(&conn);
+        schema_store.add_schema(schema("gs1_product"))?;
+        schema_store.add_schema(schema("unused"))?;
+
+        let product_store = DieselConnectionProductStore::new(&conn);
+        for product_id in ["00012345678905", "00012345678912"] {
+            product_store.add_product(
+                ProductBuilder::default()
+                    .with_product_id(product_id.to_string())
+                    .with_product_address(format!("address-{}", product_id))
+                    .with_product_namespace("Gs1".to_string())
+                    .with_owner("owner".to_string())
+                    .with_start_commit_number(1)
+                    .with_end_commit_number(MAX_COMMIT_NUM)
+                    .build()?,
+            )?;
+        }
+
+        let usage: Vec<(String, i64)> = schema_store
+            .list_with_usage(None)?
+            .into_iter()
+            .map(|usage| (usage.schema.name, usage.product_count))
+            .collect();
+        assert_eq!(
+            usage,
+            vec![("gs1_product".to_string(), 2), ("unused".to_string(), 0)]
+        );
+
+        Ok(())
+    }
+}
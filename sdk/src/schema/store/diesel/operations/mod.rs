@@ -13,11 +13,13 @@
 // limitations under the License.
 
 pub(super) mod add_schema;
+pub(super) mod count_schemas;
 pub(super) mod get_property_definition_by_name;
 pub(super) mod get_schema;
 pub(super) mod list_property_definitions;
 pub(super) mod list_property_definitions_with_schema_name;
 pub(super) mod list_schemas;
+pub(super) mod list_schemas_with_usage;
 
 pub(super) struct SchemaStoreOperations<'a, C> {
     conn: &'a C,
@@ -42,5 +42,6 @@ table! {
         enum_options -> Text,
         parent_name -> Nullable<Text>,
         service_id -> Nullable<Text>,
+        access_level -> Int8,
     }
 }
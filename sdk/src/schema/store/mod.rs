@@ -45,6 +45,8 @@ pub struct PropertyDefinition {
     pub data_type: String,
     pub required: bool,
     pub description: String,
+    // The clearance a caller needs to read values of the property; 0 means it is public.
+    pub access_level: i64,
     pub number_exponent: i64,
     pub enum_options: Vec<String>,
     pub struct_properties: Vec<PropertyDefinition>,
@@ -65,6 +67,13 @@ impl SchemaList {
     }
 }
 
+/// A schema, with the number of current products that reference it
+#[derive(Clone, Debug)]
+pub struct SchemaUsage {
+    pub schema: Schema,
+    pub product_count: i64,
+}
+
 pub trait SchemaStore {
     /// Adds a new schema to underlying storage
     ///
@@ -100,6 +109,26 @@ pub trait SchemaStore {
         limit: i64,
     ) -> Result<SchemaList, SchemaStoreError>;
 
+    /// Counts the schemas in the underlying storage, applying the same filters as `list_schemas`
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - Service ID needed for when the source of the schema is a splinter
+    ///    circuit
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError>;
+
+    /// List all schemas in underlying storage, in name order, each with the number of current
+    /// products that reference it. Schemas no product references are listed with a count of 0.
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - Service ID needed for when the source of the schema is a splinter
+    ///    circuit
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError>;
+
     /// List all property definitions in underlying storage
     ///
     /// # Arguments
@@ -162,6 +191,17 @@ where
         (**self).list_schemas(service_id, offset, limit)
     }
 
+    fn count_schemas(&self, service_id: Option<&str>) -> Result<i64, SchemaStoreError> {
+        (**self).count_schemas(service_id)
+    }
+
+    fn list_with_usage(
+        &self,
+        service_id: Option<&str>,
+    ) -> Result<Vec<SchemaUsage>, SchemaStoreError> {
+        (**self).list_with_usage(service_id)
+    }
+
     fn list_property_definitions(
         &self,
         service_id: Option<&str>,
@@ -0,0 +1,260 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Validation of property values against a schema's property definitions.
+
+use std::collections::HashSet;
+
+use crate::error::{ValidationError, ValidationErrors};
+use crate::protocol::schema::state::{DataType, PropertyDefinition, PropertyValue};
+
+/// Returns the name of the field a property's validation errors are reported against.
+fn property_field(name: &str) -> String {
+    format!("properties.{}", name)
+}
+
+/// Checks that each property value is defined by the schema, is set at most once, and has the
+/// declared type, adding an error to `errors` for each value that does not.
+///
+/// The elements of a list must each have the list's element type, and enum values, including
+/// those in lists, must also be the index of one of the definition's options.
+pub fn validate_properties(
+    definitions: &[PropertyDefinition],
+    values: &[PropertyValue],
+    errors: &mut ValidationErrors,
+) {
+    let mut seen = HashSet::new();
+
+    for value in values {
+        let field = property_field(value.name());
+
+        if !seen.insert(value.name()) {
+            errors.push(ValidationError::new(
+                field,
+                "duplicate_property".into(),
+                "property is set more than once".into(),
+            ));
+            continue;
+        }
+
+        let definition = match definitions.iter().find(|def| def.name() == value.name()) {
+            Some(definition) => definition,
+            None => {
+                errors.push(ValidationError::new(
+                    field,
+                    "unknown_property".into(),
+                    "property is not defined by the schema".into(),
+                ));
+                continue;
+            }
+        };
+
+        if definition.data_type() != value.data_type() {
+            errors.push(ValidationError::new(
+                field,
+                "invalid_type".into(),
+                format!(
+                    "property must be of type {:?}, was {:?}",
+                    definition.data_type(),
+                    value.data_type()
+                ),
+            ));
+        } else if let Some((element_type, element)) = mismatched_element(value) {
+            errors.push(ValidationError::new(
+                field,
+                "invalid_list_element".into(),
+                format!(
+                    "list element {} must be of type {:?}, was {:?}",
+                    element.name(),
+                    element_type,
+                    element.data_type()
+                ),
+            ));
+        } else if let Some(enum_value) = enum_values(value)
+            .into_iter()
+            .find(|enum_value| *enum_value as usize >= definition.enum_options().len())
+        {
+            errors.push(ValidationError::new(
+                field,
+                "invalid_enum_value".into(),
+                format!(
+                    "{} is not the index of one of the options {:?}",
+                    enum_value,
+                    definition.enum_options()
+                ),
+            ));
+        }
+    }
+}
+
+/// Returns the first element of a list, at any depth, that does not have the list's element
+/// type, along with that type.
+///
+/// Values built natively are checked as they are built, but those read from bytes are not.
+fn mismatched_element(value: &PropertyValue) -> Option<(&DataType, &PropertyValue)> {
+    match value.data_type() {
+        DataType::List(element_type) => value.list_values().iter().find_map(|element| {
+            if element.data_type() != element_type.as_ref() {
+                Some((element_type.as_ref(), element))
+            } else {
+                mismatched_element(element)
+            }
+        }),
+        _ => None,
+    }
+}
+
+/// Returns the enum values held by a value, including those of the elements of a list.
+fn enum_values(value: &PropertyValue) -> Vec<u32> {
+    match value.data_type() {
+        DataType::Enum => vec![*value.enum_value()],
+        DataType::List(_) => value.list_values().iter().flat_map(enum_values).collect(),
+        _ => vec![],
+    }
+}
+
+/// Checks that each property the schema requires has a value, adding an error to `errors` for
+/// each that does not.
+pub fn validate_required_properties(
+    definitions: &[PropertyDefinition],
+    values: &[PropertyValue],
+    errors: &mut ValidationErrors,
+) {
+    for definition in definitions.iter().filter(|def| *def.required()) {
+        if !values.iter().any(|value| value.name() == definition.name()) {
+            errors.push(ValidationError::new(
+                property_field(definition.name()),
+                "required".into(),
+                "required property is not set".into(),
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::protocol::schema::state::{PropertyDefinitionBuilder, PropertyValueBuilder};
+
+    fn definition(name: &str, data_type: DataType, required: bool) -> PropertyDefinition {
+        PropertyDefinitionBuilder::new()
+            .with_name(name.to_string())
+            .with_data_type(data_type)
+            .with_required(required)
+            .with_description("".to_string())
+            .with_number_exponent(0)
+            .with_enum_options(vec!["red".to_string(), "blue".to_string()])
+            .build()
+            .expect("Unable to build property definition")
+    }
+
+    fn value(name: &str, builder: PropertyValueBuilder) -> PropertyValue {
+        builder
+            .with_name(name.to_string())
+            .build()
+            .expect("Unable to build property value")
+    }
+
+    /// Verifies that every invalid property is reported, each against its own field.
+    #[test]
+    fn test_validate_properties() {
+        let definitions = vec![
+            definition("brand", DataType::String, true),
+            definition("color", DataType::Enum, true),
+            definition("weight", DataType::Number, false),
+        ];
+        let brand = value(
+            "brand",
+            PropertyValueBuilder::new()
+                .with_data_type(DataType::String)
+                .with_string_value("Acme".to_string()),
+        );
+        let values = vec![
+            brand.clone(),
+            brand,
+            value(
+                "color",
+                PropertyValueBuilder::new()
+                    .with_data_type(DataType::Enum)
+                    .with_enum_value(2),
+            ),
+            value(
+                "size",
+                PropertyValueBuilder::new()
+                    .with_data_type(DataType::Number)
+                    .with_number_value(1),
+            ),
+        ];
+
+        let mut errors = ValidationErrors::new();
+        validate_properties(&definitions, &values, &mut errors);
+        validate_required_properties(&definitions, &values[3..], &mut errors);
+
+        assert_eq!(
+            errors
+                .errors()
+                .iter()
+                .map(|err| (err.field(), err.code()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("properties.brand", "duplicate_property"),
+                ("properties.color", "invalid_enum_value"),
+                ("properties.size", "unknown_property"),
+                ("properties.brand", "required"),
+                ("properties.color", "required"),
+            ]
+        );
+    }
+
+    /// Verifies that an enum value in a list that is not the index of an option is reported.
+    #[test]
+    fn test_validate_properties_list() {
+        let definitions = vec![definition(
+            "colors",
+            DataType::List(Box::new(DataType::Enum)),
+            false,
+        )];
+        let color = |index| {
+            value(
+                "color",
+                PropertyValueBuilder::new()
+                    .with_data_type(DataType::Enum)
+                    .with_enum_value(index),
+            )
+        };
+        let colors = |indexes: &[u32]| {
+            value(
+                "colors",
+                PropertyValueBuilder::new()
+                    .with_data_type(DataType::List(Box::new(DataType::Enum)))
+                    .with_list_values(indexes.iter().map(|index| color(*index)).collect()),
+            )
+        };
+
+        let mut errors = ValidationErrors::new();
+        validate_properties(&definitions, &[colors(&[0, 1])], &mut errors);
+        assert!(errors.errors().is_empty());
+
+        validate_properties(&definitions, &[colors(&[1, 2])], &mut errors);
+        assert_eq!(
+            errors
+                .errors()
+                .iter()
+                .map(|err| (err.field(), err.code()))
+                .collect::<Vec<_>>(),
+            vec![("properties.colors", "invalid_enum_value")]
+        );
+    }
+}
@@ -80,8 +80,40 @@ pub trait InContextStoreFactory<'a>: StoreFactory {
     fn rollback(&self) -> Result<(), InternalError>;
 }
 
+impl dyn TransactionalStoreFactory {
+    /// Runs `f` with stores that share a single transaction, committing their writes if it
+    /// succeeds and rolling them back if it fails.
+    ///
+    /// Either all of the writes made through the stores given to `f` are applied, or none are.
+    /// The error returned by `f` is returned as-is, even if the rollback also fails.
+    pub fn transaction<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce(&dyn InContextStoreFactory<'_>) -> Result<T, E>,
+        E: From<InternalError>,
+    {
+        let txn = self.begin_transaction()?;
+
+        match f(&*txn) {
+            Ok(value) => {
+                txn.commit()?;
+                Ok(value)
+            }
+            Err(err) => {
+                if let Err(_rollback_err) = txn.rollback() {
+                    #[cfg(feature = "log")]
+                    error!("Unable to roll back store transaction: {}", _rollback_err);
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
 /// Creates a `StoreFactory` backed by the given connection
 ///
+/// An in-memory SQLite database (`:memory:`) is migrated when it is created, as it cannot be
+/// reached other than through the resulting factory.
+///
 /// # Arguments
 ///
 /// * `connection_uri` - The identifier of the storage connection that will be used by all stores
@@ -122,6 +154,20 @@ pub fn create_store_factory(
                     "Failed to build connection pool".to_string(),
                 )
             })?;
+            if conn_str == ":memory:" {
+                let conn = pool.get().map_err(|err| {
+                    InternalError::from_source_with_prefix(
+                        Box::new(err),
+                        "Failed to get connection".to_string(),
+                    )
+                })?;
+                crate::migrations::run_sqlite_migrations(&conn).map_err(|err| {
+                    InternalError::from_source_with_prefix(
+                        Box::new(err),
+                        "Failed to migrate in-memory database".to_string(),
+                    )
+                })?;
+            }
             Ok(Box::new(sqlite::SqliteStoreFactory::new(pool)))
         }
         #[cfg(all(not(feature = "sqlite"), not(feature = "postgres")))]
@@ -161,3 +207,72 @@ impl FromStr for ConnectionUri {
         }
     }
 }
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use super::*;
+
+    use crate::commits::store::Commit;
+
+    fn store_factory() -> Box<dyn TransactionalStoreFactory> {
+        create_store_factory(&ConnectionUri::Sqlite(":memory:".into()))
+            .expect("Unable to create store factory")
+    }
+
+    fn commit(commit_num: i64) -> Commit {
+        Commit {
+            commit_id: format!("commit-{}", commit_num),
+            commit_num,
+            service_id: None,
+        }
+    }
+
+    /// Verifies that the writes of a failed transaction are rolled back together, and those of
+    /// a successful one are committed together.
+    #[test]
+    fn test_transaction_is_atomic() {
+        let store_factory = store_factory();
+
+        let result: Result<(), InternalError> = store_factory.transaction(|txn| {
+            txn.get_grid_commit_store()
+                .add_commit(commit(1))
+                .map_err(|err| InternalError::from_source(Box::new(err)))?;
+            Err(InternalError::with_message("failed mid-commit".into()))
+        });
+        assert!(result.is_err());
+        assert_eq!(
+            store_factory
+                .get_grid_commit_store()
+                .get_commit_by_commit_num(1)
+                .expect("Unable to get commit"),
+            None
+        );
+
+        store_factory
+            .transaction(|txn| {
+                for commit_num in 1..=2 {
+                    txn.get_grid_commit_store()
+                        .add_commit(commit(commit_num))
+                        .map_err(|err| InternalError::from_source(Box::new(err)))?;
+                }
+                Ok::<_, InternalError>(())
+            })
+            .expect("Unable to run transaction");
+
+        let commit_store = store_factory.get_grid_commit_store();
+        for commit_num in 1..=2 {
+            assert_eq!(
+                commit_store
+                    .get_commit_by_commit_num(commit_num)
+                    .expect("Unable to get commit"),
+                Some(commit(commit_num))
+            );
+        }
+        assert_eq!(
+            commit_store
+                .get_commit_by_commit_num(3)
+                .expect("Unable to get commit"),
+            None
+        );
+    }
+}
@@ -25,8 +25,9 @@ use super::diesel::models::{
     RecordModel, ReportedValueReporterToAgentMetadataModel, ReporterModel,
 };
 use super::{
-    AssociatedAgent, LatLongValue, Property, Proposal, Record, RecordList, ReportedValue,
-    ReportedValueReporterToAgentMetadata, Reporter, TrackAndTraceStore, TrackAndTraceStoreError,
+    AssociatedAgent, CustodyTransfer, LatLongValue, Property, Proposal, Record, RecordList,
+    ReportedValue, ReportedValueReporterToAgentMetadata, Reporter, TrackAndTraceStore,
+    TrackAndTraceStoreError,
 };
 use crate::error::{
     ConstraintViolationError, ConstraintViolationType, InternalError,
@@ -38,10 +39,12 @@ use operations::add_proposals::TrackAndTraceStoreAddProposalsOperation as _;
 use operations::add_records::TrackAndTraceStoreAddRecordsOperation as _;
 use operations::add_reported_values::TrackAndTraceStoreAddReportedValuesOperation as _;
 use operations::add_reporters::TrackAndTraceStoreAddReportersOperation as _;
+use operations::count_records::TrackAndTraceStoreCountRecordsOperation as _;
 use operations::get_property_with_data_type::TrackAndTraceStoreGetPropertyWithDataTypeOperation as _;
 use operations::get_record::TrackAndTraceStoreGetRecordOperation as _;
 use operations::get_reported_value_reporter_to_agent_metadata::TrackAndTraceStoreGetReportedValueReporterToAgentMetadataOperation as _;
 use operations::list_associated_agents::TrackAndTraceStoreListAssociatedAgentsOperation as _;
+use operations::list_custody_chain::TrackAndTraceStoreListCustodyChainOperation as _;
 use operations::list_properties_with_data_type::TrackAndTraceStoreListPropertiesWithDataTypeOperation as _;
 use operations::list_proposals::TrackAndTraceStoreListProposalsOperation as _;
 use operations::list_records::TrackAndTraceStoreListRecordsOperation as _;
@@ -189,6 +192,19 @@ impl TrackAndTraceStore for DieselTrackAndTraceStore<diesel::pg::PgConnection> {
         .list_associated_agents(record_ids, service_id)
     }
 
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            TrackAndTraceStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_custody_chain(record_id, service_id)
+    }
+
     fn list_properties_with_data_type(
         &self,
         record_ids: &[String],
@@ -229,6 +245,15 @@ impl TrackAndTraceStore for DieselTrackAndTraceStore<diesel::pg::PgConnection> {
         .list_records(service_id, offset, limit)
     }
 
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            TrackAndTraceStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_records(service_id)
+    }
+
     fn list_reported_value_reporter_to_agent_metadata(
         &self,
         record_id: &str,
@@ -384,6 +409,19 @@ impl TrackAndTraceStore for DieselTrackAndTraceStore<diesel::sqlite::SqliteConne
         .list_associated_agents(record_ids, service_id)
     }
 
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            TrackAndTraceStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .list_custody_chain(record_id, service_id)
+    }
+
     fn list_properties_with_data_type(
         &self,
         record_ids: &[String],
@@ -424,6 +462,15 @@ impl TrackAndTraceStore for DieselTrackAndTraceStore<diesel::sqlite::SqliteConne
         .list_records(service_id, offset, limit)
     }
 
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(&*self.connection_pool.get().map_err(|err| {
+            TrackAndTraceStoreError::ResourceTemporarilyUnavailableError(
+                ResourceTemporarilyUnavailableError::from_source(Box::new(err)),
+            )
+        })?)
+        .count_records(service_id)
+    }
+
     fn list_reported_value_reporter_to_agent_metadata(
         &self,
         record_id: &str,
@@ -559,6 +606,14 @@ impl<'a> TrackAndTraceStore for DieselConnectionTrackAndTraceStore<'a, diesel::p
             .list_associated_agents(record_ids, service_id)
     }
 
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(self.connection).list_custody_chain(record_id, service_id)
+    }
+
     fn list_properties_with_data_type(
         &self,
         record_ids: &[String],
@@ -585,6 +640,10 @@ impl<'a> TrackAndTraceStore for DieselConnectionTrackAndTraceStore<'a, diesel::p
         TrackAndTraceStoreOperations::new(self.connection).list_records(service_id, offset, limit)
     }
 
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(self.connection).count_records(service_id)
+    }
+
     fn list_reported_value_reporter_to_agent_metadata(
         &self,
         record_id: &str,
@@ -695,6 +754,14 @@ impl<'a> TrackAndTraceStore
             .list_associated_agents(record_ids, service_id)
     }
 
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(self.connection).list_custody_chain(record_id, service_id)
+    }
+
     fn list_properties_with_data_type(
         &self,
         record_ids: &[String],
@@ -721,6 +788,10 @@ impl<'a> TrackAndTraceStore
         TrackAndTraceStoreOperations::new(self.connection).list_records(service_id, offset, limit)
     }
 
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        TrackAndTraceStoreOperations::new(self.connection).count_records(service_id)
+    }
+
     fn list_reported_value_reporter_to_agent_metadata(
         &self,
         record_id: &str,
@@ -883,6 +954,17 @@ impl From<AssociatedAgentModel> for AssociatedAgent {
     }
 }
 
+impl From<AssociatedAgentModel> for CustodyTransfer {
+    fn from(model: AssociatedAgentModel) -> Self {
+        Self {
+            record_id: model.record_id,
+            agent_id: model.agent_id,
+            timestamp: model.timestamp,
+            service_id: model.service_id,
+        }
+    }
+}
+
 impl From<PropertyModel> for Property {
     fn from(model: PropertyModel) -> Self {
         Self {
@@ -0,0 +1,149 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::TrackAndTraceStoreOperations;
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::track_and_trace::store::diesel::{schema::record, TrackAndTraceStoreError};
+
+use diesel::prelude::*;
+
+pub(in crate::track_and_trace::store::diesel) trait TrackAndTraceStoreCountRecordsOperation {
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> TrackAndTraceStoreCountRecordsOperation
+    for TrackAndTraceStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        pg::records(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> TrackAndTraceStoreCountRecordsOperation
+    for TrackAndTraceStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        sqlite::records(service_id)
+            .count()
+            .get_result(self.conn)
+            .map_err(|err| {
+                TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })
+    }
+}
+
+#[cfg(feature = "postgres")]
+pub(super) mod pg {
+    use super::*;
+    use diesel::pg::Pg;
+
+    /// Selects the current records.
+    pub fn records<'a>(service_id: Option<&'a str>) -> record::BoxedQuery<'a, Pg> {
+        let mut query = record::table
+            .into_boxed()
+            .filter(record::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(record::service_id.eq(service_id));
+        } else {
+            query = query.filter(record::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub(super) mod sqlite {
+    use super::*;
+    use diesel::sqlite::Sqlite;
+
+    /// Selects the current records.
+    pub fn records<'a>(service_id: Option<&'a str>) -> record::BoxedQuery<'a, Sqlite> {
+        let mut query = record::table
+            .into_boxed()
+            .filter(record::end_commit_num.eq(MAX_COMMIT_NUM));
+
+        if let Some(service_id) = service_id {
+            query = query.filter(record::service_id.eq(service_id));
+        } else {
+            query = query.filter(record::service_id.is_null());
+        }
+
+        query
+    }
+}
+
+#[cfg(all(test, feature = "sqlite"))]
+mod tests {
+    use diesel::{Connection, SqliteConnection};
+
+    use crate::commits::MAX_COMMIT_NUM;
+    use crate::migrations::run_sqlite_migrations;
+    use crate::track_and_trace::store::{
+        DieselConnectionTrackAndTraceStore, Record, TrackAndTraceStore,
+    };
+
+    fn record(record_id: &str, service_id: Option<&str>) -> Record {
+        Record {
+            id: None,
+            record_id: record_id.to_string(),
+            schema: "shipment".to_string(),
+            final_: false,
+            owners: vec![],
+            custodians: vec![],
+            start_commit_num: 1,
+            end_commit_num: MAX_COMMIT_NUM,
+            service_id: service_id.map(String::from),
+        }
+    }
+
+    /// Verifies that records are counted with the same filters they are listed with, and that
+    /// the total of a page of records is the unpaged count.
+    #[test]
+    fn test_count_records() -> Result<(), Box<dyn std::error::Error>> {
+        let conn = SqliteConnection::establish(":memory:")?;
+        run_sqlite_migrations(&conn)?;
+        let store = DieselConnectionTrackAndTraceStore::new(&conn);
+
+        store.add_records(vec![
+            record("record-1", None),
+            record("record-2", None),
+            record("record-3", None),
+            record("record-4", Some("service")),
+        ])?;
+
+        for (service_id, expected) in [(None, 3), (Some("service"), 1), (Some("other"), 0)] {
+            assert_eq!(store.count_records(service_id)?, expected);
+            assert_eq!(
+                store.list_records(service_id, 0, 100)?.data.len() as i64,
+                expected
+            );
+
+            let page = store.list_records(service_id, 0, 1)?;
+            assert_eq!(page.data.len() as i64, expected.min(1));
+            assert_eq!(page.paging.total, expected);
+        }
+
+        Ok(())
+    }
+}
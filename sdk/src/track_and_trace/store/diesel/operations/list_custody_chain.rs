@@ -0,0 +1,106 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::TrackAndTraceStoreOperations;
+use crate::track_and_trace::store::diesel::{schema::associated_agent, TrackAndTraceStoreError};
+
+use crate::commits::MAX_COMMIT_NUM;
+use crate::error::InternalError;
+use crate::track_and_trace::store::diesel::models::AssociatedAgentModel;
+use crate::track_and_trace::store::CustodyTransfer;
+
+use diesel::prelude::*;
+
+/// The role associated agents that have had custody of a record are stored with.
+const CUSTODIAN_ROLE: &str = "CUSTODIAN";
+
+pub(in crate::track_and_trace::store::diesel) trait TrackAndTraceStoreListCustodyChainOperation {
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError>;
+}
+
+#[cfg(feature = "postgres")]
+impl<'a> TrackAndTraceStoreListCustodyChainOperation
+    for TrackAndTraceStoreOperations<'a, diesel::pg::PgConnection>
+{
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        let mut query = associated_agent::table
+            .into_boxed()
+            .select(associated_agent::all_columns)
+            .filter(
+                associated_agent::record_id
+                    .eq(record_id)
+                    .and(associated_agent::role.eq(CUSTODIAN_ROLE))
+                    .and(associated_agent::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(associated_agent::service_id.eq(service_id));
+        } else {
+            query = query.filter(associated_agent::service_id.is_null());
+        }
+
+        let models = query
+            .order((associated_agent::timestamp, associated_agent::id))
+            .load::<AssociatedAgentModel>(self.conn)
+            .map_err(|err| {
+                TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        Ok(models.into_iter().map(CustodyTransfer::from).collect())
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl<'a> TrackAndTraceStoreListCustodyChainOperation
+    for TrackAndTraceStoreOperations<'a, diesel::sqlite::SqliteConnection>
+{
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        let mut query = associated_agent::table
+            .into_boxed()
+            .select(associated_agent::all_columns)
+            .filter(
+                associated_agent::record_id
+                    .eq(record_id)
+                    .and(associated_agent::role.eq(CUSTODIAN_ROLE))
+                    .and(associated_agent::end_commit_num.eq(MAX_COMMIT_NUM)),
+            );
+
+        if let Some(service_id) = service_id {
+            query = query.filter(associated_agent::service_id.eq(service_id));
+        } else {
+            query = query.filter(associated_agent::service_id.is_null());
+        }
+
+        let models = query
+            .order((associated_agent::timestamp, associated_agent::id))
+            .load::<AssociatedAgentModel>(self.conn)
+            .map_err(|err| {
+                TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
+            })?;
+
+        Ok(models.into_iter().map(CustodyTransfer::from).collect())
+    }
+}
@@ -12,17 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use super::count_records::{self, TrackAndTraceStoreCountRecordsOperation};
 use super::TrackAndTraceStoreOperations;
 use crate::track_and_trace::store::diesel::{schema::record, TrackAndTraceStoreError};
 
-use crate::commits::MAX_COMMIT_NUM;
 use crate::error::InternalError;
 use crate::paging::Paging;
 use crate::track_and_trace::store::diesel::models::RecordModel;
 use crate::track_and_trace::store::{Record, RecordList};
 
 use diesel::prelude::*;
-use std::convert::TryInto;
 
 pub(in crate::track_and_trace::store::diesel) trait TrackAndTraceStoreListRecordsOperation {
     fn list_records(
@@ -43,18 +42,10 @@ impl<'a> TrackAndTraceStoreListRecordsOperation
         offset: i64,
         limit: i64,
     ) -> Result<RecordList, TrackAndTraceStoreError> {
-        let mut query = record::table
-            .into_boxed()
+        let query = count_records::pg::records(service_id)
             .select(record::all_columns)
             .offset(offset)
-            .limit(limit)
-            .filter(record::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(record::service_id.eq(service_id));
-        } else {
-            query = query.filter(record::service_id.is_null());
-        }
+            .limit(limit);
 
         let records: Vec<Record> = query
             .load::<RecordModel>(self.conn)
@@ -73,9 +64,7 @@ impl<'a> TrackAndTraceStoreListRecordsOperation
             .map(Record::from)
             .collect();
 
-        let total = records.len().try_into().map_err(|err| {
-            TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
+        let total = self.count_records(service_id)?;
 
         Ok(RecordList::new(records, Paging::new(offset, limit, total)))
     }
@@ -91,18 +80,10 @@ impl<'a> TrackAndTraceStoreListRecordsOperation
         offset: i64,
         limit: i64,
     ) -> Result<RecordList, TrackAndTraceStoreError> {
-        let mut query = record::table
-            .into_boxed()
+        let query = count_records::sqlite::records(service_id)
             .select(record::all_columns)
             .offset(offset)
-            .limit(limit)
-            .filter(record::end_commit_num.eq(MAX_COMMIT_NUM));
-
-        if let Some(service_id) = service_id {
-            query = query.filter(record::service_id.eq(service_id));
-        } else {
-            query = query.filter(record::service_id.is_null());
-        }
+            .limit(limit);
 
         let records: Vec<Record> = query
             .load::<RecordModel>(self.conn)
@@ -121,9 +102,7 @@ impl<'a> TrackAndTraceStoreListRecordsOperation
             .map(Record::from)
             .collect();
 
-        let total = records.len().try_into().map_err(|err| {
-            TrackAndTraceStoreError::InternalError(InternalError::from_source(Box::new(err)))
-        })?;
+        let total = self.count_records(service_id)?;
 
         Ok(RecordList::new(records, Paging::new(offset, limit, total)))
     }
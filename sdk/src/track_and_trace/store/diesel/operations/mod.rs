@@ -18,10 +18,12 @@ pub(super) mod add_proposals;
 pub(super) mod add_records;
 pub(super) mod add_reported_values;
 pub(super) mod add_reporters;
+pub(super) mod count_records;
 pub(super) mod get_property_with_data_type;
 pub(super) mod get_record;
 pub(super) mod get_reported_value_reporter_to_agent_metadata;
 pub(super) mod list_associated_agents;
+pub(super) mod list_custody_chain;
 pub(super) mod list_properties_with_data_type;
 pub(super) mod list_proposals;
 pub(super) mod list_records;
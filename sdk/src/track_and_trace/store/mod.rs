@@ -34,6 +34,17 @@ pub struct AssociatedAgent {
     pub service_id: Option<String>,
 }
 
+/// A transfer of custody of a record to an agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustodyTransfer {
+    pub record_id: String,
+    /// The agent that took custody of the record
+    pub agent_id: String,
+    /// When the agent took custody, as reported in the record's state
+    pub timestamp: i64,
+    pub service_id: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Property {
     pub id: Option<i64>,
@@ -253,6 +264,22 @@ pub trait TrackAndTraceStore {
         service_id: Option<&str>,
     ) -> Result<Vec<AssociatedAgent>, TrackAndTraceStoreError>;
 
+    /// Fetches the custody chain of a record from the underlying storage: every transfer of
+    /// custody, oldest first
+    ///
+    /// The first transfer is to the record's creator, so a record that has never changed
+    /// custody has a chain of one. A record that does not exist has an empty chain.
+    ///
+    /// # Arguments
+    ///
+    ///  * `record_id` - The record ID to fetch for
+    ///  * `service_id` - The service ID to fetch for
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError>;
+
     /// Fetches a list of properties and their data types from the underlying storage
     ///
     /// # Arguments
@@ -291,6 +318,13 @@ pub trait TrackAndTraceStore {
         limit: i64,
     ) -> Result<RecordList, TrackAndTraceStoreError>;
 
+    /// Counts the records in the underlying storage, applying the same filters as `list_records`
+    ///
+    /// # Arguments
+    ///
+    ///  * `service_id` - The service ID to count records for
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError>;
+
     /// Fetches a list of reported value reported to agent metadata objects from the underlying
     /// storage
     ///
@@ -395,6 +429,14 @@ where
         (**self).list_associated_agents(record_ids, service_id)
     }
 
+    fn list_custody_chain(
+        &self,
+        record_id: &str,
+        service_id: Option<&str>,
+    ) -> Result<Vec<CustodyTransfer>, TrackAndTraceStoreError> {
+        (**self).list_custody_chain(record_id, service_id)
+    }
+
     fn list_properties_with_data_type(
         &self,
         record_ids: &[String],
@@ -420,6 +462,10 @@ where
         (**self).list_records(service_id, offset, limit)
     }
 
+    fn count_records(&self, service_id: Option<&str>) -> Result<i64, TrackAndTraceStoreError> {
+        (**self).count_records(service_id)
+    }
+
     fn list_reported_value_reporter_to_agent_metadata(
         &self,
         record_id: &str,
@@ -0,0 +1,344 @@
+// Copyright 2022 Cargill Incorporated
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Declarative workflow definitions.
+//!
+//! A definition describes the states of each subworkflow, the transitions between them and the
+//! permissions required to make them, in JSON. Definitions are validated when they are loaded
+//! and converted into the same [`Workflow`] model that is otherwise built in code.
+//!
+//! The built-in definitions describe the workflows the purchase order smart contract builds in
+//! code, which remain the ones it enforces; the contract's tests check that the two agree.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use crate::error::InvalidArgumentError;
+
+use super::{
+    PermissionAlias, StartWorkflowStateBuilder, SubWorkflow, SubWorkflowBuilder, Workflow,
+    WorkflowStateBuilder,
+};
+
+/// The definitions of the built-in workflows, e.g. `built-in::system_of_record::v1`.
+const BUILT_IN: &str = include_str!("built_in.json");
+
+/// A set of workflow definitions, as loaded from a single file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct WorkflowDefinitions {
+    #[serde(default)]
+    pub workflows: Vec<WorkflowDefinition>,
+}
+
+/// The definition of a single workflow and its subworkflows.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct WorkflowDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub subworkflows: Vec<SubWorkflowDefinition>,
+}
+
+/// The definition of a subworkflow, i.e. the states a single process moves through.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SubWorkflowDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub start_state: Option<StartStateDefinition>,
+    #[serde(default)]
+    pub states: Vec<StateDefinition>,
+}
+
+/// The definition of the state a subworkflow starts in.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StartStateDefinition {
+    #[serde(default)]
+    pub transitions: Vec<String>,
+    #[serde(default)]
+    pub permission_aliases: Vec<PermissionAliasDefinition>,
+}
+
+/// The definition of a named state of a subworkflow.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StateDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub constraints: Vec<String>,
+    #[serde(default)]
+    pub transitions: Vec<String>,
+    #[serde(default)]
+    pub permission_aliases: Vec<PermissionAliasDefinition>,
+}
+
+/// The definition of a permission alias, which grants its permissions and the transitions they
+/// allow to the agents that hold it.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct PermissionAliasDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub transitions: Vec<String>,
+}
+
+impl WorkflowDefinitions {
+    /// Parses and validates workflow definitions from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`InvalidArgumentError`] if the JSON cannot be parsed or a definition is not
+    /// well-formed; see [`WorkflowDefinitions::validate`].
+    pub fn from_json(json: &str) -> Result<Self, InvalidArgumentError> {
+        let definitions: WorkflowDefinitions = serde_json::from_str(json).map_err(|err| {
+            InvalidArgumentError::new(
+                "definition".to_string(),
+                format!("unable to parse workflow definitions: {}", err),
+            )
+        })?;
+        definitions.validate()?;
+
+        Ok(definitions)
+    }
+
+    /// Reads, parses and validates workflow definitions from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, InvalidArgumentError> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path).map_err(|err| {
+            InvalidArgumentError::new(
+                "path".to_string(),
+                format!("unable to read {}: {}", path.display(), err),
+            )
+        })?;
+
+        Self::from_json(&json)
+    }
+
+    /// Returns the definitions of the built-in workflows, which are used when no other
+    /// definitions are provided.
+    pub fn built_in() -> Self {
+        Self::from_json(BUILT_IN).expect("Built-in workflow definitions are invalid")
+    }
+
+    /// Checks that every definition is well-formed.
+    ///
+    /// Workflow names must be unique, as must the names of the subworkflows of a workflow and
+    /// the states of a subworkflow. Every transition, including those granted by a permission
+    /// alias, must name a state of the same subworkflow.
+    pub fn validate(&self) -> Result<(), InvalidArgumentError> {
+        let mut workflow_names = HashSet::new();
+        for workflow in &self.workflows {
+            if !workflow_names.insert(workflow.name.as_str()) {
+                return Err(invalid(format!(
+                    "workflow {} is defined more than once",
+                    workflow.name
+                )));
+            }
+            workflow.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the definition of the workflow with the given name, if there is one.
+    pub fn get(&self, name: &str) -> Option<&WorkflowDefinition> {
+        self.workflows.iter().find(|workflow| workflow.name == name)
+    }
+
+    /// Builds the workflow with the given name, if there is one.
+    pub fn workflow(&self, name: &str) -> Option<Workflow> {
+        self.get(name).map(WorkflowDefinition::to_workflow)
+    }
+}
+
+impl WorkflowDefinition {
+    fn validate(&self) -> Result<(), InvalidArgumentError> {
+        let mut subworkflow_names = HashSet::new();
+        for subworkflow in &self.subworkflows {
+            if !subworkflow_names.insert(subworkflow.name.as_str()) {
+                return Err(invalid(format!(
+                    "subworkflow {} of workflow {} is defined more than once",
+                    subworkflow.name, self.name
+                )));
+            }
+            subworkflow.validate(&self.name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the workflow this definition describes.
+    pub fn to_workflow(&self) -> Workflow {
+        Workflow::new(
+            self.subworkflows
+                .iter()
+                .map(SubWorkflowDefinition::to_subworkflow)
+                .collect(),
+        )
+    }
+}
+
+impl SubWorkflowDefinition {
+    fn validate(&self, workflow: &str) -> Result<(), InvalidArgumentError> {
+        let mut state_names = HashSet::new();
+        for state in &self.states {
+            if !state_names.insert(state.name.as_str()) {
+                return Err(invalid(format!(
+                    "state {} of {}/{} is defined more than once",
+                    state.name, workflow, self.name
+                )));
+            }
+        }
+
+        let check_transitions = |from: &str, transitions: &[String]| match transitions
+            .iter()
+            .find(|target| !state_names.contains(target.as_str()))
+        {
+            Some(target) => Err(invalid(format!(
+                "{} of {}/{} transitions to undefined state {}",
+                from, workflow, self.name, target
+            ))),
+            None => Ok(()),
+        };
+
+        if let Some(start_state) = &self.start_state {
+            check_transitions("the start state", &start_state.transitions)?;
+            for alias in &start_state.permission_aliases {
+                check_transitions(
+                    &format!("alias {} of the start state", alias.name),
+                    &alias.transitions,
+                )?;
+            }
+        }
+
+        for state in &self.states {
+            check_transitions(&format!("state {}", state.name), &state.transitions)?;
+            for alias in &state.permission_aliases {
+                check_transitions(
+                    &format!("alias {} of state {}", alias.name, state.name),
+                    &alias.transitions,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn to_subworkflow(&self) -> SubWorkflow {
+        let mut builder = SubWorkflowBuilder::new(&self.name);
+
+        if let Some(start_state) = &self.start_state {
+            let mut start = StartWorkflowStateBuilder::default();
+            for transition in &start_state.transitions {
+                start = start.add_transition(transition);
+            }
+            for alias in &start_state.permission_aliases {
+                start = start.add_permission_alias(alias.to_permission_alias());
+            }
+            builder = builder.with_start_state(start.build());
+        }
+
+        for state in &self.states {
+            let mut state_builder = WorkflowStateBuilder::new(&state.name);
+            for constraint in &state.constraints {
+                state_builder = state_builder.add_constraint(constraint);
+            }
+            for transition in &state.transitions {
+                state_builder = state_builder.add_transition(transition);
+            }
+            for alias in &state.permission_aliases {
+                state_builder = state_builder.add_permission_alias(alias.to_permission_alias());
+            }
+            builder = builder.add_state(state_builder.build());
+        }
+
+        builder.build()
+    }
+}
+
+impl PermissionAliasDefinition {
+    fn to_permission_alias(&self) -> PermissionAlias {
+        let mut alias = PermissionAlias::new(&self.name);
+        for permission in &self.permissions {
+            alias.add_permission(permission);
+        }
+        for transition in &self.transitions {
+            alias.add_transition(transition);
+        }
+        alias
+    }
+}
+
+fn invalid(message: String) -> InvalidArgumentError {
+    InvalidArgumentError::new("definition".to_string(), message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Verifies that the built-in definitions load and build the workflows the purchase order
+    /// contract uses, with their states and permissions.
+    #[test]
+    fn test_built_in_definitions() {
+        let definitions = WorkflowDefinitions::built_in();
+
+        let workflow = definitions
+            .workflow("built-in::collaborative::v1")
+            .expect("Collaborative workflow not defined");
+        let version = workflow
+            .subworkflow("version")
+            .expect("Version subworkflow not defined");
+        let proposed = version
+            .state("proposed")
+            .expect("Proposed state not defined");
+        assert!(proposed.can_transition("accepted".to_string(), &["po::partner".to_string()]));
+        assert!(!proposed.can_transition("accepted".to_string(), &["po::buyer".to_string()]));
+
+        assert!(definitions
+            .workflow("built-in::system_of_record::v1")
+            .and_then(|workflow| workflow.subworkflow("po"))
+            .and_then(|po| po.start_state().cloned())
+            .is_some());
+        assert!(definitions.workflow("unknown").is_none());
+    }
+
+    /// Verifies that a definition with a transition to a state that is not defined is rejected,
+    /// naming the transition.
+    #[test]
+    fn test_dangling_transition_rejected() {
+        let json = r#"{
+            "workflows": [{
+                "name": "test",
+                "subworkflows": [{
+                    "name": "po",
+                    "states": [
+                        { "name": "issued", "transitions": ["closed"] },
+                        {
+                            "name": "closed",
+                            "permission_aliases": [
+                                { "name": "po::buyer", "transitions": ["reopened"] }
+                            ]
+                        }
+                    ]
+                }]
+            }]
+        }"#;
+
+        let err = WorkflowDefinitions::from_json(json).unwrap_err();
+
+        assert_eq!(err.argument(), "definition");
+        assert!(err.message().contains("alias po::buyer of state closed"));
+        assert!(err.message().contains("undefined state reopened"));
+    }
+}
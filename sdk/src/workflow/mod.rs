@@ -18,9 +18,17 @@
 //! Grid Workflow module encapsulates business process complexity and allows for those rules to
 //! become decoupled from the smart contract logic.
 
+#[cfg(feature = "workflow-definition")]
+mod definition;
 mod state;
 mod subworkflow;
 
+#[cfg(feature = "workflow-definition")]
+pub use definition::{
+    PermissionAliasDefinition, StartStateDefinition, StateDefinition, SubWorkflowDefinition,
+    WorkflowDefinition, WorkflowDefinitions,
+};
+
 pub use state::{
     PermissionAlias, StartWorkflowState, StartWorkflowStateBuilder, WorkflowState,
     WorkflowStateBuilder,
@@ -29,6 +37,7 @@ pub use subworkflow::{SubWorkflow, SubWorkflowBuilder};
 
 /// A single workflow may involve multiple processes; these processes are defined by the list of
 /// subworkflows, which are different smaller workflows that make up the overall workflow.
+#[derive(Debug, PartialEq, Eq)]
 pub struct Workflow {
     subworkflow: Vec<SubWorkflow>,
 }
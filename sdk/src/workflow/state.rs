@@ -20,7 +20,7 @@
 /// Defines the current state of an item within a workflow. A `WorkflowState` contains a list of
 /// constraints for items within this state, permission aliases to allow for operations to be made
 /// within this state, and a list of transitions that can be made from this state.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct WorkflowState {
     name: String,
     /// Defines specific attributes an item must have to be in this workflow state
@@ -163,7 +163,7 @@ impl WorkflowStateBuilder {
 /// Defines the beginning state of an item within a workflow. A `StartWorkflowState` contains a
 /// list of permission aliases and transitions that an object may make once it is created within
 /// the `SubWorkflow`'s `StartWorkflowState`.
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct StartWorkflowState {
     /// Permission definitions for creating an object in the `SubWorkflow`
     permission_aliases: Vec<PermissionAlias>,
@@ -265,7 +265,7 @@ impl StartWorkflowStateBuilder {
 }
 
 /// An alias for multiple permissions
-#[derive(Clone, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct PermissionAlias {
     name: String,
     /// Permissions assigned to this alias
@@ -19,7 +19,7 @@ use super::state::{StartWorkflowState, WorkflowState};
 
 /// A smaller more specific version of a workflow used to define a more complicated business
 /// process within a workflow
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SubWorkflow {
     name: String,
     /// The states an object may be in within this subworkflow